@@ -0,0 +1,124 @@
+//! Compares RPC request throughput with and without connection reuse, against a local HTTP server.
+//!
+//! Run with `cargo bench --features rpc`.
+
+use nanopyrs::rpc::Rpc;
+use serde_json::{json, Map};
+use std::io::{BufRead, BufReader, Read, Write};
+use std::net::{TcpListener, TcpStream};
+use std::sync::atomic::{AtomicUsize, Ordering};
+use std::sync::Arc;
+use std::thread;
+use std::time::{Duration, Instant};
+
+const REQUESTS: usize = 2000;
+/// Building an HTTP client is slow, so fewer requests are sent when building a new `Rpc` for each one
+const SLOW_REQUESTS: usize = 50;
+
+/// Serve `version` responses on localhost, keeping connections open between requests.
+/// Returns the URL, and a counter of accepted connections.
+fn serve() -> (String, Arc<AtomicUsize>) {
+    let listener = TcpListener::bind("127.0.0.1:0").unwrap();
+    let url = format!("http://{}", listener.local_addr().unwrap());
+    let connections = Arc::new(AtomicUsize::new(0));
+
+    let counter = connections.clone();
+    thread::spawn(move || {
+        for stream in listener.incoming() {
+            counter.fetch_add(1, Ordering::Relaxed);
+            thread::spawn(move || handle(stream.unwrap()));
+        }
+    });
+    (url, connections)
+}
+
+fn handle(stream: TcpStream) {
+    let body = json!({"node_vendor": "Nano V26.1"}).to_string();
+    let mut reader = BufReader::new(stream);
+    loop {
+        let mut length = 0;
+        loop {
+            let mut line = String::new();
+            if reader.read_line(&mut line).unwrap_or(0) == 0 {
+                return;
+            }
+            if line == "\r\n" {
+                break;
+            }
+            if let Some((name, value)) = line.split_once(':') {
+                if name.eq_ignore_ascii_case("content-length") {
+                    length = value.trim().parse().unwrap();
+                }
+            }
+        }
+        let mut request = vec![0; length];
+        if reader.read_exact(&mut request).is_err() {
+            return;
+        }
+
+        let response = format!(
+            "HTTP/1.1 200 OK\r\ncontent-type: application/json\r\ncontent-length: {}\r\n\r\n{}",
+            body.len(),
+            body
+        );
+        if reader.get_mut().write_all(response.as_bytes()).is_err() {
+            return;
+        }
+    }
+}
+
+async fn run(name: &str, requests: usize, connections: &AtomicUsize, rpc: impl Fn() -> Rpc) {
+    let before = connections.load(Ordering::Relaxed);
+    let start = Instant::now();
+    for _ in 0..requests {
+        rpc().command("version", Map::new()).await.unwrap();
+    }
+    let elapsed = start.elapsed();
+    // give the server a moment to count the last connection
+    thread::sleep(Duration::from_millis(50));
+
+    println!(
+        "{name:<32} {:>8.0} req/s {:>6} connections / {requests} requests",
+        requests as f64 / elapsed.as_secs_f64(),
+        connections.load(Ordering::Relaxed) - before
+    );
+}
+
+fn main() {
+    let runtime = tokio::runtime::Builder::new_current_thread()
+        .enable_all()
+        .build()
+        .unwrap();
+    let (url, connections) = serve();
+
+    runtime.block_on(async {
+        run("new Rpc per request", SLOW_REQUESTS, &connections, || {
+            Rpc::new(&url, None).unwrap()
+        })
+        .await;
+
+        let rpc = Rpc::builder(&url)
+            .pool_max_idle_per_host(0)
+            .build()
+            .unwrap();
+        run(
+            "shared Rpc, no connection reuse",
+            REQUESTS,
+            &connections,
+            || rpc.clone(),
+        )
+        .await;
+
+        let rpc = Rpc::builder(&url)
+            .tcp_keepalive(Duration::from_secs(60))
+            .build()
+            .unwrap();
+        run(
+            "shared Rpc, connection reuse",
+            REQUESTS,
+            &connections,
+            || rpc.clone(),
+        )
+        .await;
+    });
+}
@@ -1,16 +1,24 @@
+use super::constants::Network;
 use super::nanopy::{
-    account_decode, account_encode, get_account_scalar, is_valid_signature, sign_message,
+    account_checksum, account_decode, account_decode_unchecked, account_decode_with_prefix,
+    account_encode, account_encode_with_prefix, get_account_scalar, is_valid_signature,
+    sign_message,
 };
 use super::{Block, Scalar, SecretBytes, Signature};
 use crate::auto_from_impl;
+#[cfg(feature = "rand")]
+use crate::hashes::blake2b_scalar;
 use auto_ops::{impl_op_ex, impl_op_ex_commutative};
 use curve25519_dalek::{
-    constants::ED25519_BASEPOINT_POINT as G,
     edwards::{CompressedEdwardsY, EdwardsPoint},
     Scalar as RawScalar,
 };
+use std::collections::HashMap;
 use std::fmt::Display;
 use std::hash::Hash;
+use std::sync::{Arc, Mutex};
+#[cfg(not(target_arch = "wasm32"))]
+use std::thread;
 use zeroize::{Zeroize, ZeroizeOnDrop};
 
 #[cfg(feature = "serde")]
@@ -18,9 +26,81 @@ use serde::{Deserialize, Serialize};
 
 pub use super::error::NanoError;
 
-#[cfg(feature = "rpc")]
+#[cfg(feature = "rpc-core")]
 use serde_json::Value as JsonValue;
 
+/// Compute the checksum which is encoded at the end of a `nano_` address
+pub fn compute_checksum(public_key: &[u8; 32]) -> [u8; 5] {
+    account_checksum(public_key)
+}
+
+/// Check whether the checksum of a `nano_` address is correct.
+///
+/// Returns an error if the address is invalid for any other reason.
+pub fn verify_checksum(address: &str) -> Result<bool, NanoError> {
+    let (key, checksum) = account_decode_unchecked(address)?;
+    Ok(checksum == account_checksum(&key))
+}
+
+/// Find the valid `nano_` addresses which differ from `address` by a single character.
+///
+/// Useful for suggesting corrections to a mistyped address.
+pub fn did_you_mean(address: &str) -> Vec<Account> {
+    single_char_typos(address, 5, |candidate| match verify_checksum(candidate) {
+        Ok(true) => Account::try_from(candidate).ok(),
+        _ => None,
+    })
+}
+
+/// Try replacing each character of `address` (after the first `skip` characters) with every character in the base32 alphabet
+pub(crate) fn single_char_typos<T>(
+    address: &str,
+    skip: usize,
+    parse: impl Fn(&str) -> Option<T>,
+) -> Vec<T> {
+    let chars: Vec<char> = address.chars().collect();
+    let mut found = vec![];
+    for i in skip..chars.len() {
+        for replacement in crate::base32::ALPHABET.chars() {
+            if replacement == chars[i] {
+                continue;
+            }
+            let mut candidate = chars.clone();
+            candidate[i] = replacement;
+            let candidate: String = candidate.into_iter().collect();
+            if let Some(parsed) = parse(&candidate) {
+                found.push(parsed)
+            }
+        }
+    }
+    found
+}
+
+/// The `count` indexes from `start`, stopping at `u32::MAX`
+fn seed_range(start: u32, count: u32) -> impl Iterator<Item = u32> {
+    (start..=u32::MAX).take(count as usize)
+}
+
+/// Call `derive` on each index of `seed_range()`, split evenly between one thread per CPU core
+#[cfg(not(target_arch = "wasm32"))]
+fn derive_parallel<T: Send>(start: u32, count: u32, derive: impl Fn(u32) -> T + Sync) -> Vec<T> {
+    let indexes: Vec<u32> = seed_range(start, count).collect();
+    let threads = thread::available_parallelism().map_or(1, |threads| threads.get());
+    let chunk_size = indexes.len().div_ceil(threads).max(1);
+
+    let derive = &derive;
+    thread::scope(|scope| {
+        let handles: Vec<_> = indexes
+            .chunks(chunk_size)
+            .map(|chunk| scope.spawn(move || chunk.iter().map(|i| derive(*i)).collect::<Vec<T>>()))
+            .collect();
+        handles
+            .into_iter()
+            .flat_map(|handle| handle.join().expect("key derivation panicked"))
+            .collect()
+    })
+}
+
 /// The private key of a `nano_` account
 #[derive(Debug, Clone, Zeroize, ZeroizeOnDrop, PartialEq, Eq)]
 #[cfg_attr(feature = "serde", derive(Serialize, Deserialize))]
@@ -31,6 +111,30 @@ impl Key {
         Key(get_account_scalar(seed, i))
     }
 
+    /// Get the keys at indexes `start..start + count` of `seed`.
+    /// Indexes past `u32::MAX` are not derived.
+    pub fn from_seed_range(seed: &SecretBytes<32>, start: u32, count: u32) -> Vec<Key> {
+        seed_range(start, count)
+            .map(|i| Key::from_seed(seed, i))
+            .collect()
+    }
+
+    /// Same as `from_seed_range()`, but derived on every CPU core
+    #[cfg(not(target_arch = "wasm32"))]
+    pub fn from_seed_range_parallel(seed: &SecretBytes<32>, start: u32, count: u32) -> Vec<Key> {
+        derive_parallel(start, count, |i| Key::from_seed(seed, i))
+    }
+
+    /// Generate a random key with the operating system's secure random number generator.
+    ///
+    /// The key can only be backed up by storing the key itself:
+    /// to derive keys from a seed instead, generate the seed with `SecretBytes::random()`.
+    #[cfg(feature = "rand")]
+    pub fn random() -> Result<Key, NanoError> {
+        let private_key = SecretBytes::<32>::random()?;
+        Ok(Key(blake2b_scalar(private_key.as_slice())))
+    }
+
     pub fn from_scalar(scalar: Scalar) -> Key {
         Key::from(scalar)
     }
@@ -92,6 +196,24 @@ impl Account {
         Account::from(key)
     }
 
+    /// Get the accounts at indexes `start..start + count` of `seed`, such as for gap scanning or deposit addresses.
+    /// Indexes past `u32::MAX` are not derived.
+    pub fn from_seed_range(seed: &SecretBytes<32>, start: u32, count: u32) -> Vec<Account> {
+        seed_range(start, count)
+            .map(|i| Key::from_seed(seed, i).to_account())
+            .collect()
+    }
+
+    /// Same as `from_seed_range()`, but derived on every CPU core
+    #[cfg(not(target_arch = "wasm32"))]
+    pub fn from_seed_range_parallel(
+        seed: &SecretBytes<32>,
+        start: u32,
+        count: u32,
+    ) -> Vec<Account> {
+        derive_parallel(start, count, |i| Key::from_seed(seed, i).to_account())
+    }
+
     pub fn from_point(point: &EdwardsPoint) -> Account {
         Account::from(point)
     }
@@ -116,22 +238,60 @@ impl Account {
         Account::try_from(bytes)
     }
 
+    /// Parse a 64-character hex public key, as used by block explorers and the `account_key` RPC
+    pub fn from_hex(public_key: &str) -> Result<Account, NanoError> {
+        let bytes: [u8; 32] = hex::decode(public_key)
+            .or(Err(NanoError::InvalidHex))?
+            .try_into()
+            .or(Err(NanoError::InvalidHex))?;
+        Account::try_from(bytes)
+    }
+
+    /// Get the public key of this account, as 64 uppercase hex characters
+    pub fn to_hex(&self) -> String {
+        hex::encode_upper(self.as_bytes())
+    }
+
+    /// Get the 32-byte public key of this account
+    pub fn as_bytes(&self) -> &[u8; 32] {
+        self.compressed.as_bytes()
+    }
+
     pub fn is_valid(account: &str) -> bool {
         Account::try_from(account).is_ok()
     }
 
+    /// Parse an address with the given network's prefix, such as `ban_`.
+    ///
+    /// Note that the returned account's `account` field (and `Display`) always use the `nano_` prefix:
+    /// use `to_network_address()` to get the address on another network.
+    pub fn from_network_address(address: &str, network: &Network) -> Result<Account, NanoError> {
+        let compressed = account_decode_with_prefix(address, network.account_prefix)?;
+        Account::try_from(compressed)
+    }
+
+    /// Get the address of this account, with the given network's prefix
+    pub fn to_network_address(&self, network: &Network) -> String {
+        account_encode_with_prefix(&self.compressed, network.account_prefix)
+    }
+
     /// Check the validity of a signature made by this account's private key
     pub fn is_valid_signature(&self, message: &[u8], signature: &Signature) -> bool {
         is_valid_signature(message, signature, self)
     }
 }
+/// Serialized as a `nano_` address in human-readable formats, and as the compressed point otherwise
 #[cfg(feature = "serde")]
 impl Serialize for Account {
     fn serialize<S>(&self, serializer: S) -> Result<S::Ok, S::Error>
     where
         S: serde::Serializer,
     {
-        self.compressed.serialize(serializer)
+        if serializer.is_human_readable() {
+            serializer.serialize_str(&self.to_string())
+        } else {
+            self.compressed.serialize(serializer)
+        }
     }
 }
 #[cfg(feature = "serde")]
@@ -140,7 +300,12 @@ impl<'de> Deserialize<'de> for Account {
     where
         D: serde::Deserializer<'de>,
     {
-        Ok(Account::from(EdwardsPoint::deserialize(deserializer)?))
+        if deserializer.is_human_readable() {
+            let account = String::deserialize(deserializer)?;
+            Account::try_from(account.as_str()).map_err(serde::de::Error::custom)
+        } else {
+            Ok(Account::from(EdwardsPoint::deserialize(deserializer)?))
+        }
     }
 }
 
@@ -154,12 +319,13 @@ auto_from_impl!(From: EdwardsPoint => Account);
 auto_from_impl!(TryFrom: String => Account);
 auto_from_impl!(TryFrom: CompressedEdwardsY => Account);
 auto_from_impl!(TryFrom: [u8; 32] => Account);
-#[cfg(feature = "rpc")]
+#[cfg(feature = "rpc-core")]
 auto_from_impl!(From: Account => JsonValue);
 
 impl From<&Key> for Account {
     fn from(value: &Key) -> Self {
-        value * G
+        // uses a precomputed table, so is faster than multiplying by the basepoint
+        Account::from(&EdwardsPoint::mul_base(value.0.as_ref()))
     }
 }
 impl From<&EdwardsPoint> for Account {
@@ -233,7 +399,7 @@ impl From<&Account> for [u8; 32] {
         val.compressed.to_bytes()
     }
 }
-#[cfg(feature = "rpc")]
+#[cfg(feature = "rpc-core")]
 impl From<&Account> for JsonValue {
     fn from(val: &Account) -> Self {
         val.to_string().into()
@@ -255,10 +421,95 @@ impl_op_ex!(+ |a: &Account, b: &Account| -> Account {
 });
 impl_op_ex!(-|a: &Account, b: &Account| -> Account { Account::from(a.point - b.point) });
 
+/// Addresses are cached without their prefix, so that `xrb_` addresses share entries with their `nano_` equivalents
+fn without_prefix(account: &str) -> Option<&str> {
+    account
+        .strip_prefix("nano_")
+        .or_else(|| account.strip_prefix("xrb_"))
+}
+
+/// A thread-safe cache of parsed `nano_` addresses.
+///
+/// Parsing an address requires a base32 decode and a point decompression, which adds up when the same
+/// few accounts (e.g. representatives) appear thousands of times.
+/// Cloning an `AccountCache` is cheap, and all clones share the same underlying cache.
+#[derive(Debug, Clone, Default)]
+pub struct AccountCache {
+    accounts: Arc<Mutex<HashMap<String, Account>>>,
+    limit: Option<usize>,
+}
+impl AccountCache {
+    /// Create an unbounded cache
+    pub fn new() -> AccountCache {
+        AccountCache::default()
+    }
+
+    /// Create a cache which holds at most `limit` accounts.
+    /// Once full, the cache is cleared before inserting any more accounts.
+    pub fn with_limit(limit: usize) -> AccountCache {
+        AccountCache {
+            accounts: Arc::default(),
+            limit: Some(limit),
+        }
+    }
+
+    /// Parse the given address, returning the cached `Account` if it has been parsed before.
+    ///
+    /// Legacy `xrb_` addresses are also accepted, and are returned as their `nano_` equivalent.
+    pub fn get_or_parse(&self, account: &str) -> Result<Account, NanoError> {
+        if let Some(account) = self.get(account) {
+            return Ok(account);
+        }
+        let account = match without_prefix(account) {
+            Some(encoded) => Account::try_from(format!("nano_{encoded}").as_str())?,
+            None => Account::try_from(account)?,
+        };
+        self.insert(account.clone());
+        Ok(account)
+    }
+
+    /// Get the cached `Account` for the given `nano_` or `xrb_` address, if any
+    pub fn get(&self, account: &str) -> Option<Account> {
+        self.lock().get(without_prefix(account)?).cloned()
+    }
+
+    /// Add the given `Account` to the cache
+    pub fn insert(&self, account: Account) {
+        let mut accounts = self.lock();
+        if self.limit.is_some_and(|limit| accounts.len() >= limit) {
+            accounts.clear()
+        }
+        let key = without_prefix(&account.account).unwrap_or(&account.account);
+        accounts.insert(key.to_string(), account);
+    }
+
+    /// The number of accounts currently in the cache
+    pub fn len(&self) -> usize {
+        self.lock().len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.len() == 0
+    }
+
+    /// Remove all accounts from the cache
+    pub fn clear(&self) {
+        self.lock().clear()
+    }
+
+    fn lock(&self) -> std::sync::MutexGuard<'_, HashMap<String, Account>> {
+        // the map is always left in a valid state, so a poisoned lock can be ignored
+        self.accounts
+            .lock()
+            .unwrap_or_else(|poisoned| poisoned.into_inner())
+    }
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
     use crate::{constants::get_genesis_account, SecretBytes};
+    use curve25519_dalek::constants::ED25519_BASEPOINT_POINT as G;
 
     #[test]
     fn from_str() {
@@ -272,6 +523,103 @@ mod tests {
             account.to_string()
                 == "nano_3i1aq1cchnmbn9x5rsbap8b15akfh7wj7pwskuzi7ahz8oq6cobd99d4r3b7"
         );
+
+        // same data as above, but with non-zero padding bits
+        assert!(
+            "nano_ki1aq1cchnmbn9x5rsbap8b15akfh7wj7pwskuzi7ahz8oq6cobd99d4r3b7"
+                .parse::<Account>()
+                .is_err()
+        );
+    }
+
+    #[test]
+    fn from_seed_range() {
+        let seed = SecretBytes::from([0; 32]);
+        let keys = Key::from_seed_range(&seed, 5, 20);
+        assert!(keys.len() == 20);
+        assert!(keys[0] == Key::from_seed(&seed, 5));
+        assert!(keys[19] == Key::from_seed(&seed, 24));
+        assert!(Key::from_seed_range_parallel(&seed, 5, 20) == keys);
+
+        let accounts: Vec<Account> = keys.iter().map(|key| key.to_account()).collect();
+        assert!(Account::from_seed_range(&seed, 5, 20) == accounts);
+        assert!(Account::from_seed_range_parallel(&seed, 5, 20) == accounts);
+        assert!(accounts[0] == Key::from_seed(&seed, 5) * G);
+
+        assert!(Account::from_seed_range(&seed, u32::MAX - 1, 5).len() == 2);
+        assert!(Account::from_seed_range_parallel(&seed, 0, 0).is_empty());
+    }
+
+    #[test]
+    #[cfg(feature = "rand")]
+    fn random() {
+        let seed = SecretBytes::<32>::random().unwrap();
+        assert!(seed != SecretBytes::random().unwrap());
+        assert!(Key::random().unwrap() != Key::random().unwrap());
+    }
+
+    #[test]
+    fn hex() {
+        let genesis = get_genesis_account();
+        let public_key = "E89208DD038FBB269987689621D52292AE9C35941A7484756ECCED92A65093BA";
+        assert!(genesis.to_hex() == public_key);
+        assert!(hex::encode_upper(genesis.as_bytes()) == public_key);
+        assert!(Account::from_bytes(*genesis.as_bytes()).unwrap() == genesis);
+        assert!(Account::from_hex(public_key).unwrap() == genesis);
+        assert!(Account::from_hex(&public_key.to_lowercase()).unwrap() == genesis);
+
+        assert!(Account::from_hex(&public_key[2..]) == Err(NanoError::InvalidHex));
+        assert!(Account::from_hex(&public_key.replace('E', "G")) == Err(NanoError::InvalidHex));
+        assert!(Account::from_hex(&genesis.to_string()).is_err());
+    }
+
+    #[test]
+    fn networks() {
+        for network in [
+            Network::MAINNET,
+            Network::BETA,
+            Network::TEST,
+            Network::BANANO,
+        ] {
+            let genesis = network.genesis_account();
+            assert!(genesis.to_network_address(&network) == network.genesis_address);
+            network.epoch_v1_signer();
+            network.epoch_v2_signer();
+        }
+        assert!(Network::default() == Network::MAINNET);
+        assert!(Network::MAINNET.genesis_account() == get_genesis_account());
+        assert!(
+            hex::encode_upper(Network::BETA.genesis_account().compressed.as_bytes())
+                == "259A43ABDB779E97452E188BA3EB951B41C961D3318CA6B925380F4D99F0577A"
+        );
+
+        let key = Key::from_seed(&SecretBytes::from([0; 32]), 0);
+        let address = key.to_account().to_network_address(&Network::BANANO);
+        assert!(address.starts_with("ban_"));
+        assert!(address[4..] == key.to_account().to_string()[5..]);
+        assert!(Network::BANANO.parse_account(&address).unwrap() == key.to_account());
+        assert!(Account::from_network_address(&address, &Network::MAINNET).is_err());
+        assert!(Account::try_from(address).is_err());
+    }
+
+    #[test]
+    fn checksum() {
+        let genesis = get_genesis_account();
+        let checksum = compute_checksum(&genesis.compressed.to_bytes());
+        assert!(verify_checksum(&genesis.to_string()).unwrap());
+        assert!(crate::base32::encode(&checksum) == genesis.to_string()[57..]);
+
+        let typo = "nano_3i1aq1cchnmbn9x5rsbap8b15akfh7wj7pwskuzi7ahz8oq6cobd99d4r3b8";
+        assert!(!verify_checksum(typo).unwrap());
+        assert!(verify_checksum("nano_3i1aq").is_err());
+
+        let suggestions = did_you_mean(typo);
+        assert!(suggestions.len() == 1);
+        assert!(
+            suggestions[0].to_string()
+                == "nano_3i1aq1cchnmbn9x5rsbap8b15akfh7wj7pwskuzi7ahz8oq6cobd99d4r3b7"
+        );
+        assert!(did_you_mean("nano_3i1aq").is_empty());
     }
 
     #[test]
@@ -284,6 +632,37 @@ mod tests {
         let account_2 = key_2.to_account();
         assert!((key_1 + key_2).to_account() == account_1 + account_2)
     }
+
+    #[test]
+    fn account_cache() {
+        let genesis = get_genesis_account().to_string();
+        let cache = AccountCache::new();
+        assert!(cache.get(&genesis).is_none());
+        assert!(cache.get_or_parse(&genesis).unwrap() == get_genesis_account());
+        assert!(cache.get(&genesis).unwrap() == get_genesis_account());
+        assert!(cache.clone().len() == 1);
+
+        assert!(cache.get_or_parse("nano_1111").is_err());
+        assert!(cache.len() == 1);
+
+        // `xrb_` addresses share entries with their `nano_` equivalents
+        let legacy = genesis.replacen("nano_", "xrb_", 1);
+        assert!(cache.get(&legacy).unwrap() == get_genesis_account());
+        let cache = AccountCache::new();
+        assert!(cache.get_or_parse(&legacy).unwrap() == get_genesis_account());
+        assert!(cache.get(&genesis).unwrap() == get_genesis_account());
+        assert!(cache.get_or_parse("xrb_1111").is_err());
+        assert!(cache.len() == 1);
+
+        let cache = AccountCache::with_limit(1);
+        let seed = SecretBytes::from([0; 32]);
+        cache.insert(Key::from_seed(&seed, 0).to_account());
+        cache.insert(Key::from_seed(&seed, 1).to_account());
+        assert!(cache.len() == 1);
+        assert!(cache
+            .get(&Key::from_seed(&seed, 1).to_account().to_string())
+            .is_some());
+    }
 }
 
 #[cfg(test)]
@@ -294,4 +673,13 @@ mod serde_tests {
 
     serde_test!(key: Key::from_seed(&[9; 32].into(), 0) => 32);
     serde_test!(account: get_genesis_account() => 32);
+
+    #[test]
+    fn account_json() {
+        let account = get_genesis_account();
+        let json = serde_json::to_string(&account).unwrap();
+        assert!(json == format!("\"{account}\""));
+        assert!(serde_json::from_str::<Account>(&json).unwrap() == account);
+        assert!(serde_json::from_str::<Account>("\"nano_1111\"").is_err());
+    }
 }
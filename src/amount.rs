@@ -0,0 +1,330 @@
+use crate::constants::{ONE_MICRO_NANO, ONE_MILLI_NANO, ONE_NANO, ONE_NANO_NANO, ONE_RAW};
+use crate::NanoError;
+use std::fmt::Display;
+use std::str::FromStr;
+
+#[cfg(feature = "serde")]
+use serde::{de::Visitor, Deserialize, Deserializer, Serialize, Serializer};
+
+/// Units which may follow an amount, such as `"1.5 nano"`
+const UNITS: &[(&str, u128)] = &[
+    ("raw", ONE_RAW),
+    ("nanonano", ONE_NANO_NANO),
+    ("micronano", ONE_MICRO_NANO),
+    ("millinano", ONE_MILLI_NANO),
+    ("nano", ONE_NANO),
+    ("xno", ONE_NANO),
+];
+
+/// Parse a non-negative decimal number, multiplied by `unit` (which must be a power of 10)
+fn parse_decimal(s: &str, unit: u128) -> Result<u128, NanoError> {
+    let (whole, fraction) = s.split_once('.').unwrap_or((s, ""));
+    let decimals = unit.ilog10() as usize;
+    let digits = |s: &str| s.bytes().all(|byte| byte.is_ascii_digit());
+    if (whole.is_empty() && fraction.is_empty())
+        || !digits(whole)
+        || !digits(fraction)
+        || fraction.len() > decimals
+    {
+        return Err(NanoError::InvalidAmount);
+    }
+
+    let whole: u128 = match whole {
+        "" => 0,
+        whole => whole.parse().map_err(|_| NanoError::InvalidAmount)?,
+    };
+    let fraction: u128 = match fraction {
+        "" => 0,
+        fraction => {
+            let padding = 10_u128.pow((decimals - fraction.len()) as u32);
+            fraction
+                .parse::<u128>()
+                .map_err(|_| NanoError::InvalidAmount)?
+                * padding
+        }
+    };
+    whole
+        .checked_mul(unit)
+        .and_then(|whole| whole.checked_add(fraction))
+        .ok_or(NanoError::InvalidAmount)
+}
+
+/// An amount of Nano, stored in raw (10<sup>-30</sup> Nano)
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, PartialOrd, Ord, Hash)]
+pub struct Amount(u128);
+impl Amount {
+    pub const ZERO: Amount = Amount(0);
+
+    pub const fn from_raw(raw: u128) -> Amount {
+        Amount(raw)
+    }
+
+    pub const fn raw(&self) -> u128 {
+        self.0
+    }
+
+    /// Parse an amount in raw, such as `"1000"`
+    pub fn from_raw_str(s: &str) -> Result<Amount, NanoError> {
+        Ok(Amount(parse_decimal(s, ONE_RAW)?))
+    }
+
+    /// Parse an amount followed by its unit, such as `"1.5 nano"` or `"1000 raw"`.
+    ///
+    /// The supported units are `raw`, `nanonano`, `micronano`, `millinano`, `nano`, and `xno` (case-insensitive).
+    pub fn from_str_with_unit(s: &str) -> Result<Amount, NanoError> {
+        let s = s.trim();
+        let split = s
+            .find(|c: char| c.is_ascii_alphabetic())
+            .ok_or(NanoError::InvalidAmount)?;
+        let (number, unit) = s.split_at(split);
+        let unit = UNITS
+            .iter()
+            .find(|(name, _)| name.eq_ignore_ascii_case(unit))
+            .ok_or(NanoError::InvalidAmount)?
+            .1;
+        Ok(Amount(parse_decimal(number.trim_end(), unit)?))
+    }
+
+    /// Returns `None` on overflow
+    pub const fn checked_add(self, rhs: Amount) -> Option<Amount> {
+        match self.0.checked_add(rhs.0) {
+            Some(raw) => Some(Amount(raw)),
+            None => None,
+        }
+    }
+
+    /// Returns `None` on underflow
+    pub const fn checked_sub(self, rhs: Amount) -> Option<Amount> {
+        match self.0.checked_sub(rhs.0) {
+            Some(raw) => Some(Amount(raw)),
+            None => None,
+        }
+    }
+
+    /// Returns `None` on overflow
+    pub const fn checked_mul(self, rhs: u128) -> Option<Amount> {
+        match self.0.checked_mul(rhs) {
+            Some(raw) => Some(Amount(raw)),
+            None => None,
+        }
+    }
+
+    /// Returns `None` if `rhs` is zero
+    pub const fn checked_div(self, rhs: u128) -> Option<Amount> {
+        match self.0.checked_div(rhs) {
+            Some(raw) => Some(Amount(raw)),
+            None => None,
+        }
+    }
+
+    pub const fn saturating_add(self, rhs: Amount) -> Amount {
+        Amount(self.0.saturating_add(rhs.0))
+    }
+
+    pub const fn saturating_sub(self, rhs: Amount) -> Amount {
+        Amount(self.0.saturating_sub(rhs.0))
+    }
+}
+/// Formatted in Nano, such as `1.5`.
+///
+/// A precision (such as `{:.2}`) sets the number of decimal places, rounding down.
+/// Otherwise, every significant decimal place is shown.
+impl Display for Amount {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        let decimals = ONE_NANO.ilog10() as usize;
+        let whole = self.0 / ONE_NANO;
+        let fraction = format!("{:0decimals$}", self.0 % ONE_NANO);
+        let fraction = match f.precision() {
+            Some(precision) => &fraction[..precision.min(decimals)],
+            None => fraction.trim_end_matches('0'),
+        };
+        let amount = match fraction.is_empty() {
+            true => whole.to_string(),
+            false => format!("{whole}.{fraction}"),
+        };
+        f.pad_integral(true, "", &amount)
+    }
+}
+/// Parses an amount in Nano, such as `"1.234"`, or an amount followed by its unit (see `Amount::from_str_with_unit()`)
+impl FromStr for Amount {
+    type Err = NanoError;
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match s.contains(|c: char| c.is_ascii_alphabetic()) {
+            true => Amount::from_str_with_unit(s),
+            false => Ok(Amount(parse_decimal(s.trim(), ONE_NANO)?)),
+        }
+    }
+}
+impl From<u128> for Amount {
+    fn from(value: u128) -> Self {
+        Amount(value)
+    }
+}
+impl From<Amount> for u128 {
+    fn from(value: Amount) -> Self {
+        value.0
+    }
+}
+
+/// Serialized as a string of raw (such as `"1000"`) in human-readable formats, and as a `u128` otherwise.
+///
+/// When deserializing from a human-readable format, strings with a unit (such as `"1.5 nano"`) and integers are also accepted.
+#[cfg(feature = "serde")]
+impl Serialize for Amount {
+    fn serialize<S: Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+        if serializer.is_human_readable() {
+            serializer.serialize_str(&self.0.to_string())
+        } else {
+            serializer.serialize_u128(self.0)
+        }
+    }
+}
+
+#[cfg(feature = "serde")]
+struct AmountVisitor;
+#[cfg(feature = "serde")]
+impl<'de> Visitor<'de> for AmountVisitor {
+    type Value = Amount;
+
+    fn expecting(&self, formatter: &mut std::fmt::Formatter) -> std::fmt::Result {
+        formatter.write_str("an amount of raw, or an amount with a unit such as \"1.5 nano\"")
+    }
+
+    fn visit_str<E: serde::de::Error>(self, v: &str) -> Result<Amount, E> {
+        let amount = match v.contains(|c: char| c.is_ascii_alphabetic()) {
+            true => Amount::from_str_with_unit(v),
+            false => Amount::from_raw_str(v.trim()),
+        };
+        amount.map_err(E::custom)
+    }
+
+    fn visit_u64<E: serde::de::Error>(self, v: u64) -> Result<Amount, E> {
+        Ok(Amount(v.into()))
+    }
+
+    fn visit_u128<E: serde::de::Error>(self, v: u128) -> Result<Amount, E> {
+        Ok(Amount(v))
+    }
+}
+
+#[cfg(feature = "serde")]
+impl<'de> Deserialize<'de> for Amount {
+    fn deserialize<D: Deserializer<'de>>(deserializer: D) -> Result<Self, D::Error> {
+        if deserializer.is_human_readable() {
+            deserializer.deserialize_any(AmountVisitor)
+        } else {
+            deserializer.deserialize_u128(AmountVisitor)
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::Amount;
+    use crate::constants::{ONE_MILLI_NANO, ONE_NANO};
+
+    #[test]
+    fn from_raw_str() {
+        assert!(Amount::from_raw_str("1000").unwrap().raw() == 1000);
+        assert!(Amount::from_raw_str(&u128::MAX.to_string()).unwrap().raw() == u128::MAX);
+        assert!(Amount::from_raw_str("340282366920938463463374607431768211456").is_err());
+        assert!(Amount::from_raw_str("1.5").is_err());
+        assert!(Amount::from_raw_str("-1").is_err());
+        assert!(Amount::from_raw_str("").is_err());
+    }
+
+    #[test]
+    fn from_str() {
+        assert!("1.234".parse::<Amount>().unwrap().raw() == 1234 * ONE_MILLI_NANO);
+        assert!(" 2 ".parse::<Amount>().unwrap().raw() == 2 * ONE_NANO);
+        assert!("1000 raw".parse::<Amount>().unwrap().raw() == 1000);
+        assert!("1.2.3".parse::<Amount>().is_err());
+        assert!("".parse::<Amount>().is_err());
+    }
+
+    #[test]
+    fn display() {
+        let amount = Amount::from_raw(ONE_NANO + 1234 * ONE_MILLI_NANO / 1000);
+        assert!(amount.to_string() == "1.001234");
+        assert!(format!("{amount:.3}") == "1.001");
+        assert!(format!("{amount:.0}") == "1");
+        assert!(format!("{amount:.8}") == "1.00123400");
+        assert!(format!("{amount:>10.2}") == "      1.00");
+        assert!(Amount::ZERO.to_string() == "0");
+        assert!(Amount::from_raw(1).to_string() == "0.000000000000000000000000000001");
+        assert!(
+            Amount::from_raw(u128::MAX)
+                .to_string()
+                .parse::<Amount>()
+                .unwrap()
+                .raw()
+                == u128::MAX
+        );
+    }
+
+    #[test]
+    fn arithmetic() {
+        let one = Amount::from_raw(ONE_NANO);
+        assert!(one.checked_add(one).unwrap().raw() == 2 * ONE_NANO);
+        assert!(Amount::from_raw(u128::MAX)
+            .checked_add(Amount::from_raw(1))
+            .is_none());
+        assert!(one.checked_sub(Amount::from_raw(ONE_NANO + 1)).is_none());
+        assert!(one.checked_mul(3).unwrap().raw() == 3 * ONE_NANO);
+        assert!(one.checked_mul(u128::MAX).is_none());
+        assert!(one.checked_div(0).is_none());
+        assert!(one.checked_div(2).unwrap().raw() == ONE_NANO / 2);
+        assert!(one.saturating_sub(one.checked_mul(2).unwrap()) == Amount::ZERO);
+        assert!(Amount::from_raw(u128::MAX).saturating_add(one).raw() == u128::MAX);
+    }
+
+    #[test]
+    fn from_str_with_unit() {
+        let one_and_a_half = ONE_NANO + ONE_NANO / 2;
+        assert!(Amount::from_str_with_unit("1.5 nano").unwrap().raw() == one_and_a_half);
+        assert!(Amount::from_str_with_unit("1.5nano").unwrap().raw() == one_and_a_half);
+        assert!(Amount::from_str_with_unit("1.5 XNO").unwrap().raw() == one_and_a_half);
+        assert!(Amount::from_str_with_unit(".5 nano").unwrap().raw() == ONE_NANO / 2);
+        assert!(Amount::from_str_with_unit("2. millinano").unwrap().raw() == 2 * ONE_MILLI_NANO);
+        assert!(Amount::from_str_with_unit("1000 raw").unwrap().raw() == 1000);
+        assert!(
+            Amount::from_str_with_unit("0.000000000000000000000000000001 nano")
+                .unwrap()
+                .raw()
+                == 1
+        );
+
+        assert!(Amount::from_str_with_unit("0.0000000000000000000000000000001 nano").is_err());
+        assert!(Amount::from_str_with_unit("1.5 raw").is_err());
+        assert!(Amount::from_str_with_unit("1.5").is_err());
+        assert!(Amount::from_str_with_unit("1.5 btc").is_err());
+        assert!(Amount::from_str_with_unit(". nano").is_err());
+        assert!(Amount::from_str_with_unit("1,5 nano").is_err());
+        assert!(Amount::from_str_with_unit("340282366920 nano").is_err());
+    }
+}
+
+#[cfg(test)]
+#[cfg(feature = "serde")]
+mod serde_tests {
+    use super::Amount;
+    use crate::{constants::ONE_NANO, serde_test};
+
+    serde_test!(amount: Amount::from_raw(ONE_NANO + 1) => 16);
+
+    #[test]
+    fn json() {
+        let amount = Amount::from_raw(u128::MAX);
+        let json = serde_json::to_string(&amount).unwrap();
+        assert!(json == format!("\"{}\"", u128::MAX));
+        assert!(serde_json::from_str::<Amount>(&json).unwrap() == amount);
+
+        let parse = |json: &str| serde_json::from_str::<Amount>(json);
+        assert!(parse("\"1.5 nano\"").unwrap().raw() == ONE_NANO + ONE_NANO / 2);
+        assert!(parse("\"1000 raw\"").unwrap().raw() == 1000);
+        assert!(parse("1000").unwrap().raw() == 1000);
+        assert!(parse("\"1.5\"").is_err());
+        assert!(parse("-1").is_err());
+        assert!(parse("1.5").is_err());
+    }
+}
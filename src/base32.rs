@@ -2,7 +2,7 @@
 
 use bitvec::prelude::*;
 
-const ALPHABET: &str = "13456789abcdefghijkmnopqrstuwxyz";
+pub(crate) const ALPHABET: &str = "13456789abcdefghijkmnopqrstuwxyz";
 const ALPHABET_ARRAY: [char; 32] = [
     '1', '3', '4', '5', '6', '7', '8', '9', 'a', 'b', 'c', 'd', 'e', 'f', 'g', 'h', 'i', 'j', 'k',
     'm', 'n', 'o', 'p', 'q', 'r', 's', 't', 'u', 'w', 'x', 'y', 'z',
@@ -33,6 +33,25 @@ pub fn decode(s: &str) -> Option<Vec<u8>> {
     Some(bits.into_vec())
 }
 
+/// Same as `decode`, but only accepts the canonical encoding of the data.
+///
+/// Rejects strings whose length cannot correspond to a whole number of bytes,
+/// and strings whose trailing padding bits are not zero.
+pub fn decode_strict(s: &str) -> Option<Vec<u8>> {
+    let n_bits = s.chars().count() * 5;
+    let n_bytes = n_bits / 8;
+    // a whole character of padding means this is not the shortest encoding
+    if n_bits - (n_bytes * 8) >= 5 {
+        return None;
+    }
+
+    let mut bytes = decode(s)?;
+    if bytes.len() > n_bytes && bytes.pop()? != 0 {
+        return None;
+    }
+    Some(bytes)
+}
+
 #[cfg(test)]
 mod tests {
     const TEST_BYTES: [u8; 10] = [127, 255, 32, 8, 16, 50, 254, 0, 42, 96];
@@ -46,4 +65,16 @@ mod tests {
     fn decode() {
         assert!(super::decode(TEST_STR).unwrap() == TEST_BYTES.to_vec())
     }
+    #[test]
+    fn decode_strict() {
+        assert!(super::decode_strict(TEST_STR).unwrap() == TEST_BYTES.to_vec());
+        // 1 byte: 2 characters, with 2 bits of padding
+        assert!(super::decode_strict("z1").unwrap() == vec![0b11111000]);
+        assert!(super::decode_strict("z3").is_none());
+        assert!(super::decode("z3").unwrap() == vec![0b11111000, 0b01000000]);
+        // 3 characters cannot encode a whole number of bytes
+        assert!(super::decode_strict("z11").is_none());
+        assert!(super::decode_strict("").unwrap().is_empty());
+        assert!(super::decode_strict("0").is_none());
+    }
 }
@@ -1,13 +1,20 @@
-use super::constants::{epoch_signers::*, get_genesis_account};
+use super::constants::Network;
 use super::nanopy::{hash_block, sign_message};
 use super::{Account, Key, NanoError, Signature};
+use std::error::Error;
 use std::fmt::Display;
 use zeroize::{Zeroize, ZeroizeOnDrop};
 
 #[cfg(feature = "serde")]
 use serde::{Deserialize, Serialize};
 
-pub use super::nanopy::{check_work, get_local_work};
+pub use super::nanopy::{check_work, get_local_work, work_difficulty};
+
+#[cfg(feature = "rpc-core")]
+use crate::rpc::{
+    util::{block_from_json, block_to_json},
+    BlockInfo, RpcError,
+};
 
 /// The type of a Nano block
 ///
@@ -25,7 +32,7 @@ pub enum BlockType {
     Receive,
     /// A `state` block, with `subtype` set to `epoch`
     Epoch,
-    /// A `legacy` block of the specified variant
+    /// A `legacy` block of the specified variant (`send`, `receive`, `open`, or `change`)
     Legacy(String),
 }
 impl BlockType {
@@ -55,12 +62,25 @@ impl BlockType {
         matches!(self, BlockType::Legacy(_))
     }
 
+    /// Get the `state` subtype which behaves like this type.
+    ///
+    /// Legacy `send`, `receive`, and `change` blocks behave like their `state` equivalents, and legacy `open` blocks like `receive` blocks.
+    /// Returns `None` for unknown legacy types.
+    pub fn state_equivalent(&self) -> Option<BlockType> {
+        match self {
+            BlockType::Legacy(block_type) => BlockType::from_subtype_string(block_type)
+                .filter(|block_type| block_type != &BlockType::Epoch),
+            block_type => Some(block_type.clone()),
+        }
+    }
+
     /// Create a `state` `BlockType` from a `subtype`
     pub fn from_subtype_string(value: &str) -> Option<BlockType> {
         match value {
             "change" => Some(BlockType::Change),
             "send" => Some(BlockType::Send),
-            "receive" => Some(BlockType::Receive),
+            // `open` blocks are treated as `receive` blocks with no `previous`
+            "receive" | "open" => Some(BlockType::Receive),
             "epoch" => Some(BlockType::Epoch),
             _ => None,
         }
@@ -80,18 +100,36 @@ impl Display for BlockType {
     }
 }
 
+/// Whether a block added to, or took from, the account's balance
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum Direction {
+    Incoming,
+    Outgoing,
+    /// The balance did not change (`change` and `epoch` blocks)
+    Unchanged,
+}
+
 /// A Nano block. See the official [Nano documentation](https://docs.nano.org/protocol-design/blocks/) for details.
+///
+/// Legacy blocks are stored in the same fields, and are hashed using only the fields of their type:
+/// the `destination` of a `send` block, and the `source` of a `receive` or `open` block, are stored in `link`.
+/// Legacy blocks other than `open` blocks do not contain their `account`,
+/// and legacy blocks other than `send` blocks do not contain their `balance`, so these must be filled in separately.
 #[derive(Debug, Clone, Zeroize, ZeroizeOnDrop, PartialEq, Eq)]
 #[cfg_attr(feature = "serde", derive(Serialize, Deserialize))]
 pub struct Block {
     #[cfg_attr(feature = "serde", serde(rename = "type"))]
     pub block_type: BlockType,
     pub account: Account,
+    #[cfg_attr(feature = "serde", serde(with = "crate::serde_util::hex_bytes"))]
     pub previous: [u8; 32],
     pub representative: Account,
+    #[cfg_attr(feature = "serde", serde(with = "crate::serde_util::raw"))]
     pub balance: u128,
+    #[cfg_attr(feature = "serde", serde(with = "crate::serde_util::hex_bytes"))]
     pub link: [u8; 32],
     pub signature: Signature,
+    #[cfg_attr(feature = "serde", serde(with = "crate::serde_util::hex_bytes"))]
     pub work: [u8; 8],
 }
 impl Block {
@@ -116,9 +154,25 @@ impl Block {
         }
     }
 
-    /// Interpret the `link` field as an account
-    pub fn link_as_account(&self) -> Result<Account, NanoError> {
-        Account::try_from(self.link)
+    /// Interpret the `link` field as an account: the recipient of a `send` block.
+    ///
+    /// Returns `None` for other blocks, whose `link` is not an account
+    /// (the hash of the `send` block received by a `receive` block, the epoch marker of an `epoch` block, or unused).
+    pub fn link_as_account(&self) -> Result<Option<Account>, NanoError> {
+        match self.block_type.state_equivalent() {
+            Some(BlockType::Send) => Ok(Some(Account::try_from(self.link)?)),
+            _ => Ok(None),
+        }
+    }
+
+    /// Get the amount sent or received by this block, and in which direction, by comparing its balance to `previous`,
+    /// which should be the block before it
+    pub fn amount(&self, previous: &Block) -> (u128, Direction) {
+        match self.balance.checked_sub(previous.balance) {
+            Some(0) => (0, Direction::Unchanged),
+            Some(amount) => (amount, Direction::Incoming),
+            None => (previous.balance - self.balance, Direction::Outgoing),
+        }
     }
 
     /// Sign this block with the given `Key`, returning a `Signature`
@@ -136,20 +190,25 @@ impl Block {
         self.set_signature(self.get_signature(private_key))
     }
 
-    /// Check if the signature for this block is valid
+    /// Check if the signature for this block is valid on the Nano main network
     pub fn has_valid_signature(&self) -> bool {
+        self.has_valid_signature_on(&Network::MAINNET)
+    }
+
+    /// Check if the signature for this block is valid on the given network
+    pub fn has_valid_signature_on(&self, network: &Network) -> bool {
         if self.block_type != BlockType::Epoch {
             // "normal" block
             self.account.clone()
         } else if self.link[7] == 49 {
             // epoch v1
-            get_v1_epoch_signer()
+            network.epoch_v1_signer()
         } else if self.link[7] == 50 {
             // epoch v2
-            get_v2_epoch_signer()
+            network.epoch_v2_signer()
         } else {
             // "uhhh let's try genesis I guess"
-            get_genesis_account()
+            network.genesis_account()
         }
         .is_valid_signature(&self.hash(), &self.signature)
     }
@@ -176,11 +235,382 @@ impl Block {
         }
         check_work(self.work_hash(), difficulty, self.work)
     }
+
+    /// Get the given network's minimum work difficulty for this block's type:
+    /// the `receive` threshold for `receive` blocks, the legacy (epoch v1) threshold for legacy blocks, and the `send` threshold otherwise
+    pub fn work_threshold_on(&self, network: &Network) -> [u8; 8] {
+        network.work_difficulty(&self.block_type)
+    }
+
+    /// Get the minimum work difficulty for this block's type
+    /// (`work::RECEIVE_DIFFICULTY` for `receive` blocks, `work::LEGACY_DIFFICULTY` for legacy blocks, and `work::SEND_DIFFICULTY` otherwise)
+    pub fn work_threshold(&self) -> [u8; 8] {
+        self.work_threshold_on(&Network::MAINNET)
+    }
+
+    /// Check if the work for this block meets the given network's minimum difficulty for its block type.
+    /// Use `has_valid_work()` to check against a custom difficulty.
+    pub fn has_valid_work_on(&self, network: &Network) -> bool {
+        self.has_valid_work(self.work_threshold_on(network))
+    }
+}
+
+#[cfg(feature = "rpc-core")]
+impl Block {
+    /// Serialize this block exactly as the node does when `json_block` is set
+    /// (`"type": "state"`, uppercase hex, and the balance as a string of raw).
+    /// Legacy blocks only include the fields of their type.
+    ///
+    /// Like the node, the `subtype` field is not included.
+    pub fn to_json_string(&self) -> String {
+        // in the same order as the node
+        let fields: &[&str] = match &self.block_type {
+            BlockType::Legacy(block_type) => match block_type.as_str() {
+                "send" => &[
+                    "type",
+                    "previous",
+                    "destination",
+                    "balance",
+                    "work",
+                    "signature",
+                ],
+                "receive" => &["type", "previous", "source", "work", "signature"],
+                "open" => &[
+                    "type",
+                    "source",
+                    "representative",
+                    "account",
+                    "work",
+                    "signature",
+                ],
+                "change" => &["type", "previous", "representative", "work", "signature"],
+                _ => &["type", "work", "signature"],
+            },
+            _ => &[
+                "type",
+                "account",
+                "previous",
+                "representative",
+                "balance",
+                "link",
+                "signature",
+                "work",
+            ],
+        };
+        let json = block_to_json(self);
+        let fields: Vec<String> = fields
+            .iter()
+            .map(|field| format!("\"{field}\":{}", json[*field]))
+            .collect();
+        format!("{{{}}}", fields.join(","))
+    }
+
+    /// Parse a block in the node's JSON format.
+    ///
+    /// The node's JSON format does not include the subtype of `state` blocks, so it must be given as `block_type`.
+    pub fn from_json_str(json: &str, block_type: BlockType) -> Result<Block, RpcError> {
+        block_from_json(&serde_json::from_str(json)?, block_type)
+    }
+}
+
+/// Builds unsigned `state` blocks on top of an account's frontier, handling the balance math and subtype.
+///
+/// The resulting blocks must still be signed, and have work attached.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct BlockBuilder {
+    pub account: Account,
+    /// Hash of the account's frontier, or all zeros if the account has not been opened
+    pub previous: [u8; 32],
+    /// The account's current representative
+    pub representative: Account,
+    /// The account's current balance
+    pub balance: u128,
+}
+impl BlockBuilder {
+    pub fn new(
+        account: Account,
+        previous: [u8; 32],
+        representative: Account,
+        balance: u128,
+    ) -> BlockBuilder {
+        BlockBuilder {
+            account,
+            previous,
+            representative,
+            balance,
+        }
+    }
+
+    /// Build blocks for an account which has not been opened yet
+    pub fn unopened(account: Account, representative: Account) -> BlockBuilder {
+        BlockBuilder::new(account, [0; 32], representative, 0)
+    }
+
+    /// Whether the account has been opened
+    pub fn is_opened(&self) -> bool {
+        self.previous != [0; 32]
+    }
+
+    fn block(&self, block_type: BlockType, balance: u128, link: [u8; 32]) -> Block {
+        Block {
+            block_type,
+            account: self.account.clone(),
+            previous: self.previous,
+            representative: self.representative.clone(),
+            balance,
+            link,
+            signature: Signature::default(),
+            work: [0; 8],
+        }
+    }
+
+    /// Send `amount` to `recipient`
+    pub fn send(&self, recipient: &Account, amount: u128) -> Result<Block, NanoError> {
+        let balance = self
+            .balance
+            .checked_sub(amount)
+            .ok_or(NanoError::InsufficientBalance)?;
+        Ok(self.block(BlockType::Send, balance, recipient.compressed.to_bytes()))
+    }
+
+    /// Receive the `send` block with hash `send`, which sent `amount`.
+    /// If the account has not been opened, this is its `open` block.
+    pub fn receive(&self, send: [u8; 32], amount: u128) -> Result<Block, NanoError> {
+        let balance = self
+            .balance
+            .checked_add(amount)
+            .ok_or(NanoError::InvalidAmount)?;
+        Ok(self.block(BlockType::Receive, balance, send))
+    }
+
+    /// Change the account's representative
+    pub fn change(&self, representative: &Account) -> Block {
+        let mut block = self.block(BlockType::Change, self.balance, [0; 32]);
+        block.representative = representative.clone();
+        block
+    }
+
+    /// Use `representative` for subsequent blocks
+    pub fn with_representative(mut self, representative: Account) -> BlockBuilder {
+        self.representative = representative;
+        self
+    }
+
+    /// Move this builder on top of `block`, which must have been built on top of the current frontier
+    pub fn advance(&mut self, block: &Block) {
+        self.previous = block.hash();
+        self.representative = block.representative.clone();
+        self.balance = block.balance;
+    }
+}
+impl From<&Block> for BlockBuilder {
+    /// Build blocks on top of `block`, which should be the account's frontier
+    fn from(value: &Block) -> Self {
+        BlockBuilder::new(
+            value.account.clone(),
+            value.hash(),
+            value.representative.clone(),
+            value.balance,
+        )
+    }
+}
+
+/// Number of balance buckets used by the node to prioritize elections
+pub const BUCKET_COUNT: usize = 63;
+
+/// The lowest balance of each bucket.
+///
+/// See [here](https://github.com/nanocurrency/nano-node/blob/V27.0/nano/node/bucketing.cpp) for details.
+const BUCKET_MINIMUMS: [u128; BUCKET_COUNT] = {
+    // (start, end, number of buckets) for each region, as powers of 2
+    const REGIONS: [(u32, u32, u128); 9] = [
+        (79, 88, 1),
+        (88, 92, 2),
+        (92, 96, 4),
+        (96, 100, 8),
+        (100, 104, 16),
+        (104, 108, 16),
+        (108, 112, 8),
+        (112, 116, 4),
+        (116, 120, 2),
+    ];
+    let mut minimums = [0; BUCKET_COUNT];
+    let mut i = 1;
+    let mut region = 0;
+    while region < REGIONS.len() {
+        let (start, end, count) = REGIONS[region];
+        let width = ((1 << end) - (1 << start)) / count;
+        let mut j = 0;
+        while j < count {
+            minimums[i] = (1 << start) + j * width;
+            i += 1;
+            j += 1;
+        }
+        region += 1;
+    }
+    minimums[i] = 1 << 120;
+    minimums
+};
+
+/// Get the index of the bucket which the node places blocks with this priority balance into
+pub fn bucket_index(balance: u128) -> usize {
+    BUCKET_MINIMUMS.partition_point(|minimum| *minimum <= balance) - 1
+}
+
+/// The priority which the node gives to a block's election when the network is saturated.
+///
+/// Each bucket is given an equal share of the network's capacity, so blocks in less crowded buckets are confirmed sooner.
+/// Within a bucket, blocks from accounts which have been inactive for longer (lower `timestamp`) are prioritized.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct ElectionPriority {
+    /// Index of the bucket which the block is placed into
+    pub bucket: usize,
+    /// The balance used to choose the bucket
+    pub balance: u128,
+    /// Timestamp of the previous block.
+    /// If `None`, the block opens its account, and the time at which the node first sees the block is used instead.
+    pub timestamp: Option<u64>,
+}
+
+/// Compute the priority which the node will give to `block`, given the previous block and its timestamp (if any).
+///
+/// A `send` is prioritized using the larger of its balance and the previous balance,
+/// so that sending away an account's whole balance does not lower its priority.
+pub fn election_priority(block: &Block, previous: Option<(&Block, u64)>) -> ElectionPriority {
+    let previous_balance = match (&block.block_type, previous) {
+        (BlockType::Send, Some((previous, _))) => previous.balance,
+        _ => 0,
+    };
+    let balance = block.balance.max(previous_balance);
+    ElectionPriority {
+        bucket: bucket_index(balance),
+        balance,
+        timestamp: previous.map(|(_, timestamp)| timestamp),
+    }
+}
+
+/// Why a sequence of blocks is not a valid account chain, returned by `validate_chain()`.
+///
+/// Each variant contains the index of the offending block.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum ChainError {
+    /// The block's `previous` is not the hash of the block after it
+    BrokenLink(usize),
+    /// The block belongs to a different account than the first block
+    WrongAccount(usize),
+    /// The block's signature is not valid
+    InvalidSignature(usize),
+    /// The block's balance or representative changed in a way that its type does not allow
+    InvalidBalance(usize),
+    /// The block is a legacy block of an unknown type
+    UnknownLegacyBlock(usize),
+    /// The block's height is not one more than the height of the block after it
+    /// (or is not `1`, if it opened the account)
+    WrongHeight(usize),
+}
+impl Display for ChainError {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match &self {
+            ChainError::BrokenLink(i) => write!(f, "block {i} does not follow the next block"),
+            ChainError::WrongAccount(i) => write!(f, "block {i} belongs to a different account"),
+            ChainError::InvalidSignature(i) => write!(f, "block {i} has an invalid signature"),
+            ChainError::InvalidBalance(i) => {
+                write!(
+                    f,
+                    "block {i} changes its balance in a way its type does not allow"
+                )
+            }
+            ChainError::UnknownLegacyBlock(i) => {
+                write!(f, "block {i} is a legacy block of an unknown type")
+            }
+            ChainError::WrongHeight(i) => write!(f, "block {i} has the wrong height"),
+        }
+    }
+}
+impl Error for ChainError {}
+
+/// Check that `blocks`, newest first (as returned by `Rpc::account_history()`), form a valid chain on the Nano main network.
+///
+/// Each block must be signed by its account (or by the epoch signer, for `epoch` blocks), and follow the next block by hash.
+/// `send` blocks must lower the balance, `receive` blocks must raise it, and other blocks must leave it unchanged.
+/// Legacy blocks are checked in the same way as their `state` equivalents (see `BlockType::state_equivalent()`).
+/// The oldest block is only checked against the previous block if it opened the account.
+///
+/// Blocks do not contain their height, so heights are not checked: use `validate_chain_info()` to check them as well.
+pub fn validate_chain(blocks: &[Block]) -> Result<(), ChainError> {
+    validate_chain_on(blocks, &Network::MAINNET)
+}
+
+/// Same as `validate_chain()`, but checks signatures on the given network
+pub fn validate_chain_on(blocks: &[Block], network: &Network) -> Result<(), ChainError> {
+    for (i, block) in blocks.iter().enumerate() {
+        let block_type = block
+            .block_type
+            .state_equivalent()
+            .ok_or(ChainError::UnknownLegacyBlock(i))?;
+        if block.account != blocks[0].account {
+            return Err(ChainError::WrongAccount(i));
+        }
+        if !block.has_valid_signature_on(network) {
+            return Err(ChainError::InvalidSignature(i));
+        }
+
+        let valid_balance = match blocks.get(i + 1) {
+            Some(previous) => {
+                if block.previous != previous.hash() {
+                    return Err(ChainError::BrokenLink(i));
+                }
+                match block_type {
+                    BlockType::Send => block.balance < previous.balance,
+                    BlockType::Receive => block.balance > previous.balance,
+                    BlockType::Epoch => block.follows_epoch_rules(previous),
+                    _ => block.balance == previous.balance,
+                }
+            }
+            // the account was opened by receiving, or by an epoch block (with no balance)
+            None if block.previous == [0; 32] => match block_type {
+                BlockType::Receive => block.balance > 0,
+                BlockType::Epoch => block.balance == 0,
+                _ => false,
+            },
+            None => true,
+        };
+        if !valid_balance {
+            return Err(ChainError::InvalidBalance(i));
+        }
+    }
+    Ok(())
+}
+
+/// Same as `validate_chain()`, but also checks that the heights of the blocks are consecutive
+/// (and that the height of the block which opened the account, if included, is `1`)
+#[cfg(feature = "rpc-core")]
+pub fn validate_chain_info(infos: &[BlockInfo]) -> Result<(), ChainError> {
+    validate_chain_info_on(infos, &Network::MAINNET)
+}
+
+/// Same as `validate_chain_info()`, but checks signatures on the given network
+#[cfg(feature = "rpc-core")]
+pub fn validate_chain_info_on(infos: &[BlockInfo], network: &Network) -> Result<(), ChainError> {
+    let blocks: Vec<Block> = infos.iter().map(|info| info.block.clone()).collect();
+    validate_chain_on(&blocks, network)?;
+    for (i, info) in infos.iter().enumerate() {
+        let valid_height = match infos.get(i + 1) {
+            Some(previous) => Some(info.height) == previous.height.checked_add(1),
+            None if info.block.previous == [0; 32] => info.height == 1,
+            None => info.height > 1,
+        };
+        if !valid_height {
+            return Err(ChainError::WrongHeight(i));
+        }
+    }
+    Ok(())
 }
 
 #[cfg(test)]
 mod tests {
     use super::*;
+    use crate::work::{LEGACY_DIFFICULTY, RECEIVE_DIFFICULTY, SEND_DIFFICULTY};
     use crate::{constants::ONE_NANO, Key, SecretBytes};
 
     const TEST_WORK_DIFFICULTY: [u8; 8] = 0xfff8000000000000_u64.to_be_bytes();
@@ -206,6 +636,222 @@ mod tests {
         }
     }
 
+    #[test]
+    #[cfg(feature = "rpc-core")]
+    fn json() {
+        let mut block = create_test_block();
+        block.sign(&Key::from_seed(&SecretBytes::from([0; 32]), 0));
+        let json = block.to_json_string();
+        assert!(json.starts_with("{\"type\":\"state\",\"account\":\"nano_"));
+        assert!(json.ends_with(",\"work\":\"0000000000000000\"}"));
+        assert!(json.contains(&format!("\"balance\":\"{ONE_NANO}\"")));
+        assert!(json.contains(&format!("\"previous\":\"{}\"", "7F".repeat(32))));
+        assert!(Block::from_json_str(&json, BlockType::Send).unwrap() == block);
+
+        assert!(Block::from_json_str(&json[1..], BlockType::Send).is_err());
+        let json = json.replace("\"balance\"", "\"amount\"");
+        assert!(Block::from_json_str(&json, BlockType::Send).is_err());
+    }
+
+    #[test]
+    #[cfg(feature = "rpc-core")]
+    fn legacy() {
+        // the genesis block
+        let json = r#"{"type":"open","source":"E89208DD038FBB269987689621D52292AE9C35941A7484756ECCED92A65093BA","representative":"nano_3t6k35gi95xu6tergt6p69ck76ogmitsa8mnijtpxm9fkcm736xtoncuohr3","account":"nano_3t6k35gi95xu6tergt6p69ck76ogmitsa8mnijtpxm9fkcm736xtoncuohr3","work":"62f05417dd3fb691","signature":"9F0C933C8ADE004D808EA1985FA746A7E95BA2A38F867640F53EC8F180BDFE9E2C1268DEAD7C2664F356E37ABA362BC58E46DBA03E523A7B5A19E4B6EB12BB02"}"#;
+        let open = Block::from_json_str(json, BlockType::Legacy("open".into())).unwrap();
+        assert!(
+            hex::encode_upper(open.hash())
+                == "991CF190094C00F0B68E2E5F75F6BEE95A2E0BD93CEAA4A6734DB9F19B728948"
+        );
+        assert!(open.has_valid_signature());
+        assert!(open.to_json_string() == json);
+        assert!(open.block_type.state_equivalent() == Some(BlockType::Receive));
+        // the genesis block's work only meets the epoch v1 threshold
+        assert!(open.has_valid_work_on(&Network::MAINNET));
+        assert!(!open.has_valid_work(SEND_DIFFICULTY));
+
+        // the other legacy types
+        let key = Key::from_seed(&SecretBytes::from([0; 32]), 0);
+        let mut send = create_test_block();
+        send.block_type = BlockType::Legacy("send".into());
+        send.sign(&key);
+        assert!(send.hash() != create_test_block().hash());
+        let json = send.to_json_string();
+        assert!(json.starts_with("{\"type\":\"send\",\"previous\":"));
+        assert!(json.contains(&format!("\"balance\":\"{:032X}\"", ONE_NANO)));
+        let parsed = Block::from_json_str(&json, send.block_type.clone()).unwrap();
+        assert!(parsed.hash() == send.hash());
+        assert!(parsed.link_as_account().unwrap().is_some());
+
+        for block_type in ["receive", "change"] {
+            let mut block = create_test_block();
+            block.block_type = BlockType::Legacy(block_type.into());
+            block.sign(&key);
+            let json = block.to_json_string();
+            let parsed = Block::from_json_str(&json, block.block_type.clone()).unwrap();
+            assert!(parsed.hash() == block.hash());
+        }
+        assert!(Block::from_json_str(&json, BlockType::Legacy("state".into())).is_err());
+    }
+
+    #[test]
+    fn buckets() {
+        assert!(bucket_index(0) == 0);
+        assert!(bucket_index((1 << 79) - 1) == 0);
+        assert!(bucket_index(1 << 79) == 1);
+        assert!(bucket_index(1 << 88) == 2);
+        let midpoint = (1 << 88) + ((1 << 92) - (1 << 88)) / 2;
+        assert!(bucket_index(midpoint - 1) == 2);
+        assert!(bucket_index(midpoint) == 3);
+        assert!(bucket_index(ONE_NANO) == 14);
+        assert!(bucket_index((1 << 120) - 1) == BUCKET_COUNT - 2);
+        assert!(bucket_index(1 << 120) == BUCKET_COUNT - 1);
+        assert!(bucket_index(u128::MAX) == BUCKET_COUNT - 1);
+        assert!(BUCKET_MINIMUMS.windows(2).all(|pair| pair[0] < pair[1]));
+    }
+
+    #[test]
+    fn priority() {
+        let mut previous = create_test_block();
+        previous.block_type = BlockType::Receive;
+        let mut block = create_test_block();
+        block.balance = 0;
+
+        let priority = election_priority(&block, Some((&previous, 1700000000)));
+        assert!(priority.balance == ONE_NANO);
+        assert!(priority.bucket == bucket_index(ONE_NANO));
+        assert!(priority.timestamp == Some(1700000000));
+
+        block.block_type = BlockType::Change;
+        assert!(election_priority(&block, Some((&previous, 1700000000))).bucket == 0);
+
+        previous.balance = 0;
+        block.block_type = BlockType::Receive;
+        block.balance = ONE_NANO;
+        let priority = election_priority(&block, None);
+        assert!(priority.balance == ONE_NANO);
+        assert!(priority.timestamp.is_none());
+    }
+
+    #[test]
+    fn builder() {
+        let seed = SecretBytes::from([0; 32]);
+        let account = Key::from_seed(&seed, 0).to_account();
+        let recipient = Key::from_seed(&seed, 1).to_account();
+        let representative = Key::from_seed(&seed, 2).to_account();
+
+        let mut builder = BlockBuilder::unopened(account.clone(), representative.clone());
+        assert!(!builder.is_opened());
+        let open = builder.receive([1; 32], ONE_NANO).unwrap();
+        assert!(open.block_type == BlockType::Receive);
+        assert!(open.previous == [0; 32]);
+        assert!(open.balance == ONE_NANO);
+        assert!(open.link == [1; 32]);
+        assert!(open.work_hash() == account.compressed.to_bytes());
+
+        builder.advance(&open);
+        assert!(builder == BlockBuilder::from(&open));
+        let send = builder.send(&recipient, ONE_NANO / 4).unwrap();
+        assert!(send.block_type == BlockType::Send);
+        assert!(send.previous == open.hash());
+        assert!(send.balance == ONE_NANO - ONE_NANO / 4);
+        assert!(send.link_as_account().unwrap() == Some(recipient.clone()));
+        assert!(builder.send(&recipient, ONE_NANO + 1) == Err(NanoError::InsufficientBalance));
+
+        builder.advance(&send);
+        let change = builder.change(&recipient);
+        assert!(change.block_type == BlockType::Change);
+        assert!(change.balance == send.balance);
+        assert!(change.representative == recipient);
+        assert!(change.link == [0; 32]);
+
+        assert!(send.amount(&open) == (ONE_NANO / 4, Direction::Outgoing));
+        assert!(change.amount(&send) == (0, Direction::Unchanged));
+        assert!(open.amount(&send) == (ONE_NANO / 4, Direction::Incoming));
+        assert!(open.link_as_account().unwrap().is_none());
+        assert!(change.link_as_account().unwrap().is_none());
+
+        builder.balance = u128::MAX;
+        assert!(builder.receive([2; 32], 1) == Err(NanoError::InvalidAmount));
+    }
+
+    /// An account's first three blocks, newest first
+    fn test_chain(key: &Key, other: &Key) -> Vec<Block> {
+        let mut builder = BlockBuilder::unopened(key.to_account(), other.to_account());
+        let mut blocks = vec![];
+        for i in 0..3 {
+            let mut block = match i {
+                0 => builder.receive([1; 32], ONE_NANO).unwrap(),
+                1 => builder.send(&other.to_account(), 1).unwrap(),
+                _ => builder.change(&key.to_account()),
+            };
+            block.sign(key);
+            builder.advance(&block);
+            blocks.insert(0, block);
+        }
+        blocks
+    }
+
+    #[test]
+    fn chain() {
+        let key = Key::from_seed(&SecretBytes::from([0; 32]), 0);
+        let other = Key::from_seed(&SecretBytes::from([0; 32]), 1);
+        let blocks = test_chain(&key, &other);
+        assert!(validate_chain(&blocks) == Ok(()));
+        assert!(validate_chain(&blocks[..2]) == Ok(()));
+        assert!(validate_chain(&[]) == Ok(()));
+
+        let mut invalid = blocks.clone();
+        invalid.swap(0, 1);
+        assert!(validate_chain(&invalid) == Err(ChainError::BrokenLink(0)));
+
+        let mut invalid = blocks.clone();
+        invalid[1].signature = Signature::default();
+        assert!(validate_chain(&invalid) == Err(ChainError::InvalidSignature(1)));
+
+        // a `send` which raises the balance
+        let mut invalid = blocks[1..].to_vec();
+        invalid[0].balance = ONE_NANO + 1;
+        invalid[0].sign(&key);
+        assert!(validate_chain(&invalid) == Err(ChainError::InvalidBalance(0)));
+
+        let mut invalid = blocks.clone();
+        invalid[0].account = other.to_account();
+        invalid[0].sign(&other);
+        assert!(validate_chain(&invalid) == Err(ChainError::WrongAccount(1)));
+    }
+
+    #[test]
+    #[cfg(feature = "rpc-core")]
+    fn chain_heights() {
+        let key = Key::from_seed(&SecretBytes::from([0; 32]), 0);
+        let other = Key::from_seed(&SecretBytes::from([0; 32]), 1);
+        let mut infos: Vec<BlockInfo> = test_chain(&key, &other)
+            .into_iter()
+            .zip([3, 2, 1])
+            .map(|(block, height)| BlockInfo {
+                height,
+                timestamp: 0,
+                confirmed: true,
+                amount: 0,
+                successor: None,
+                block,
+            })
+            .collect();
+        assert!(validate_chain_info(&infos) == Ok(()));
+        assert!(validate_chain_info(&infos[..2]) == Ok(()));
+
+        infos[0].height = 5;
+        assert!(validate_chain_info(&infos) == Err(ChainError::WrongHeight(0)));
+        infos[0].height = 3;
+        infos[2].height = 0;
+        assert!(validate_chain_info(&infos) == Err(ChainError::WrongHeight(1)));
+        // only the block which opened the account has a height of 1
+        assert!(validate_chain_info(&infos[..1]) == Ok(()));
+        infos[0].height = 1;
+        assert!(validate_chain_info(&infos[..1]) == Err(ChainError::WrongHeight(0)));
+    }
+
     #[test]
     fn create_work() {
         let mut block = create_test_block();
@@ -213,6 +859,26 @@ mod tests {
         assert!(!block.has_valid_work([255; 8]));
         block.local_work(TEST_WORK_DIFFICULTY);
         assert!(block.has_valid_work(TEST_WORK_DIFFICULTY));
+
+        let network = Network {
+            send_work_difficulty: TEST_WORK_DIFFICULTY,
+            ..Network::MAINNET
+        };
+        assert!(block.has_valid_work_on(&network));
+        block.block_type = BlockType::Receive;
+        assert!(block.work_threshold_on(&network) == RECEIVE_DIFFICULTY);
+        assert!(!block.has_valid_work_on(&network));
+        block.block_type = BlockType::Change;
+        assert!(block.work_threshold() == SEND_DIFFICULTY);
+        // legacy blocks predate epoch v2
+        for block_type in ["open", "send", "receive", "change"] {
+            block.block_type = BlockType::Legacy(block_type.into());
+            assert!(block.work_threshold() == LEGACY_DIFFICULTY);
+        }
+
+        // epoch blocks are exempt
+        block.block_type = BlockType::Epoch;
+        assert!(block.has_valid_work([255; 8]));
     }
 
     #[test]
@@ -257,6 +923,7 @@ mod tests {
             work: [55, 16, 153, 165, 103, 12, 179, 237],
         };
         assert!(block.has_valid_work(NORMAL_WORK_DIFFICULTY));
+        assert!(block.has_valid_work_on(&Network::MAINNET));
         assert!(block.has_valid_signature());
     }
 
@@ -367,7 +1034,8 @@ mod tests {
 #[cfg(feature = "serde")]
 mod serde_tests {
     use super::*;
-    use crate::{constants::ONE_NANO, serde_test};
+    use crate::constants::{epoch_signers::get_v2_epoch_signer, get_genesis_account, ONE_NANO};
+    use crate::serde_test;
 
     serde_test!(block_type: BlockType::Receive => 4);
     serde_test!(block: Block {
@@ -380,4 +1048,27 @@ mod serde_tests {
         signature: Signature::default(),
         work: [22; 8]
     } => 4 + 32 + 32 + 32 + 16 + 32 + 64 + 8);
+
+    #[test]
+    fn block_json() {
+        let mut block = Block {
+            block_type: BlockType::Send,
+            account: get_genesis_account(),
+            previous: [19; 32],
+            representative: get_v2_epoch_signer(),
+            balance: ONE_NANO,
+            link: [91; 32],
+            signature: Signature::default(),
+            work: [22; 8],
+        };
+        let key = Key::from_seed(&[0; 32].into(), 0);
+        block.sign(&key);
+        let json = serde_json::to_value(&block).unwrap();
+        assert!(json["account"] == get_genesis_account().to_string());
+        assert!(json["previous"] == hex::encode_upper([19; 32]));
+        assert!(json["balance"] == ONE_NANO.to_string());
+        assert!(json["signature"] == block.signature.to_string());
+        assert!(json["work"] == "1616161616161616");
+        assert!(serde_json::from_value::<Block>(json).unwrap() == block);
+    }
 }
@@ -7,8 +7,10 @@ use crate::{
         blake2b512, blake2b_checksum, blake2b_scalar, get_camo_spend_seed, get_camo_view_seed,
         hazmat::{get_account_scalar, get_account_seed},
     },
-    secret, try_compressed_from_slice, try_point_from_slice, version_bits, Account, Key, NanoError,
-    Scalar, SecretBytes,
+    secret,
+    secrets::secret_concat,
+    try_compressed_from_slice, try_point_from_slice, version_bits, Account, Key, NanoError, Scalar,
+    SecretBytes,
 };
 use curve25519_dalek::{
     constants::ED25519_BASEPOINT_POINT as G,
@@ -22,7 +24,7 @@ use zeroize::{Zeroize, ZeroizeOnDrop};
 #[cfg(feature = "serde")]
 use serde::{Deserialize, Serialize};
 
-const ADDRESS_LENGTH: usize = 117;
+pub(super) const ADDRESS_LENGTH: usize = 117;
 
 fn ecdh(key_1: &Scalar, key_2: &EdwardsPoint) -> SecretBytes<32> {
     secret!((key_1 * key_2).compress().to_bytes())
@@ -212,12 +214,12 @@ auto_from_impl!(TryFrom: SecretBytes<65> => CamoViewKeysType1);
 
 impl From<&CamoViewKeysType1> for SecretBytes<65> {
     fn from(value: &CamoViewKeysType1) -> Self {
-        let bytes: [u8; 65] = [
+        let bytes: [u8; 65] = secret_concat(&[
             [value.versions.encode_to_bits()].as_slice(),
             value.compressed_spend_key.as_bytes(),
             value.private_view.as_bytes(),
-        ]
-        .concat()
+        ])
+        .as_slice()
         .try_into()
         .unwrap();
         SecretBytes::from(bytes)
@@ -309,14 +311,11 @@ impl CamoAccountType1 {
         sender_key: &Key,
         sender_frontier: [u8; 32],
     ) -> (SecretBytes<32>, Notification) {
-        let r = blake2b_scalar(
-            &[
-                sender_key.as_scalar().as_slice(),
-                &sender_frontier,
-                self.compressed_spend_key.as_bytes(),
-            ]
-            .concat(),
-        );
+        let r = blake2b_scalar(&secret_concat(&[
+            sender_key.as_scalar().as_slice(),
+            &sender_frontier,
+            self.compressed_spend_key.as_bytes(),
+        ]));
         (ecdh(&r, &self.point_view_key), self.create_notification(&r))
     }
 
@@ -337,7 +336,7 @@ impl CamoAccountType1 {
 impl FromStr for CamoAccountType1 {
     type Err = NanoError;
     fn from_str(s: &str) -> Result<Self, Self::Err> {
-        let data = base32::decode(&s[CAMO_PREFIX_LEN..]).ok_or(NanoError::InvalidBase32)?;
+        let data = base32::decode_strict(&s[CAMO_PREFIX_LEN..]).ok_or(NanoError::InvalidBase32)?;
         Self::from_data(s, &data)
     }
 }
@@ -352,12 +351,16 @@ impl Hash for CamoAccountType1 {
     }
 }
 
+/// Serialized as a `camo_` address in human-readable formats
 #[cfg(feature = "serde")]
 impl Serialize for CamoAccountType1 {
     fn serialize<S>(&self, serializer: S) -> Result<S::Ok, S::Error>
     where
         S: serde::Serializer,
     {
+        if serializer.is_human_readable() {
+            return serializer.serialize_str(&self.to_string());
+        }
         CamoAccountType1Serde {
             versions: self.versions,
             point_spend_key: self.point_spend_key,
@@ -372,6 +375,10 @@ impl<'de> Deserialize<'de> for CamoAccountType1 {
     where
         D: serde::Deserializer<'de>,
     {
+        if deserializer.is_human_readable() {
+            let account = String::deserialize(deserializer)?;
+            return CamoAccountType1::from_str(&account).map_err(serde::de::Error::custom);
+        }
         let keys = CamoAccountType1Serde::deserialize(deserializer)?;
         Ok(points_to_account(
             keys.versions,
@@ -2,17 +2,29 @@ mod addressv1;
 mod notification;
 mod version;
 
+pub mod payment;
+pub mod scanner;
+
+#[cfg(feature = "test-vectors")]
+pub mod test_vectors;
+
 use crate::{
+    account::single_char_typos,
     auto_from_impl, base32,
-    constants::{ADDRESS_CHARS_SAMPLE_END, CAMO_ACCOUNT_PREFIX, CAMO_PREFIX_LEN},
+    constants::{
+        ADDRESS_CHARS_SAMPLE_END, CAMO_ACCOUNT_PREFIX, CAMO_PREFIX_LEN, CAMO_VIEW_KEYS_PREFIX,
+        VIEW_KEYS_FORMAT_VERSION, VIEW_KEYS_MAGIC,
+    },
+    nanopy::account_checksum,
+    secrets::secret_concat,
     version_bits, Account, Block, Key, NanoError, SecretBytes, Signature,
 };
-use addressv1::{CamoAccountType1, CamoKeysType1, CamoViewKeysType1};
+use addressv1::{CamoAccountType1, CamoKeysType1, CamoViewKeysType1, ADDRESS_LENGTH};
 use curve25519_dalek::edwards::EdwardsPoint;
 use std::fmt::Display;
 use std::hash::Hash;
 use std::str::FromStr;
-use zeroize::{Zeroize, ZeroizeOnDrop};
+use zeroize::{Zeroize, ZeroizeOnDrop, Zeroizing};
 
 #[cfg(feature = "serde")]
 use serde::{Deserialize, Serialize};
@@ -56,6 +68,17 @@ impl CamoKeys {
         }
     }
 
+    /// Generate new keys from a random seed (see `SecretBytes::random()`).
+    ///
+    /// Returns `NanoError::IncompatibleCamoVersions` if no supported version is given.
+    /// The seed is not returned, so the keys can only be backed up by storing the keys themselves:
+    /// to back up a seed instead, generate it with `SecretBytes::random()` and use `from_seed()`.
+    #[cfg(feature = "rand")]
+    pub fn generate(versions: CamoVersions) -> Result<CamoKeys, NanoError> {
+        let seed = SecretBytes::random()?;
+        CamoKeys::from_seed(&seed, 0, versions).ok_or(NanoError::IncompatibleCamoVersions)
+    }
+
     /// Get the camo protocol versions that this address supports
     pub fn camo_versions(&self) -> CamoVersions {
         unwrap_enum!(CamoKeys, self.camo_versions())
@@ -86,6 +109,7 @@ impl CamoKeys {
     }
 
     /// Calculate the shared secret between this key and the given account.
+    #[cfg_attr(feature = "tracing", tracing::instrument(level = "trace", skip_all))]
     pub fn receiver_ecdh(&self, notification: &Notification) -> SecretBytes<32> {
         unwrap_enum!(CamoKeys, self.receiver_ecdh(notification))
     }
@@ -140,6 +164,49 @@ impl CamoViewKeys {
         CamoViewKeys::try_from(value).ok()
     }
 
+    /// Export the view keys in a versioned container, so that they can be moved between devices:
+    /// 4 magic bytes (`"camv"`), the version of the format (currently `1`), `to_bytes()`, and a 5-byte checksum.
+    ///
+    /// `to_string()` encodes this as a `camoview_` string.
+    pub fn to_export_bytes(&self) -> SecretBytes<75> {
+        let data = secret_concat(&[
+            VIEW_KEYS_MAGIC.as_slice(),
+            &[VIEW_KEYS_FORMAT_VERSION],
+            self.to_bytes().as_slice(),
+        ]);
+        let bytes: [u8; 75] = secret_concat(&[&data, &account_checksum(&data)])
+            .as_slice()
+            .try_into()
+            .unwrap();
+        SecretBytes::from(bytes)
+    }
+
+    /// Import view keys exported with `to_export_bytes()`.
+    ///
+    /// Returns `NanoError::InvalidAddressPrefix` if the magic bytes are wrong,
+    /// and `NanoError::IncompatibleCamoVersions` if the format version or the keys' camo versions are not supported.
+    pub fn from_export_bytes(value: &SecretBytes<75>) -> Result<CamoViewKeys, NanoError> {
+        let bytes = value.as_slice();
+        if bytes[..4] != VIEW_KEYS_MAGIC {
+            return Err(NanoError::InvalidAddressPrefix);
+        }
+        if bytes[4] != VIEW_KEYS_FORMAT_VERSION {
+            return Err(NanoError::IncompatibleCamoVersions);
+        }
+        if bytes[70..] != account_checksum(&bytes[..70]) {
+            return Err(NanoError::InvalidAddressChecksum);
+        }
+
+        let keys: [u8; 65] = bytes[5..70].try_into().unwrap();
+        let keys = SecretBytes::from(keys);
+        match CamoVersions::decode_from_bits(keys.as_slice()[0]).highest_supported_version() {
+            Some(CamoVersion::One | CamoVersion::Two) => Ok(CamoViewKeys::V1(Box::new(
+                CamoViewKeysType1::try_from(&keys)?,
+            ))),
+            _ => Err(NanoError::IncompatibleCamoVersions),
+        }
+    }
+
     /// The public spend key of this camo address.
     ///
     /// Also the account for "notification" transactions to be sent to, if applicable.
@@ -154,6 +221,7 @@ impl CamoViewKeys {
     }
 
     /// Calculate the shared secret between this key and the given account.
+    #[cfg_attr(feature = "tracing", tracing::instrument(level = "trace", skip_all))]
     pub fn receiver_ecdh(&self, notification: &Notification) -> SecretBytes<32> {
         unwrap_enum!(CamoViewKeys, self.receiver_ecdh(notification))
     }
@@ -202,11 +270,37 @@ impl From<&CamoKeys> for CamoViewKeys {
         }
     }
 }
+impl FromStr for CamoViewKeys {
+    type Err = NanoError;
 
-/// A `camo_` account
+    /// Parse a `camoview_` string (see `to_export_bytes()`)
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        let encoded = s
+            .strip_prefix(CAMO_VIEW_KEYS_PREFIX)
+            .ok_or(NanoError::InvalidAddressPrefix)?;
+        if encoded.len() != 120 {
+            return Err(NanoError::InvalidAddressLength);
+        }
+        let bytes = Zeroizing::new(base32::decode_strict(encoded).ok_or(NanoError::InvalidBase32)?);
+        let bytes: [u8; 75] = bytes.as_slice().try_into().unwrap();
+        CamoViewKeys::from_export_bytes(&SecretBytes::from(bytes))
+    }
+}
+/// A `camoview_` string, which encodes `to_export_bytes()`.
+///
+/// **The string contains the private view key**, and is not zeroized: only share it with trusted devices.
+impl Display for CamoViewKeys {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        let encoded = Zeroizing::new(base32::encode(self.to_export_bytes().as_slice()));
+        write!(f, "{CAMO_VIEW_KEYS_PREFIX}{}", encoded.as_str())
+    }
+}
+
+/// A `camo_` account.
+///
+/// Serialized as a `camo_` address in human-readable formats.
 #[repr(u32)]
 #[derive(Debug, Clone, Hash, Zeroize, ZeroizeOnDrop, PartialEq, Eq)]
-#[cfg_attr(feature = "serde", derive(Serialize, Deserialize))]
 pub enum CamoAccount {
     V1(Box<CamoAccountType1>) = 1,
 }
@@ -244,6 +338,10 @@ impl CamoAccount {
     /// Calculate the shared secret between this account and the given key.
     ///
     /// `sender_frontier` is used to ensure that all generated keys are unique per-camo-payment.
+    #[cfg_attr(
+        feature = "tracing",
+        tracing::instrument(level = "debug", skip_all, fields(recipient = %self))
+    )]
     pub fn sender_ecdh(
         &self,
         sender_key: &Key,
@@ -303,6 +401,147 @@ impl Display for CamoAccount {
     }
 }
 
+#[cfg(feature = "serde")]
+impl Serialize for CamoAccount {
+    fn serialize<S>(&self, serializer: S) -> Result<S::Ok, S::Error>
+    where
+        S: serde::Serializer,
+    {
+        if serializer.is_human_readable() {
+            return serializer.serialize_str(&self.to_string());
+        }
+        match self {
+            CamoAccount::V1(v1) => CamoAccountSerdeRef::V1(v1),
+        }
+        .serialize(serializer)
+    }
+}
+#[cfg(feature = "serde")]
+impl<'de> Deserialize<'de> for CamoAccount {
+    fn deserialize<D>(deserializer: D) -> Result<Self, D::Error>
+    where
+        D: serde::Deserializer<'de>,
+    {
+        if deserializer.is_human_readable() {
+            let account = String::deserialize(deserializer)?;
+            return CamoAccount::from_str(&account).map_err(serde::de::Error::custom);
+        }
+        Ok(match CamoAccountSerde::deserialize(deserializer)? {
+            CamoAccountSerde::V1(v1) => CamoAccount::V1(v1),
+        })
+    }
+}
+/// Serde-compatible representations of `CamoAccount` in binary formats
+#[cfg(feature = "serde")]
+#[derive(Serialize)]
+#[serde(rename = "CamoAccount")]
+enum CamoAccountSerdeRef<'a> {
+    V1(&'a CamoAccountType1),
+}
+#[cfg(feature = "serde")]
+#[derive(Deserialize)]
+#[serde(rename = "CamoAccount")]
+enum CamoAccountSerde {
+    V1(Box<CamoAccountType1>),
+}
+
+/// Check whether the checksum of a `camo_` address is correct.
+///
+/// Returns an error if the address is invalid for any other reason.
+pub fn verify_checksum(address: &str) -> Result<bool, NanoError> {
+    if address.len() != ADDRESS_LENGTH {
+        return Err(NanoError::InvalidAddressLength);
+    }
+    if !address.starts_with(CAMO_ACCOUNT_PREFIX) {
+        return Err(NanoError::InvalidAddressPrefix);
+    }
+    let data =
+        base32::decode_strict(&address[CAMO_PREFIX_LEN..]).ok_or(NanoError::InvalidBase32)?;
+    let (data, checksum) = data.split_at(data.len() - 5);
+    Ok(checksum == account_checksum(data))
+}
+
+/// Find the valid `camo_` addresses which differ from `address` by a single character.
+///
+/// Useful for suggesting corrections to a mistyped address.
+pub fn did_you_mean(address: &str) -> Vec<CamoAccount> {
+    single_char_typos(
+        address,
+        CAMO_PREFIX_LEN,
+        |candidate| match verify_checksum(candidate) {
+            Ok(true) => CamoAccount::from_str(candidate).ok(),
+            _ => None,
+        },
+    )
+}
+
+#[cfg(test)]
+mod checksum_tests {
+    use super::{did_you_mean, verify_checksum};
+
+    const ADDRESS: &str = "camo_168be68tsxk1o8xferck89gj75kzk8fpbhote77ed1db975htuf11psgpwq9wabcxdjssycim6tidgkau48x6tgcqnsnxj341mamjpoy8umaz45c";
+    const TYPO: &str = "camo_168be68tsxk1o8xferck89gj75kzk8fpbhote77ed1db975htuf11psgpwq9wabcxdjssycim6tidgkau48x6tgcqnsnxj341mamjpoy8umaz46c";
+
+    #[test]
+    fn checksum() {
+        assert!(verify_checksum(ADDRESS).unwrap());
+        assert!(!verify_checksum(TYPO).unwrap());
+        assert!(verify_checksum(&ADDRESS[..100]).is_err());
+
+        let suggestions = did_you_mean(TYPO);
+        assert!(suggestions.len() == 1);
+        assert!(suggestions[0].to_string() == ADDRESS);
+    }
+}
+
+#[cfg(test)]
+mod view_keys_tests {
+    use super::*;
+
+    #[test]
+    fn view_keys_string() {
+        let seed = SecretBytes::from([24; 32]);
+        let view_keys = CamoKeys::from_seed(&seed, 99, version_bits!(0x01))
+            .unwrap()
+            .to_view_keys();
+        let string = view_keys.to_string();
+        assert!(string.starts_with("camoview_"));
+        assert!(string.parse::<CamoViewKeys>().unwrap() == view_keys);
+
+        let mut typo = string.clone().into_bytes();
+        typo[20] = if typo[20] == b'1' { b'3' } else { b'1' };
+        let typo = String::from_utf8(typo).unwrap();
+        assert!(typo.parse::<CamoViewKeys>() == Err(NanoError::InvalidAddressChecksum));
+        let wrong_prefix = string.replacen("camoview_", "camo_", 1);
+        assert!(wrong_prefix.parse::<CamoViewKeys>() == Err(NanoError::InvalidAddressPrefix));
+        let too_short = &string[..string.len() - 1];
+        assert!(too_short.parse::<CamoViewKeys>() == Err(NanoError::InvalidAddressLength));
+
+        let mut bytes: [u8; 75] = view_keys.to_export_bytes().as_slice().try_into().unwrap();
+        bytes[4] = 2;
+        assert!(
+            CamoViewKeys::from_export_bytes(&SecretBytes::from(bytes))
+                == Err(NanoError::IncompatibleCamoVersions)
+        );
+    }
+}
+
+#[cfg(test)]
+#[cfg(feature = "rand")]
+mod rand_tests {
+    use super::*;
+
+    #[test]
+    fn generate() {
+        let keys = CamoKeys::generate(version_bits!(0x01)).unwrap();
+        assert!(keys != CamoKeys::generate(version_bits!(0x01)).unwrap());
+        assert!(keys.camo_versions() == version_bits!(0x01));
+        assert!(
+            CamoKeys::generate(version_bits!(0x80)) == Err(NanoError::IncompatibleCamoVersions)
+        );
+    }
+}
+
 #[cfg(test)]
 mod protocol_docs_tests {
     use super::*;
@@ -409,6 +648,15 @@ macro_rules! camo_address_tests {
 
             serde_test!(view_keys: $keys::from_seed(&SecretBytes::from([24; 32]), 99, $versions).unwrap().to_view_keys() => $vk_len);
             serde_test!(account: $addr.parse::<$account>().unwrap() => $account_len);
+
+            #[test]
+            fn account_json() {
+                let account = $addr.parse::<crate::camo::CamoAccount>().unwrap();
+                let json = serde_json::to_string(&account).unwrap();
+                assert!(json == format!("\"{}\"", $addr));
+                assert!(serde_json::from_str::<crate::camo::CamoAccount>(&json).unwrap() == account);
+                assert!(serde_json::to_string(&$addr.parse::<$account>().unwrap()).unwrap() == json);
+            }
         }
     };
 }
@@ -0,0 +1,158 @@
+//! Building the blocks of a Camo payment.
+
+use super::{CamoAccount, Notification};
+use crate::constants::CAMO_SENDER_DUST_THRESHOLD;
+use crate::{Account, Block, BlockBuilder, Key, NanoError};
+
+/// The blocks of a Camo payment, built by `CamoPaymentBuilder`.
+///
+/// The blocks must be signed, have work attached, and be published in order (see `blocks()`).
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct CamoPayment {
+    /// The notification for the recipient
+    pub notification: Notification,
+    /// The one-time account which the payment is sent to
+    pub account: Account,
+    /// The `send` to the notification account, with the representative set to the notification payload
+    pub notification_block: Block,
+    /// The `send` to `account`, which also restores the sender's representative
+    pub payment_block: Block,
+}
+impl CamoPayment {
+    /// The blocks of this payment, in the order they must be published
+    pub fn blocks(&self) -> [&Block; 2] {
+        [&self.notification_block, &self.payment_block]
+    }
+
+    /// Sign both blocks with the sender's key
+    pub fn sign(&mut self, sender: &Key) {
+        self.notification_block.sign(sender);
+        self.payment_block.sign(sender);
+    }
+}
+
+/// Builds the blocks of a Camo payment from the sender's frontier:
+/// a `send` to the recipient's notification account (carrying the notification in its representative field),
+/// followed by a `send` of `amount` to the derived one-time account.
+#[derive(Debug, Clone)]
+pub struct CamoPaymentBuilder<'a> {
+    sender: &'a Key,
+    frontier: &'a Block,
+    recipient: &'a CamoAccount,
+    amount: u128,
+    notification_amount: u128,
+}
+impl<'a> CamoPaymentBuilder<'a> {
+    /// `frontier` is the sender's current frontier block, which must belong to `sender`
+    pub fn new(
+        sender: &'a Key,
+        frontier: &'a Block,
+        recipient: &'a CamoAccount,
+        amount: u128,
+    ) -> CamoPaymentBuilder<'a> {
+        CamoPaymentBuilder {
+            sender,
+            frontier,
+            recipient,
+            amount,
+            notification_amount: CAMO_SENDER_DUST_THRESHOLD,
+        }
+    }
+
+    /// Set the amount sent to the notification account.
+    /// Defaults to `constants::CAMO_SENDER_DUST_THRESHOLD`, below which the recipient may ignore the notification.
+    pub fn notification_amount(mut self, amount: u128) -> CamoPaymentBuilder<'a> {
+        self.notification_amount = amount;
+        self
+    }
+
+    /// Build the (unsigned) blocks of the payment.
+    ///
+    /// Returns an error if the sender's balance is too low.
+    pub fn build(&self) -> Result<CamoPayment, NanoError> {
+        let (secret, notification) = self
+            .recipient
+            .sender_ecdh(self.sender, self.frontier.hash());
+        let account = self.recipient.derive_account(&secret);
+        let Notification::V1(notification_v1) = &notification;
+
+        let mut builder = BlockBuilder::from(self.frontier);
+        let representative = builder.representative.clone();
+
+        builder.representative = notification_v1.representative_payload.clone();
+        let notification_block =
+            builder.send(&notification_v1.recipient, self.notification_amount)?;
+        builder.advance(&notification_block);
+
+        builder.representative = representative;
+        let payment_block = builder.send(&account, self.amount)?;
+
+        Ok(CamoPayment {
+            notification,
+            account,
+            notification_block,
+            payment_block,
+        })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::CamoPaymentBuilder;
+    use crate::camo::scanner::scan;
+    use crate::camo::{CamoKeys, CamoVersion, CamoVersions};
+    use crate::constants::{get_genesis_account, CAMO_SENDER_DUST_THRESHOLD, ONE_NANO};
+    use crate::{Block, BlockType, Key, NanoError, SecretBytes, Signature};
+
+    #[test]
+    fn build() {
+        let mut versions = CamoVersions::empty();
+        versions.enable_version(CamoVersion::One);
+        let keys = CamoKeys::from_seed(&SecretBytes::from([0; 32]), 0, versions).unwrap();
+        let sender = Key::from_seed(&SecretBytes::from([1; 32]), 0);
+        let frontier = Block {
+            block_type: BlockType::Receive,
+            account: sender.to_account(),
+            previous: [1; 32],
+            representative: get_genesis_account(),
+            balance: 2 * ONE_NANO,
+            link: [2; 32],
+            signature: Signature::default(),
+            work: [0; 8],
+        };
+
+        let camo_account = keys.to_camo_account();
+        let mut payment = CamoPaymentBuilder::new(&sender, &frontier, &camo_account, ONE_NANO)
+            .build()
+            .unwrap();
+        let [notification_block, payment_block] = payment.blocks().map(Block::clone);
+        assert!(notification_block.previous == frontier.hash());
+        assert!(notification_block.balance == 2 * ONE_NANO - CAMO_SENDER_DUST_THRESHOLD);
+        assert!(payment_block.previous == notification_block.hash());
+        assert!(payment_block.balance == ONE_NANO - CAMO_SENDER_DUST_THRESHOLD);
+        assert!(payment_block.representative == get_genesis_account());
+        assert!(payment_block.link_as_account().unwrap() == Some(payment.account.clone()));
+
+        // the recipient detects the payment
+        let detected = scan(&keys.to_view_keys(), &[notification_block, payment_block]);
+        assert!(detected.len() == 1);
+        assert!(detected[0].notification == payment.notification);
+        assert!(detected[0].account == payment.account);
+
+        payment.sign(&sender);
+        assert!(payment
+            .blocks()
+            .iter()
+            .all(|block| block.has_valid_signature()));
+
+        assert!(matches!(
+            CamoPaymentBuilder::new(&sender, &frontier, &camo_account, 2 * ONE_NANO).build(),
+            Err(NanoError::InsufficientBalance)
+        ));
+        let payment = CamoPaymentBuilder::new(&sender, &frontier, &camo_account, ONE_NANO)
+            .notification_amount(ONE_NANO)
+            .build()
+            .unwrap();
+        assert!(payment.payment_block.balance == 0);
+    }
+}
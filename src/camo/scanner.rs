@@ -0,0 +1,145 @@
+//! Detecting Camo notifications in lists of blocks.
+//!
+//! The notification account's own history only contains the `receive` blocks which pocketed each notification.
+//! Use `notification_hashes()` to get the hashes of the notification (`send`) blocks, fetch them (such as with `Rpc::blocks_info()`),
+//! and pass them to `scan()`.
+
+use super::{CamoViewKeys, Notification};
+use crate::{Account, Block};
+
+/// A Camo payment detected by `scan()`
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct DetectedPayment {
+    /// The hash of the notification block
+    pub notification_hash: [u8; 32],
+    /// The notification
+    pub notification: Notification,
+    /// The one-time account which the Camo payment was (or will be) sent to
+    pub account: Account,
+}
+
+/// Returns `true` if the block is a notification for these keys
+/// (a `send` to the notification account, which is the same as `CamoViewKeys::signer_account()`)
+pub fn is_notification(keys: &CamoViewKeys, block: &Block) -> bool {
+    block.block_type.is_send() && block.link == keys.signer_account().compressed.to_bytes()
+}
+
+/// Get the hashes of the notification blocks received by the notification account,
+/// given that account's history (such as from `Rpc::account_history()`).
+///
+/// Blocks belonging to other accounts are ignored.
+pub fn notification_hashes(keys: &CamoViewKeys, history: &[Block]) -> Vec<[u8; 32]> {
+    let notification_account = keys.signer_account();
+    history
+        .iter()
+        .filter(|block| block.block_type.is_receive() && block.account == notification_account)
+        .map(|block| block.link)
+        .collect()
+}
+
+/// Detect notifications in `blocks`, returning the derived one-time accounts, in the same order as `blocks`.
+///
+/// Blocks which are not notifications for these keys are ignored.
+/// Note that the amount of each notification is not checked (see `constants::CAMO_RECIPIENT_DUST_THRESHOLD`).
+pub fn scan(keys: &CamoViewKeys, blocks: &[Block]) -> Vec<DetectedPayment> {
+    blocks
+        .iter()
+        .filter(|block| is_notification(keys, block))
+        .map(|block| {
+            let notification =
+                Notification::create_v1(keys.signer_account(), block.representative.clone());
+            let secret = keys.receiver_ecdh(&notification);
+            DetectedPayment {
+                notification_hash: block.hash(),
+                account: keys.derive_account(&secret),
+                notification,
+            }
+        })
+        .collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{notification_hashes, scan};
+    use crate::camo::{CamoKeys, CamoVersion, CamoVersions, Notification};
+    use crate::constants::get_genesis_account;
+    use crate::{Account, Block, BlockType, Key, SecretBytes, Signature};
+
+    fn block(
+        account: Account,
+        block_type: BlockType,
+        representative: Account,
+        link: [u8; 32],
+    ) -> Block {
+        Block {
+            block_type,
+            account,
+            previous: [0; 32],
+            representative,
+            balance: 1,
+            link,
+            signature: Signature::default(),
+            work: [0; 8],
+        }
+    }
+
+    #[test]
+    fn scan_notifications() {
+        let mut versions = CamoVersions::empty();
+        versions.enable_version(CamoVersion::One);
+        let keys = CamoKeys::from_seed(&SecretBytes::from([0; 32]), 0, versions).unwrap();
+        let view_keys = keys.to_view_keys();
+        let camo_account = keys.to_camo_account();
+
+        let sender = Key::from_seed(&SecretBytes::from([1; 32]), 0);
+        let (secret, notification) = camo_account.sender_ecdh(&sender, [2; 32]);
+        let Notification::V1(notification_v1) = &notification;
+        let notification_link = notification_v1.recipient.compressed.to_bytes();
+
+        let notification_block = block(
+            sender.to_account(),
+            BlockType::Send,
+            notification_v1.representative_payload.clone(),
+            notification_link,
+        );
+        let other_send = block(
+            sender.to_account(),
+            BlockType::Send,
+            notification_v1.representative_payload.clone(),
+            get_genesis_account().compressed.to_bytes(),
+        );
+        let change = block(
+            sender.to_account(),
+            BlockType::Change,
+            notification_v1.representative_payload.clone(),
+            notification_link,
+        );
+
+        let detected = scan(
+            &view_keys,
+            &[other_send, notification_block.clone(), change],
+        );
+        assert!(detected.len() == 1);
+        assert!(detected[0].notification_hash == notification_block.hash());
+        assert!(detected[0].notification == notification);
+        assert!(detected[0].account == camo_account.derive_account(&secret));
+        assert!(detected[0].account == keys.derive_key(&secret).to_account());
+
+        let receive = block(
+            notification_v1.recipient.clone(),
+            BlockType::Receive,
+            get_genesis_account(),
+            notification_block.hash(),
+        );
+        let unrelated = block(
+            get_genesis_account(),
+            BlockType::Receive,
+            get_genesis_account(),
+            [3; 32],
+        );
+        assert!(
+            notification_hashes(&view_keys, &[receive, unrelated])
+                == vec![notification_block.hash()]
+        );
+    }
+}
@@ -0,0 +1,118 @@
+//! Canonical test vectors for the Camo protocol.
+//!
+//! Alternative implementations can use these to check that they are compatible with this library.
+//! See `CAMO-PROTOCOL.md` for details on each step.
+
+use super::{CamoAccount, CamoKeys, CamoVersion, CamoVersions, Notification};
+use crate::{Key, SecretBytes};
+
+/// A single Camo payment, from key generation to the derived one-time account
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct TestVector {
+    /// The protocol version used
+    pub version: CamoVersion,
+    /// The recipient's seed
+    pub recipient_seed: [u8; 32],
+    /// The index of the recipient's camo account
+    pub recipient_index: u32,
+    /// The recipient's `camo_` address
+    pub recipient_address: &'static str,
+    /// The seed of the sender's (normal) account
+    pub sender_seed: [u8; 32],
+    /// The index of the sender's (normal) account
+    pub sender_index: u32,
+    /// The frontier of the sender's account
+    pub sender_frontier: [u8; 32],
+    /// The account which the notification is sent to
+    pub notification_recipient: &'static str,
+    /// The representative payload of the notification
+    pub notification_payload: &'static str,
+    /// The shared secret, in hex
+    pub ecdh_secret: &'static str,
+    /// The one-time account derived from the shared secret
+    pub derived_account: &'static str,
+}
+impl TestVector {
+    fn versions(&self) -> CamoVersions {
+        let mut versions = CamoVersions::empty();
+        versions.enable_version(self.version);
+        versions
+    }
+
+    /// Check that this library produces the expected values for this test vector
+    pub fn verify(&self) -> bool {
+        let versions = self.versions();
+        let recipient_seed = SecretBytes::from(self.recipient_seed);
+        let keys = match CamoKeys::from_seed(&recipient_seed, self.recipient_index, versions) {
+            Some(keys) => keys,
+            None => return false,
+        };
+        let account = keys.to_camo_account();
+        if account.to_string() != self.recipient_address
+            || self.recipient_address.parse::<CamoAccount>().ok() != Some(account.clone())
+        {
+            return false;
+        }
+
+        let sender = Key::from_seed(&SecretBytes::from(self.sender_seed), self.sender_index);
+        let (sender_secret, notification) = account.sender_ecdh(&sender, self.sender_frontier);
+        let Notification::V1(notification_v1) = &notification;
+        if notification_v1.recipient.to_string() != self.notification_recipient
+            || notification_v1.representative_payload.to_string() != self.notification_payload
+            || hex::encode_upper(sender_secret.as_ref()) != self.ecdh_secret
+        {
+            return false;
+        }
+
+        let receiver_secret = keys.receiver_ecdh(&notification);
+        receiver_secret == sender_secret
+            && account.derive_account(&sender_secret).to_string() == self.derived_account
+            && keys.derive_key(&receiver_secret).to_account().to_string() == self.derived_account
+    }
+}
+
+/// Test vectors for each implemented protocol version
+pub const TEST_VECTORS: &[TestVector] = &[
+    TestVector {
+        version: CamoVersion::One,
+        recipient_seed: [0; 32],
+        recipient_index: 0,
+        recipient_address: "camo_18wydi3gmaw4aefwhkijrjw4qd87i4tc85wbnij95gz4em3qssickhpoj9i4t6taqk46wdnie7aj8ijrjhtcdgsp3c1oqnahct3otygxx4k7f3o4",
+        sender_seed: [1; 32],
+        sender_index: 0,
+        sender_frontier: [0; 32],
+        notification_recipient: "nano_3gh7r1qbng3688y9saaway3dom4r3f753y6taamjqhj8biuwwr7bj6ijx5ps",
+        notification_payload: "nano_3hku7gzxxbxw8ym3qm8gyrou4ckrecuiehfsskjusb9i8d9qm9ix3jo6i9xd",
+        ecdh_secret: "4F363AEB80824E5F858D3CF4D5A39EF3A553AB1D80642365D560C0442E988BF2",
+        derived_account: "nano_1ut4xpopgbcdzt8cz95boxmtrja3tbm347umxtobdr5niz71ydgys43dxcgc",
+    },
+    TestVector {
+        version: CamoVersion::One,
+        recipient_seed: [200; 32],
+        recipient_index: 5,
+        recipient_address: "camo_168be68tsxk1o8xferck89gj75kzk8fpbhote77ed1db975htuf11psgpwq9wabcxdjssycim6tidgkau48x6tgcqnsnxj341mamjpoy8umaz45c",
+        sender_seed: [127; 32],
+        sender_index: 3,
+        sender_frontier: [50; 32],
+        notification_recipient: "nano_156p45feys1cmgppe7b55qakjshs58u6qtx84kp7i7nmkjqxfpi149zsukde",
+        notification_payload: "nano_14afds61ndu4ik4wxssi3nywz4ead9wio5anzm1hz79oo4dep5ozb6tf6kiq",
+        ecdh_secret: "68A2AFDAAEBC1DE02E1BFE8587787E5A2F5007150F26EC3BAE04524406D8CC4A",
+        derived_account: "nano_37zas7fgndgijtp8awuryb31chfuj1w1ihz53iq4pirhgor4gasmjzomr3fw",
+    },
+];
+
+#[cfg(test)]
+mod tests {
+    use super::TEST_VECTORS;
+
+    #[test]
+    fn test_vectors() {
+        for vector in TEST_VECTORS {
+            assert!(vector.verify());
+
+            let mut wrong = *vector;
+            wrong.sender_frontier = [255; 32];
+            assert!(!wrong.verify());
+        }
+    }
+}
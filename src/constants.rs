@@ -1,4 +1,4 @@
-use super::Account;
+use super::{Account, BlockType, NanoError};
 
 pub const ACCOUNT_PREFIX: &str = "nano_";
 
@@ -13,8 +13,128 @@ pub const ONE_MILLI_NANO: u128 = ONE_RAW * 1_000_000_000_000_000_000_000_000_000
 /// 1 Nano
 pub const ONE_NANO: u128 = ONE_RAW * 1_000_000_000_000_000_000_000_000_000_000;
 
+/// Minimum work difficulty of `send` and `change` blocks
+pub const SEND_WORK_DIFFICULTY: [u8; 8] = 0xfffffff800000000_u64.to_be_bytes();
+/// Minimum work difficulty of `receive` blocks
+pub const RECEIVE_WORK_DIFFICULTY: [u8; 8] = 0xfffffe0000000000_u64.to_be_bytes();
+/// Minimum work difficulty of legacy blocks (the threshold before epoch v2)
+pub const LEGACY_WORK_DIFFICULTY: [u8; 8] = 0xffffffc000000000_u64.to_be_bytes();
+
 pub fn get_genesis_account() -> Account {
-    Account::try_from("nano_3t6k35gi95xu6tergt6p69ck76ogmitsa8mnijtpxm9fkcm736xtoncuohr3").unwrap()
+    Network::MAINNET.genesis_account()
+}
+
+/// The parameters of a Nano (or Nano-like) network.
+///
+/// Functions which do not take a `Network` (such as `get_genesis_account()` and `Block::has_valid_signature()`) use `Network::MAINNET`.
+/// Note that the unit sizes in this module (such as `ONE_NANO`) are specific to Nano.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub struct Network {
+    pub name: &'static str,
+    /// The prefix of addresses on this network, such as `nano_`
+    pub account_prefix: &'static str,
+    /// The address of the genesis account, with this network's prefix
+    pub genesis_address: &'static str,
+    /// The address of the account which signs `epoch` v1 blocks, with this network's prefix
+    pub epoch_v1_signer_address: &'static str,
+    /// The address of the account which signs `epoch` v2 blocks, with this network's prefix
+    pub epoch_v2_signer_address: &'static str,
+    /// Minimum work difficulty of `send` and `change` blocks
+    pub send_work_difficulty: [u8; 8],
+    /// Minimum work difficulty of `receive` blocks
+    pub receive_work_difficulty: [u8; 8],
+    /// Minimum work difficulty of legacy blocks, which all predate epoch v2 (the epoch v1 threshold)
+    pub legacy_work_difficulty: [u8; 8],
+}
+impl Network {
+    /// The Nano main network
+    pub const MAINNET: Network = Network {
+        name: "live",
+        account_prefix: ACCOUNT_PREFIX,
+        genesis_address: "nano_3t6k35gi95xu6tergt6p69ck76ogmitsa8mnijtpxm9fkcm736xtoncuohr3",
+        epoch_v1_signer_address:
+            "nano_3t6k35gi95xu6tergt6p69ck76ogmitsa8mnijtpxm9fkcm736xtoncuohr3",
+        epoch_v2_signer_address:
+            "nano_3qb6o6i1tkzr6jwr5s7eehfxwg9x6eemitdinbpi7u8bjjwsgqfj4wzser3x",
+        send_work_difficulty: SEND_WORK_DIFFICULTY,
+        receive_work_difficulty: RECEIVE_WORK_DIFFICULTY,
+        legacy_work_difficulty: LEGACY_WORK_DIFFICULTY,
+    };
+
+    /// The Nano beta network
+    pub const BETA: Network = Network {
+        name: "beta",
+        account_prefix: ACCOUNT_PREFIX,
+        genesis_address: "nano_1betagoxpxwykx4kw86dnhosc8t3s7ix8eeentwkcg1hbpez1outjrcyg4n1",
+        epoch_v1_signer_address:
+            "nano_1betagoxpxwykx4kw86dnhosc8t3s7ix8eeentwkcg1hbpez1outjrcyg4n1",
+        epoch_v2_signer_address:
+            "nano_1betagoxpxwykx4kw86dnhosc8t3s7ix8eeentwkcg1hbpez1outjrcyg4n1",
+        send_work_difficulty: 0xfffff00000000000_u64.to_be_bytes(),
+        receive_work_difficulty: 0xffffe00000000000_u64.to_be_bytes(),
+        legacy_work_difficulty: 0xfffff00000000000_u64.to_be_bytes(),
+    };
+
+    /// The Nano public test network
+    pub const TEST: Network = Network {
+        name: "test",
+        account_prefix: ACCOUNT_PREFIX,
+        genesis_address: "nano_1jg8zygjg3pp5w644emqcbmjqpnzmubfni3kfe1s8pooeuxsw49fdq1mco9j",
+        epoch_v1_signer_address:
+            "nano_1jg8zygjg3pp5w644emqcbmjqpnzmubfni3kfe1s8pooeuxsw49fdq1mco9j",
+        epoch_v2_signer_address:
+            "nano_1jg8zygjg3pp5w644emqcbmjqpnzmubfni3kfe1s8pooeuxsw49fdq1mco9j",
+        send_work_difficulty: SEND_WORK_DIFFICULTY,
+        receive_work_difficulty: RECEIVE_WORK_DIFFICULTY,
+        legacy_work_difficulty: LEGACY_WORK_DIFFICULTY,
+    };
+
+    /// The Banano main network
+    pub const BANANO: Network = Network {
+        name: "banano",
+        account_prefix: "ban_",
+        genesis_address: "ban_1bananobh5rat99qfgt1ptpieie5swmoth87thi74qgbfrij7dcgjiij94xr",
+        epoch_v1_signer_address: "ban_1bananobh5rat99qfgt1ptpieie5swmoth87thi74qgbfrij7dcgjiij94xr",
+        epoch_v2_signer_address: "ban_1bananobh5rat99qfgt1ptpieie5swmoth87thi74qgbfrij7dcgjiij94xr",
+        send_work_difficulty: 0xfffffe0000000000_u64.to_be_bytes(),
+        receive_work_difficulty: 0xfffffe0000000000_u64.to_be_bytes(),
+        legacy_work_difficulty: 0xfffffe0000000000_u64.to_be_bytes(),
+    };
+
+    /// Parse an address with this network's prefix
+    pub fn parse_account(&self, address: &str) -> Result<Account, NanoError> {
+        Account::from_network_address(address, self)
+    }
+
+    pub fn genesis_account(&self) -> Account {
+        self.parse_account(self.genesis_address)
+            .expect("invalid genesis address")
+    }
+
+    pub fn epoch_v1_signer(&self) -> Account {
+        self.parse_account(self.epoch_v1_signer_address)
+            .expect("invalid epoch v1 signer address")
+    }
+
+    pub fn epoch_v2_signer(&self) -> Account {
+        self.parse_account(self.epoch_v2_signer_address)
+            .expect("invalid epoch v2 signer address")
+    }
+
+    /// The minimum work difficulty of a block of the given type.
+    /// Legacy blocks use `legacy_work_difficulty`, since they were created before epoch v2.
+    pub fn work_difficulty(&self, block_type: &BlockType) -> [u8; 8] {
+        match block_type {
+            BlockType::Legacy(_) => self.legacy_work_difficulty,
+            BlockType::Receive => self.receive_work_difficulty,
+            _ => self.send_work_difficulty,
+        }
+    }
+}
+impl Default for Network {
+    fn default() -> Self {
+        Network::MAINNET
+    }
 }
 
 /// See [here](https://github.com/nanocurrency/nano-node/blob/220ac3de022c61ead2611a1fe2703b3fe4726eae/nano/secure/common.cpp#L103) for details
@@ -24,12 +144,11 @@ pub mod epoch_signers {
 
     /// This happens to be the genesis account
     pub fn get_v1_epoch_signer() -> Account {
-        get_genesis_account()
+        Network::MAINNET.epoch_v1_signer()
     }
 
     pub fn get_v2_epoch_signer() -> Account {
-        Account::try_from("nano_3qb6o6i1tkzr6jwr5s7eehfxwg9x6eemitdinbpi7u8bjjwsgqfj4wzser3x")
-            .unwrap()
+        Network::MAINNET.epoch_v2_signer()
     }
 }
 
@@ -39,6 +158,11 @@ mod camo {
     use crate::camo::CamoVersion;
 
     pub(crate) const CAMO_ACCOUNT_PREFIX: &str = "camo_";
+    pub(crate) const CAMO_VIEW_KEYS_PREFIX: &str = "camoview_";
+    /// The first bytes of exported `CamoViewKeys` (`CamoViewKeys::to_export_bytes()`)
+    pub(crate) const VIEW_KEYS_MAGIC: [u8; 4] = *b"camv";
+    /// The version of the format of exported `CamoViewKeys`
+    pub(crate) const VIEW_KEYS_FORMAT_VERSION: u8 = 1;
     pub(crate) const ADDRESS_CHARS_SAMPLE_SIZE: usize = 8;
 
     pub(crate) const CAMO_PREFIX_LEN: usize = CAMO_ACCOUNT_PREFIX.len();
@@ -13,6 +13,16 @@ pub enum NanoError {
     InvalidCurvePoint,
     /// Invalid base32 encoding
     InvalidBase32,
+    /// Invalid hex encoding
+    InvalidHex,
+    /// Invalid amount
+    InvalidAmount,
+    /// The account's balance is too low
+    InsufficientBalance,
+    /// Invalid payment URI
+    InvalidUri,
+    /// The operating system's random number generator failed
+    RandomnessUnavailable,
     /// incompatible camo protocol versions
     #[cfg(feature = "camo")]
     IncompatibleCamoVersions,
@@ -23,6 +33,11 @@ impl Display for NanoError {
             NanoError::InvalidAddressLength => "invalid length",
             NanoError::InvalidAddressPrefix => "invalid formatting",
             NanoError::InvalidBase32 => "invalid base 32 encoding",
+            NanoError::InvalidHex => "invalid hex encoding",
+            NanoError::InvalidAmount => "invalid amount",
+            NanoError::InsufficientBalance => "insufficient balance",
+            NanoError::InvalidUri => "invalid payment URI",
+            NanoError::RandomnessUnavailable => "failed to generate random bytes",
             NanoError::InvalidAddressChecksum => "invalid checksum",
             NanoError::InvalidCurvePoint => "invalid ed25519 point",
             #[cfg(feature = "camo")]
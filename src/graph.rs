@@ -0,0 +1,212 @@
+use crate::{Account, Block, BlockType};
+use std::collections::HashSet;
+use std::fmt::Write;
+
+/// How two blocks in a `BlockGraph` are related
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum EdgeKind {
+    /// `from` is the block which follows `to` on the same account
+    Previous,
+    /// `from` is a `send`, and `to` is the `receive` which pocketed it
+    Receive,
+}
+
+/// A link between two blocks in a `BlockGraph`
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub struct Edge {
+    pub from: [u8; 32],
+    pub to: [u8; 32],
+    pub kind: EdgeKind,
+}
+
+/// A graph of blocks from one or more account histories,
+/// linking each block to its predecessor, and each `send` to its `receive`.
+///
+/// Blocks which refer to blocks outside of the graph are included, but the missing links are not.
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct BlockGraph {
+    blocks: Vec<Block>,
+    /// The hash of each block in `blocks`
+    hashes: HashSet<[u8; 32]>,
+}
+impl BlockGraph {
+    pub fn new() -> BlockGraph {
+        BlockGraph::default()
+    }
+
+    /// Add a block to the graph.
+    /// Blocks which are already in the graph (with the same hash) are ignored.
+    pub fn insert(&mut self, block: Block) {
+        if self.hashes.insert(block.hash()) {
+            self.blocks.push(block)
+        }
+    }
+
+    /// Check if a block with this hash is in the graph
+    pub fn contains(&self, hash: &[u8; 32]) -> bool {
+        self.hashes.contains(hash)
+    }
+
+    /// Add every block in an account's history to the graph
+    pub fn extend(&mut self, blocks: impl IntoIterator<Item = Block>) {
+        for block in blocks {
+            self.insert(block)
+        }
+    }
+
+    pub fn blocks(&self) -> &[Block] {
+        &self.blocks
+    }
+
+    pub fn len(&self) -> usize {
+        self.blocks.len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.blocks.is_empty()
+    }
+
+    /// Get each account with blocks in the graph, in the order they were first added
+    pub fn accounts(&self) -> Vec<Account> {
+        let mut seen = HashSet::new();
+        let mut accounts: Vec<Account> = vec![];
+        for block in &self.blocks {
+            if seen.insert(&block.account) {
+                accounts.push(block.account.clone())
+            }
+        }
+        accounts
+    }
+
+    /// Get the links between blocks in the graph
+    pub fn edges(&self) -> Vec<Edge> {
+        let mut edges = vec![];
+        for block in &self.blocks {
+            let hash = block.hash();
+            if self.contains(&block.previous) {
+                edges.push(Edge {
+                    from: hash,
+                    to: block.previous,
+                    kind: EdgeKind::Previous,
+                })
+            }
+            // `link` is the hash of the `send` being received
+            if block.block_type == BlockType::Receive && self.contains(&block.link) {
+                edges.push(Edge {
+                    from: block.link,
+                    to: hash,
+                    kind: EdgeKind::Receive,
+                })
+            }
+        }
+        edges
+    }
+
+    /// Export this graph as [Graphviz](https://graphviz.org/) DOT text, with one cluster per account
+    pub fn to_dot(&self) -> String {
+        let mut dot = String::from("digraph {\n    rankdir=LR;\n");
+        for (i, account) in self.accounts().iter().enumerate() {
+            writeln!(dot, "    subgraph cluster_{i} {{").unwrap();
+            writeln!(dot, "        label=\"{account}\";").unwrap();
+            for block in self.blocks.iter().filter(|block| &block.account == account) {
+                writeln!(
+                    dot,
+                    "        \"{}\" [label=\"{}\\n{}\\nbalance: {}\"];",
+                    hex::encode_upper(block.hash()),
+                    block.block_type,
+                    &hex::encode_upper(block.hash())[..8],
+                    block.balance
+                )
+                .unwrap();
+            }
+            dot.push_str("    }\n");
+        }
+        for edge in self.edges() {
+            let style = match edge.kind {
+                EdgeKind::Previous => "style=dashed",
+                EdgeKind::Receive => "color=blue",
+            };
+            writeln!(
+                dot,
+                "    \"{}\" -> \"{}\" [{style}];",
+                hex::encode_upper(edge.from),
+                hex::encode_upper(edge.to)
+            )
+            .unwrap();
+        }
+        dot.push_str("}\n");
+        dot
+    }
+}
+impl From<Vec<Block>> for BlockGraph {
+    fn from(value: Vec<Block>) -> Self {
+        let mut graph = BlockGraph::new();
+        graph.extend(value);
+        graph
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{BlockGraph, Edge, EdgeKind};
+    use crate::{constants::get_genesis_account, Block, BlockType, Key, SecretBytes, Signature};
+
+    fn block(key: &Key, block_type: BlockType, previous: [u8; 32], link: [u8; 32]) -> Block {
+        Block {
+            block_type,
+            account: key.to_account(),
+            previous,
+            representative: get_genesis_account(),
+            balance: 1,
+            link,
+            signature: Signature::default(),
+            work: [0; 8],
+        }
+    }
+
+    #[test]
+    fn edges() {
+        let sender = Key::from_seed(&SecretBytes::from([0; 32]), 0);
+        let recipient = Key::from_seed(&SecretBytes::from([0; 32]), 1);
+        let recipient_bytes = recipient.to_account().compressed.to_bytes();
+
+        let open = block(&sender, BlockType::Receive, [0; 32], [1; 32]);
+        let send = block(&sender, BlockType::Send, open.hash(), recipient_bytes);
+        let receive = block(&recipient, BlockType::Receive, [0; 32], send.hash());
+
+        let graph = BlockGraph::from(vec![
+            open.clone(),
+            send.clone(),
+            receive.clone(),
+            send.clone(),
+        ]);
+        assert!(graph.len() == 3);
+        assert!(graph.contains(&send.hash()));
+        assert!(!graph.contains(&[0; 32]));
+        assert!(graph.accounts() == vec![sender.to_account(), recipient.to_account()]);
+        assert!(
+            graph.edges()
+                == vec![
+                    Edge {
+                        from: send.hash(),
+                        to: open.hash(),
+                        kind: EdgeKind::Previous
+                    },
+                    Edge {
+                        from: send.hash(),
+                        to: receive.hash(),
+                        kind: EdgeKind::Receive
+                    }
+                ]
+        );
+
+        let dot = graph.to_dot();
+        assert!(dot.starts_with("digraph {"));
+        assert!(dot.contains("subgraph cluster_1"));
+        assert!(dot.contains(&format!(
+            "\"{}\" -> \"{}\" [color=blue];",
+            hex::encode_upper(send.hash()),
+            hex::encode_upper(receive.hash())
+        )));
+    }
+}
@@ -4,6 +4,7 @@ use blake2::{
     Blake2b as _Blake2b, Digest,
 };
 use curve25519_dalek::scalar::{clamp_integer, Scalar as RawScalar};
+use zeroize::Zeroize;
 
 #[cfg(feature = "camo")]
 use crate::constants::{SPEND_CONSTANTS_X_INDEX, VIEW_CONSTANTS_X_INDEX};
@@ -15,7 +16,10 @@ pub mod hazmat {
     use super::*;
     #[cfg(feature = "camo")]
     pub fn get_category_seed(seed: &SecretBytes<32>, i: u32) -> SecretBytes<32> {
-        blake2b256(&[&i.to_be_bytes(), seed.as_slice()].concat())
+        blake2b256(&crate::secrets::secret_concat(&[
+            &i.to_be_bytes(),
+            seed.as_slice(),
+        ]))
     }
 }
 #[cfg(feature = "camo")]
@@ -69,9 +73,10 @@ pub fn blake2b_checksum(input: &[u8]) -> [u8; 5] {
 }
 
 pub fn blake2b_scalar(input: &[u8]) -> Scalar {
-    scalar!(RawScalar::from_bytes_mod_order(clamp_integer(
-        blake2b512(input).as_ref()[..32].try_into().unwrap()
-    )))
+    let mut bytes: [u8; 32] = blake2b512(input).as_ref()[..32].try_into().unwrap();
+    let scalar = scalar!(RawScalar::from_bytes_mod_order(clamp_integer(bytes)));
+    bytes.zeroize();
+    scalar
 }
 
 #[cfg(test)]
@@ -0,0 +1,201 @@
+pub use crate::block::Direction;
+use crate::{Account, Block};
+
+#[cfg(feature = "rpc-core")]
+use crate::rpc::{Rpc, RpcError};
+
+/// A block in an account's history, as seen by the account's owner
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Transaction {
+    pub direction: Direction,
+    /// The recipient of an outgoing transaction, or the sender of an incoming transaction.
+    ///
+    /// Incoming transactions only refer to the `send` block which they receive (see `source`),
+    /// so their sender is `None` until found with `resolve_senders()`.
+    pub counterparty: Option<Account>,
+    /// The amount sent or received (`0` if the balance did not change)
+    pub amount: u128,
+    /// Hash of the block
+    pub hash: [u8; 32],
+    /// Height of the block on the account's blockchain
+    pub height: u64,
+    /// Hash of the `send` block received by an incoming transaction
+    pub source: Option<[u8; 32]>,
+}
+
+/// Convert an account's blocks, newest first (as returned by `Rpc::account_history()`), into transactions, newest first.
+///
+/// The amount of each block is found by comparing its balance to the block before it,
+/// so the oldest block is only included if it opened the account.
+/// `oldest_height` is the height of the oldest block (`1` if it opened the account).
+///
+/// Only the blocks which follow on from the oldest block are converted:
+/// conversion stops at the first block whose `previous` is not the block before it.
+pub fn transactions(blocks: &[Block], oldest_height: u64) -> Vec<Transaction> {
+    let mut transactions = vec![];
+    let mut previous: Option<&Block> = None;
+    for (i, block) in blocks.iter().rev().enumerate() {
+        let (amount, direction) = match previous {
+            Some(previous) if block.previous == previous.hash() => block.amount(previous),
+            Some(_) => break,
+            // the account was opened by receiving its whole balance
+            None if block.previous == [0; 32] => (block.balance, Direction::Incoming),
+            None => {
+                previous = Some(block);
+                continue;
+            }
+        };
+        previous = Some(block);
+
+        transactions.push(Transaction {
+            direction,
+            counterparty: block.link_as_account().ok().flatten(),
+            amount,
+            hash: block.hash(),
+            height: oldest_height + i as u64,
+            source: match direction {
+                Direction::Incoming => Some(block.link),
+                _ => None,
+            },
+        })
+    }
+    transactions.reverse();
+    transactions
+}
+
+/// Find the sender of each incoming transaction, by looking up its `source` with `Rpc::blocks_info()`.
+///
+/// Transactions whose source could not be found, or is not a `send` to the transaction's account, are left unchanged.
+#[cfg(feature = "rpc-core")]
+pub async fn resolve_senders(
+    rpc: &Rpc,
+    account: &Account,
+    transactions: &mut [Transaction],
+) -> Result<(), RpcError> {
+    let sources: Vec<[u8; 32]> = transactions.iter().filter_map(|tx| tx.source).collect();
+    let mut infos = rpc.blocks_info(&sources).await?.into_iter();
+
+    for transaction in transactions {
+        let Some(source) = transaction.source else {
+            continue;
+        };
+        if let Some(info) = infos.next().flatten() {
+            if info.block.hash() == source
+                && info.block.block_type.is_send()
+                && &info.block.link == account.as_bytes()
+            {
+                transaction.counterparty = Some(info.block.account.clone())
+            }
+        }
+    }
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{transactions, Direction};
+    use crate::constants::get_genesis_account;
+    use crate::{Block, BlockBuilder, Key, SecretBytes};
+
+    /// The history of an account, newest first: `open` (10), `send` (3), `change`, and `receive` (5)
+    fn history(key: &Key) -> Vec<Block> {
+        let genesis = get_genesis_account();
+        let mut builder = BlockBuilder::unopened(key.to_account(), genesis.clone());
+        let mut blocks = vec![builder.receive([1; 32], 10).unwrap()];
+        builder.advance(&blocks[0]);
+        blocks.push(builder.send(&genesis, 3).unwrap());
+        builder.advance(&blocks[1]);
+        blocks.push(builder.change(&key.to_account()));
+        builder.advance(&blocks[2]);
+        blocks.push(builder.receive([2; 32], 5).unwrap());
+        blocks.reverse();
+        blocks
+    }
+
+    #[test]
+    fn from_blocks() {
+        let key = Key::from_seed(&SecretBytes::from([0; 32]), 0);
+        let blocks = history(&key);
+
+        let history = transactions(&blocks, 1);
+        let directions: Vec<Direction> = history.iter().map(|tx| tx.direction).collect();
+        assert!(
+            directions
+                == [
+                    Direction::Incoming,
+                    Direction::Unchanged,
+                    Direction::Outgoing,
+                    Direction::Incoming
+                ]
+        );
+        let amounts: Vec<u128> = history.iter().map(|tx| tx.amount).collect();
+        assert!(amounts == [5, 0, 3, 10]);
+        let heights: Vec<u64> = history.iter().map(|tx| tx.height).collect();
+        assert!(heights == [4, 3, 2, 1]);
+        assert!(history[0].hash == blocks[0].hash());
+        assert!(history[0].source == Some([2; 32]));
+        assert!(history[0].counterparty.is_none());
+        assert!(history[2].counterparty == Some(get_genesis_account()));
+
+        // without the `open` block, the oldest block is only used for its balance
+        let history = transactions(&blocks[..3], 2);
+        assert!(history.len() == 2);
+        assert!(history[1].height == 3);
+
+        // blocks which don't follow on are not converted
+        let history = transactions(
+            &[blocks[0].clone(), blocks[2].clone(), blocks[3].clone()],
+            1,
+        );
+        assert!(history.len() == 2);
+        assert!(transactions(&[], 1).is_empty());
+    }
+
+    #[cfg(feature = "rpc-core")]
+    #[test]
+    fn resolve_senders() {
+        use crate::rpc::mock::MockRpc;
+        use crate::rpc::util::block_to_json;
+        use futures::executor::block_on;
+        use serde_json::json;
+
+        let key = Key::from_seed(&SecretBytes::from([0; 32]), 0);
+        let sender = Key::from_seed(&SecretBytes::from([0; 32]), 1);
+        let mut send = BlockBuilder::new(sender.to_account(), [3; 32], get_genesis_account(), 5)
+            .send(&key.to_account(), 5)
+            .unwrap();
+        send.sign(&sender);
+
+        let mut blocks = history(&key);
+        blocks[0] = BlockBuilder::from(&blocks[1])
+            .receive(send.hash(), 5)
+            .unwrap();
+        let mut history = transactions(&blocks, 1);
+
+        let mock = MockRpc::new();
+        mock.respond(
+            "blocks_info",
+            json!({"blocks": {hex::encode_upper(send.hash()): {
+                "block_account": sender.to_account().to_string(),
+                "amount": "5",
+                "balance": "0",
+                "height": "2",
+                "local_timestamp": "1000",
+                "successor": hex::encode([0; 32]),
+                "confirmed": "true",
+                "contents": block_to_json(&send),
+                "subtype": "send"
+            }}}),
+        );
+        block_on(super::resolve_senders(
+            &mock.rpc(),
+            &key.to_account(),
+            &mut history,
+        ))
+        .unwrap();
+        assert!(history[0].counterparty == Some(sender.to_account()));
+        // the `open` block's source was not found
+        assert!(history[3].counterparty.is_none());
+        assert!(mock.requests()[0]["hashes"].as_array().unwrap().len() == 2);
+    }
+}
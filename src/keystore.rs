@@ -0,0 +1,417 @@
+//! Password-based encryption of seeds, for storing them on disk.
+//!
+//! The password is stretched with Argon2id, and the seed is encrypted with XChaCha20-Poly1305.
+//! The version, KDF parameters, and salt are authenticated along with the seed,
+//! so an `EncryptedSeed` cannot be tampered with without detection.
+
+use crate::{Account, Key, SecretBytes};
+use argon2::{Algorithm, Argon2, Params, Version};
+use chacha20poly1305::aead::{Aead, KeyInit, Payload};
+use chacha20poly1305::{XChaCha20Poly1305, XNonce};
+use serde_json::{json, Value as JsonValue};
+use std::error::Error;
+use std::fmt::Display;
+use zeroize::Zeroizing;
+
+#[cfg(feature = "serde")]
+use serde::{Deserialize, Serialize};
+
+/// The current (and only) version of the `EncryptedSeed` format
+pub const KEYSTORE_VERSION: u8 = 1;
+
+const SALT_LEN: usize = 16;
+const NONCE_LEN: usize = 24;
+/// The 32-byte seed, plus the 16-byte Poly1305 tag
+const CIPHERTEXT_LEN: usize = 32 + 16;
+const HEADER_LEN: usize = 1 + 4 * 3 + SALT_LEN;
+const ENCODED_LEN: usize = HEADER_LEN + NONCE_LEN + CIPHERTEXT_LEN;
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum KeystoreError {
+    /// The password is wrong, or the data has been modified
+    DecryptionFailed,
+    /// The data was created by an unknown version of the keystore format
+    UnsupportedVersion(u8),
+    /// The Argon2 parameters are invalid, or exceed the limits in `KdfParams`
+    InvalidParameters,
+    /// The data is malformed
+    InvalidData,
+    /// The operating system's random number generator failed
+    RandomnessUnavailable,
+}
+impl Display for KeystoreError {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match &self {
+            KeystoreError::DecryptionFailed => write!(f, "wrong password, or corrupted data"),
+            KeystoreError::UnsupportedVersion(version) => {
+                write!(f, "unsupported keystore version: {version}")
+            }
+            KeystoreError::InvalidParameters => write!(f, "invalid Argon2 parameters"),
+            KeystoreError::InvalidData => write!(f, "invalid keystore data"),
+            KeystoreError::RandomnessUnavailable => write!(f, "failed to generate random bytes"),
+        }
+    }
+}
+impl Error for KeystoreError {}
+
+/// Argon2id parameters
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+#[cfg_attr(feature = "serde", derive(Serialize, Deserialize))]
+pub struct KdfParams {
+    /// Memory size, in KiB
+    pub memory_cost: u32,
+    /// Number of iterations
+    pub time_cost: u32,
+    /// Degree of parallelism
+    pub parallelism: u32,
+}
+impl Default for KdfParams {
+    /// The parameters recommended by OWASP (19 MiB, 2 iterations, 1 lane)
+    fn default() -> Self {
+        KdfParams {
+            memory_cost: Params::DEFAULT_M_COST,
+            time_cost: Params::DEFAULT_T_COST,
+            parallelism: Params::DEFAULT_P_COST,
+        }
+    }
+}
+impl KdfParams {
+    /// The largest accepted memory size: 4 GiB
+    pub const MAX_MEMORY_COST: u32 = 4 * 1024 * 1024;
+    /// The largest accepted number of iterations
+    pub const MAX_TIME_COST: u32 = 64;
+    /// The largest accepted degree of parallelism
+    pub const MAX_PARALLELISM: u32 = 64;
+
+    /// Check that the parameters are within the limits above, so that decrypting untrusted data
+    /// cannot exhaust memory or run for hours.
+    ///
+    /// Returns `KeystoreError::InvalidParameters` otherwise.
+    pub fn check_limits(&self) -> Result<(), KeystoreError> {
+        if self.memory_cost > KdfParams::MAX_MEMORY_COST
+            || self.time_cost > KdfParams::MAX_TIME_COST
+            || self.parallelism > KdfParams::MAX_PARALLELISM
+        {
+            return Err(KeystoreError::InvalidParameters);
+        }
+        Ok(())
+    }
+}
+
+/// A seed encrypted with a password.
+///
+/// Can be stored as JSON (`to_json()`) or bytes (`to_bytes()`), or with `serde` if the feature is enabled.
+#[derive(Debug, Clone, PartialEq, Eq)]
+#[cfg_attr(feature = "serde", derive(Serialize, Deserialize))]
+pub struct EncryptedSeed {
+    pub version: u8,
+    pub kdf: KdfParams,
+    pub salt: [u8; SALT_LEN],
+    pub nonce: [u8; NONCE_LEN],
+    /// The encrypted seed, followed by the authentication tag
+    pub ciphertext: Vec<u8>,
+}
+impl EncryptedSeed {
+    /// Encrypt `seed` with `password`, using the default Argon2id parameters
+    pub fn encrypt(
+        seed: &SecretBytes<32>,
+        password: &[u8],
+    ) -> Result<EncryptedSeed, KeystoreError> {
+        EncryptedSeed::encrypt_with_params(seed, password, KdfParams::default())
+    }
+
+    pub fn encrypt_with_params(
+        seed: &SecretBytes<32>,
+        password: &[u8],
+        kdf: KdfParams,
+    ) -> Result<EncryptedSeed, KeystoreError> {
+        let mut salt = [0; SALT_LEN];
+        let mut nonce = [0; NONCE_LEN];
+        getrandom::getrandom(&mut salt).or(Err(KeystoreError::RandomnessUnavailable))?;
+        getrandom::getrandom(&mut nonce).or(Err(KeystoreError::RandomnessUnavailable))?;
+
+        let mut encrypted = EncryptedSeed {
+            version: KEYSTORE_VERSION,
+            kdf,
+            salt,
+            nonce,
+            ciphertext: vec![],
+        };
+        let payload = Payload {
+            msg: seed.as_slice(),
+            aad: &encrypted.header(),
+        };
+        encrypted.ciphertext = encrypted
+            .cipher(password)?
+            .encrypt(XNonce::from_slice(&nonce), payload)
+            .or(Err(KeystoreError::InvalidData))?;
+        Ok(encrypted)
+    }
+
+    /// Decrypt the seed
+    pub fn decrypt(&self, password: &[u8]) -> Result<SecretBytes<32>, KeystoreError> {
+        if self.version != KEYSTORE_VERSION {
+            return Err(KeystoreError::UnsupportedVersion(self.version));
+        }
+        let payload = Payload {
+            msg: &self.ciphertext,
+            aad: &self.header(),
+        };
+        // zeroized when dropped, even if the seed has the wrong length
+        let seed = Zeroizing::new(
+            self.cipher(password)?
+                .decrypt(XNonce::from_slice(&self.nonce), payload)
+                .or(Err(KeystoreError::DecryptionFailed))?,
+        );
+        let bytes: [u8; 32] = seed
+            .as_slice()
+            .try_into()
+            .or(Err(KeystoreError::InvalidData))?;
+        Ok(SecretBytes::from(bytes))
+    }
+
+    /// Decrypt the seed, and derive the key at index `i`
+    pub fn derive_key(&self, password: &[u8], i: u32) -> Result<Key, KeystoreError> {
+        Ok(Key::from_seed(&self.decrypt(password)?, i))
+    }
+
+    /// Decrypt the seed, and derive the account at index `i`
+    pub fn derive_account(&self, password: &[u8], i: u32) -> Result<Account, KeystoreError> {
+        Ok(self.derive_key(password, i)?.to_account())
+    }
+
+    /// The authenticated (but unencrypted) data: version, KDF parameters, and salt
+    fn header(&self) -> Vec<u8> {
+        [
+            &[self.version][..],
+            &self.kdf.memory_cost.to_be_bytes(),
+            &self.kdf.time_cost.to_be_bytes(),
+            &self.kdf.parallelism.to_be_bytes(),
+            &self.salt,
+        ]
+        .concat()
+    }
+
+    fn cipher(&self, password: &[u8]) -> Result<XChaCha20Poly1305, KeystoreError> {
+        self.kdf.check_limits()?;
+        let params = Params::new(
+            self.kdf.memory_cost,
+            self.kdf.time_cost,
+            self.kdf.parallelism,
+            Some(32),
+        )
+        .or(Err(KeystoreError::InvalidParameters))?;
+        let mut key = SecretBytes::from([0; 32]);
+        Argon2::new(Algorithm::Argon2id, Version::V0x13, params)
+            .hash_password_into(password, &self.salt, key.as_mut())
+            .or(Err(KeystoreError::InvalidParameters))?;
+        XChaCha20Poly1305::new_from_slice(key.as_slice()).or(Err(KeystoreError::InvalidData))
+    }
+
+    pub fn to_bytes(&self) -> Vec<u8> {
+        [&self.header()[..], &self.nonce, &self.ciphertext].concat()
+    }
+
+    pub fn from_bytes(bytes: &[u8]) -> Result<EncryptedSeed, KeystoreError> {
+        match bytes.first() {
+            Some(&KEYSTORE_VERSION) => (),
+            Some(version) => return Err(KeystoreError::UnsupportedVersion(*version)),
+            None => return Err(KeystoreError::InvalidData),
+        }
+        if bytes.len() != ENCODED_LEN {
+            return Err(KeystoreError::InvalidData);
+        }
+        let u32_at = |i: usize| u32::from_be_bytes(bytes[i..i + 4].try_into().unwrap());
+        let kdf = KdfParams {
+            memory_cost: u32_at(1),
+            time_cost: u32_at(5),
+            parallelism: u32_at(9),
+        };
+        kdf.check_limits()?;
+        Ok(EncryptedSeed {
+            version: bytes[0],
+            kdf,
+            salt: bytes[13..HEADER_LEN].try_into().unwrap(),
+            nonce: bytes[HEADER_LEN..HEADER_LEN + NONCE_LEN]
+                .try_into()
+                .unwrap(),
+            ciphertext: bytes[HEADER_LEN + NONCE_LEN..].to_vec(),
+        })
+    }
+
+    /// Encode as JSON, with binary fields in hex
+    pub fn to_json(&self) -> String {
+        json!({
+            "version": self.version,
+            "kdf": {
+                "algorithm": "argon2id",
+                "memory_cost": self.kdf.memory_cost,
+                "time_cost": self.kdf.time_cost,
+                "parallelism": self.kdf.parallelism,
+                "salt": hex::encode(self.salt)
+            },
+            "cipher": {
+                "algorithm": "xchacha20-poly1305",
+                "nonce": hex::encode(self.nonce),
+                "ciphertext": hex::encode(&self.ciphertext)
+            }
+        })
+        .to_string()
+    }
+
+    pub fn from_json(json: &str) -> Result<EncryptedSeed, KeystoreError> {
+        let json: JsonValue = serde_json::from_str(json).or(Err(KeystoreError::InvalidData))?;
+        let version = json["version"].as_u64().ok_or(KeystoreError::InvalidData)?;
+        if version != KEYSTORE_VERSION as u64 {
+            return Err(KeystoreError::UnsupportedVersion(
+                version.try_into().unwrap_or(u8::MAX),
+            ));
+        }
+        if json["kdf"]["algorithm"] != "argon2id"
+            || json["cipher"]["algorithm"] != "xchacha20-poly1305"
+        {
+            return Err(KeystoreError::InvalidData);
+        }
+
+        let u32_from_json = |value: &JsonValue| -> Result<u32, KeystoreError> {
+            value
+                .as_u64()
+                .and_then(|value| value.try_into().ok())
+                .ok_or(KeystoreError::InvalidData)
+        };
+        let hex_from_json = |value: &JsonValue| -> Result<Vec<u8>, KeystoreError> {
+            hex::decode(value.as_str().ok_or(KeystoreError::InvalidData)?)
+                .or(Err(KeystoreError::InvalidData))
+        };
+        let ciphertext = hex_from_json(&json["cipher"]["ciphertext"])?;
+        if ciphertext.len() != CIPHERTEXT_LEN {
+            return Err(KeystoreError::InvalidData);
+        }
+        let kdf = KdfParams {
+            memory_cost: u32_from_json(&json["kdf"]["memory_cost"])?,
+            time_cost: u32_from_json(&json["kdf"]["time_cost"])?,
+            parallelism: u32_from_json(&json["kdf"]["parallelism"])?,
+        };
+        kdf.check_limits()?;
+        Ok(EncryptedSeed {
+            version: KEYSTORE_VERSION,
+            kdf,
+            salt: hex_from_json(&json["kdf"]["salt"])?
+                .try_into()
+                .or(Err(KeystoreError::InvalidData))?,
+            nonce: hex_from_json(&json["cipher"]["nonce"])?
+                .try_into()
+                .or(Err(KeystoreError::InvalidData))?,
+            ciphertext,
+        })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{EncryptedSeed, KdfParams, KeystoreError};
+    use crate::{Key, SecretBytes};
+
+    /// Cheap parameters, to keep the tests fast
+    const PARAMS: KdfParams = KdfParams {
+        memory_cost: 64,
+        time_cost: 1,
+        parallelism: 1,
+    };
+
+    #[test]
+    fn encrypt_decrypt() {
+        let seed = SecretBytes::from([7; 32]);
+        let encrypted = EncryptedSeed::encrypt_with_params(&seed, b"password", PARAMS).unwrap();
+        assert!(encrypted.decrypt(b"password").unwrap() == seed);
+        assert!(encrypted.derive_key(b"password", 3).unwrap() == Key::from_seed(&seed, 3));
+        assert!(
+            encrypted.derive_account(b"password", 3).unwrap()
+                == Key::from_seed(&seed, 3).to_account()
+        );
+        assert!(encrypted.decrypt(b"wrong") == Err(KeystoreError::DecryptionFailed));
+
+        // salt and nonce are random
+        let other = EncryptedSeed::encrypt_with_params(&seed, b"password", PARAMS).unwrap();
+        assert!(other.salt != encrypted.salt && other.ciphertext != encrypted.ciphertext);
+
+        // the parameters are authenticated
+        let mut tampered = encrypted.clone();
+        tampered.kdf.time_cost = 2;
+        assert!(tampered.decrypt(b"password") == Err(KeystoreError::DecryptionFailed));
+        let mut tampered = encrypted;
+        tampered.version = 2;
+        assert!(tampered.decrypt(b"password") == Err(KeystoreError::UnsupportedVersion(2)));
+    }
+
+    #[test]
+    fn encoding() {
+        let seed = SecretBytes::from([7; 32]);
+        let encrypted = EncryptedSeed::encrypt_with_params(&seed, b"password", PARAMS).unwrap();
+
+        let bytes = encrypted.to_bytes();
+        assert!(EncryptedSeed::from_bytes(&bytes).unwrap() == encrypted);
+        assert!(EncryptedSeed::from_bytes(&bytes[1..]).is_err());
+        assert!(EncryptedSeed::from_bytes(&bytes[..bytes.len() - 1]).is_err());
+
+        let json = encrypted.to_json();
+        assert!(EncryptedSeed::from_json(&json).unwrap() == encrypted);
+        assert!(EncryptedSeed::from_json(&json.replace("argon2id", "scrypt")).is_err());
+        assert!(
+            EncryptedSeed::from_json(&json.replace("\"version\":1", "\"version\":9"))
+                == Err(KeystoreError::UnsupportedVersion(9))
+        );
+    }
+
+    #[test]
+    fn limits() {
+        let seed = SecretBytes::from([7; 32]);
+        let encrypted = EncryptedSeed::encrypt_with_params(&seed, b"password", PARAMS).unwrap();
+
+        for kdf in [
+            KdfParams {
+                memory_cost: KdfParams::MAX_MEMORY_COST + 1,
+                ..PARAMS
+            },
+            KdfParams {
+                time_cost: u32::MAX,
+                ..PARAMS
+            },
+            KdfParams {
+                parallelism: KdfParams::MAX_PARALLELISM + 1,
+                ..PARAMS
+            },
+        ] {
+            let mut hostile = encrypted.clone();
+            hostile.kdf = kdf;
+            assert!(hostile.decrypt(b"password") == Err(KeystoreError::InvalidParameters));
+            assert!(
+                EncryptedSeed::from_bytes(&hostile.to_bytes())
+                    == Err(KeystoreError::InvalidParameters)
+            );
+            assert!(
+                EncryptedSeed::from_json(&hostile.to_json())
+                    == Err(KeystoreError::InvalidParameters)
+            );
+            assert!(
+                EncryptedSeed::encrypt_with_params(&seed, b"password", kdf)
+                    == Err(KeystoreError::InvalidParameters)
+            );
+        }
+    }
+}
+
+#[cfg(test)]
+#[cfg(feature = "serde")]
+mod serde_tests {
+    use super::*;
+    use crate::serde_test;
+
+    serde_test!(encrypted_seed: EncryptedSeed {
+        version: KEYSTORE_VERSION,
+        kdf: KdfParams::default(),
+        salt: [1; SALT_LEN],
+        nonce: [2; NONCE_LEN],
+        ciphertext: vec![3; CIPHERTEXT_LEN]
+    } => 1 + 12 + SALT_LEN + NONCE_LEN + 8 + CIPHERTEXT_LEN);
+}
@@ -0,0 +1,145 @@
+use super::{LedgerError, LedgerTransport};
+use hidapi::{HidApi, HidDevice};
+
+/// USB vendor ID of Ledger devices
+const VENDOR_ID: u16 = 0x2c97;
+/// HID usage page of the interface which carries APDUs
+const USAGE_PAGE: u16 = 0xffa0;
+const CHANNEL: u16 = 0x0101;
+/// Tag of packets carrying APDUs
+const TAG_APDU: u8 = 0x05;
+const PACKET_LEN: usize = 64;
+/// Channel, tag, and sequence number
+const HEADER_LEN: usize = 5;
+
+/// Split an APDU into HID packets: the first packet also contains the length of the APDU
+fn to_packets(apdu: &[u8]) -> Result<Vec<[u8; PACKET_LEN]>, LedgerError> {
+    let length = u16::try_from(apdu.len()).or(Err(LedgerError::InvalidResponse))?;
+    let data = [&length.to_be_bytes()[..], apdu].concat();
+
+    let mut packets = vec![];
+    for (sequence, chunk) in data.chunks(PACKET_LEN - HEADER_LEN).enumerate() {
+        let mut packet = [0; PACKET_LEN];
+        packet[..2].copy_from_slice(&CHANNEL.to_be_bytes());
+        packet[2] = TAG_APDU;
+        packet[3..5].copy_from_slice(&(sequence as u16).to_be_bytes());
+        packet[HEADER_LEN..HEADER_LEN + chunk.len()].copy_from_slice(chunk);
+        packets.push(packet)
+    }
+    Ok(packets)
+}
+
+/// Reassemble a response from HID packets, as they are read.
+/// Returns the response once all of it has been read.
+#[derive(Debug, Default)]
+struct Response {
+    data: Vec<u8>,
+    length: Option<usize>,
+    sequence: u16,
+}
+impl Response {
+    fn push(&mut self, packet: &[u8]) -> Result<Option<Vec<u8>>, LedgerError> {
+        if packet.len() < HEADER_LEN
+            || packet[..2] != CHANNEL.to_be_bytes()
+            || packet[2] != TAG_APDU
+            || packet[3..5] != self.sequence.to_be_bytes()
+        {
+            return Err(LedgerError::InvalidResponse);
+        }
+        let mut chunk = &packet[HEADER_LEN..];
+        if self.length.is_none() {
+            let length = chunk.get(..2).ok_or(LedgerError::InvalidResponse)?;
+            self.length = Some(u16::from_be_bytes([length[0], length[1]]) as usize);
+            chunk = &chunk[2..];
+        }
+        self.sequence = self.sequence.wrapping_add(1);
+
+        let length = self.length.unwrap_or_default();
+        let remaining = length - self.data.len();
+        self.data.extend(&chunk[..remaining.min(chunk.len())]);
+        match self.data.len() == length {
+            true => Ok(Some(std::mem::take(&mut self.data))),
+            false => Ok(None),
+        }
+    }
+}
+
+fn hid_error(err: hidapi::HidError) -> LedgerError {
+    LedgerError::Transport(err.to_string())
+}
+
+/// Talks to a Ledger device connected over USB, using [hidapi](https://docs.rs/hidapi/latest/hidapi/).
+///
+/// On Linux, `libudev` is required, and the user needs permission to access the device
+/// (see Ledger's [udev rules](https://github.com/LedgerHQ/udev-rules)).
+#[derive(Debug)]
+pub struct HidTransport {
+    device: HidDevice,
+}
+impl HidTransport {
+    /// Open the first connected Ledger device
+    pub fn open() -> Result<HidTransport, LedgerError> {
+        let api = HidApi::new().map_err(hid_error)?;
+        let info = api
+            .device_list()
+            .find(|info| {
+                info.vendor_id() == VENDOR_ID
+                    && (info.usage_page() == USAGE_PAGE || info.interface_number() == 0)
+            })
+            .ok_or_else(|| LedgerError::Transport("no Ledger device found".into()))?;
+        Ok(HidTransport {
+            device: info.open_device(&api).map_err(hid_error)?,
+        })
+    }
+}
+impl LedgerTransport for HidTransport {
+    /// Blocks until the device responds, which may require confirmation from the user
+    fn exchange(&self, apdu: &[u8]) -> Result<Vec<u8>, LedgerError> {
+        for packet in to_packets(apdu)? {
+            // the first byte is the report ID, which Ledger devices do not use
+            let report = [&[0x00][..], &packet].concat();
+            self.device.write(&report).map_err(hid_error)?;
+        }
+
+        let mut response = Response::default();
+        loop {
+            let mut packet = [0; PACKET_LEN];
+            let read = self.device.read(&mut packet).map_err(hid_error)?;
+            if let Some(data) = response.push(&packet[..read])? {
+                return Ok(data);
+            }
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{to_packets, Response, PACKET_LEN};
+
+    #[test]
+    fn framing() {
+        let apdu = [0xa1, 0x02, 0x00, 0x00, 0x00];
+        let packets = to_packets(&apdu).unwrap();
+        assert!(packets.len() == 1);
+        assert!(
+            packets[0][..12]
+                == [0x01, 0x01, 0x05, 0x00, 0x00, 0x00, 0x05, 0xa1, 0x02, 0x00, 0x00, 0x00]
+        );
+        assert!(packets[0][12..] == [0; PACKET_LEN - 12]);
+
+        // long APDUs are split across packets, and reassembled
+        let apdu: Vec<u8> = (0..150).collect();
+        let packets = to_packets(&apdu).unwrap();
+        assert!(packets.len() == 3);
+        assert!(packets[2][3..5] == [0x00, 0x02]);
+        let mut response = Response::default();
+        assert!(response.push(&packets[0]).unwrap().is_none());
+        assert!(response.push(&packets[1]).unwrap().is_none());
+        assert!(response.push(&packets[2]).unwrap() == Some(apdu));
+
+        // packets out of order
+        let mut response = Response::default();
+        assert!(response.push(&packets[1]).is_err());
+        assert!(Response::default().push(&[0x01, 0x01]).is_err());
+    }
+}
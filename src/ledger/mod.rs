@@ -0,0 +1,208 @@
+//! Signing with a Ledger hardware wallet running the Nano app.
+//!
+//! This module implements the Nano app's APDU protocol. With the `ledger-hid` feature, `HidTransport`
+//! talks to a device over USB; otherwise, implement `LedgerTransport` on top of another transport.
+//! Only `state` blocks can be signed.
+
+#[cfg(feature = "ledger-hid")]
+mod hid;
+
+#[cfg(feature = "ledger-hid")]
+pub use hid::HidTransport;
+
+use crate::signer::Signer;
+use crate::{Account, Block, Signature};
+use std::error::Error;
+use std::fmt::Display;
+
+/// Class byte of the Nano app's commands
+const CLA: u8 = 0xa1;
+const INS_GET_ADDRESS: u8 = 0x02;
+const INS_SIGN_BLOCK: u8 = 0x04;
+/// Status word of a successful command
+const SW_OK: u16 = 0x9000;
+/// BIP-44 coin type of Nano
+const COIN_TYPE: u32 = 165;
+const HARDENED: u32 = 0x8000_0000;
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum LedgerError {
+    /// Communication with the device failed
+    Transport(String),
+    /// The device returned an error status word, such as when the user rejects the request
+    Status(u16),
+    /// The device's response is malformed, or does not match the request
+    InvalidResponse,
+    /// Legacy blocks cannot be signed
+    UnsupportedBlock,
+}
+impl Display for LedgerError {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match &self {
+            LedgerError::Transport(err) => write!(f, "Ledger transport error: {err}"),
+            LedgerError::Status(status) => write!(f, "Ledger returned status {status:#06x}"),
+            LedgerError::InvalidResponse => write!(f, "invalid response from Ledger"),
+            LedgerError::UnsupportedBlock => write!(f, "Ledger can only sign state blocks"),
+        }
+    }
+}
+impl Error for LedgerError {}
+
+/// Exchanges APDUs with a Ledger device
+pub trait LedgerTransport {
+    /// Send a command APDU, and return the response, including the trailing 2-byte status word
+    fn exchange(&self, apdu: &[u8]) -> Result<Vec<u8>, LedgerError>;
+}
+
+/// The account at `m/44'/165'/index'` on a Ledger device
+#[derive(Debug, Clone)]
+pub struct Ledger<T: LedgerTransport> {
+    transport: T,
+    index: u32,
+}
+impl<T: LedgerTransport> Ledger<T> {
+    pub fn new(transport: T, index: u32) -> Ledger<T> {
+        Ledger { transport, index }
+    }
+
+    pub fn index(&self) -> u32 {
+        self.index
+    }
+
+    /// The BIP-32 path, as expected by the Nano app
+    fn path(&self) -> Vec<u8> {
+        [44, COIN_TYPE, self.index]
+            .iter()
+            .fold(vec![3], |mut path, component| {
+                path.extend((component | HARDENED).to_be_bytes());
+                path
+            })
+    }
+
+    /// Send a command, returning the response data if successful
+    fn command(&self, ins: u8, p1: u8, data: &[u8]) -> Result<Vec<u8>, LedgerError> {
+        let length = u8::try_from(data.len()).or(Err(LedgerError::InvalidResponse))?;
+        let apdu = [&[CLA, ins, p1, 0x00, length][..], data].concat();
+        let mut response = self.transport.exchange(&apdu)?;
+        if response.len() < 2 {
+            return Err(LedgerError::InvalidResponse);
+        }
+        let status = response.split_off(response.len() - 2);
+        match u16::from_be_bytes([status[0], status[1]]) {
+            SW_OK => Ok(response),
+            status => Err(LedgerError::Status(status)),
+        }
+    }
+
+    /// Get the account, optionally showing its address on the device's screen so the user can verify it
+    pub fn get_account(&self, display: bool) -> Result<Account, LedgerError> {
+        let response = self.command(INS_GET_ADDRESS, display as u8, &self.path())?;
+        let public_key: [u8; 32] = response
+            .get(..32)
+            .and_then(|key| key.try_into().ok())
+            .ok_or(LedgerError::InvalidResponse)?;
+        Account::from_bytes(public_key).or(Err(LedgerError::InvalidResponse))
+    }
+}
+impl<T: LedgerTransport> Signer for Ledger<T> {
+    type Error = LedgerError;
+
+    fn account(&self) -> Result<Account, LedgerError> {
+        self.get_account(false)
+    }
+
+    /// The user must confirm the block on the device.
+    /// The hash returned by the device is checked against the block.
+    fn sign_block(&self, block: &Block) -> Result<Signature, LedgerError> {
+        if !block.block_type.is_state() {
+            return Err(LedgerError::UnsupportedBlock);
+        }
+        let data = [
+            &self.path()[..],
+            &block.previous,
+            &block.link,
+            block.representative.compressed.as_bytes(),
+            &block.balance.to_be_bytes(),
+        ]
+        .concat();
+        let response = self.command(INS_SIGN_BLOCK, 0x00, &data)?;
+        if response.len() != 32 + 64 || response[..32] != block.hash() {
+            return Err(LedgerError::InvalidResponse);
+        }
+        let signature: [u8; 64] = response[32..].try_into().unwrap();
+        Signature::try_from(&signature).or(Err(LedgerError::InvalidResponse))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{Ledger, LedgerError, LedgerTransport};
+    use crate::constants::get_genesis_account;
+    use crate::signer::Signer;
+    use crate::{BlockBuilder, BlockType, Key, SecretBytes};
+    use std::cell::RefCell;
+
+    /// Checks each request, and returns the recorded response
+    struct MockTransport(RefCell<Vec<(Vec<u8>, Vec<u8>)>>);
+    impl LedgerTransport for MockTransport {
+        fn exchange(&self, apdu: &[u8]) -> Result<Vec<u8>, LedgerError> {
+            let (request, response) = self.0.borrow_mut().remove(0);
+            assert!(apdu == request);
+            Ok(response)
+        }
+    }
+
+    const PATH: [u8; 13] = [3, 0x80, 0, 0, 44, 0x80, 0, 0, 165, 0x80, 0, 0, 2];
+
+    #[test]
+    fn get_account() {
+        let key = Key::from_seed(&SecretBytes::from([0; 32]), 0);
+        let request = [&[0xa1, 0x02, 0x01, 0x00, 13][..], &PATH].concat();
+        let response = [key.to_account().compressed.as_bytes(), &[0x90, 0x00][..]].concat();
+        let ledger = Ledger::new(MockTransport(RefCell::new(vec![(request, response)])), 2);
+        assert!(ledger.get_account(true).unwrap() == key.to_account());
+
+        let request = [&[0xa1, 0x02, 0x00, 0x00, 13][..], &PATH].concat();
+        let ledger = Ledger::new(
+            MockTransport(RefCell::new(vec![(request, vec![0x69, 0x85])])),
+            2,
+        );
+        assert!(ledger.account() == Err(LedgerError::Status(0x6985)));
+    }
+
+    #[test]
+    fn sign_block() {
+        let key = Key::from_seed(&SecretBytes::from([0; 32]), 0);
+        let block = BlockBuilder::new(key.to_account(), [1; 32], get_genesis_account(), 10)
+            .send(&get_genesis_account(), 3)
+            .unwrap();
+        let signature = key.sign_block(&block);
+
+        let data = [
+            &PATH[..],
+            &[1; 32],
+            get_genesis_account().compressed.as_bytes(),
+            get_genesis_account().compressed.as_bytes(),
+            &7_u128.to_be_bytes(),
+        ]
+        .concat();
+        let request = [&[0xa1, 0x04, 0x00, 0x00, data.len() as u8][..], &data].concat();
+        let response = [&block.hash()[..], &signature.to_bytes(), &[0x90, 0x00]].concat();
+        let mut wrong_hash = response.clone();
+        wrong_hash[0] ^= 1;
+
+        let ledger = Ledger::new(
+            MockTransport(RefCell::new(vec![
+                (request.clone(), response),
+                (request, wrong_hash),
+            ])),
+            2,
+        );
+        assert!(ledger.sign_block(&block).unwrap() == signature);
+        assert!(ledger.sign_block(&block) == Err(LedgerError::InvalidResponse));
+
+        let mut legacy = block;
+        legacy.block_type = BlockType::Legacy("send".into());
+        assert!(ledger.sign_block(&legacy) == Err(LedgerError::UnsupportedBlock));
+    }
+}
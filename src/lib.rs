@@ -3,22 +3,43 @@
 #[cfg(test)]
 #[cfg(not(feature = "serde"))]
 use bincode as _;
+#[cfg(test)]
+#[cfg(not(feature = "rpc-core"))]
+use futures as _;
+#[cfg(test)]
+#[cfg(not(any(feature = "serde", feature = "rpc-core", feature = "keystore")))]
+use serde_json as _;
+#[cfg(test)]
+#[cfg(not(feature = "rpc-isahc"))]
+use smol as _;
+#[cfg(test)]
+#[cfg(not(feature = "rpc"))]
+use tokio as _;
 
-mod account;
 mod error;
 mod nanopy;
 mod secrets;
+#[cfg(feature = "serde")]
+mod serde_util;
 
+pub mod account;
+pub mod amount;
 pub mod base32;
 pub mod block;
 /// Various Nano-related constants
 pub mod constants;
+pub mod graph;
 /// Various hash functions
 pub mod hashes;
+pub mod history;
 pub mod signature;
+pub mod signer;
+pub mod uri;
+pub mod work;
 
-pub use account::{Account, Key};
-pub use block::{Block, BlockType};
+pub use account::{Account, AccountCache, Key};
+pub use amount::Amount;
+pub use block::{Block, BlockBuilder, BlockType};
 pub use error::NanoError;
 pub use secrets::{Scalar, SecretBytes};
 pub use signature::Signature;
@@ -26,9 +47,24 @@ pub use signature::Signature;
 #[cfg(feature = "camo")]
 pub mod camo;
 
-#[cfg(feature = "rpc")]
+#[cfg(feature = "keystore")]
+pub mod keystore;
+
+#[cfg(feature = "ledger")]
+pub mod ledger;
+
+#[cfg(feature = "rpc-core")]
 pub mod rpc;
 
+#[cfg(feature = "rpc-core")]
+pub mod wallet;
+
+#[cfg(feature = "slip10")]
+pub mod slip10;
+
+#[cfg(feature = "test-vectors")]
+pub mod test_vectors;
+
 use curve25519_dalek::edwards::{CompressedEdwardsY, EdwardsPoint};
 
 pub(crate) fn try_compressed_from_slice(key: &[u8]) -> Result<CompressedEdwardsY, NanoError> {
@@ -5,54 +5,92 @@
 
 // https://docs.nano.org/protocol-design/
 
+use super::constants::ACCOUNT_PREFIX;
 use super::error::NanoError;
 use super::hashes::*;
 use super::{
-    base32, try_compressed_from_slice, Account, Block, Key, Scalar, SecretBytes, Signature,
+    base32, try_compressed_from_slice, Account, Block, BlockType, Key, Scalar, SecretBytes,
+    Signature,
 };
 use crate::scalar;
-use curve25519_dalek::{constants::ED25519_BASEPOINT_POINT as G, edwards::CompressedEdwardsY};
+use crate::secrets::secret_concat;
+use curve25519_dalek::traits::{IsIdentity, VartimeMultiscalarMul};
+use curve25519_dalek::{
+    constants::ED25519_BASEPOINT_POINT as G,
+    edwards::{CompressedEdwardsY, EdwardsPoint},
+    Scalar as RawScalar,
+};
 
 pub(crate) fn account_encode(key: &CompressedEdwardsY) -> String {
+    account_encode_with_prefix(key, ACCOUNT_PREFIX)
+}
+
+pub(crate) fn account_encode_with_prefix(key: &CompressedEdwardsY, prefix: &str) -> String {
     let key = key.as_bytes();
 
-    let mut checksum = blake2b_checksum(key);
-    checksum.reverse();
+    let checksum = account_checksum(key);
 
-    let mut account = "nano_".to_string();
+    let mut account = prefix.to_string();
     let data = [[0, 0, 0].as_slice(), key, &checksum].concat();
     account.push_str(&base32::encode(&data)[4..]);
     account
 }
 
+/// The checksum of a public key, as encoded in a `nano_` address
+pub(crate) fn account_checksum(key: &[u8]) -> [u8; 5] {
+    let mut checksum = blake2b_checksum(key);
+    checksum.reverse();
+    checksum
+}
+
 pub(crate) fn account_decode(account: &str) -> Result<CompressedEdwardsY, NanoError> {
-    if account.len() != 65 {
-        return Err(NanoError::InvalidAddressLength);
-    }
+    account_decode_with_prefix(account, ACCOUNT_PREFIX)
+}
 
-    if &account[..5] != "nano_" {
-        return Err(NanoError::InvalidAddressPrefix);
+pub(crate) fn account_decode_with_prefix(
+    account: &str,
+    prefix: &str,
+) -> Result<CompressedEdwardsY, NanoError> {
+    let (key, checksum) = account_decode_unchecked_with_prefix(account, prefix)?;
+    if checksum != account_checksum(&key) {
+        return Err(NanoError::InvalidAddressChecksum);
     }
+    try_compressed_from_slice(&key)
+}
 
-    let mut data = "1111".to_string();
-    data.push_str(&account[5..]);
+/// Decode a `nano_` address into its public key and checksum, without verifying the checksum
+pub(crate) fn account_decode_unchecked(account: &str) -> Result<([u8; 32], [u8; 5]), NanoError> {
+    account_decode_unchecked_with_prefix(account, ACCOUNT_PREFIX)
+}
 
-    let data = base32::decode(&data).ok_or(NanoError::InvalidBase32)?;
+fn account_decode_unchecked_with_prefix(
+    account: &str,
+    prefix: &str,
+) -> Result<([u8; 32], [u8; 5]), NanoError> {
+    if account.len() != prefix.len() + 60 {
+        return Err(NanoError::InvalidAddressLength);
+    }
+    let encoded = account
+        .strip_prefix(prefix)
+        .ok_or(NanoError::InvalidAddressPrefix)?;
 
-    let checksum = &data[35..40];
-    let key = &data[3..35];
-    let mut calculated_checksum = blake2b_checksum(key);
-    calculated_checksum.reverse();
+    let mut data = "1111".to_string();
+    data.push_str(encoded);
 
-    if checksum != calculated_checksum {
-        return Err(NanoError::InvalidAddressChecksum);
+    let data = base32::decode_strict(&data).ok_or(NanoError::InvalidBase32)?;
+    // the 4 padding bits at the start of the address must be zero
+    if data[..3] != [0, 0, 0] {
+        return Err(NanoError::InvalidBase32);
     }
-    try_compressed_from_slice(key)
+
+    let key = data[3..35].try_into().unwrap();
+    let checksum = data[35..40].try_into().unwrap();
+    Ok((key, checksum))
 }
 
 /// Return the "sub"-seed for the seed's account
 pub fn get_account_seed(master_seed: &SecretBytes<32>, i: u32) -> SecretBytes<32> {
-    blake2b256(&[master_seed.as_slice(), &i.to_be_bytes()].concat())
+    blake2b256(&secret_concat(&[master_seed.as_slice(), &i.to_be_bytes()]))
 }
 
 /// Return the private key, in `Scalar` form, for the seed's account
@@ -61,6 +99,11 @@ pub fn get_account_scalar(master_seed: &SecretBytes<32>, i: u32) -> Scalar {
 }
 
 /// Get work using the local CPU (likely very slow)
+#[cfg_attr(feature = "tracing", tracing::instrument(
+    level = "debug",
+    skip_all,
+    fields(hash = %hex::encode_upper(block_hash), difficulty = %hex::encode(difficulty))
+))]
 pub fn get_local_work(block_hash: [u8; 32], difficulty: [u8; 8]) -> [u8; 8] {
     let mut data: [u8; 40] = [[0; 8].as_slice(), &block_hash]
         .concat()
@@ -76,6 +119,8 @@ pub fn get_local_work(block_hash: [u8; 32], difficulty: [u8; 8]) -> [u8; 8] {
         if bytes >= difficulty {
             let mut work: [u8; 8] = data[..8].try_into().unwrap();
             work.reverse();
+            #[cfg(feature = "tracing")]
+            tracing::debug!(work = %hex::encode(work), "found work");
             return work;
         }
         i = 0;
@@ -89,15 +134,19 @@ pub fn get_local_work(block_hash: [u8; 32], difficulty: [u8; 8]) -> [u8; 8] {
     }
 }
 
-/// Check if the given work is valid, given a difficulty target
-pub fn check_work(work_hash: [u8; 32], difficulty: [u8; 8], work: [u8; 8]) -> bool {
+/// Get the difficulty which the given work achieves
+pub fn work_difficulty(work_hash: [u8; 32], work: [u8; 8]) -> [u8; 8] {
     let mut work = work;
     work.reverse();
 
     let mut bytes = blake2b_work(&[work.as_slice(), &work_hash].concat());
     bytes.reverse();
+    bytes
+}
 
-    bytes >= difficulty
+/// Check if the given work is valid, given a difficulty target
+pub fn check_work(work_hash: [u8; 32], difficulty: [u8; 8], work: [u8; 8]) -> bool {
+    work_difficulty(work_hash, work) >= difficulty
 }
 
 /// Given a specific `r` value, sign the `message` with the `Key`, returning a `Signature`.
@@ -123,8 +172,15 @@ pub fn sign_message_with_r(message: &[u8], private_key: &Key, r: &Scalar) -> Sig
 /// Sign the `message` with the `Key`, returning a `Signature`.
 ///
 /// This function does **not** produce identical signatures to the original Python `nanopy` library.
+///
+/// Buffers and scalars derived from the private key (including the nonce `r`) are zeroized when dropped.
+/// The internal state of the `blake2` hasher, and copies made by the compiler, cannot be zeroized.
+#[cfg_attr(feature = "tracing", tracing::instrument(level = "trace", skip_all))]
 pub fn sign_message(message: &[u8], private_key: &Key) -> Signature {
-    let r = blake2b_scalar(&[private_key.as_scalar().as_bytes(), message].concat());
+    let r = blake2b_scalar(&secret_concat(&[
+        private_key.as_scalar().as_slice(),
+        message,
+    ]));
     sign_message_with_r(message, private_key, &r)
 }
 
@@ -144,18 +200,88 @@ pub fn is_valid_signature(message: &[u8], signature: &Signature, public_key: &Ac
     signature.s * G == signature.r + (message * public_key.point)
 }
 
+/// Check if every account's `signature` for its `message` is valid, using batched verification.
+///
+/// This is much faster than checking each signature individually, but only reports whether *all* signatures are valid.
+/// Signatures are checked with the cofactored equation, so (unlike `is_valid_signature()`) signatures with small-order
+/// components may be accepted. Such signatures can only be produced deliberately.
+#[cfg_attr(feature = "tracing", tracing::instrument(
+    level = "trace",
+    skip_all,
+    fields(count = signatures.len())
+))]
+pub fn verify_batch(signatures: &[(&[u8], &Signature, &Account)]) -> bool {
+    if signatures.is_empty() {
+        return true;
+    }
+
+    // The random coefficients are derived from every signature, message, and public key,
+    // so that they cannot be chosen before the batch is fixed.
+    let mut transcript = Vec::new();
+    let mut challenges = Vec::with_capacity(signatures.len());
+    for (message, signature, public_key) in signatures {
+        let r_bytes = signature.r.compress().to_bytes();
+        let challenge =
+            blake2b512(&[&r_bytes, public_key.compressed.as_bytes(), *message].concat());
+        challenges.push(RawScalar::from_bytes_mod_order_wide(challenge.as_ref()));
+        transcript.extend_from_slice(&r_bytes);
+        transcript.extend_from_slice(signature.s.as_bytes());
+        transcript.extend_from_slice(public_key.compressed.as_bytes());
+        transcript.extend_from_slice(challenge.as_ref());
+    }
+    let seed = blake2b512(&transcript);
+
+    // sum(z * s)G - sum(z * R) - sum(z * H(R, A, m) * A) == 0
+    let mut s_sum = RawScalar::ZERO;
+    let mut scalars = Vec::with_capacity(signatures.len() * 2 + 1);
+    let mut points = Vec::with_capacity(signatures.len() * 2 + 1);
+    for (i, ((_, signature, public_key), challenge)) in
+        signatures.iter().zip(challenges).enumerate()
+    {
+        let z = blake2b512(&[seed.as_slice(), &(i as u64).to_be_bytes()].concat());
+        let mut z_bytes = [0; 32];
+        z_bytes[..16].copy_from_slice(&z.as_ref()[..16]);
+        let z = RawScalar::from_bytes_mod_order(z_bytes);
+
+        s_sum += z * signature.s;
+        scalars.push(-z);
+        points.push(signature.r);
+        scalars.push(-(z * challenge));
+        points.push(public_key.point);
+    }
+    scalars.push(s_sum);
+    points.push(G);
+
+    EdwardsPoint::vartime_multiscalar_mul(scalars, points)
+        .mul_by_cofactor()
+        .is_identity()
+}
+
 pub(crate) fn hash_block(block: &Block) -> [u8; 32] {
-    *blake2b256(
-        &[
+    let account = block.account.compressed.as_bytes();
+    let representative = block.representative.compressed.as_bytes();
+    let balance = block.balance.to_be_bytes();
+    let fields: &[&[u8]] = match &block.block_type {
+        // legacy blocks only hash the fields of their type
+        BlockType::Legacy(block_type) if block_type == "send" => {
+            &[&block.previous, &block.link, &balance]
+        }
+        BlockType::Legacy(block_type) if block_type == "receive" => &[&block.previous, &block.link],
+        BlockType::Legacy(block_type) if block_type == "open" => {
+            &[&block.link, representative, account]
+        }
+        BlockType::Legacy(block_type) if block_type == "change" => {
+            &[&block.previous, representative]
+        }
+        _ => &[
             [0; 31].as_slice(),
             &[6],
-            block.account.compressed.as_bytes(),
+            account,
             &block.previous,
-            block.representative.compressed.as_bytes(),
-            &block.balance.to_be_bytes(),
+            representative,
+            &balance,
             &block.link,
-        ]
-        .concat(),
-    )
-    .as_ref()
+        ],
+    };
+    *blake2b256(&fields.concat()).as_ref()
 }
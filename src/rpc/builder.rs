@@ -0,0 +1,367 @@
+use super::debug::{DebugRpc, DEFAULT_BATCH_SIZE};
+use super::transport::{default_transport, RpcTransport};
+use super::{RetryPolicy, Rpc, RpcError};
+use base64::prelude::{Engine, BASE64_STANDARD};
+use std::fmt::Debug;
+use std::sync::Arc;
+use std::time::Duration;
+
+#[cfg(feature = "wasm")]
+use super::transport::FetchTransport;
+#[cfg(feature = "rpc-isahc")]
+use super::transport::IsahcTransport;
+
+/// Which HTTP versions the client may use
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub enum HttpVersion {
+    /// Use HTTP/2 if the node supports it, otherwise HTTP/1.1
+    #[default]
+    Negotiate,
+    /// Only use HTTP/1.1
+    Http1Only,
+    /// Assume that the node supports HTTP/2, without negotiating.
+    /// Required for HTTP/2 over unencrypted (`http://`) connections.
+    Http2PriorKnowledge,
+}
+
+/// Connection settings for the HTTP transports.
+/// Settings which are `None` use the HTTP client's default.
+#[derive(Clone, Default, PartialEq, Eq)]
+pub(crate) struct HttpOptions {
+    pub(crate) connect_timeout: Option<Duration>,
+    pub(crate) tcp_keepalive: Option<Duration>,
+    pub(crate) pool_max_idle_per_host: Option<usize>,
+    pub(crate) pool_idle_timeout: Option<Duration>,
+    pub(crate) http_version: HttpVersion,
+    pub(crate) http2_keep_alive_interval: Option<Duration>,
+    pub(crate) proxy: Option<String>,
+    /// Sent with every request
+    pub(crate) headers: Vec<(String, String)>,
+}
+/// Header values may contain credentials, so only their names are shown
+impl Debug for HttpOptions {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        let headers: Vec<&str> = self.headers.iter().map(|(name, _)| name.as_str()).collect();
+        f.debug_struct("HttpOptions")
+            .field("connect_timeout", &self.connect_timeout)
+            .field("tcp_keepalive", &self.tcp_keepalive)
+            .field("pool_max_idle_per_host", &self.pool_max_idle_per_host)
+            .field("pool_idle_timeout", &self.pool_idle_timeout)
+            .field("http_version", &self.http_version)
+            .field("http2_keep_alive_interval", &self.http2_keep_alive_interval)
+            .field("proxy", &self.proxy)
+            .field("headers", &headers)
+            .finish()
+    }
+}
+
+/// Builder for `Rpc` and `DebugRpc`, with control over how connections to the node are reused.
+///
+/// A single HTTP client is created when the RPC is built, and its connection pool is shared by all clones of the RPC.
+/// For high-frequency callers, reuse (or clone) one RPC rather than building a new one for each request.
+#[derive(Debug, Clone)]
+pub struct RpcBuilder {
+    url: String,
+    #[cfg(feature = "rpc-isahc")]
+    isahc: bool,
+    #[cfg(feature = "wasm")]
+    fetch: bool,
+    options: HttpOptions,
+    retry: Option<RetryPolicy>,
+    batch_size: usize,
+    batch_concurrency: usize,
+    timeout: Option<Duration>,
+    transport: Option<Arc<dyn RpcTransport>>,
+}
+impl RpcBuilder {
+    pub fn new(url: &str) -> RpcBuilder {
+        RpcBuilder {
+            url: url.into(),
+            #[cfg(feature = "rpc-isahc")]
+            isahc: false,
+            #[cfg(feature = "wasm")]
+            fetch: false,
+            options: HttpOptions::default(),
+            retry: None,
+            batch_size: DEFAULT_BATCH_SIZE,
+            batch_concurrency: 1,
+            timeout: None,
+            transport: None,
+        }
+    }
+
+    /// Send requests through a proxy, such as `http://127.0.0.1:8080` or `socks5h://127.0.0.1:9050`.
+    ///
+    /// Not supported by `fetch`: this setting is ignored in the browser.
+    pub fn proxy(mut self, proxy: impl Into<Option<String>>) -> RpcBuilder {
+        self.options.proxy = proxy.into();
+        self
+    }
+
+    /// Use `isahc` (which works with any async runtime), even if the `rpc` feature is enabled
+    #[cfg(feature = "rpc-isahc")]
+    pub fn isahc(mut self) -> RpcBuilder {
+        self.isahc = true;
+        self
+    }
+
+    /// Use the browser's `fetch` API, even if the `rpc` or `rpc-isahc` feature is enabled
+    #[cfg(feature = "wasm")]
+    pub fn fetch(mut self) -> RpcBuilder {
+        self.fetch = true;
+        self
+    }
+
+    /// Fail requests which take longer than `timeout` with `RpcError::Timeout` (see `DebugRpc::set_timeout()`).
+    ///
+    /// Use `Rpc::with_timeout()` to override this for a single request.
+    pub fn timeout(mut self, timeout: Duration) -> RpcBuilder {
+        self.timeout = Some(timeout);
+        self
+    }
+
+    /// How long to wait for a connection to the node to be established.
+    ///
+    /// Not supported by `fetch`: this setting is ignored in the browser.
+    pub fn connect_timeout(mut self, timeout: Duration) -> RpcBuilder {
+        self.options.connect_timeout = Some(timeout);
+        self
+    }
+
+    /// Send a header with every request, such as the API key required by some hosted nodes
+    pub fn header(mut self, name: &str, value: &str) -> RpcBuilder {
+        self.options.headers.push((name.into(), value.into()));
+        self
+    }
+
+    /// Authenticate every request with HTTP basic authentication
+    pub fn basic_auth(self, username: &str, password: &str) -> RpcBuilder {
+        let credentials = BASE64_STANDARD.encode(format!("{username}:{password}"));
+        self.header("authorization", &format!("Basic {credentials}"))
+    }
+
+    /// Send TCP keep-alive probes on idle connections, at the given interval
+    pub fn tcp_keepalive(mut self, interval: Duration) -> RpcBuilder {
+        self.options.tcp_keepalive = Some(interval);
+        self
+    }
+
+    /// Maximum number of idle connections to keep open to the node.
+    /// Setting this to `0` disables connection reuse.
+    pub fn pool_max_idle_per_host(mut self, max: usize) -> RpcBuilder {
+        self.options.pool_max_idle_per_host = Some(max);
+        self
+    }
+
+    /// How long an idle connection is kept open before being closed
+    pub fn pool_idle_timeout(mut self, timeout: Duration) -> RpcBuilder {
+        self.options.pool_idle_timeout = Some(timeout);
+        self
+    }
+
+    pub fn http_version(mut self, version: HttpVersion) -> RpcBuilder {
+        self.options.http_version = version;
+        self
+    }
+
+    /// Send HTTP/2 `PING` frames at the given interval, to keep idle connections alive.
+    ///
+    /// Only supported by `reqwest`: this setting is ignored when using `isahc`.
+    pub fn http2_keep_alive_interval(mut self, interval: Duration) -> RpcBuilder {
+        self.options.http2_keep_alive_interval = Some(interval);
+        self
+    }
+
+    /// Retry requests which fail due to transient problems (see `RetryPolicy`)
+    pub fn retry_policy(mut self, policy: RetryPolicy) -> RpcBuilder {
+        self.retry = Some(policy);
+        self
+    }
+
+    /// Maximum number of accounts sent in a single request (see `DebugRpc::set_batch_size()`)
+    pub fn batch_size(mut self, batch_size: usize) -> RpcBuilder {
+        self.batch_size = batch_size;
+        self
+    }
+
+    /// Maximum number of batches requested at the same time (see `DebugRpc::set_batch_concurrency()`)
+    pub fn batch_concurrency(mut self, batch_concurrency: usize) -> RpcBuilder {
+        self.batch_concurrency = batch_concurrency;
+        self
+    }
+
+    /// Send requests through a custom `RpcTransport`, instead of the built-in HTTP clients.
+    ///
+    /// The proxy and HTTP settings are ignored, since they only apply to the built-in HTTP clients.
+    pub fn transport(mut self, transport: impl RpcTransport + 'static) -> RpcBuilder {
+        self.transport = Some(Arc::new(transport));
+        self
+    }
+
+    pub fn build(self) -> Result<Rpc, RpcError> {
+        Ok(Rpc(self.build_debug()?))
+    }
+
+    pub fn build_debug(self) -> Result<DebugRpc, RpcError> {
+        let transport = match self.transport {
+            Some(transport) => transport,
+            #[cfg(feature = "wasm")]
+            None if self.fetch => Arc::new(FetchTransport::new(&self.url, &self.options)?),
+            #[cfg(feature = "rpc-isahc")]
+            None if self.isahc => Arc::new(IsahcTransport::new(&self.url, &self.options)?),
+            None => default_transport(&self.url, &self.options)?,
+        };
+
+        let mut rpc = DebugRpc::with_transport(&self.url, self.options.proxy, transport);
+        rpc.set_retry_policy(self.retry);
+        rpc.set_batch_size(self.batch_size);
+        rpc.set_batch_concurrency(self.batch_concurrency);
+        rpc.set_timeout(self.timeout);
+        Ok(rpc)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::super::mock::MockRpc;
+    use super::super::transport::TransportFuture;
+    use super::{HttpVersion, RetryPolicy, RpcBuilder, RpcError, RpcTransport};
+    use crate::{Account, Key, SecretBytes};
+    use futures::executor::block_on;
+    use futures::future;
+    use serde_json::{json, Map, Value as JsonValue};
+    use std::time::Duration;
+
+    #[test]
+    fn builder() {
+        let builder = RpcBuilder::new("http://127.0.0.1:7076")
+            .proxy("proxy".to_string())
+            .tcp_keepalive(Duration::from_secs(30))
+            .pool_max_idle_per_host(4)
+            .pool_idle_timeout(Duration::from_secs(60))
+            .http_version(HttpVersion::Http1Only)
+            .http2_keep_alive_interval(Duration::from_secs(10))
+            .connect_timeout(Duration::from_secs(5))
+            .header("x-api-key", "key")
+            .basic_auth("user", "password")
+            .retry_policy(RetryPolicy::none());
+        assert!(builder.options.pool_max_idle_per_host == Some(4));
+        assert!(
+            builder.options.headers[1]
+                == ("authorization".into(), "Basic dXNlcjpwYXNzd29yZA==".into())
+        );
+        assert!(!format!("{builder:?}").contains("dXNlcjpwYXNzd29yZA=="));
+        assert!(builder.options.http_version == HttpVersion::Http1Only);
+
+        #[cfg(any(feature = "rpc", feature = "rpc-isahc"))]
+        {
+            let rpc = builder.build().unwrap();
+            assert!(rpc.get_url() == "http://127.0.0.1:7076");
+            assert!(rpc.get_proxy() == Some("proxy"));
+            assert!(rpc.get_retry_policy().unwrap().max_attempts == 1);
+            assert!(RpcBuilder::new("http://127.0.0.1:7076")
+                .header("invalid header", "value")
+                .build()
+                .is_err());
+        }
+        #[cfg(not(any(feature = "rpc", feature = "rpc-isahc", feature = "wasm")))]
+        assert!(builder.build().is_err());
+        #[cfg(feature = "wasm")]
+        assert!(RpcBuilder::new("http://127.0.0.1:7076")
+            .fetch()
+            .build()
+            .is_ok());
+    }
+
+    #[test]
+    fn custom_transport() {
+        let mock = MockRpc::new();
+        mock.respond(
+            "block_count",
+            json!({"count": "10", "unchecked": "0", "cemented": "10"}),
+        );
+        let rpc = RpcBuilder::new("ipc:///tmp/nano")
+            .transport(mock.clone())
+            .retry_policy(RetryPolicy::none())
+            .build()
+            .unwrap();
+        assert!(rpc.get_url() == "ipc:///tmp/nano");
+        assert!(block_on(rpc.block_count()).unwrap().count == 10);
+        assert!(mock.requests() == vec![json!({"action": "block_count"})]);
+    }
+
+    #[test]
+    fn batch_size() {
+        let accounts: Vec<Account> = (0..5)
+            .map(|i| Key::from_seed(&SecretBytes::from([0; 32]), i).to_account())
+            .collect();
+        let mock = MockRpc::new();
+        mock.on("accounts_frontiers", |request| {
+            let frontiers: Map<String, JsonValue> = request["accounts"]
+                .as_array()
+                .unwrap()
+                .iter()
+                .map(|account| {
+                    (
+                        account.as_str().unwrap().into(),
+                        hex::encode([1; 32]).into(),
+                    )
+                })
+                .collect();
+            Ok(json!({ "frontiers": frontiers }))
+        });
+        let rpc = RpcBuilder::new("ipc:///tmp/nano")
+            .transport(mock.clone())
+            .batch_size(2)
+            .batch_concurrency(2)
+            .build()
+            .unwrap();
+        assert!(rpc.get_batch_size() == 2);
+        assert!(rpc.get_batch_concurrency() == 2);
+
+        let frontiers = block_on(rpc.accounts_frontiers(&accounts)).unwrap();
+        assert!(frontiers == vec![Some([1; 32]); 5]);
+        let requests = mock.requests();
+        assert!(requests.len() == 3);
+        assert!(requests[2]["accounts"] == json!([accounts[4].to_string()]));
+
+        mock.remove("accounts_frontiers");
+        assert!(block_on(rpc.accounts_frontiers(&accounts)).is_err());
+        assert!(mock.requests().len() == 4);
+        assert!(block_on(rpc.accounts_frontiers(&[])).unwrap().is_empty());
+    }
+
+    /// A transport which never responds
+    #[derive(Debug)]
+    struct Unresponsive;
+    impl RpcTransport for Unresponsive {
+        fn send<'a>(&'a self, _request: &'a JsonValue) -> TransportFuture<'a> {
+            Box::pin(future::pending())
+        }
+    }
+
+    #[test]
+    fn timeout() {
+        let rpc = RpcBuilder::new("ipc:///tmp/nano")
+            .transport(Unresponsive)
+            .timeout(Duration::from_millis(10))
+            .build()
+            .unwrap();
+        assert!(rpc.get_timeout() == Some(Duration::from_millis(10)));
+        assert!(matches!(
+            block_on(rpc.block_count()),
+            Err(RpcError::Timeout)
+        ));
+
+        let mut rpc = rpc.with_timeout(Some(Duration::from_millis(20)));
+        assert!(rpc.get_timeout() == Some(Duration::from_millis(20)));
+        rpc.set_retry_policy(Some(RetryPolicy {
+            initial_delay: Duration::ZERO,
+            ..RetryPolicy::default()
+        }));
+        assert!(matches!(
+            block_on(rpc.block_count()),
+            Err(RpcError::Timeout)
+        ));
+    }
+}
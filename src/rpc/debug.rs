@@ -1,9 +1,26 @@
-use super::{encode, error::RpcError, parse, AccountInfo, BlockInfo, Receivable};
-use crate::{Account, Block};
+use super::transport::RpcTransport;
+use super::{
+    builder::RpcBuilder, encode, error::RpcError, parse, AccountInfo, ActiveDifficulty, Balances,
+    BlockConformance, BlockCount, BlockInfo, ConfirmationOptions, ConfirmationOutcome,
+    ConfirmationQuorum, CreatedBlock, ElectionInfo, Fixture, FixtureMode, LedgerEntry,
+    NodeTelemetry, NodeVersion, Peer, Receivable, RetryPolicy, Rpc, TrustAnchor, UncheckedBlock,
+    WorkValidation,
+};
+use crate::work::WorkProvider;
+use crate::{Account, AccountCache, Block, BlockType, Key};
 
+use futures::future::{self, Either};
+use futures::stream::{self, StreamExt};
+use futures_timer::Delay;
 use json::{Map, Value as JsonValue};
-use reqwest::{ClientBuilder, RequestBuilder};
 use serde_json as json;
+use std::future::Future;
+use std::sync::{Arc, Mutex, MutexGuard};
+use std::time::Duration;
+#[cfg(not(feature = "wasm"))]
+use std::time::Instant;
+#[cfg(feature = "wasm")]
+use web_time::Instant;
 
 macro_rules! request {
     ($rpc: expr, $json: expr) => {
@@ -12,15 +29,41 @@ macro_rules! request {
 }
 
 macro_rules! map_response {
-    ($response: expr, $new_result: expr) => {
+    ($response: expr, $new_result: expr) => {{
+        let result = node_error($new_result, $response.raw_response.as_ref());
         Response {
             raw_request: $response.raw_request,
             raw_response: $response.raw_response,
-            result: $new_result,
+            result,
         }
+    }};
+}
+
+/// If the response could not be parsed because the node returned an error instead,
+/// replace the parse error with `RpcError::NodeError`
+fn node_error<T>(
+    result: Result<T, RpcError>,
+    raw_response: Option<&JsonValue>,
+) -> Result<T, RpcError> {
+    let error = match raw_response.and_then(|json| json["error"].as_str()) {
+        Some(error) => error,
+        None => return result,
     };
+    match result {
+        Err(
+            RpcError::JsonError(_)
+            | RpcError::FromHexError(_)
+            | RpcError::InvalidAccount(_)
+            | RpcError::InvalidInteger(_)
+            | RpcError::InvalidJsonDataType(_),
+        ) => Err(RpcError::NodeError(error.into())),
+        result => result,
+    }
 }
 
+/// The default maximum number of accounts sent in a single request, see `DebugRpc::set_batch_size()`
+pub const DEFAULT_BATCH_SIZE: usize = 5000;
+
 #[derive(Debug)]
 pub struct Response<T> {
     pub raw_request: Option<JsonValue>,
@@ -37,24 +80,67 @@ impl<T> Response<T> {
     }
 }
 
+/// How long `active_difficulty_cached()` reuses the node's thresholds
+const DIFFICULTY_CACHE_DURATION: Duration = Duration::from_secs(60);
+
 /// See the official [Nano RPC documentation](https://docs.nano.org/commands/rpc-protocol/) for details.
-#[derive(Debug)]
+#[derive(Debug, Clone)]
 pub struct DebugRpc {
-    builder: RequestBuilder,
+    transport: Arc<dyn RpcTransport>,
     url: String,
     proxy: Option<String>,
+    account_cache: Option<AccountCache>,
+    retry: Option<RetryPolicy>,
+    fixture: Option<FixtureMode>,
+    batch_size: usize,
+    batch_concurrency: usize,
+    timeout: Option<Duration>,
+    /// The last result of `active_difficulty`, and when it was received
+    difficulty: Arc<Mutex<Option<(Instant, ActiveDifficulty)>>>,
 }
 impl DebugRpc {
+    /// Create a new RPC, using the default HTTP client.
+    ///
+    /// `reqwest` (which requires a `tokio` runtime) is used if the `rpc` feature is enabled,
+    /// otherwise `isahc` (which works with any runtime) is used if the `rpc-isahc` feature is enabled.
+    /// If neither is enabled, the browser's `fetch` API is used if the `wasm` feature is enabled.
     pub fn new(url: &str, proxy: impl Into<Option<String>>) -> Result<DebugRpc, RpcError> {
-        let rpc = DebugRpc {
-            builder: ClientBuilder::new().build()?.post(url),
+        RpcBuilder::new(url).proxy(proxy).build_debug()
+    }
+
+    /// Create a new RPC using `isahc`, which works with any async runtime
+    #[cfg(feature = "rpc-isahc")]
+    pub fn new_isahc(url: &str, proxy: impl Into<Option<String>>) -> Result<DebugRpc, RpcError> {
+        RpcBuilder::new(url).proxy(proxy).isahc().build_debug()
+    }
+
+    /// Configure a new RPC, with control over connection reuse and HTTP versions
+    pub fn builder(url: &str) -> RpcBuilder {
+        RpcBuilder::new(url)
+    }
+
+    pub(super) fn with_transport(
+        url: &str,
+        proxy: Option<String>,
+        transport: Arc<dyn RpcTransport>,
+    ) -> DebugRpc {
+        DebugRpc {
+            transport,
             url: url.into(),
-            proxy: proxy.into(),
-        };
-        if rpc.try_clone().is_none() {
-            return Err(RpcError::InvalidRPC);
+            proxy,
+            account_cache: None,
+            retry: None,
+            fixture: None,
+            batch_size: DEFAULT_BATCH_SIZE,
+            batch_concurrency: 1,
+            timeout: None,
+            difficulty: Arc::default(),
         }
-        Ok(rpc)
+    }
+
+    #[cfg(feature = "nodes")]
+    pub(super) fn transport(&self) -> Arc<dyn RpcTransport> {
+        self.transport.clone()
     }
 
     /// Get the url of this RPC
@@ -67,27 +153,214 @@ impl DebugRpc {
         self.proxy.as_deref()
     }
 
-    /// Same as `command`, but *everything* must be set manually
-    pub async fn _raw_request(&self, json: JsonValue) -> Response<JsonValue> {
-        let response_json = self
-            .clone()
-            .builder
-            .json(&json)
-            .send()
-            .await
-            .map_err(RpcError::ReqwestError)
-            .map(|response| response.json::<JsonValue>());
+    /// Get the `AccountCache` used when parsing responses, if set
+    pub fn get_account_cache(&self) -> Option<&AccountCache> {
+        self.account_cache.as_ref()
+    }
 
-        let result = match response_json {
-            Ok(response) => response.await.map_err(RpcError::ReqwestError),
-            Err(err) => Err(err),
+    /// Set the `AccountCache` used when parsing responses.
+    /// The cache may be shared with other RPCs, or other parts of the application.
+    pub fn set_account_cache(&mut self, cache: Option<AccountCache>) {
+        self.account_cache = cache
+    }
+
+    /// Get the `RetryPolicy` of this RPC, if set
+    pub fn get_retry_policy(&self) -> Option<&RetryPolicy> {
+        self.retry.as_ref()
+    }
+
+    /// Retry requests which fail due to transient problems.
+    /// If `None`, failed requests are never retried.
+    pub fn set_retry_policy(&mut self, policy: Option<RetryPolicy>) {
+        self.retry = policy
+    }
+
+    /// Get the timeout of each request, if set
+    pub fn get_timeout(&self) -> Option<Duration> {
+        self.timeout
+    }
+
+    /// Fail requests which take longer than `timeout` with `RpcError::Timeout`.
+    /// If a `RetryPolicy` is set, each attempt has its own timeout, and timeouts are retried.
+    ///
+    /// If `None`, requests only time out if the HTTP client times out.
+    pub fn set_timeout(&mut self, timeout: Option<Duration>) {
+        self.timeout = timeout
+    }
+
+    /// Get a clone of this RPC with a different timeout, for a single slow request (such as `work_generate`).
+    /// The clone shares this RPC's connections.
+    pub fn with_timeout(&self, timeout: Option<Duration>) -> DebugRpc {
+        let mut rpc = self.clone();
+        rpc.set_timeout(timeout);
+        rpc
+    }
+
+    /// Get the maximum number of accounts sent in a single request
+    pub fn get_batch_size(&self) -> usize {
+        self.batch_size
+    }
+
+    /// Set the maximum number of accounts sent in a single request (at least `1`).
+    ///
+    /// `accounts_balances`, `accounts_frontiers` and `accounts_receivable` split larger slices into several requests,
+    /// and merge the results. The raw request and response are those of the last request.
+    pub fn set_batch_size(&mut self, batch_size: usize) {
+        self.batch_size = batch_size.max(1)
+    }
+
+    /// Get the maximum number of batches which are requested at the same time
+    pub fn get_batch_concurrency(&self) -> usize {
+        self.batch_concurrency
+    }
+
+    /// Set the maximum number of batches (see `set_batch_size`) which are requested at the same time (at least `1`).
+    ///
+    /// By default, batches are requested one at a time.
+    /// Results are always merged in the order of the accounts, regardless of which batch completes first.
+    pub fn set_batch_concurrency(&mut self, batch_concurrency: usize) {
+        self.batch_concurrency = batch_concurrency.max(1)
+    }
+
+    /// Create an RPC which replays the responses recorded in `fixture`, without sending any requests
+    pub fn from_fixture(fixture: Fixture) -> DebugRpc {
+        let mut rpc = DebugRpc::with_transport("fixture", None, Arc::new(fixture.clone()));
+        rpc.set_fixture_mode(Some(FixtureMode::Replay(fixture)));
+        rpc
+    }
+
+    /// Get the `FixtureMode` of this RPC, if set
+    pub fn get_fixture_mode(&self) -> Option<&FixtureMode> {
+        self.fixture.as_ref()
+    }
+
+    /// Record requests to, or replay responses from, a `Fixture`
+    pub fn set_fixture_mode(&mut self, mode: Option<FixtureMode>) {
+        self.fixture = mode
+    }
+
+    async fn send(&self, json: &JsonValue) -> Result<JsonValue, RpcError> {
+        if let Some(FixtureMode::Replay(fixture)) = &self.fixture {
+            return fixture.take(json).ok_or(RpcError::NoRecordedResponse);
+        }
+
+        let response = match &self.retry {
+            Some(policy) => self.send_with_retries(json, policy).await?,
+            None => self.send_once(json).await?,
         };
 
+        if let Some(FixtureMode::Record(fixture)) = &self.fixture {
+            fixture.record(json.clone(), response.clone())
+        }
+        Ok(response)
+    }
+
+    async fn send_once(&self, json: &JsonValue) -> Result<JsonValue, RpcError> {
+        let timeout = match self.timeout {
+            Some(timeout) => timeout,
+            None => return self.transport.send(json).await,
+        };
+        match future::select(self.transport.send(json), Delay::new(timeout)).await {
+            Either::Left((response, _)) => response,
+            Either::Right(_) => Err(RpcError::Timeout),
+        }
+    }
+
+    async fn send_with_retries(
+        &self,
+        json: &JsonValue,
+        policy: &RetryPolicy,
+    ) -> Result<JsonValue, RpcError> {
+        let mut retry = 0;
+        loop {
+            let response = self.send_once(json).await;
+            let retryable = match &response {
+                Ok(response) => policy.is_retryable_response(response),
+                Err(err) => (policy.is_retryable)(err),
+            };
+            if !retryable || retry + 1 >= policy.max_attempts {
+                return response;
+            }
+
+            #[cfg(feature = "tracing")]
+            tracing::debug!(retry, "retrying request");
+            Delay::new(policy.jittered_delay(retry)).await;
+            retry += 1;
+        }
+    }
+
+    /// Split `accounts` into batches of at most `batch_size`, request up to `batch_concurrency` of them at a time,
+    /// and merge the results in order
+    async fn batched<'a, T, F, Fut>(&self, accounts: &'a [Account], request: F) -> Response<Vec<T>>
+    where
+        F: Fn(&'a [Account]) -> Fut,
+        Fut: Future<Output = Response<Vec<T>>>,
+    {
+        // futures do nothing until polled, so creating them all up front does not send any requests
+        let batches: Vec<Fut> = accounts.chunks(self.batch_size).map(request).collect();
+        let mut responses = stream::iter(batches).buffered(self.batch_concurrency);
+
+        let mut merged = Response::no_request(Ok(Vec::with_capacity(accounts.len())));
+        while let Some(response) = responses.next().await {
+            merged.raw_request = response.raw_request;
+            merged.raw_response = response.raw_response;
+            match (&mut merged.result, response.result) {
+                (Ok(results), Ok(batch)) => results.extend(batch),
+                (_, result) => {
+                    merged.result = result;
+                    break;
+                }
+            }
+        }
+        merged
+    }
+
+    /// Same as `command`, but *everything* must be set manually.
+    ///
+    /// With the `tracing` feature, each request is given a span with its `action`, the node's `url`,
+    /// and (once it completes) its `latency_ms` and `outcome`.
+    #[cfg_attr(feature = "tracing", tracing::instrument(
+        level = "debug",
+        skip_all,
+        fields(
+            action = json["action"].as_str().unwrap_or_default(),
+            url = self.url,
+            latency_ms = tracing::field::Empty,
+            outcome = tracing::field::Empty,
+        )
+    ))]
+    pub async fn _raw_request(&self, json: JsonValue) -> Response<JsonValue> {
+        #[cfg(feature = "tracing")]
+        let start = Instant::now();
+
+        let result = self.send(&json).await;
+
         let raw_response = match &result {
             Ok(json) => Some(json.clone()),
             Err(_) => None,
         };
 
+        #[cfg(feature = "tracing")]
+        {
+            let outcome = match &result {
+                Ok(json) if !json["error"].is_null() => "node_error",
+                Ok(_) => "ok",
+                Err(RpcError::Timeout) => "timeout",
+                Err(_) => "failed",
+            };
+            let span = tracing::Span::current();
+            span.record("latency_ms", start.elapsed().as_millis() as u64);
+            span.record("outcome", outcome);
+
+            match &result {
+                Ok(json) if !json["error"].is_null() => {
+                    tracing::debug!(error = %json["error"], "node returned an error")
+                }
+                Ok(_) => tracing::trace!("request succeeded"),
+                Err(err) => tracing::debug!(error = %err, "request failed"),
+            }
+        }
+
         Response {
             raw_request: Some(json),
             raw_response,
@@ -114,8 +387,54 @@ impl DebugRpc {
         map_response!(response, result)
     }
 
+    /// Same as `account_balance`, but also returns the sum of the receivable transactions
+    pub async fn account_balance_detailed(&self, account: &Account) -> Response<Balances> {
+        let response = request!(self, encode::account_balance(account));
+        let result = match response.result {
+            Ok(json) => parse::account_balance_detailed(json),
+            Err(err) => Err(err),
+        };
+        map_response!(response, result)
+    }
+
+    /// Returns `None` if the account has not been opened
+    pub async fn account_block_count(&self, account: &Account) -> Response<Option<usize>> {
+        let response = request!(self, encode::account_block_count(account));
+        let result = match response.result {
+            Ok(json) => parse::account_block_count(json),
+            Err(err) => Err(err),
+        };
+        map_response!(response, result)
+    }
+
+    /// The account with the given public key.
+    ///
+    /// Checked against the local conversion (`Account::from_bytes()`), which should be used instead where possible.
+    pub async fn account_get(&self, public_key: &[u8; 32]) -> Response<Account> {
+        let response = request!(self, encode::account_get(public_key));
+        let result = match response.result {
+            Ok(json) => parse::account_get(json, public_key, self.get_account_cache()),
+            Err(err) => Err(err),
+        };
+        map_response!(response, result)
+    }
+
+    /// Indirect, relies on `accounts_frontiers`.
+    ///
+    /// Returns `false` if the account has not been opened.
+    pub async fn account_exists(&self, account: &Account) -> Response<bool> {
+        let response = self.accounts_frontiers(std::slice::from_ref(account)).await;
+        let result = match response.result {
+            Ok(frontiers) => Ok(frontiers.first().is_some_and(|frontier| frontier.is_some())),
+            Err(err) => Err(err),
+        };
+        map_response!(response, result)
+    }
+
     /// Lists the account's blocks, starting at `head` (or the newest block if `head` is `None`), and going back at most `count` number of blocks.
-    /// Will stop at first legacy block.
+    ///
+    /// Legacy blocks other than `send` blocks do not contain their balance, so it is found from the blocks around them.
+    /// Will stop at the first legacy block whose balance cannot be found.
     pub async fn account_history(
         &self,
         account: &Account,
@@ -125,7 +444,60 @@ impl DebugRpc {
     ) -> Response<Vec<Block>> {
         let response = request!(self, encode::account_history(account, count, head, offset));
         let result = match response.result {
-            Ok(json) => parse::account_history(json, account),
+            Ok(json) => parse::account_history(json, account, self.get_account_cache()),
+            Err(err) => Err(err),
+        };
+        map_response!(response, result)
+    }
+
+    /// Lists the account's blocks, starting at the newest block, and going back to (and including) the `anchor` block.
+    /// Blocks are requested `page_size` at a time, with `head` set to the `previous` of the last block,
+    /// until the anchor is reached.
+    ///
+    /// Since every block is linked to the previous one by its hash, and every signature is checked,
+    /// the returned blocks are verified against the anchor, rather than simply trusted.
+    /// If the history does not reach the anchor, or the anchor's balance does not match, an error is returned.
+    ///
+    /// The raw request and response are those of the last page.
+    pub async fn account_history_anchored(
+        &self,
+        account: &Account,
+        page_size: usize,
+        anchor: &TrustAnchor,
+    ) -> Response<Vec<Block>> {
+        let page_size = page_size.max(1);
+        let mut history: Vec<Block> = vec![];
+        loop {
+            let head = history.last().map(|block| block.previous);
+            let response = self.account_history(account, page_size, head, None).await;
+            let page = match response.result {
+                Ok(page) => page,
+                Err(err) => return map_response!(response, Err(err)),
+            };
+            if let (Some(head), Some(first)) = (head, page.first()) {
+                if first.hash() != head {
+                    return map_response!(response, Err(RpcError::InvalidData));
+                }
+            }
+
+            let reached = page.iter().any(|block| block.hash() == anchor.hash);
+            let last_page = page.len() < page_size;
+            history.extend(page);
+            let opened = history.last().is_none_or(|block| block.previous == [0; 32]);
+            if reached || last_page || opened {
+                let result = parse::account_history_anchored(history, anchor);
+                return map_response!(response, result);
+            }
+        }
+    }
+
+    /// The public key of the account.
+    ///
+    /// Checked against the local conversion (`Account::to_hex()`), which should be used instead where possible.
+    pub async fn account_key(&self, account: &Account) -> Response<[u8; 32]> {
+        let response = request!(self, encode::account_key(account));
+        let result = match response.result {
+            Ok(json) => parse::account_key(json, account),
             Err(err) => Err(err),
         };
         map_response!(response, result)
@@ -155,11 +527,22 @@ impl DebugRpc {
         map_response!(response, result)
     }
 
+    /// The voting weight delegated to the account
+    pub async fn account_weight(&self, account: &Account) -> Response<u128> {
+        let response = request!(self, encode::account_weight(account));
+        let result = match response.result {
+            Ok(json) => parse::account_weight(json),
+            Err(err) => Err(err),
+        };
+        map_response!(response, result)
+    }
+
     pub async fn accounts_balances(&self, accounts: &[Account]) -> Response<Vec<u128>> {
-        if accounts.is_empty() {
-            return Response::no_request(Ok(vec![]));
-        }
+        self.batched(accounts, |batch| self.accounts_balances_batch(batch))
+            .await
+    }
 
+    async fn accounts_balances_batch(&self, accounts: &[Account]) -> Response<Vec<u128>> {
         let response = request!(self, encode::accounts_balances(accounts));
         let result = match response.result {
             Ok(json) => parse::accounts_balances(json, accounts),
@@ -168,16 +551,43 @@ impl DebugRpc {
         map_response!(response, result)
     }
 
+    /// Same as `accounts_balances`, but also returns the sum of each account's receivable transactions
+    pub async fn accounts_balances_detailed(
+        &self,
+        accounts: &[Account],
+    ) -> Response<Vec<Balances>> {
+        self.batched(accounts, |batch| {
+            self.accounts_balances_detailed_batch(batch)
+        })
+        .await
+    }
+
+    async fn accounts_balances_detailed_batch(
+        &self,
+        accounts: &[Account],
+    ) -> Response<Vec<Balances>> {
+        let response = request!(self, encode::accounts_balances(accounts));
+        let result = match response.result {
+            Ok(json) => parse::accounts_balances_detailed(json, accounts),
+            Err(err) => Err(err),
+        };
+        map_response!(response, result)
+    }
+
     /// Returns the hash of the frontier (newest) block of the given accounts.
     /// If an account is not yet opened, its frontier will be returned as `None`.
     pub async fn accounts_frontiers(
         &self,
         accounts: &[Account],
     ) -> Response<Vec<Option<[u8; 32]>>> {
-        if accounts.is_empty() {
-            return Response::no_request(Ok(vec![]));
-        }
+        self.batched(accounts, |batch| self.accounts_frontiers_batch(batch))
+            .await
+    }
 
+    async fn accounts_frontiers_batch(
+        &self,
+        accounts: &[Account],
+    ) -> Response<Vec<Option<[u8; 32]>>> {
         let response = request!(self, encode::accounts_frontiers(accounts));
         let result = match response.result {
             Ok(json) => parse::accounts_frontiers(json, accounts),
@@ -186,17 +596,25 @@ impl DebugRpc {
         map_response!(response, result)
     }
 
-    /// For each account, returns the receivable transactions as `Vec<Receivable>`
+    /// For each account, returns the receivable transactions (including their senders) as `Vec<Receivable>`
     pub async fn accounts_receivable(
         &self,
         accounts: &[Account],
         count: usize,
         threshold: u128,
     ) -> Response<Vec<Vec<Receivable>>> {
-        if accounts.is_empty() {
-            return Response::no_request(Ok(vec![]));
-        }
+        self.batched(accounts, |batch| {
+            self.accounts_receivable_batch(batch, count, threshold)
+        })
+        .await
+    }
 
+    async fn accounts_receivable_batch(
+        &self,
+        accounts: &[Account],
+        count: usize,
+        threshold: u128,
+    ) -> Response<Vec<Vec<Receivable>>> {
         let response = request!(
             self,
             encode::accounts_receivable(accounts, count, threshold)
@@ -208,6 +626,35 @@ impl DebugRpc {
         map_response!(response, result)
     }
 
+    /// Same as `accounts_receivable`, but looks up each send block with `blocks_info`,
+    /// dropping any transactions whose send block does not exist, or does not actually send the amount to the recipient.
+    ///
+    /// The raw request and response are those of the `blocks_info` call.
+    pub async fn accounts_receivable_verified(
+        &self,
+        accounts: &[Account],
+        count: usize,
+        threshold: u128,
+    ) -> Response<Vec<Vec<Receivable>>> {
+        let response = self.accounts_receivable(accounts, count, threshold).await;
+        let receivable = match response.result {
+            Ok(receivable) => receivable,
+            Err(err) => return map_response!(response, Err(err)),
+        };
+
+        let hashes: Vec<[u8; 32]> = receivable
+            .iter()
+            .flatten()
+            .map(|receivable| receivable.block_hash)
+            .collect();
+        let response = self.blocks_info(&hashes).await;
+        let result = match response.result {
+            Ok(infos) => parse::accounts_receivable_verified(receivable, infos),
+            Err(err) => Err(err),
+        };
+        map_response!(response, result)
+    }
+
     /// If an account is not yet opened, its representative will be returned as `None`
     pub async fn accounts_representatives(
         &self,
@@ -219,7 +666,7 @@ impl DebugRpc {
 
         let response = request!(self, encode::accounts_representatives(accounts));
         let result = match response.result {
-            Ok(json) => parse::accounts_representatives(json, accounts),
+            Ok(json) => parse::accounts_representatives(json, accounts, self.get_account_cache()),
             Err(err) => Err(err),
         };
         map_response!(response, result)
@@ -229,7 +676,7 @@ impl DebugRpc {
     pub async fn block_info(&self, hash: [u8; 32]) -> Response<Option<BlockInfo>> {
         let response = request!(self, encode::block_info(hash));
         let result = match response.result {
-            Ok(json) => parse::block_info(json),
+            Ok(json) => parse::block_info(json, self.get_account_cache()),
             Err(err) => Err(err),
         };
         map_response!(response, result)
@@ -243,7 +690,78 @@ impl DebugRpc {
 
         let response = request!(self, encode::blocks_info(hashes));
         let result = match response.result {
-            Ok(json) => parse::blocks_info(json, hashes),
+            Ok(json) => parse::blocks_info(json, hashes, self.get_account_cache()),
+            Err(err) => Err(err),
+        };
+        map_response!(response, result)
+    }
+
+    /// Get the hashes of the block and its predecessors, newest first
+    pub async fn chain(&self, hash: [u8; 32], count: usize) -> Response<Vec<[u8; 32]>> {
+        let response = request!(self, encode::chain(hash, count));
+        let result = match response.result {
+            Ok(json) => parse::chain(json),
+            Err(err) => Err(err),
+        };
+        map_response!(response, result)
+    }
+
+    /// Same as `chain`, but fetches the blocks with `blocks_info`,
+    /// and checks that they are signed and link together.
+    /// Stops at the first legacy block.
+    pub async fn chain_verified(&self, hash: [u8; 32], count: usize) -> Response<Vec<BlockInfo>> {
+        self.walk_chain_verified(hash, count, false).await
+    }
+
+    /// Get the hashes of the block and its successors, oldest first
+    pub async fn successors(&self, hash: [u8; 32], count: usize) -> Response<Vec<[u8; 32]>> {
+        let response = request!(self, encode::successors(hash, count));
+        let result = match response.result {
+            Ok(json) => parse::chain(json),
+            Err(err) => Err(err),
+        };
+        map_response!(response, result)
+    }
+
+    /// Same as `successors`, but fetches the blocks with `blocks_info`,
+    /// and checks that they are signed and link together.
+    /// Returns an empty list if `hash` is a legacy block.
+    pub async fn successors_verified(
+        &self,
+        hash: [u8; 32],
+        count: usize,
+    ) -> Response<Vec<BlockInfo>> {
+        self.walk_chain_verified(hash, count, true).await
+    }
+
+    async fn walk_chain_verified(
+        &self,
+        hash: [u8; 32],
+        count: usize,
+        successors: bool,
+    ) -> Response<Vec<BlockInfo>> {
+        let response = match successors {
+            true => self.successors(hash, count).await,
+            false => self.chain(hash, count).await,
+        };
+        let hashes = match response.result {
+            Ok(hashes) => hashes,
+            Err(err) => return map_response!(response, Err(err)),
+        };
+
+        let response = self.blocks_info(&hashes).await;
+        let result = match response.result {
+            Ok(infos) => parse::chain_verified(&hashes, infos, successors),
+            Err(err) => Err(err),
+        };
+        map_response!(response, result)
+    }
+
+    /// The node's peers, sorted by address
+    pub async fn peers(&self) -> Response<Vec<Peer>> {
+        let response = request!(self, encode::peers());
+        let result = match response.result {
+            Ok(json) => parse::peers(json),
             Err(err) => Err(err),
         };
         map_response!(response, result)
@@ -264,6 +782,499 @@ impl DebugRpc {
         map_response!(response, result)
     }
 
+    /// Publish the block, first generating work for it with the node's `work_generate` if its work is not valid
+    /// for the given difficulty.
+    ///
+    /// The raw request and response are those of the `process` call.
+    pub async fn process_with_work(
+        &self,
+        block: &mut Block,
+        difficulty: [u8; 8],
+    ) -> Response<[u8; 32]> {
+        self.process_with_work_from(block, difficulty, &Rpc(self.clone()))
+            .await
+    }
+
+    /// Same as `process_with_work`, but generates work with the given `WorkProvider`
+    pub async fn process_with_work_from(
+        &self,
+        block: &mut Block,
+        difficulty: [u8; 8],
+        provider: &dyn WorkProvider,
+    ) -> Response<[u8; 32]> {
+        if !block.has_valid_work(difficulty) {
+            match provider.generate(block.work_hash(), difficulty).await {
+                Ok(work) => block.set_work(work),
+                Err(err) => return Response::no_request(Err(err)),
+            }
+        }
+        self.process(block).await
+    }
+
+    /// Publish the block, and wait until it is either confirmed, replaced by a fork, or the timeout is reached.
+    /// Publishing a block which already exists on the ledger is not considered an error.
+    ///
+    /// A block which the node has not seen (yet) is waited for. It is only considered to be replaced by a fork
+    /// if the node rejects it as a fork, or the node has a different block after `block.previous`
+    /// (or a different `open` block, if `block` opens its account).
+    ///
+    /// The raw request and response are those of the last call made to the node.
+    pub async fn process_and_confirm(
+        &self,
+        block: &Block,
+        options: ConfirmationOptions,
+    ) -> Response<ConfirmationOutcome> {
+        let deadline = Instant::now() + options.timeout;
+
+        let response = self.process(block).await;
+        if let Err(err) = response.result {
+            let node_error = response
+                .raw_response
+                .as_ref()
+                .and_then(|json| json["error"].as_str());
+            match node_error {
+                Some("Fork") => return map_response!(response, Ok(ConfirmationOutcome::Forked)),
+                // the block has already been published
+                Some("Old block") => (),
+                _ => return map_response!(response, Err(err)),
+            }
+        }
+
+        self.poll_confirmation(block.hash(), options.poll_interval, deadline, Some(block))
+            .await
+    }
+
+    /// Wait until the block with the given hash is confirmed, or the timeout is reached.
+    ///
+    /// Unlike `process_and_confirm`, the block is never checked for forks,
+    /// so this never returns `ConfirmationOutcome::Forked`.
+    ///
+    /// The raw request and response are those of the last call made to the node.
+    pub async fn wait_for_confirmation(
+        &self,
+        hash: [u8; 32],
+        options: ConfirmationOptions,
+    ) -> Response<ConfirmationOutcome> {
+        let deadline = Instant::now() + options.timeout;
+        self.poll_confirmation(hash, options.poll_interval, deadline, None)
+            .await
+    }
+
+    /// Poll `block_info` until the block is confirmed, or the deadline is reached.
+    /// If the node does not have the block, and `published` is given, check whether it was replaced by a fork.
+    async fn poll_confirmation(
+        &self,
+        hash: [u8; 32],
+        poll_interval: Duration,
+        deadline: Instant,
+        published: Option<&Block>,
+    ) -> Response<ConfirmationOutcome> {
+        loop {
+            let response = self.block_info(hash).await;
+            let info = match response.result {
+                Ok(info) => info,
+                Err(err) => return map_response!(response, Err(err)),
+            };
+            if let Some(outcome) = parse::confirmation_outcome(info.clone()) {
+                return map_response!(response, Ok(outcome));
+            }
+            if let (None, Some(block)) = (info, published) {
+                let forked = self.is_forked(block).await;
+                match forked.result {
+                    Ok(true) => return map_response!(forked, Ok(ConfirmationOutcome::Forked)),
+                    Ok(false) => (),
+                    Err(err) => return map_response!(forked, Err(err)),
+                }
+            }
+            if Instant::now() + poll_interval > deadline {
+                return map_response!(response, Ok(ConfirmationOutcome::TimedOut));
+            }
+            Delay::new(poll_interval).await;
+        }
+    }
+
+    /// Check whether the node has a different block in place of `block`:
+    /// a different successor of `block.previous`, or a different `open` block for the account.
+    ///
+    /// Returns `false` if the node has not seen the blocks needed to tell.
+    async fn is_forked(&self, block: &Block) -> Response<bool> {
+        let hash = block.hash();
+        if block.previous == [0; 32] {
+            let response = self.account_info(&block.account).await;
+            let result = response
+                .result
+                .map(|info| info.is_some_and(|info| info.open_block != hash));
+            return map_response!(response, result);
+        }
+        let response = self.block_info(block.previous).await;
+        let result = response.result.map(|previous| {
+            previous
+                .and_then(|previous| previous.successor)
+                .is_some_and(|successor| successor != hash)
+        });
+        map_response!(response, result)
+    }
+
+    /// The receivable transactions of a single account, including their senders, sorted by amount (highest first)
+    pub async fn receivable(
+        &self,
+        account: &Account,
+        count: usize,
+        threshold: u128,
+    ) -> Response<Vec<Receivable>> {
+        let response = request!(self, encode::receivable(account, count, threshold));
+        let result = match response.result {
+            Ok(json) => parse::receivable(json, account, self.get_account_cache()),
+            Err(err) => Err(err),
+        };
+        map_response!(response, result)
+    }
+
+    /// The minimum amount which the node's wallets will automatically receive
+    pub async fn receive_minimum(&self) -> Response<u128> {
+        let response = request!(self, encode::receive_minimum());
+        let result = match response.result {
+            Ok(json) => parse::receive_minimum(json),
+            Err(err) => Err(err),
+        };
+        map_response!(response, result)
+    }
+
+    /// Makes the node search for receivable transactions for the accounts in one of its wallets, and receive them.
+    /// Returns whether the search was started.
+    ///
+    /// Requires `enable_control` on the node.
+    pub async fn search_receivable(&self, wallet: &[u8; 32]) -> Response<bool> {
+        let response = request!(self, encode::search_receivable(wallet));
+        let result = match response.result {
+            Ok(json) => parse::search_receivable(json),
+            Err(err) => Err(err),
+        };
+        map_response!(response, result)
+    }
+
+    /// Makes the node search for receivable transactions for the accounts in all of its unlocked wallets, and receive them.
+    ///
+    /// Requires `enable_control` on the node.
+    pub async fn search_receivable_all(&self) -> Response<()> {
+        let response = request!(self, encode::search_receivable_all());
+        let result = match response.result {
+            Ok(json) => parse::search_receivable_all(json),
+            Err(err) => Err(err),
+        };
+        map_response!(response, result)
+    }
+
+    pub async fn active_difficulty(&self) -> Response<ActiveDifficulty> {
+        let response = request!(self, encode::active_difficulty());
+        let result = match response.result {
+            Ok(json) => parse::active_difficulty(json),
+            Err(err) => Err(err),
+        };
+        map_response!(response, result)
+    }
+
+    fn lock_difficulty(&self) -> MutexGuard<'_, Option<(Instant, ActiveDifficulty)>> {
+        self.difficulty
+            .lock()
+            .unwrap_or_else(|poisoned| poisoned.into_inner())
+    }
+
+    /// Same as `active_difficulty`, but reuses the result for up to a minute.
+    /// The cached result is shared by all clones of this RPC.
+    ///
+    /// If the cached result is used, there is no raw request or response.
+    pub async fn active_difficulty_cached(&self) -> Response<ActiveDifficulty> {
+        if let Some((received, difficulty)) = *self.lock_difficulty() {
+            if received.elapsed() < DIFFICULTY_CACHE_DURATION {
+                return Response::no_request(Ok(difficulty));
+            }
+        }
+        let response = self.active_difficulty().await;
+        if let Ok(difficulty) = &response.result {
+            *self.lock_difficulty() = Some((Instant::now(), *difficulty))
+        }
+        response
+    }
+
+    /// The total supply, minus the balances of the burn and reserve accounts
+    pub async fn available_supply(&self) -> Response<u128> {
+        let response = request!(self, encode::available_supply());
+        let result = match response.result {
+            Ok(json) => parse::available_supply(json),
+            Err(err) => Err(err),
+        };
+        map_response!(response, result)
+    }
+
+    /// The account which created the block.
+    /// Blocks that don't exist will return `None`.
+    pub async fn block_account(&self, hash: [u8; 32]) -> Response<Option<Account>> {
+        let response = request!(self, encode::block_account(hash));
+        let result = match response.result {
+            Ok(json) => parse::block_account(json, self.get_account_cache()),
+            Err(err) => Err(err),
+        };
+        map_response!(response, result)
+    }
+
+    /// Have the node create and sign `block` (a `state` block) with `key`, using the block's `work`.
+    ///
+    /// **The private key is sent to the node**: only use this with a node which you control.
+    /// Blocks can be created locally with `BlockBuilder`, so this is mostly useful for testing (see `block_create_conformance()`).
+    pub async fn block_create(&self, block: &Block, key: &Key) -> Response<CreatedBlock> {
+        if !block.block_type.is_state() {
+            return Response::no_request(Err(RpcError::LegacyBlockType));
+        }
+
+        let response = request!(self, encode::block_create(block, key));
+        let result = match response.result {
+            Ok(json) => {
+                parse::block_create(json, block.block_type.clone(), self.get_account_cache())
+            }
+            Err(err) => Err(err),
+        };
+        map_response!(response, result)
+    }
+
+    /// Have the node create `block` with `key` (as in `block_create()`), and compare it to the block signed locally,
+    /// checking that this library hashes, signs, and serializes blocks exactly as the node does.
+    ///
+    /// **The private key is sent to the node**: only use this with a node which you control, and a key used only for testing.
+    pub async fn block_create_conformance(
+        &self,
+        block: &Block,
+        key: &Key,
+    ) -> Response<BlockConformance> {
+        if !block.block_type.is_state() {
+            return Response::no_request(Err(RpcError::LegacyBlockType));
+        }
+
+        let mut local = block.clone();
+        local.sign(key);
+        let response = request!(self, encode::block_create(block, key));
+        let result = match response.result {
+            Ok(json) => parse::block_create_conformance(&json, &local, self.get_account_cache()),
+            Err(err) => Err(err),
+        };
+        map_response!(response, result)
+    }
+
+    pub async fn block_count(&self) -> Response<BlockCount> {
+        let response = request!(self, encode::block_count());
+        let result = match response.result {
+            Ok(json) => parse::block_count(json),
+            Err(err) => Err(err),
+        };
+        map_response!(response, result)
+    }
+
+    /// The qualified roots (the previous block's hash, followed by the root) of the node's active elections
+    pub async fn confirmation_active(&self) -> Response<Vec<[u8; 64]>> {
+        let response = request!(self, encode::confirmation_active());
+        let result = match response.result {
+            Ok(json) => parse::confirmation_active(json),
+            Err(err) => Err(err),
+        };
+        map_response!(response, result)
+    }
+
+    /// Gets the status of an active election, given its qualified root.
+    /// Returns `None` if the election is not active.
+    pub async fn confirmation_info(&self, root: [u8; 64]) -> Response<Option<ElectionInfo>> {
+        let response = request!(self, encode::confirmation_info(root));
+        let result = match response.result {
+            Ok(json) => parse::confirmation_info(json, self.get_account_cache()),
+            Err(err) => Err(err),
+        };
+        map_response!(response, result)
+    }
+
+    pub async fn confirmation_quorum(&self) -> Response<ConfirmationQuorum> {
+        let response = request!(self, encode::confirmation_quorum(false));
+        let result = match response.result {
+            Ok(json) => parse::confirmation_quorum(json, self.get_account_cache()),
+            Err(err) => Err(err),
+        };
+        map_response!(response, result)
+    }
+
+    /// Same as `confirmation_quorum`, but also returns the representatives which the node is connected to
+    pub async fn confirmation_quorum_detailed(&self) -> Response<ConfirmationQuorum> {
+        let response = request!(self, encode::confirmation_quorum(true));
+        let result = match response.result {
+            Ok(json) => parse::confirmation_quorum(json, self.get_account_cache()),
+            Err(err) => Err(err),
+        };
+        map_response!(response, result)
+    }
+
+    /// Up to `count` accounts which delegate at least `threshold` (in raw) to the representative `account`,
+    /// and their balances, sorted by balance (highest first)
+    pub async fn delegators(
+        &self,
+        account: &Account,
+        threshold: u128,
+        count: usize,
+    ) -> Response<Vec<(Account, u128)>> {
+        let response = request!(self, encode::delegators(account, threshold, count));
+        let result = match response.result {
+            Ok(json) => parse::delegators(json, self.get_account_cache()),
+            Err(err) => Err(err),
+        };
+        map_response!(response, result)
+    }
+
+    /// The number of accounts which delegate to the representative `account`
+    pub async fn delegators_count(&self, account: &Account) -> Response<usize> {
+        let response = request!(self, encode::delegators_count(account));
+        let result = match response.result {
+            Ok(json) => parse::delegators_count(json),
+            Err(err) => Err(err),
+        };
+        map_response!(response, result)
+    }
+
+    /// The frontiers of (at most) `count` accounts, starting at `start`, sorted by public key.
+    ///
+    /// The frontier of `start` is included, if it has been opened.
+    pub async fn frontiers(
+        &self,
+        start: &Account,
+        count: usize,
+    ) -> Response<Vec<(Account, [u8; 32])>> {
+        let response = request!(self, encode::frontiers(start, count));
+        let result = match response.result {
+            Ok(json) => parse::frontiers(json, self.get_account_cache()),
+            Err(err) => Err(err),
+        };
+        map_response!(response, result)
+    }
+
+    /// The ledger entries of (at most) `count` accounts, starting at `start`, sorted by public key.
+    /// If `modified_since` is set, only accounts which have been modified since that timestamp are included.
+    ///
+    /// The entry of `start` is included, if it has been opened.
+    pub async fn ledger(
+        &self,
+        start: &Account,
+        count: usize,
+        modified_since: Option<u64>,
+    ) -> Response<Vec<(Account, LedgerEntry)>> {
+        let response = request!(self, encode::ledger(start, count, modified_since));
+        let result = match response.result {
+            Ok(json) => parse::ledger(json, self.get_account_cache()),
+            Err(err) => Err(err),
+        };
+        map_response!(response, result)
+    }
+
+    /// The `count` representatives with the most voting weight, and their weights, sorted by weight (highest first)
+    pub async fn representatives(&self, count: usize) -> Response<Vec<(Account, u128)>> {
+        let response = request!(self, encode::representatives(count));
+        let result = match response.result {
+            Ok(json) => parse::representatives(json, self.get_account_cache()),
+            Err(err) => Err(err),
+        };
+        map_response!(response, result)
+    }
+
+    /// The representatives which have recently voted
+    pub async fn representatives_online(&self) -> Response<Vec<Account>> {
+        let response = request!(self, encode::representatives_online(false));
+        let result = match response.result {
+            Ok(json) => parse::representatives_online(json, self.get_account_cache()),
+            Err(err) => Err(err),
+        };
+        map_response!(response, result)
+    }
+
+    /// Same as `representatives_online`, but also returns the weight of each representative, sorted by weight (highest first)
+    pub async fn representatives_online_detailed(&self) -> Response<Vec<(Account, u128)>> {
+        let response = request!(self, encode::representatives_online(true));
+        let result = match response.result {
+            Ok(json) => parse::representatives_online_detailed(json, self.get_account_cache()),
+            Err(err) => Err(err),
+        };
+        map_response!(response, result)
+    }
+
+    /// Rebroadcasts the block, and (if non-zero) up to `sources` blocks before each of the sends it receives,
+    /// and up to `destinations` blocks after each of the receives of its sends.
+    /// Returns the hashes of the rebroadcast blocks.
+    pub async fn republish(
+        &self,
+        hash: [u8; 32],
+        sources: usize,
+        destinations: usize,
+    ) -> Response<Vec<[u8; 32]>> {
+        let response = request!(self, encode::republish(hash, sources, destinations));
+        let result = match response.result {
+            Ok(json) => parse::republish(json),
+            Err(err) => Err(err),
+        };
+        map_response!(response, result)
+    }
+
+    pub async fn telemetry(&self) -> Response<NodeTelemetry> {
+        let response = request!(self, encode::telemetry(false));
+        let result = match response.result {
+            Ok(json) => parse::telemetry(json),
+            Err(err) => Err(err),
+        };
+        map_response!(response, result)
+    }
+
+    pub async fn telemetry_raw(&self) -> Response<Vec<NodeTelemetry>> {
+        let response = request!(self, encode::telemetry(true));
+        let result = match response.result {
+            Ok(json) => parse::telemetry_raw(json),
+            Err(err) => Err(err),
+        };
+        map_response!(response, result)
+    }
+
+    /// Up to `count` blocks from the node's unchecked table.
+    ///
+    /// Since the subtype of a block depends on the balance of its previous block, which may not be known yet,
+    /// `block_type` is inferred from the block alone:
+    /// blocks with an epoch link are `Epoch`, open blocks are `Receive`, blocks with a zero link are `Change`,
+    /// and all other blocks are `Send`, even though they may be receives.
+    /// Legacy blocks are skipped.
+    pub async fn unchecked(&self, count: usize) -> Response<Vec<Block>> {
+        let response = request!(self, encode::unchecked(count));
+        let result = match response.result {
+            Ok(json) => parse::unchecked(json, self.get_account_cache()),
+            Err(err) => Err(err),
+        };
+        map_response!(response, result)
+    }
+
+    /// Up to `count` blocks from the node's unchecked table, starting at the dependency `key`.
+    ///
+    /// `block_type` is inferred in the same way as `unchecked()`. Legacy blocks are skipped.
+    pub async fn unchecked_keys(
+        &self,
+        key: [u8; 32],
+        count: usize,
+    ) -> Response<Vec<UncheckedBlock>> {
+        let response = request!(self, encode::unchecked_keys(key, count));
+        let result = match response.result {
+            Ok(json) => parse::unchecked_keys(json, self.get_account_cache()),
+            Err(err) => Err(err),
+        };
+        map_response!(response, result)
+    }
+
+    pub async fn version(&self) -> Response<NodeVersion> {
+        let response = request!(self, encode::version());
+        let result = match response.result {
+            Ok(json) => parse::version(json),
+            Err(err) => Err(err),
+        };
+        map_response!(response, result)
+    }
+
     /// Returns the generated work, assuming no error is encountered
     pub async fn work_generate(
         &self,
@@ -278,16 +1289,39 @@ impl DebugRpc {
         map_response!(response, result)
     }
 
-    fn try_clone(&self) -> Option<DebugRpc> {
-        Some(DebugRpc {
-            builder: self.builder.try_clone()?,
-            url: self.url.clone(),
-            proxy: self.proxy.clone(),
-        })
+    /// Generate work for a block of the given type, at the network's current difficulty for that type
+    /// (see `active_difficulty_cached()`)
+    ///
+    /// The raw request and response are those of the last call made to the node.
+    pub async fn work_generate_for(
+        &self,
+        work_hash: [u8; 32],
+        block_type: &BlockType,
+    ) -> Response<[u8; 8]> {
+        let response = self.active_difficulty_cached().await;
+        match response.result {
+            Ok(difficulty) => {
+                let difficulty = difficulty.work_difficulty(block_type);
+                self.work_generate(work_hash, Some(difficulty)).await
+            }
+            Err(err) => map_response!(response, Err(err)),
+        }
     }
-}
-impl Clone for DebugRpc {
-    fn clone(&self) -> Self {
-        self.try_clone().unwrap()
+
+    pub async fn work_validate(
+        &self,
+        work: [u8; 8],
+        work_hash: [u8; 32],
+        custom_difficulty: Option<[u8; 8]>,
+    ) -> Response<WorkValidation> {
+        let response = request!(
+            self,
+            encode::work_validate(work, work_hash, custom_difficulty)
+        );
+        let result = match response.result {
+            Ok(json) => parse::work_validate(json, work, work_hash, custom_difficulty),
+            Err(err) => Err(err),
+        };
+        map_response!(response, result)
     }
 }
@@ -1,5 +1,5 @@
 use super::util::{block_to_json, to_uppercase_hex};
-use crate::{Account, Block};
+use crate::{Account, Block, Key};
 use json::{Map, Value as JsonValue};
 use serde_json as json;
 
@@ -10,6 +10,20 @@ pub fn account_balance(account: &Account) -> JsonValue {
     JsonValue::Object(arguments)
 }
 
+pub fn account_block_count(account: &Account) -> JsonValue {
+    let mut arguments = Map::new();
+    arguments.insert("action".into(), "account_block_count".into());
+    arguments.insert("account".into(), account.into());
+    JsonValue::Object(arguments)
+}
+
+pub fn account_get(public_key: &[u8; 32]) -> JsonValue {
+    let mut arguments = Map::new();
+    arguments.insert("action".into(), "account_get".into());
+    arguments.insert("key".into(), to_uppercase_hex(public_key).into());
+    JsonValue::Object(arguments)
+}
+
 pub fn account_history(
     account: &Account,
     count: usize,
@@ -30,6 +44,13 @@ pub fn account_history(
     JsonValue::Object(arguments)
 }
 
+pub fn account_key(account: &Account) -> JsonValue {
+    let mut arguments = Map::new();
+    arguments.insert("action".into(), "account_key".into());
+    arguments.insert("account".into(), account.into());
+    JsonValue::Object(arguments)
+}
+
 pub fn account_info(account: &Account) -> JsonValue {
     let mut arguments = Map::new();
     arguments.insert("action".into(), "account_info".into());
@@ -40,6 +61,13 @@ pub fn account_info(account: &Account) -> JsonValue {
     JsonValue::Object(arguments)
 }
 
+pub fn account_weight(account: &Account) -> JsonValue {
+    let mut arguments = Map::new();
+    arguments.insert("action".into(), "account_weight".into());
+    arguments.insert("account".into(), account.into());
+    JsonValue::Object(arguments)
+}
+
 pub fn accounts_balances(accounts: &[Account]) -> JsonValue {
     let accounts: Vec<String> = accounts.iter().map(|account| account.to_string()).collect();
 
@@ -67,6 +95,7 @@ pub fn accounts_receivable(accounts: &[Account], count: usize, threshold: u128)
     arguments.insert("threshold".into(), threshold.to_string().into());
     arguments.insert("accounts".into(), accounts.as_slice().into());
     arguments.insert("count".into(), count.to_string().into());
+    arguments.insert("source".into(), true.into());
     JsonValue::Object(arguments)
 }
 
@@ -87,6 +116,171 @@ pub fn block_info(hash: [u8; 32]) -> JsonValue {
     JsonValue::Object(arguments)
 }
 
+pub fn available_supply() -> JsonValue {
+    let mut arguments = Map::new();
+    arguments.insert("action".into(), "available_supply".into());
+    JsonValue::Object(arguments)
+}
+
+pub fn active_difficulty() -> JsonValue {
+    let mut arguments = Map::new();
+    arguments.insert("action".into(), "active_difficulty".into());
+    JsonValue::Object(arguments)
+}
+
+pub fn block_account(hash: [u8; 32]) -> JsonValue {
+    let mut arguments = Map::new();
+    arguments.insert("action".into(), "block_account".into());
+    arguments.insert("hash".into(), to_uppercase_hex(&hash).into());
+    JsonValue::Object(arguments)
+}
+
+/// **The private key is included in the request**
+pub fn block_create(block: &Block, key: &Key) -> JsonValue {
+    let mut arguments = Map::new();
+    arguments.insert("action".into(), "block_create".into());
+    arguments.insert("json_block".into(), true.into());
+    arguments.insert("type".into(), "state".into());
+    arguments.insert("account".into(), (&block.account).into());
+    arguments.insert("previous".into(), to_uppercase_hex(&block.previous).into());
+    arguments.insert("representative".into(), (&block.representative).into());
+    arguments.insert("balance".into(), block.balance.to_string().into());
+    arguments.insert("link".into(), to_uppercase_hex(&block.link).into());
+    arguments.insert("key".into(), to_uppercase_hex(key.as_bytes()).into());
+    arguments.insert("work".into(), hex::encode(block.work).into());
+    JsonValue::Object(arguments)
+}
+
+pub fn block_count() -> JsonValue {
+    let mut arguments = Map::new();
+    arguments.insert("action".into(), "block_count".into());
+    JsonValue::Object(arguments)
+}
+
+pub fn delegators(account: &Account, threshold: u128, count: usize) -> JsonValue {
+    let mut arguments = Map::new();
+    arguments.insert("action".into(), "delegators".into());
+    arguments.insert("account".into(), account.to_string().into());
+    arguments.insert("threshold".into(), threshold.to_string().into());
+    arguments.insert("count".into(), count.to_string().into());
+    JsonValue::Object(arguments)
+}
+
+pub fn delegators_count(account: &Account) -> JsonValue {
+    let mut arguments = Map::new();
+    arguments.insert("action".into(), "delegators_count".into());
+    arguments.insert("account".into(), account.to_string().into());
+    JsonValue::Object(arguments)
+}
+
+pub fn frontiers(start: &Account, count: usize) -> JsonValue {
+    let mut arguments = Map::new();
+    arguments.insert("action".into(), "frontiers".into());
+    arguments.insert("account".into(), start.into());
+    arguments.insert("count".into(), count.to_string().into());
+    JsonValue::Object(arguments)
+}
+
+pub fn ledger(start: &Account, count: usize, modified_since: Option<u64>) -> JsonValue {
+    let mut arguments = Map::new();
+    arguments.insert("action".into(), "ledger".into());
+    arguments.insert("account".into(), start.into());
+    arguments.insert("count".into(), count.to_string().into());
+    arguments.insert("representative".into(), true.into());
+    arguments.insert("weight".into(), true.into());
+    arguments.insert("receivable".into(), true.into());
+    if let Some(modified_since) = modified_since {
+        arguments.insert("modified_since".into(), modified_since.to_string().into());
+    }
+    JsonValue::Object(arguments)
+}
+
+/// The transactions are sorted by amount (highest first), and include the sender and minimum epoch version
+pub fn receivable(account: &Account, count: usize, threshold: u128) -> JsonValue {
+    let mut arguments = Map::new();
+    arguments.insert("action".into(), "receivable".into());
+    arguments.insert("account".into(), account.to_string().into());
+    arguments.insert("count".into(), count.to_string().into());
+    arguments.insert("threshold".into(), threshold.to_string().into());
+    arguments.insert("sorting".into(), true.into());
+    arguments.insert("source".into(), true.into());
+    arguments.insert("min_version".into(), true.into());
+    JsonValue::Object(arguments)
+}
+
+pub fn receive_minimum() -> JsonValue {
+    let mut arguments = Map::new();
+    arguments.insert("action".into(), "receive_minimum".into());
+    JsonValue::Object(arguments)
+}
+
+pub fn search_receivable(wallet: &[u8; 32]) -> JsonValue {
+    let mut arguments = Map::new();
+    arguments.insert("action".into(), "search_receivable".into());
+    arguments.insert("wallet".into(), to_uppercase_hex(wallet).into());
+    JsonValue::Object(arguments)
+}
+
+pub fn search_receivable_all() -> JsonValue {
+    let mut arguments = Map::new();
+    arguments.insert("action".into(), "search_receivable_all".into());
+    JsonValue::Object(arguments)
+}
+
+/// The representatives are sorted by weight (highest first), so only the heaviest `count` are returned
+pub fn representatives(count: usize) -> JsonValue {
+    let mut arguments = Map::new();
+    arguments.insert("action".into(), "representatives".into());
+    arguments.insert("count".into(), count.to_string().into());
+    arguments.insert("sorting".into(), true.into());
+    JsonValue::Object(arguments)
+}
+
+pub fn representatives_online(weight: bool) -> JsonValue {
+    let mut arguments = Map::new();
+    arguments.insert("action".into(), "representatives_online".into());
+    if weight {
+        arguments.insert("weight".into(), true.into());
+    }
+    JsonValue::Object(arguments)
+}
+
+pub fn republish(hash: [u8; 32], sources: usize, destinations: usize) -> JsonValue {
+    let mut arguments = Map::new();
+    arguments.insert("action".into(), "republish".into());
+    arguments.insert("hash".into(), to_uppercase_hex(&hash).into());
+    if sources > 0 {
+        arguments.insert("sources".into(), sources.to_string().into());
+    }
+    if destinations > 0 {
+        arguments.insert("destinations".into(), destinations.to_string().into());
+    }
+    JsonValue::Object(arguments)
+}
+
+pub fn confirmation_active() -> JsonValue {
+    let mut arguments = Map::new();
+    arguments.insert("action".into(), "confirmation_active".into());
+    JsonValue::Object(arguments)
+}
+
+pub fn confirmation_info(root: [u8; 64]) -> JsonValue {
+    let mut arguments = Map::new();
+    arguments.insert("action".into(), "confirmation_info".into());
+    arguments.insert("root".into(), to_uppercase_hex(&root).into());
+    arguments.insert("representatives".into(), true.into());
+    JsonValue::Object(arguments)
+}
+
+pub fn confirmation_quorum(peer_details: bool) -> JsonValue {
+    let mut arguments = Map::new();
+    arguments.insert("action".into(), "confirmation_quorum".into());
+    if peer_details {
+        arguments.insert("peer_details".into(), true.into());
+    }
+    JsonValue::Object(arguments)
+}
+
 pub fn blocks_info(hashes: &[[u8; 32]]) -> JsonValue {
     let hashes: Vec<String> = hashes.iter().map(|hash| to_uppercase_hex(hash)).collect();
 
@@ -98,6 +292,29 @@ pub fn blocks_info(hashes: &[[u8; 32]]) -> JsonValue {
     JsonValue::Object(arguments)
 }
 
+pub fn chain(hash: [u8; 32], count: usize) -> JsonValue {
+    let mut arguments = Map::new();
+    arguments.insert("action".into(), "chain".into());
+    arguments.insert("block".into(), to_uppercase_hex(&hash).into());
+    arguments.insert("count".into(), count.to_string().into());
+    JsonValue::Object(arguments)
+}
+
+pub fn successors(hash: [u8; 32], count: usize) -> JsonValue {
+    let mut arguments = Map::new();
+    arguments.insert("action".into(), "successors".into());
+    arguments.insert("block".into(), to_uppercase_hex(&hash).into());
+    arguments.insert("count".into(), count.to_string().into());
+    JsonValue::Object(arguments)
+}
+
+pub fn peers() -> JsonValue {
+    let mut arguments = Map::new();
+    arguments.insert("action".into(), "peers".into());
+    arguments.insert("peer_details".into(), true.into());
+    JsonValue::Object(arguments)
+}
+
 pub fn process(block: &Block) -> JsonValue {
     let mut arguments = Map::new();
     arguments.insert("action".into(), "process".into());
@@ -107,6 +324,39 @@ pub fn process(block: &Block) -> JsonValue {
     JsonValue::Object(arguments)
 }
 
+/// If `raw`, the telemetry of each peer is requested, rather than the average
+pub fn telemetry(raw: bool) -> JsonValue {
+    let mut arguments = Map::new();
+    arguments.insert("action".into(), "telemetry".into());
+    if raw {
+        arguments.insert("raw".into(), true.into());
+    }
+    JsonValue::Object(arguments)
+}
+
+pub fn unchecked(count: usize) -> JsonValue {
+    let mut arguments = Map::new();
+    arguments.insert("action".into(), "unchecked".into());
+    arguments.insert("count".into(), count.to_string().into());
+    arguments.insert("json_block".into(), true.into());
+    JsonValue::Object(arguments)
+}
+
+pub fn unchecked_keys(key: [u8; 32], count: usize) -> JsonValue {
+    let mut arguments = Map::new();
+    arguments.insert("action".into(), "unchecked_keys".into());
+    arguments.insert("key".into(), to_uppercase_hex(&key).into());
+    arguments.insert("count".into(), count.to_string().into());
+    arguments.insert("json_block".into(), true.into());
+    JsonValue::Object(arguments)
+}
+
+pub fn version() -> JsonValue {
+    let mut arguments = Map::new();
+    arguments.insert("action".into(), "version".into());
+    JsonValue::Object(arguments)
+}
+
 pub fn work_generate(work_hash: [u8; 32], custom_difficulty: Option<[u8; 8]>) -> JsonValue {
     let mut arguments = Map::new();
     arguments.insert("action".into(), "work_generate".into());
@@ -118,9 +368,25 @@ pub fn work_generate(work_hash: [u8; 32], custom_difficulty: Option<[u8; 8]>) ->
     JsonValue::Object(arguments)
 }
 
+pub fn work_validate(
+    work: [u8; 8],
+    work_hash: [u8; 32],
+    custom_difficulty: Option<[u8; 8]>,
+) -> JsonValue {
+    let mut arguments = Map::new();
+    arguments.insert("action".into(), "work_validate".into());
+    arguments.insert("work".into(), hex::encode(work).into());
+    arguments.insert("hash".into(), to_uppercase_hex(&work_hash).into());
+    if let Some(difficulty) = custom_difficulty {
+        arguments.insert("difficulty".into(), hex::encode(difficulty).into());
+    }
+    JsonValue::Object(arguments)
+}
+
 #[cfg(test)]
 mod tests {
-    use crate::{Block, BlockType};
+    use crate::constants::get_genesis_account;
+    use crate::{Account, Block, BlockType};
     use serde_json::json;
 
     #[test]
@@ -137,6 +403,28 @@ mod tests {
         )
     }
 
+    #[test]
+    fn account_get() {
+        let json = super::account_get(get_genesis_account().as_bytes());
+        assert!(
+            json == json!({
+                "action": "account_get",
+                "key": "E89208DD038FBB269987689621D52292AE9C35941A7484756ECCED92A65093BA"
+            })
+        )
+    }
+
+    #[test]
+    fn account_key() {
+        let json = super::account_key(&get_genesis_account());
+        assert!(
+            json == json!({
+                "action": "account_key",
+                "account": "nano_3t6k35gi95xu6tergt6p69ck76ogmitsa8mnijtpxm9fkcm736xtoncuohr3"
+            })
+        )
+    }
+
     #[test]
     fn account_history() {
         let account = "nano_1ipx847tk8o46pwxt5qjdbncjqcbwcc1rrmqnkztrfjy5k7z4imsrata9est"
@@ -184,6 +472,17 @@ mod tests {
         );
     }
 
+    #[test]
+    fn account_weight() {
+        let json = super::account_weight(&get_genesis_account());
+        assert!(
+            json == json!({
+                "action": "account_weight",
+                "account": "nano_3t6k35gi95xu6tergt6p69ck76ogmitsa8mnijtpxm9fkcm736xtoncuohr3"
+            })
+        )
+    }
+
     #[test]
     fn accounts_balances() {
         let accounts = vec![
@@ -239,7 +538,8 @@ mod tests {
                 "accounts": ["nano_1111111111111111111111111111111111111111111111111117353trpda", "nano_3t6k35gi95xu6tergt6p69ck76ogmitsa8mnijtpxm9fkcm736xtoncuohr3"],
                 "count": "9",
                 "threshold": "1000000000000000000000000",
-                "sorting": true
+                "sorting": true,
+                "source": true
             })
         )
     }
@@ -263,6 +563,22 @@ mod tests {
         )
     }
 
+    #[test]
+    fn available_supply() {
+        assert!(super::available_supply() == json!({"action": "available_supply"}))
+    }
+
+    #[test]
+    fn block_account() {
+        let json = super::block_account([0xab; 32]);
+        assert!(
+            json == json!({
+                "action": "block_account",
+                "hash": "ABABABABABABABABABABABABABABABABABABABABABABABABABABABABABABABAB"
+            })
+        )
+    }
+
     #[test]
     fn block_info() {
         let hash = hex::decode("87434F8041869A01C8F6F263B87972D7BA443A72E0A97D7A3FD0CCC2358FD6F9")
@@ -299,6 +615,11 @@ mod tests {
         )
     }
 
+    #[test]
+    fn peers() {
+        assert!(super::peers() == json!({"action": "peers", "peer_details": true}))
+    }
+
     #[test]
     fn process() {
         let signature: [u8; 64] = hex::decode("A5DB164F6B81648F914E49CAB533900C389FAAD64FBB24F6902F9261312B29F730D07E9BCCD21D918301419B4E05B181637CF8419ED4DCBF8EF2539EB2467F07").unwrap().try_into().unwrap();
@@ -344,6 +665,192 @@ mod tests {
         )
     }
 
+    #[test]
+    fn chain() {
+        let json = super::chain([255; 32], 10);
+        assert!(
+            json == json!({
+                "action": "chain",
+                "block": "FFFFFFFFFFFFFFFFFFFFFFFFFFFFFFFFFFFFFFFFFFFFFFFFFFFFFFFFFFFFFFFF",
+                "count": "10"
+            })
+        );
+        let json = super::successors([255; 32], 10);
+        assert!(
+            json == json!({
+                "action": "successors",
+                "block": "FFFFFFFFFFFFFFFFFFFFFFFFFFFFFFFFFFFFFFFFFFFFFFFFFFFFFFFFFFFFFFFF",
+                "count": "10"
+            })
+        );
+    }
+
+    #[test]
+    fn confirmation_active() {
+        assert!(super::confirmation_active() == json!({"action": "confirmation_active"}));
+
+        let json = super::confirmation_info([0xab; 64]);
+        assert!(
+            json == json!({
+                "action": "confirmation_info",
+                "root": "AB".repeat(64),
+                "representatives": true
+            })
+        )
+    }
+
+    #[test]
+    fn confirmation_quorum() {
+        let json = super::confirmation_quorum(false);
+        assert!(json == json!({"action": "confirmation_quorum"}));
+        let json = super::confirmation_quorum(true);
+        assert!(json == json!({"action": "confirmation_quorum", "peer_details": true}));
+    }
+
+    #[test]
+    fn delegators() {
+        let account: Account = "nano_3t6k35gi95xu6tergt6p69ck76ogmitsa8mnijtpxm9fkcm736xtoncuohr3"
+            .try_into()
+            .unwrap();
+        let json = super::delegators(&account, 1000, 50);
+        assert!(
+            json == json!({
+                "action": "delegators",
+                "account": "nano_3t6k35gi95xu6tergt6p69ck76ogmitsa8mnijtpxm9fkcm736xtoncuohr3",
+                "threshold": "1000",
+                "count": "50"
+            })
+        );
+
+        let json = super::delegators_count(&account);
+        assert!(
+            json == json!({
+                "action": "delegators_count",
+                "account": "nano_3t6k35gi95xu6tergt6p69ck76ogmitsa8mnijtpxm9fkcm736xtoncuohr3"
+            })
+        );
+    }
+
+    #[test]
+    fn ledger() {
+        let json = super::ledger(&get_genesis_account(), 100, Some(1594654710));
+        assert!(
+            json == json!({
+                "action": "ledger",
+                "account": "nano_3t6k35gi95xu6tergt6p69ck76ogmitsa8mnijtpxm9fkcm736xtoncuohr3",
+                "count": "100",
+                "representative": true,
+                "weight": true,
+                "receivable": true,
+                "modified_since": "1594654710"
+            })
+        )
+    }
+
+    #[test]
+    fn frontiers() {
+        let json = super::frontiers(&get_genesis_account(), 100);
+        assert!(
+            json == json!({
+                "action": "frontiers",
+                "account": "nano_3t6k35gi95xu6tergt6p69ck76ogmitsa8mnijtpxm9fkcm736xtoncuohr3",
+                "count": "100"
+            })
+        )
+    }
+
+    #[test]
+    fn receivable() {
+        let account: Account = "nano_3t6k35gi95xu6tergt6p69ck76ogmitsa8mnijtpxm9fkcm736xtoncuohr3"
+            .try_into()
+            .unwrap();
+        let json = super::receivable(&account, 5, 1000);
+        assert!(
+            json == json!({
+                "action": "receivable",
+                "account": "nano_3t6k35gi95xu6tergt6p69ck76ogmitsa8mnijtpxm9fkcm736xtoncuohr3",
+                "count": "5",
+                "threshold": "1000",
+                "sorting": true,
+                "source": true,
+                "min_version": true
+            })
+        );
+    }
+
+    #[test]
+    fn search_receivable() {
+        assert!(super::receive_minimum() == json!({"action": "receive_minimum"}));
+        assert!(super::search_receivable_all() == json!({"action": "search_receivable_all"}));
+        let json = super::search_receivable(&[0xab; 32]);
+        assert!(
+            json == json!({
+                "action": "search_receivable",
+                "wallet": "ABABABABABABABABABABABABABABABABABABABABABABABABABABABABABABABAB"
+            })
+        )
+    }
+
+    #[test]
+    fn representatives() {
+        let json = super::representatives(10);
+        assert!(
+            json == json!({
+                "action": "representatives",
+                "count": "10",
+                "sorting": true
+            })
+        );
+
+        let json = super::representatives_online(false);
+        assert!(json == json!({"action": "representatives_online"}));
+        let json = super::representatives_online(true);
+        assert!(json == json!({"action": "representatives_online", "weight": true}));
+    }
+
+    #[test]
+    fn republish() {
+        let json = super::republish([0xab; 32], 0, 0);
+        assert!(
+            json == json!({
+                "action": "republish",
+                "hash": "ABABABABABABABABABABABABABABABABABABABABABABABABABABABABABABABAB"
+            })
+        );
+
+        let json = super::republish([0xab; 32], 2, 3);
+        assert!(
+            json == json!({
+                "action": "republish",
+                "hash": "ABABABABABABABABABABABABABABABABABABABABABABABABABABABABABABABAB",
+                "sources": "2",
+                "destinations": "3"
+            })
+        )
+    }
+
+    #[test]
+    fn unchecked() {
+        let json = super::unchecked(10);
+        assert!(
+            json == json!({
+                "action": "unchecked",
+                "count": "10",
+                "json_block": true
+            })
+        );
+
+        let json = super::unchecked_keys([0x19; 32], 2);
+        assert!(
+            json == json!({
+                "action": "unchecked_keys",
+                "key": "1919191919191919191919191919191919191919191919191919191919191919",
+                "count": "2",
+                "json_block": true
+            })
+        )
+    }
+
     #[test]
     fn work_generate() {
         let hash = hex::decode("718CC2121C3E641059BC1C2CFC45666C99E8AE922F7A807B7D07B62C995D79E2")
@@ -373,4 +880,17 @@ mod tests {
             })
         )
     }
+
+    #[test]
+    fn work_validate() {
+        let json = super::work_validate([1; 8], [255; 32], Some([2; 8]));
+        assert!(
+            json == json!({
+                "action": "work_validate",
+                "work": "0101010101010101",
+                "hash": "FFFFFFFFFFFFFFFFFFFFFFFFFFFFFFFFFFFFFFFFFFFFFFFFFFFFFFFFFFFFFFFF",
+                "difficulty": "0202020202020202"
+            })
+        );
+    }
 }
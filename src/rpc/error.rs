@@ -1,7 +1,9 @@
+use super::retry::BUSY_NODE_ERRORS;
 use hex::FromHexError;
 use json::Error as JsonError;
-use reqwest::Error as ReqwestError;
 use serde_json as json;
+use std::error::Error;
+use std::io::Error as IoError;
 use thiserror::Error;
 
 #[derive(Debug, Error)]
@@ -9,27 +11,163 @@ pub enum RpcError {
     /// Could not create RPC: possibly invalid URL
     #[error("could not create RPC: possibly invalid URL")]
     InvalidRPC,
+    #[cfg(feature = "rpc")]
     #[error(transparent)]
-    ReqwestError(#[from] ReqwestError),
+    ReqwestError(#[from] reqwest::Error),
+    #[cfg(feature = "rpc-isahc")]
+    #[error(transparent)]
+    IsahcError(#[from] isahc::Error),
+    #[cfg(feature = "wasm")]
+    #[error(transparent)]
+    GlooError(#[from] gloo_net::Error),
     /// Error while parsing json
     #[error(transparent)]
     JsonError(#[from] JsonError),
     /// Error while parsing json: invalid hex value
     #[error(transparent)]
     FromHexError(#[from] FromHexError),
-    /// Error while parsing json: invalid account
-    #[error("error while parsing json: invalid account")]
-    InvalidAccount,
-    /// Error while parsing json: invalid integer
-    #[error("error while parsing json: invalid integer")]
-    InvalidInteger,
-    /// error while parsing json: unexpected data type
-    #[error("error while parsing json: unexpected data type")]
-    InvalidJsonDataType,
+    /// Error while parsing json: invalid account in the named field
+    #[error("error while parsing json: invalid account in `{0}`")]
+    InvalidAccount(String),
+    /// Error while parsing json: invalid integer in the named field
+    #[error("error while parsing json: invalid integer in `{0}`")]
+    InvalidInteger(String),
+    /// error while parsing json: unexpected data type in the named field
+    #[error("error while parsing json: unexpected data type in `{0}`")]
+    InvalidJsonDataType(String),
     /// The returned data is invalid
     #[error("the returned data is invalid")]
     InvalidData,
+    /// The node returned an error, such as `Account not found`
+    #[error("the node returned an error: {0}")]
+    NodeError(String),
     /// Cannot publish block of type `legacy`
     #[error("cannot publish block of type 'legacy'")]
     LegacyBlockType,
+    /// None of the probed nodes responded successfully
+    #[error("none of the probed nodes responded successfully")]
+    NoNodeAvailable,
+    /// The request took longer than the configured timeout
+    #[error("the request timed out")]
+    Timeout,
+    /// Not enough nodes returned the same result, or the nodes were split between several results
+    #[error("not enough nodes returned the same result")]
+    QuorumMismatch,
+    /// No recorded response matches the request
+    #[error("no recorded response matches the request")]
+    NoRecordedResponse,
+    /// Error while reading or writing a fixture file
+    #[error(transparent)]
+    IoError(#[from] IoError),
+    #[cfg(feature = "websocket")]
+    #[error(transparent)]
+    WebSocketError(Box<tokio_tungstenite::tungstenite::Error>),
+    /// Error from a user-provided `RpcTransport`
+    #[error(transparent)]
+    TransportError(Box<dyn Error + Send + Sync>),
+    /// The GPU failed to generate work
+    #[cfg(feature = "gpu")]
+    #[error("GPU work generation failed: {0}")]
+    GpuError(String),
+    /// No HTTP client is enabled: enable the `rpc`, `rpc-isahc`, or `wasm` feature, or use `RpcBuilder::transport()`
+    #[error("no HTTP client is enabled")]
+    NoTransport,
+}
+#[cfg(feature = "websocket")]
+impl From<tokio_tungstenite::tungstenite::Error> for RpcError {
+    fn from(value: tokio_tungstenite::tungstenite::Error) -> Self {
+        RpcError::WebSocketError(Box::new(value))
+    }
+}
+
+/// Broad categories of `RpcError`, returned by `RpcError::kind()`
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum RpcErrorKind {
+    /// The request could not be sent, or no response was received in time.
+    /// Usually transient.
+    Network,
+    /// The node is too busy, or is rate limiting requests.
+    /// Usually transient.
+    NodeBusy,
+    /// The node returned any other error, such as `Account not found`
+    Node,
+    /// The node's response was malformed, or failed validation
+    InvalidData,
+    /// The RPC was used incorrectly, such as with an invalid URL or an unsupported block
+    Usage,
+}
+
+impl RpcError {
+    /// The category of this error
+    pub fn kind(&self) -> RpcErrorKind {
+        match self {
+            #[cfg(feature = "rpc")]
+            RpcError::ReqwestError(err) if err.is_decode() => RpcErrorKind::InvalidData,
+            #[cfg(feature = "rpc")]
+            RpcError::ReqwestError(_) => RpcErrorKind::Network,
+            #[cfg(feature = "rpc-isahc")]
+            RpcError::IsahcError(_) => RpcErrorKind::Network,
+            #[cfg(feature = "wasm")]
+            RpcError::GlooError(_) => RpcErrorKind::Network,
+            #[cfg(feature = "websocket")]
+            RpcError::WebSocketError(_) => RpcErrorKind::Network,
+            RpcError::IoError(_)
+            | RpcError::TransportError(_)
+            | RpcError::Timeout
+            | RpcError::NoNodeAvailable => RpcErrorKind::Network,
+            RpcError::NodeError(error) => {
+                let error = error.to_lowercase();
+                match BUSY_NODE_ERRORS.iter().any(|busy| error.contains(busy)) {
+                    true => RpcErrorKind::NodeBusy,
+                    false => RpcErrorKind::Node,
+                }
+            }
+            RpcError::JsonError(_)
+            | RpcError::FromHexError(_)
+            | RpcError::InvalidAccount(_)
+            | RpcError::InvalidInteger(_)
+            | RpcError::InvalidJsonDataType(_)
+            | RpcError::InvalidData
+            | RpcError::QuorumMismatch => RpcErrorKind::InvalidData,
+            #[cfg(feature = "gpu")]
+            RpcError::GpuError(_) => RpcErrorKind::Usage,
+            RpcError::InvalidRPC
+            | RpcError::LegacyBlockType
+            | RpcError::NoRecordedResponse
+            | RpcError::NoTransport => RpcErrorKind::Usage,
+        }
+    }
+
+    /// Returns `true` if the error is likely to be transient, so the request may succeed if retried
+    /// (`RpcErrorKind::Network` or `RpcErrorKind::NodeBusy`)
+    pub fn is_retryable(&self) -> bool {
+        matches!(self.kind(), RpcErrorKind::Network | RpcErrorKind::NodeBusy)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{RpcError, RpcErrorKind};
+    use std::io::{Error as IoError, ErrorKind};
+
+    #[test]
+    fn kind() {
+        let reset = RpcError::IoError(IoError::from(ErrorKind::ConnectionReset));
+        assert!(reset.kind() == RpcErrorKind::Network);
+        assert!(reset.is_retryable());
+        assert!(RpcError::Timeout.is_retryable());
+
+        let busy = RpcError::NodeError("Too many requests".into());
+        assert!(busy.kind() == RpcErrorKind::NodeBusy);
+        assert!(busy.is_retryable());
+        let not_found = RpcError::NodeError("Account not found".into());
+        assert!(not_found.kind() == RpcErrorKind::Node);
+        assert!(!not_found.is_retryable());
+
+        let invalid = RpcError::InvalidInteger("count".into());
+        assert!(invalid.kind() == RpcErrorKind::InvalidData);
+        assert!(invalid.to_string() == "error while parsing json: invalid integer in `count`");
+        assert!(!RpcError::InvalidData.is_retryable());
+        assert!(RpcError::LegacyBlockType.kind() == RpcErrorKind::Usage);
+    }
 }
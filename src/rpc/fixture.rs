@@ -0,0 +1,165 @@
+use super::transport::{RpcTransport, TransportFuture};
+use super::RpcError;
+use json::{json, Value as JsonValue};
+use serde_json as json;
+use std::path::Path;
+use std::sync::{Arc, Mutex, MutexGuard};
+
+/// A request sent to the node, and the node's response
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Interaction {
+    pub request: JsonValue,
+    pub response: JsonValue,
+}
+
+/// A recording of requests and responses, for deterministic tests without the network.
+///
+/// Cloning a `Fixture` does not copy the interactions: both clones will refer to the same recording.
+#[derive(Debug, Clone, Default)]
+pub struct Fixture {
+    interactions: Arc<Mutex<Vec<Interaction>>>,
+}
+impl Fixture {
+    pub fn new() -> Fixture {
+        Fixture::default()
+    }
+
+    fn lock(&self) -> MutexGuard<'_, Vec<Interaction>> {
+        self.interactions
+            .lock()
+            .unwrap_or_else(|poisoned| poisoned.into_inner())
+    }
+
+    /// Parse a fixture from JSON, in the format written by `to_json()`
+    pub fn from_json(value: &JsonValue) -> Result<Fixture, RpcError> {
+        let interactions = value
+            .as_array()
+            .ok_or_else(|| RpcError::InvalidJsonDataType("interactions".into()))?
+            .iter()
+            .map(
+                |interaction| match (&interaction["request"], &interaction["response"]) {
+                    (JsonValue::Null, _) => Err(RpcError::InvalidJsonDataType("request".into())),
+                    (_, JsonValue::Null) => Err(RpcError::InvalidJsonDataType("response".into())),
+                    (request, response) => Ok(Interaction {
+                        request: request.clone(),
+                        response: response.clone(),
+                    }),
+                },
+            )
+            .collect::<Result<Vec<Interaction>, RpcError>>()?;
+        Ok(Fixture::from(interactions))
+    }
+
+    pub fn to_json(&self) -> JsonValue {
+        self.lock()
+            .iter()
+            .map(|interaction| {
+                json!({
+                    "request": interaction.request,
+                    "response": interaction.response
+                })
+            })
+            .collect()
+    }
+
+    /// Load a fixture file
+    pub fn load(path: impl AsRef<Path>) -> Result<Fixture, RpcError> {
+        let data = std::fs::read(path)?;
+        Fixture::from_json(&json::from_slice(&data)?)
+    }
+
+    /// Save this fixture to a file
+    pub fn save(&self, path: impl AsRef<Path>) -> Result<(), RpcError> {
+        let data = json::to_vec_pretty(&self.to_json())?;
+        Ok(std::fs::write(path, data)?)
+    }
+
+    /// Get a copy of the recorded interactions
+    pub fn interactions(&self) -> Vec<Interaction> {
+        self.lock().clone()
+    }
+
+    pub fn len(&self) -> usize {
+        self.lock().len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.lock().is_empty()
+    }
+
+    /// Record an interaction
+    pub fn record(&self, request: JsonValue, response: JsonValue) {
+        self.lock().push(Interaction { request, response })
+    }
+
+    /// Remove and return the response to the first recorded interaction with a matching request
+    pub fn take(&self, request: &JsonValue) -> Option<JsonValue> {
+        let mut interactions = self.lock();
+        let i = interactions
+            .iter()
+            .position(|interaction| &interaction.request == request)?;
+        Some(interactions.remove(i).response)
+    }
+}
+impl From<Vec<Interaction>> for Fixture {
+    fn from(value: Vec<Interaction>) -> Self {
+        Fixture {
+            interactions: Arc::new(Mutex::new(value)),
+        }
+    }
+}
+/// Replays recorded responses
+impl RpcTransport for Fixture {
+    fn send<'a>(&'a self, request: &'a JsonValue) -> TransportFuture<'a> {
+        Box::pin(async move { self.take(request).ok_or(RpcError::NoRecordedResponse) })
+    }
+}
+
+/// Whether an RPC is recording to, or replaying from, a `Fixture`
+#[derive(Debug, Clone)]
+pub enum FixtureMode {
+    /// Send requests to the node as normal, and record each successful request and response
+    Record(Fixture),
+    /// Do not send any requests: instead, respond with the recorded responses.
+    /// Each recorded response is only used once.
+    Replay(Fixture),
+}
+
+#[cfg(test)]
+mod tests {
+    use super::super::Rpc;
+    use super::Fixture;
+    use crate::constants::get_genesis_account;
+    use futures::executor::block_on;
+    use serde_json::json;
+
+    fn test_fixture() -> Fixture {
+        let fixture = Fixture::new();
+        fixture.record(
+            json!({
+                "action": "account_balance",
+                "account": get_genesis_account().to_string()
+            }),
+            json!({"balance": "1000", "pending": "0", "receivable": "0"}),
+        );
+        fixture
+    }
+
+    #[test]
+    fn json() {
+        let fixture = test_fixture();
+        let json = fixture.to_json();
+        assert!(Fixture::from_json(&json).unwrap().interactions() == fixture.interactions());
+        assert!(Fixture::from_json(&json!([{"request": {}}])).is_err());
+        assert!(Fixture::from_json(&json!({})).is_err());
+    }
+
+    #[test]
+    fn replay() {
+        // no async runtime is needed when replaying a fixture
+        let rpc = Rpc::from_fixture(test_fixture());
+        let account = get_genesis_account();
+        assert!(block_on(rpc.account_balance(&account)).unwrap() == 1000);
+        assert!(block_on(rpc.account_balance(&account)).is_err());
+    }
+}
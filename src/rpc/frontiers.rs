@@ -0,0 +1,146 @@
+use super::{Rpc, RpcError};
+use crate::Account;
+use std::collections::VecDeque;
+
+/// Pages through the frontiers of every account, sorted by public key, created by `Rpc::frontiers_paged()`.
+///
+/// Each page is requested starting at the last account of the previous page, which is then skipped,
+/// so no account is returned twice.
+/// Iteration stops once the node returns a partial page.
+#[derive(Debug, Clone)]
+pub struct FrontierPages {
+    rpc: Rpc,
+    page_size: usize,
+    start: Account,
+    /// Whether `start` was returned in the previous page, and should be skipped
+    skip_start: bool,
+    finished: bool,
+    buffer: VecDeque<(Account, [u8; 32])>,
+}
+impl FrontierPages {
+    pub(crate) fn new(rpc: Rpc, start: Account, page_size: usize) -> FrontierPages {
+        FrontierPages {
+            rpc,
+            page_size: page_size.max(1),
+            start,
+            skip_start: false,
+            finished: false,
+            buffer: VecDeque::new(),
+        }
+    }
+
+    /// Request the next page of (at most `page_size`) frontiers.
+    ///
+    /// Returns `None` once every frontier has been returned.
+    pub async fn next_page(&mut self) -> Result<Option<Vec<(Account, [u8; 32])>>, RpcError> {
+        if !self.buffer.is_empty() {
+            return Ok(Some(self.buffer.drain(..).collect()));
+        }
+        if self.finished {
+            return Ok(None);
+        }
+
+        let count = self.page_size + self.skip_start as usize;
+        let mut page = self.rpc.frontiers(&self.start, count).await?;
+        if page
+            .first()
+            .is_some_and(|(account, _)| account.as_bytes() < self.start.as_bytes())
+        {
+            return Err(RpcError::InvalidData);
+        }
+        if page.len() < count {
+            self.finished = true;
+        }
+        if self.skip_start
+            && page
+                .first()
+                .is_some_and(|(account, _)| account == &self.start)
+        {
+            page.remove(0);
+        }
+
+        match page.last() {
+            Some((last, _)) => {
+                self.start = last.clone();
+                self.skip_start = true;
+            }
+            None => {
+                self.finished = true;
+                return Ok(None);
+            }
+        }
+        Ok(Some(page))
+    }
+
+    /// Get the next account and its frontier, requesting the next page if needed.
+    ///
+    /// Returns `None` once every frontier has been returned.
+    pub async fn next(&mut self) -> Result<Option<(Account, [u8; 32])>, RpcError> {
+        if self.buffer.is_empty() {
+            match self.next_page().await? {
+                Some(page) => self.buffer.extend(page),
+                None => return Ok(None),
+            }
+        }
+        Ok(self.buffer.pop_front())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::super::{Fixture, Rpc};
+    use crate::{Account, Key, SecretBytes};
+    use futures::executor::block_on;
+    use serde_json::{json, Map, Value as JsonValue};
+
+    /// 5 accounts and their frontiers, sorted by public key
+    fn frontiers() -> Vec<(Account, [u8; 32])> {
+        let mut frontiers: Vec<(Account, [u8; 32])> = (0..5)
+            .map(|i| {
+                let account = Key::from_seed(&SecretBytes::from([0; 32]), i).to_account();
+                (account, [i as u8; 32])
+            })
+            .collect();
+        frontiers.sort_by_key(|(account, _)| *account.as_bytes());
+        frontiers
+    }
+
+    fn record(fixture: &Fixture, start: &Account, count: usize, page: &[(Account, [u8; 32])]) {
+        let request = json!({
+            "action": "frontiers",
+            "account": start.to_string(),
+            "count": count.to_string()
+        });
+        let page: Map<String, JsonValue> = page
+            .iter()
+            .map(|(account, frontier)| (account.to_string(), hex::encode(frontier).into()))
+            .collect();
+        fixture.record(request, json!({ "frontiers": page }));
+    }
+
+    #[test]
+    fn frontiers_paged() {
+        let frontiers = frontiers();
+        let start = Account::from_bytes([0; 32]).unwrap();
+        let fixture = Fixture::new();
+        let rpc = Rpc::from_fixture(fixture.clone());
+
+        record(&fixture, &start, 2, &frontiers[0..2]);
+        record(&fixture, &frontiers[1].0, 3, &frontiers[1..4]);
+        record(&fixture, &frontiers[3].0, 3, &frontiers[3..]);
+        let mut pages = rpc.frontiers_paged(&start, 2);
+        let mut all = vec![];
+        while let Some(frontier) = block_on(pages.next()).unwrap() {
+            all.push(frontier)
+        }
+        assert!(all == frontiers);
+        assert!(fixture.is_empty());
+
+        // pages must not go backwards
+        record(&fixture, &start, 2, &frontiers[1..3]);
+        record(&fixture, &frontiers[2].0, 3, &frontiers[0..3]);
+        let mut pages = rpc.frontiers_paged(&start, 2);
+        assert!(block_on(pages.next_page()).unwrap().unwrap() == frontiers[1..3]);
+        assert!(block_on(pages.next_page()).is_err());
+    }
+}
@@ -0,0 +1,235 @@
+use super::{parse, Rpc, RpcError, TrustAnchor};
+use crate::{Account, Block};
+use futures::stream::{self, Stream};
+use std::collections::VecDeque;
+
+/// Pages through an account's history, newest block first, created by `Rpc::account_history_paged()`.
+///
+/// Each page is requested with `head` set to the `previous` of the last block, and is checked to link to it,
+/// so every returned block is verified back to the first one.
+/// Iteration stops at the open block (or at the first legacy block whose balance cannot be found),
+/// or at the anchor block if created by `Rpc::account_history_stream()`.
+#[derive(Debug, Clone)]
+pub struct AccountHistoryPages {
+    rpc: Rpc,
+    account: Account,
+    page_size: usize,
+    /// The hash of the next block to request, or `None` if the first page has not been requested yet
+    head: Option<[u8; 32]>,
+    /// If set, the history must reach this block, and stops there
+    anchor: Option<TrustAnchor>,
+    finished: bool,
+    buffer: VecDeque<Block>,
+}
+impl AccountHistoryPages {
+    pub(crate) fn new(rpc: Rpc, account: Account, page_size: usize) -> AccountHistoryPages {
+        AccountHistoryPages {
+            rpc,
+            account,
+            page_size: page_size.max(1),
+            head: None,
+            anchor: None,
+            finished: false,
+            buffer: VecDeque::new(),
+        }
+    }
+
+    pub(crate) fn anchored(
+        rpc: Rpc,
+        account: Account,
+        page_size: usize,
+        anchor: TrustAnchor,
+    ) -> AccountHistoryPages {
+        AccountHistoryPages {
+            anchor: Some(anchor),
+            ..AccountHistoryPages::new(rpc, account, page_size)
+        }
+    }
+
+    /// Request the next page of (at most `page_size`) blocks.
+    ///
+    /// Returns `None` once the whole history has been returned.
+    pub async fn next_page(&mut self) -> Result<Option<Vec<Block>>, RpcError> {
+        if !self.buffer.is_empty() {
+            return Ok(Some(self.buffer.drain(..).collect()));
+        }
+        if self.finished {
+            return Ok(None);
+        }
+
+        let mut page = self
+            .rpc
+            .account_history(&self.account, self.page_size, self.head, None)
+            .await?;
+        if let (Some(head), Some(first)) = (self.head, page.first()) {
+            if first.hash() != head {
+                return Err(RpcError::InvalidData);
+            }
+        }
+
+        match page.last() {
+            Some(last) if last.previous != [0; 32] && page.len() == self.page_size => {
+                self.head = Some(last.previous)
+            }
+            Some(_) => self.finished = true,
+            None if self.anchor.is_none() => {
+                self.finished = true;
+                return Ok(None);
+            }
+            None => self.finished = true,
+        }
+
+        if let Some(anchor) = &self.anchor {
+            if page.iter().any(|block| block.hash() == anchor.hash) {
+                page = parse::account_history_anchored(page, anchor)?;
+                self.finished = true;
+            } else if self.finished {
+                // the history ended without reaching the anchor
+                return Err(RpcError::InvalidData);
+            }
+        }
+        Ok(Some(page))
+    }
+
+    /// Get the next block, requesting the next page if needed.
+    ///
+    /// Returns `None` once the whole history has been returned.
+    pub async fn next(&mut self) -> Result<Option<Block>, RpcError> {
+        if self.buffer.is_empty() {
+            match self.next_page().await? {
+                Some(page) => self.buffer.extend(page),
+                None => return Ok(None),
+            }
+        }
+        Ok(self.buffer.pop_front())
+    }
+
+    /// Turn this into a `Stream` of blocks, which ends after the first error
+    pub fn into_stream(self) -> impl Stream<Item = Result<Block, RpcError>> + Send + 'static {
+        stream::unfold(Some(self), |pages| async move {
+            let mut pages = pages?;
+            match pages.next().await {
+                Ok(Some(block)) => Some((Ok(block), Some(pages))),
+                Ok(None) => None,
+                Err(err) => Some((Err(err), None)),
+            }
+        })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::super::util::block_to_json;
+    use super::super::{Fixture, Rpc, TrustAnchor};
+    use crate::constants::get_genesis_account;
+    use crate::{Block, BlockBuilder, Key, SecretBytes};
+    use futures::executor::block_on;
+    use futures::StreamExt;
+    use serde_json::{json, Value as JsonValue};
+
+    /// An open block followed by 4 sends, newest first
+    fn history(key: &Key) -> Vec<Block> {
+        let mut builder = BlockBuilder::unopened(key.to_account(), get_genesis_account());
+        let mut blocks = vec![];
+        for i in 0..5 {
+            let mut block = match i {
+                0 => builder.receive([1; 32], 100).unwrap(),
+                _ => builder.send(&get_genesis_account(), 1).unwrap(),
+            };
+            block.sign(key);
+            builder.advance(&block);
+            blocks.insert(0, block);
+        }
+        blocks
+    }
+
+    fn record(fixture: &Fixture, key: &Key, head: Option<&Block>, count: usize, page: &[Block]) {
+        let mut request = json!({
+            "action": "account_history",
+            "account": key.to_account().to_string(),
+            "count": count.to_string(),
+            "raw": true
+        });
+        if let Some(head) = head {
+            request["head"] = hex::encode(head.hash()).into();
+        }
+        let page: Vec<JsonValue> = page
+            .iter()
+            .map(|block| {
+                let mut json = block_to_json(block);
+                json.insert("subtype".into(), block.block_type.to_string().into());
+                JsonValue::Object(json)
+            })
+            .collect();
+        fixture.record(request, json!({ "history": page }));
+    }
+
+    #[test]
+    fn account_history_paged() {
+        let key = Key::from_seed(&SecretBytes::from([0; 32]), 0);
+        let history = history(&key);
+        let fixture = Fixture::new();
+        let rpc = Rpc::from_fixture(fixture.clone());
+
+        record(&fixture, &key, None, 2, &history[0..2]);
+        record(&fixture, &key, Some(&history[2]), 2, &history[2..4]);
+        record(&fixture, &key, Some(&history[4]), 2, &history[4..]);
+        let mut pages = rpc.account_history_paged(&key.to_account(), 2);
+        let mut blocks = vec![];
+        while let Some(block) = block_on(pages.next()).unwrap() {
+            blocks.push(block)
+        }
+        assert!(blocks == history);
+        assert!(fixture.is_empty());
+
+        // pages must link together
+        record(&fixture, &key, None, 2, &history[0..2]);
+        record(&fixture, &key, Some(&history[2]), 2, &history[3..5]);
+        let mut pages = rpc.account_history_paged(&key.to_account(), 2);
+        assert!(block_on(pages.next_page()).unwrap().unwrap() == history[0..2]);
+        assert!(block_on(pages.next_page()).is_err());
+    }
+
+    #[test]
+    fn account_history_anchored() {
+        let key = Key::from_seed(&SecretBytes::from([0; 32]), 0);
+        let account = key.to_account();
+        let history = history(&key);
+        let fixture = Fixture::new();
+        let rpc = Rpc::from_fixture(fixture.clone());
+        let anchor = TrustAnchor {
+            hash: history[3].hash(),
+            balance: Some(history[3].balance),
+        };
+
+        // the anchor is on the second page
+        record(&fixture, &key, None, 2, &history[0..2]);
+        record(&fixture, &key, Some(&history[2]), 2, &history[2..4]);
+        let anchored = block_on(rpc.account_history_anchored(&account, 2, &anchor)).unwrap();
+        assert!(anchored == history[..4]);
+        assert!(fixture.is_empty());
+
+        record(&fixture, &key, None, 2, &history[0..2]);
+        record(&fixture, &key, Some(&history[2]), 2, &history[2..4]);
+        let stream = rpc.account_history_stream(&account, 2, &anchor);
+        let blocks: Vec<Block> = block_on(stream.map(Result::unwrap).collect());
+        assert!(blocks == history[..4]);
+        assert!(fixture.is_empty());
+
+        // the history ends without reaching the anchor
+        let anchor = TrustAnchor {
+            hash: [9; 32],
+            balance: None,
+        };
+        record(&fixture, &key, None, 4, &history[0..4]);
+        record(&fixture, &key, Some(&history[4]), 4, &history[4..]);
+        assert!(block_on(rpc.account_history_anchored(&account, 4, &anchor)).is_err());
+
+        record(&fixture, &key, None, 4, &history[0..4]);
+        record(&fixture, &key, Some(&history[4]), 4, &history[4..]);
+        let results: Vec<_> = block_on(rpc.account_history_stream(&account, 4, &anchor).collect());
+        assert!(results.len() == 5);
+        assert!(results[..4].iter().all(|result| result.is_ok()));
+        assert!(results[4].is_err());
+    }
+}
@@ -0,0 +1,184 @@
+use super::{LedgerEntry, Rpc, RpcError};
+use crate::Account;
+use std::collections::VecDeque;
+
+/// Pages through the ledger entries of every account, sorted by public key, created by `Rpc::ledger_paged()`.
+///
+/// Each page is requested starting at the last account of the previous page, which is then skipped,
+/// so no account is returned twice.
+/// Iteration stops once the node returns a partial page.
+#[derive(Debug, Clone)]
+pub struct LedgerPages {
+    rpc: Rpc,
+    page_size: usize,
+    modified_since: Option<u64>,
+    start: Account,
+    /// Whether `start` was returned in the previous page, and should be skipped
+    skip_start: bool,
+    finished: bool,
+    buffer: VecDeque<(Account, LedgerEntry)>,
+}
+impl LedgerPages {
+    pub(crate) fn new(
+        rpc: Rpc,
+        start: Account,
+        page_size: usize,
+        modified_since: Option<u64>,
+    ) -> LedgerPages {
+        LedgerPages {
+            rpc,
+            page_size: page_size.max(1),
+            modified_since,
+            start,
+            skip_start: false,
+            finished: false,
+            buffer: VecDeque::new(),
+        }
+    }
+
+    /// Request the next page of (at most `page_size`) entries.
+    ///
+    /// Returns `None` once every entry has been returned.
+    pub async fn next_page(&mut self) -> Result<Option<Vec<(Account, LedgerEntry)>>, RpcError> {
+        if !self.buffer.is_empty() {
+            return Ok(Some(self.buffer.drain(..).collect()));
+        }
+        if self.finished {
+            return Ok(None);
+        }
+
+        let count = self.page_size + self.skip_start as usize;
+        let mut page = self
+            .rpc
+            .ledger(&self.start, count, self.modified_since)
+            .await?;
+        if page
+            .first()
+            .is_some_and(|(account, _)| account.as_bytes() < self.start.as_bytes())
+        {
+            return Err(RpcError::InvalidData);
+        }
+        if page.len() < count {
+            self.finished = true;
+        }
+        if self.skip_start
+            && page
+                .first()
+                .is_some_and(|(account, _)| account == &self.start)
+        {
+            page.remove(0);
+        }
+
+        match page.last() {
+            Some((last, _)) => {
+                self.start = last.clone();
+                self.skip_start = true;
+            }
+            None => {
+                self.finished = true;
+                return Ok(None);
+            }
+        }
+        Ok(Some(page))
+    }
+
+    /// Get the next account and its entry, requesting the next page if needed.
+    ///
+    /// Returns `None` once every entry has been returned.
+    pub async fn next(&mut self) -> Result<Option<(Account, LedgerEntry)>, RpcError> {
+        if self.buffer.is_empty() {
+            match self.next_page().await? {
+                Some(page) => self.buffer.extend(page),
+                None => return Ok(None),
+            }
+        }
+        Ok(self.buffer.pop_front())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::super::{Fixture, LedgerEntry, Rpc};
+    use crate::constants::get_genesis_account;
+    use crate::{Account, Key, SecretBytes};
+    use futures::executor::block_on;
+    use serde_json::{json, Map, Value as JsonValue};
+
+    /// 4 accounts and their entries, sorted by public key
+    fn entries() -> Vec<(Account, LedgerEntry)> {
+        let mut entries: Vec<(Account, LedgerEntry)> = (0..4)
+            .map(|i| {
+                let account = Key::from_seed(&SecretBytes::from([0; 32]), i).to_account();
+                let entry = LedgerEntry {
+                    frontier: [i as u8; 32],
+                    open_block: [1; 32],
+                    representative_block: [1; 32],
+                    balance: i as u128,
+                    modified_timestamp: 1000 + i as u64,
+                    block_count: 1,
+                    representative: get_genesis_account(),
+                    weight: 0,
+                    receivable: 0,
+                };
+                (account, entry)
+            })
+            .collect();
+        entries.sort_by_key(|(account, _)| *account.as_bytes());
+        entries
+    }
+
+    fn record(fixture: &Fixture, start: &Account, count: usize, page: &[(Account, LedgerEntry)]) {
+        let request = json!({
+            "action": "ledger",
+            "account": start.to_string(),
+            "count": count.to_string(),
+            "representative": true,
+            "weight": true,
+            "receivable": true,
+            "modified_since": "1000"
+        });
+        let page: Map<String, JsonValue> = page
+            .iter()
+            .map(|(account, entry)| {
+                let entry = json!({
+                    "frontier": hex::encode(entry.frontier),
+                    "open_block": hex::encode(entry.open_block),
+                    "representative_block": hex::encode(entry.representative_block),
+                    "balance": entry.balance.to_string(),
+                    "modified_timestamp": entry.modified_timestamp.to_string(),
+                    "block_count": entry.block_count.to_string(),
+                    "representative": entry.representative.to_string(),
+                    "weight": entry.weight.to_string(),
+                    "receivable": entry.receivable.to_string()
+                });
+                (account.to_string(), entry)
+            })
+            .collect();
+        fixture.record(request, json!({ "accounts": page }));
+    }
+
+    #[test]
+    fn ledger_paged() {
+        let entries = entries();
+        let start = Account::from_bytes([0; 32]).unwrap();
+        let fixture = Fixture::new();
+        let rpc = Rpc::from_fixture(fixture.clone());
+
+        record(&fixture, &start, 3, &entries[0..3]);
+        record(&fixture, &entries[2].0, 4, &entries[2..]);
+        let mut pages = rpc.ledger_paged(&start, 3, Some(1000));
+        let mut all = vec![];
+        while let Some(entry) = block_on(pages.next()).unwrap() {
+            all.push(entry)
+        }
+        assert!(all == entries);
+        assert!(fixture.is_empty());
+
+        // pages must not go backwards
+        record(&fixture, &start, 3, &entries[1..4]);
+        record(&fixture, &entries[3].0, 4, &entries[0..2]);
+        let mut pages = rpc.ledger_paged(&start, 3, Some(1000));
+        assert!(block_on(pages.next_page()).unwrap().unwrap() == entries[1..4]);
+        assert!(block_on(pages.next_page()).is_err());
+    }
+}
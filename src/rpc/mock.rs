@@ -0,0 +1,402 @@
+//! A mock node, for unit-testing code which uses an `Rpc` without a live node.
+//!
+//! `MockRpc::rpc()` returns an ordinary `Rpc`, so all of its methods are available,
+//! and code under test does not need to be generic over the RPC type.
+
+use super::debug::DebugRpc;
+use super::transport::{RpcTransport, TransportFuture};
+use super::{Rpc, RpcError};
+use json::Value as JsonValue;
+use serde_json as json;
+use std::collections::HashMap;
+use std::fmt::Debug;
+use std::sync::{Arc, Mutex, MutexGuard};
+
+type Handler = Arc<dyn Fn(&JsonValue) -> Result<JsonValue, RpcError> + Send + Sync>;
+
+#[derive(Default)]
+struct MockState {
+    handlers: HashMap<String, Handler>,
+    requests: Vec<JsonValue>,
+}
+
+/// Responds to requests by their `action`, with either a canned response or a handler.
+///
+/// Requests with no matching handler fail with `RpcError::NoRecordedResponse`.
+/// Cloning a `MockRpc` does not copy its handlers: both clones will refer to the same mock.
+#[derive(Clone, Default)]
+pub struct MockRpc {
+    state: Arc<Mutex<MockState>>,
+}
+impl MockRpc {
+    pub fn new() -> MockRpc {
+        MockRpc::default()
+    }
+
+    fn lock(&self) -> MutexGuard<'_, MockState> {
+        self.state
+            .lock()
+            .unwrap_or_else(|poisoned| poisoned.into_inner())
+    }
+
+    /// Create an RPC which sends its requests to this mock
+    pub fn rpc(&self) -> Rpc {
+        Rpc(DebugRpc::with_transport(
+            "mock",
+            None,
+            Arc::new(self.clone()),
+        ))
+    }
+
+    /// Respond to every request with this `action` with `response`, replacing any previous handler
+    pub fn respond(&self, action: &str, response: JsonValue) {
+        self.on(action, move |_| Ok(response.clone()))
+    }
+
+    /// Respond to every request with this `action` by calling `handler` with the request,
+    /// replacing any previous handler
+    pub fn on<F>(&self, action: &str, handler: F)
+    where
+        F: Fn(&JsonValue) -> Result<JsonValue, RpcError> + Send + Sync + 'static,
+    {
+        self.lock()
+            .handlers
+            .insert(action.into(), Arc::new(handler));
+    }
+
+    /// Remove the handler for this `action`, if any
+    pub fn remove(&self, action: &str) {
+        self.lock().handlers.remove(action);
+    }
+
+    /// Get a copy of every request received so far, in order
+    pub fn requests(&self) -> Vec<JsonValue> {
+        self.lock().requests.clone()
+    }
+}
+impl Debug for MockRpc {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        let state = self.lock();
+        let mut actions: Vec<&String> = state.handlers.keys().collect();
+        actions.sort();
+        f.debug_struct("MockRpc")
+            .field("actions", &actions)
+            .field("requests", &state.requests.len())
+            .finish()
+    }
+}
+impl RpcTransport for MockRpc {
+    fn send<'a>(&'a self, request: &'a JsonValue) -> TransportFuture<'a> {
+        Box::pin(async move {
+            let handler = {
+                let mut state = self.lock();
+                state.requests.push(request.clone());
+                request["action"]
+                    .as_str()
+                    .and_then(|action| state.handlers.get(action))
+                    .cloned()
+            };
+            handler.ok_or(RpcError::NoRecordedResponse)?(request)
+        })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::MockRpc;
+    use crate::constants::get_genesis_account;
+    use crate::rpc::{ConfirmationOptions, ConfirmationOutcome, RpcError};
+    use crate::work::{LocalWork, WorkProvider};
+    use crate::{BlockBuilder, BlockType, Key, SecretBytes};
+    use futures::executor::block_on;
+    use serde_json::{json, Value as JsonValue};
+    use std::sync::atomic::{AtomicUsize, Ordering};
+    use std::time::Duration;
+
+    #[test]
+    fn respond() {
+        let mock = MockRpc::new();
+        let rpc = mock.rpc();
+        assert!(matches!(
+            block_on(rpc.block_count()),
+            Err(RpcError::NoRecordedResponse)
+        ));
+
+        mock.respond(
+            "block_count",
+            json!({"count": "1000", "unchecked": "10", "cemented": "900"}),
+        );
+        let count = block_on(rpc.block_count()).unwrap();
+        assert!(count.count == 1000);
+        assert!(count.cemented == 900);
+        assert!(mock.requests().len() == 2);
+        assert!(mock.requests()[1] == json!({"action": "block_count"}));
+
+        mock.remove("block_count");
+        assert!(block_on(rpc.block_count()).is_err());
+    }
+
+    #[test]
+    fn handler() {
+        let genesis = get_genesis_account();
+        let mock = MockRpc::new();
+        mock.on("account_block_count", |request| {
+            match request["account"].as_str() {
+                Some(account) if account == get_genesis_account().to_string() => {
+                    Ok(json!({"block_count": "19"}))
+                }
+                _ => Ok(json!({"error": "Account not found"})),
+            }
+        });
+        let rpc = mock.rpc();
+        assert!(block_on(rpc.account_block_count(&genesis)).unwrap() == Some(19));
+        let key = Key::from_seed(&SecretBytes::from([0; 32]), 0);
+        assert!(block_on(rpc.account_block_count(&key.to_account()))
+            .unwrap()
+            .is_none());
+
+        mock.on("account_block_count", |_| Err(RpcError::InvalidRPC));
+        assert!(matches!(
+            block_on(rpc.account_block_count(&genesis)),
+            Err(RpcError::InvalidRPC)
+        ));
+    }
+
+    #[test]
+    fn node_error() {
+        let genesis = get_genesis_account();
+        let mock = MockRpc::new();
+        let rpc = mock.rpc();
+
+        mock.respond("account_balance", json!({"error": "Bad account number"}));
+        assert!(matches!(
+            block_on(rpc.account_balance(&genesis)),
+            Err(RpcError::NodeError(error)) if error == "Bad account number"
+        ));
+        mock.respond("account_balance", json!({"balance": "invalid"}));
+        assert!(matches!(
+            block_on(rpc.account_balance(&genesis)),
+            Err(RpcError::InvalidInteger(field)) if field == "balance"
+        ));
+    }
+
+    fn block_info(confirmed: bool) -> JsonValue {
+        json!({
+            "block_account": "nano_1ipx847tk8o46pwxt5qjdbncjqcbwcc1rrmqnkztrfjy5k7z4imsrata9est",
+            "amount": "30000000000000000000000000000000000",
+            "balance": "5606157000000000000000000000000000000",
+            "height": "58",
+            "local_timestamp": "999888777",
+            "successor": "0000000000000000000000000000000000000000000000000000000000000000",
+            "confirmed": confirmed.to_string(),
+            "contents": {
+                "type": "state",
+                "account": "nano_1ipx847tk8o46pwxt5qjdbncjqcbwcc1rrmqnkztrfjy5k7z4imsrata9est",
+                "previous": "CE898C131AAEE25E05362F247760F8A3ACF34A9796A5AE0D9204E86B0637965E",
+                "representative": "nano_1stofnrxuz3cai7ze75o174bpm7scwj9jn3nxsn8ntzg784jf1gzn1jjdkou",
+                "balance": "5606157000000000000000000000000000000",
+                "link": "5D1AA8A45F8736519D707FCB375976A7F9AF795091021D7E9C7548D6F45DD8D5",
+                "link_as_account": "nano_1qato4k7z3spc8gq1zyd8xeqfbzsoxwo36a45ozbrxcatut7up8ohyardu1z",
+                "signature": "82D41BC16F313E4B2243D14DFFA2FB04679C540C2095FEE7EAE0F2F26880AD56DD48D87A7CC5DD760C5B2D76EE2C205506AA557BF00B60D8DEE312EC7343A501",
+                "work": "8a142e07a10996d5"
+            },
+            "subtype": "send"
+        })
+    }
+
+    #[test]
+    fn wait_for_confirmation() {
+        let options = ConfirmationOptions {
+            poll_interval: Duration::from_millis(1),
+            timeout: Duration::from_secs(10),
+        };
+        let mock = MockRpc::new();
+        let rpc = mock.rpc();
+        let hash = [1; 32];
+
+        // the block is waited for until the node sees it, and then until it is confirmed
+        let polls = AtomicUsize::new(0);
+        mock.on("block_info", move |_| {
+            Ok(match polls.fetch_add(1, Ordering::Relaxed) {
+                0 => json!({"error": "Block not found"}),
+                1 => block_info(false),
+                _ => block_info(true),
+            })
+        });
+        let outcome = block_on(rpc.wait_for_confirmation(hash, options)).unwrap();
+        assert!(matches!(outcome, ConfirmationOutcome::Confirmed(info) if info.confirmed));
+        assert!(mock.requests().len() == 3);
+
+        mock.respond("block_info", block_info(false));
+        let options = ConfirmationOptions {
+            timeout: Duration::from_millis(10),
+            ..options
+        };
+        let outcome = block_on(rpc.wait_for_confirmation(hash, options)).unwrap();
+        assert!(outcome == ConfirmationOutcome::TimedOut);
+    }
+
+    #[test]
+    fn process_and_confirm() {
+        let options = ConfirmationOptions {
+            poll_interval: Duration::from_millis(1),
+            timeout: Duration::from_secs(10),
+        };
+        let key = Key::from_seed(&SecretBytes::from([0; 32]), 0);
+        let mut block = BlockBuilder::unopened(key.to_account(), get_genesis_account())
+            .receive([1; 32], 100)
+            .unwrap();
+        block.sign(&key);
+        let hash = block.hash();
+
+        let mock = MockRpc::new();
+        let rpc = mock.rpc();
+        mock.respond("process", json!({"hash": hex::encode_upper(hash)}));
+        mock.respond("account_info", json!({"error": "Account not found"}));
+
+        // the node has not seen the block at first, which is not a fork
+        let polls = AtomicUsize::new(0);
+        mock.on("block_info", move |_| {
+            Ok(match polls.fetch_add(1, Ordering::Relaxed) {
+                0 => json!({"error": "Block not found"}),
+                _ => block_info(true),
+            })
+        });
+        let outcome = block_on(rpc.process_and_confirm(&block, options)).unwrap();
+        assert!(matches!(outcome, ConfirmationOutcome::Confirmed(info) if info.confirmed));
+        let actions: Vec<JsonValue> = mock
+            .requests()
+            .iter()
+            .map(|request| request["action"].clone())
+            .collect();
+        assert!(actions == ["process", "block_info", "account_info", "block_info"]);
+
+        // the account was opened by a different block
+        mock.respond("block_info", json!({"error": "Block not found"}));
+        mock.respond(
+            "account_info",
+            json!({
+                "frontier": "80A6745762493FA21A22718ABFA4F635656A707B48B3324198AC7F3938DE6D4F",
+                "open_block": "0E3F07F7F2B8AEDEA4A984E29BFE1E3933BA473DD3E27C662EC041F6EA3917A0",
+                "balance": "100",
+                "modified_timestamp": "1606934662",
+                "block_count": "2",
+                "account_version": "1",
+                "representative": "nano_1gyeqc6u5j3oaxbe5qy1hyz3q745a318kh8h9ocnpan7fuxnq85cxqboapu5",
+                "weight": "0",
+                "receivable": "0"
+            }),
+        );
+        let outcome = block_on(rpc.process_and_confirm(&block, options)).unwrap();
+        assert!(outcome == ConfirmationOutcome::Forked);
+
+        mock.respond("process", json!({"error": "Fork"}));
+        let outcome = block_on(rpc.process_and_confirm(&block, options)).unwrap();
+        assert!(outcome == ConfirmationOutcome::Forked);
+    }
+
+    #[test]
+    fn process_with_work() {
+        let key = Key::from_seed(&SecretBytes::from([0; 32]), 0);
+        let mut block = BlockBuilder::unopened(key.to_account(), get_genesis_account())
+            .receive([1; 32], 100)
+            .unwrap();
+        block.sign(&key);
+        let hash = block.hash();
+        let difficulty = [0xff, 0xf0, 0, 0, 0, 0, 0, 0];
+        let work = block.get_local_work(difficulty);
+        assert!(!block.has_valid_work(difficulty));
+
+        let mock = MockRpc::new();
+        let rpc = mock.rpc();
+        mock.respond("process", json!({"hash": hex::encode_upper(hash)}));
+        mock.respond("work_generate", json!({"work": hex::encode(work)}));
+
+        assert!(block_on(rpc.process_with_work(&mut block, difficulty)).unwrap() == hash);
+        assert!(block.work == work);
+        assert!(mock.requests().len() == 2);
+        assert!(mock.requests()[0]["action"] == "work_generate");
+
+        // valid work is not replaced
+        assert!(block_on(rpc.process_with_work(&mut block, difficulty)).unwrap() == hash);
+        assert!(mock.requests().len() == 3);
+
+        block.set_work([0; 8]);
+        assert!(block_on(rpc.process_with_work_from(&mut block, difficulty, &LocalWork)).is_ok());
+        assert!(block.has_valid_work(difficulty));
+        assert!(mock.requests().len() == 4);
+    }
+
+    #[test]
+    fn work_generate_for() {
+        let work = block_on(LocalWork.generate([7; 32], [0xff, 0xf0, 0, 0, 0, 0, 0, 0])).unwrap();
+        let mock = MockRpc::new();
+        let rpc = mock.rpc();
+        mock.respond(
+            "active_difficulty",
+            json!({
+                "multiplier": "1.0",
+                "network_current": "fff0000000000000",
+                "network_minimum": "fff0000000000000",
+                "network_receive_current": "ffe0000000000000",
+                "network_receive_minimum": "ffe0000000000000"
+            }),
+        );
+        mock.respond("work_generate", json!({"work": hex::encode(work)}));
+
+        assert!(block_on(rpc.work_generate_for([7; 32], &BlockType::Send)).unwrap() == work);
+        assert!(block_on(rpc.work_generate_for([7; 32], &BlockType::Receive)).unwrap() == work);
+        let requests = mock.requests();
+        // the thresholds are only requested once
+        assert!(requests.len() == 3);
+        assert!(requests[0]["action"] == "active_difficulty");
+        assert!(requests[1]["difficulty"] == "fff0000000000000");
+        assert!(requests[2]["difficulty"] == "ffe0000000000000");
+
+        // errors are not cached
+        let mock = MockRpc::new();
+        mock.respond("active_difficulty", json!({"error": "Unknown"}));
+        let rpc = mock.rpc();
+        assert!(block_on(rpc.work_generate_for([7; 32], &BlockType::Send)).is_err());
+        assert!(block_on(rpc.active_difficulty_cached()).is_err());
+        assert!(mock.requests().len() == 2);
+    }
+
+    #[test]
+    fn block_create_conformance() {
+        let key = Key::from_seed(&SecretBytes::from([0; 32]), 0);
+        let block = BlockBuilder::new(key.to_account(), [1; 32], get_genesis_account(), 100)
+            .send(&get_genesis_account(), 1)
+            .unwrap();
+        let mut signed = block.clone();
+        signed.sign(&key);
+
+        // the node also returns "link_as_account", which is not compared
+        let mut created: JsonValue = serde_json::from_str(&signed.to_json_string()).unwrap();
+        created["link_as_account"] = get_genesis_account().to_string().into();
+        let response = json!({
+            "hash": hex::encode_upper(signed.hash()),
+            "difficulty": "fffffff800000000",
+            "block": created
+        });
+
+        let mock = MockRpc::new();
+        let rpc = mock.rpc();
+        mock.respond("block_create", response.clone());
+        let conformance = block_on(rpc.block_create_conformance(&block, &key)).unwrap();
+        assert!(conformance.matches());
+        assert!(mock.requests()[0]["key"] == hex::encode_upper(key.as_bytes()));
+        assert!(mock.requests()[0]["work"] == "0000000000000000");
+        let created = block_on(rpc.block_create(&block, &key)).unwrap();
+        assert!(created.block == signed);
+
+        let mut different = response;
+        different["hash"] = hex::encode([0; 32]).into();
+        different["block"]["balance"] = "1".into();
+        mock.respond("block_create", different);
+        let conformance = block_on(rpc.block_create_conformance(&block, &key)).unwrap();
+        assert!(!conformance.hash);
+        assert!(conformance.signature);
+        assert!(!conformance.json);
+    }
+}
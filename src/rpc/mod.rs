@@ -1,20 +1,55 @@
+mod builder;
 mod encode;
 mod error;
+mod fixture;
+mod frontiers;
+mod history;
+mod ledger;
 mod parse;
+mod pool;
+mod queue;
+mod quorum;
+mod receivable;
+mod retry;
+mod transport;
 
 pub mod debug;
+pub mod mock;
+#[cfg(feature = "node-wallet")]
+pub mod node_wallet;
+#[cfg(feature = "nodes")]
+pub mod nodes;
 pub mod util;
+#[cfg(feature = "websocket")]
+pub mod websocket;
 
-use crate::{Account, Block};
+use crate::work::WorkProvider;
+use crate::{Account, AccountCache, Block, BlockType, Key};
 use debug::DebugRpc;
+use futures::Stream;
 use json::{Map, Value as JsonValue};
 use serde_json as json;
+use std::time::Duration;
 use zeroize::{Zeroize, ZeroizeOnDrop};
 
 #[cfg(feature = "serde")]
 use serde::{Deserialize, Serialize};
 
-pub use error::RpcError;
+pub use builder::{HttpVersion, RpcBuilder};
+pub use error::{RpcError, RpcErrorKind};
+pub use fixture::{Fixture, FixtureMode, Interaction};
+pub use frontiers::FrontierPages;
+pub use history::AccountHistoryPages;
+pub use ledger::LedgerPages;
+pub use pool::{NodeHealth, RpcPool, DEFAULT_COOLDOWN};
+pub use queue::{FlushReport, OfflineQueue};
+pub use quorum::RpcQuorum;
+pub use receivable::{Receivable, ReceivableSet};
+pub use retry::RetryPolicy;
+pub use transport::{RpcTransport, TransportFuture};
+
+pub(crate) use builder::HttpOptions;
+pub(crate) use transport::default_transport;
 
 #[cfg(test)]
 #[cfg(feature = "serde")]
@@ -30,19 +65,40 @@ pub struct BlockInfo {
     pub timestamp: u64,
     /// Whether or not this block has been confirmed
     pub confirmed: bool,
+    /// The amount sent or received by this block (`0` for `change` and `epoch` blocks)
+    #[cfg_attr(feature = "serde", serde(with = "crate::serde_util::raw"))]
+    pub amount: u128,
+    /// Hash of the next block on the account's blockchain, if any
+    #[cfg_attr(feature = "serde", serde(with = "crate::serde_util::hex_bytes_option"))]
+    pub successor: Option<[u8; 32]>,
     /// The block
     pub block: Block,
 }
 
+/// The balances of an account
+#[derive(Debug, Clone, Copy, Default, Zeroize, PartialEq, Eq)]
+#[cfg_attr(feature = "serde", derive(Serialize, Deserialize))]
+pub struct Balances {
+    /// Balance of this account, as of its frontier
+    #[cfg_attr(feature = "serde", serde(with = "crate::serde_util::raw"))]
+    pub confirmed: u128,
+    /// Sum of the receivable transactions for this account
+    #[cfg_attr(feature = "serde", serde(with = "crate::serde_util::raw"))]
+    pub receivable: u128,
+}
+
 /// General info about an account
 #[derive(Debug, Clone, Zeroize, ZeroizeOnDrop, PartialEq, Eq)]
 #[cfg_attr(feature = "serde", derive(Serialize, Deserialize))]
 pub struct AccountInfo {
     /// Hash of the frontier block of this account
+    #[cfg_attr(feature = "serde", serde(with = "crate::serde_util::hex_bytes"))]
     pub frontier: [u8; 32],
     /// Hash of the `open` block of this account
+    #[cfg_attr(feature = "serde", serde(with = "crate::serde_util::hex_bytes"))]
     pub open_block: [u8; 32],
     /// Balance of this account
+    #[cfg_attr(feature = "serde", serde(with = "crate::serde_util::raw"))]
     pub balance: u128,
     /// Timestamp of this account's last block
     #[cfg_attr(feature = "serde", serde(rename = "timestamp"))]
@@ -54,41 +110,361 @@ pub struct AccountInfo {
     /// The representative of this account
     pub representative: Account,
     /// The voting weight of this account
+    #[cfg_attr(feature = "serde", serde(with = "crate::serde_util::raw"))]
     pub weight: u128,
     /// The number of receivable transactions for this account
     pub receivable: usize,
 }
 
-/// A receivable (pending) transaction.
+/// An account's entry in the ledger, as returned by `Rpc::ledger()`
 #[derive(Debug, Clone, Zeroize, ZeroizeOnDrop, PartialEq, Eq)]
 #[cfg_attr(feature = "serde", derive(Serialize, Deserialize))]
-pub struct Receivable {
-    /// The recipient account of this transaction
-    pub recipient: Account,
-    /// The hash of the send block on the sender's account
-    #[cfg_attr(feature = "serde", serde(rename = "hash"))]
-    pub block_hash: [u8; 32],
-    /// The amount being transferred
-    pub amount: u128,
+pub struct LedgerEntry {
+    /// Hash of the frontier block of this account
+    #[cfg_attr(feature = "serde", serde(with = "crate::serde_util::hex_bytes"))]
+    pub frontier: [u8; 32],
+    /// Hash of the `open` block of this account
+    #[cfg_attr(feature = "serde", serde(with = "crate::serde_util::hex_bytes"))]
+    pub open_block: [u8; 32],
+    /// Hash of the last block which set this account's representative
+    #[cfg_attr(feature = "serde", serde(with = "crate::serde_util::hex_bytes"))]
+    pub representative_block: [u8; 32],
+    /// Balance of this account
+    #[cfg_attr(feature = "serde", serde(with = "crate::serde_util::raw"))]
+    pub balance: u128,
+    /// Timestamp of this account's last block
+    pub modified_timestamp: u64,
+    /// Number of blocks in this account's history
+    pub block_count: usize,
+    /// The representative of this account
+    pub representative: Account,
+    /// The voting weight of this account
+    #[cfg_attr(feature = "serde", serde(with = "crate::serde_util::raw"))]
+    pub weight: u128,
+    /// The sum of the receivable transactions for this account
+    #[cfg_attr(feature = "serde", serde(with = "crate::serde_util::raw"))]
+    pub receivable: u128,
+}
+
+/// The version of a node, and of its components
+#[derive(Debug, Clone, PartialEq, Eq)]
+#[cfg_attr(feature = "serde", derive(Serialize, Deserialize))]
+pub struct NodeVersion {
+    pub rpc_version: u64,
+    pub store_version: u64,
+    pub protocol_version: u64,
+    /// Such as `"Nano V27.1"`
+    pub node_vendor: String,
+    /// Such as `"LMDB 0.9.70"`
+    pub store_vendor: String,
+    /// Such as `"live"` or `"beta"`
+    pub network: String,
+    /// Hash of the network's genesis block
+    #[cfg_attr(feature = "serde", serde(with = "crate::serde_util::hex_bytes"))]
+    pub network_identifier: [u8; 32],
+    pub build_info: String,
+}
+
+/// The number of blocks in a node's ledger
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+#[cfg_attr(feature = "serde", derive(Serialize, Deserialize))]
+pub struct BlockCount {
+    /// The number of blocks in the ledger
+    pub count: u64,
+    /// The number of blocks waiting for their dependencies
+    pub unchecked: u64,
+    /// The number of confirmed blocks
+    pub cemented: u64,
+}
+
+/// The network's current work difficulty thresholds
+#[derive(Debug, Clone, Copy, PartialEq)]
+#[cfg_attr(feature = "serde", derive(Serialize, Deserialize))]
+pub struct ActiveDifficulty {
+    /// The minimum difficulty of `send` and `change` blocks
+    #[cfg_attr(feature = "serde", serde(with = "crate::serde_util::hex_bytes"))]
+    pub network_minimum: [u8; 8],
+    /// The minimum difficulty of `receive` blocks
+    #[cfg_attr(feature = "serde", serde(with = "crate::serde_util::hex_bytes"))]
+    pub network_receive_minimum: [u8; 8],
+    /// The difficulty of `send` and `change` blocks needed to be prioritized during saturation
+    #[cfg_attr(feature = "serde", serde(with = "crate::serde_util::hex_bytes"))]
+    pub network_current: [u8; 8],
+    /// The difficulty of `receive` blocks needed to be prioritized during saturation
+    #[cfg_attr(feature = "serde", serde(with = "crate::serde_util::hex_bytes"))]
+    pub network_receive_current: [u8; 8],
+    /// `network_current` relative to `network_minimum`
+    pub multiplier: f64,
+}
+impl ActiveDifficulty {
+    /// The current difficulty of a block of the given type, for use with `work_generate`.
+    /// Legacy blocks use the difficulty of their `state` equivalent.
+    pub fn work_difficulty(&self, block_type: &BlockType) -> [u8; 8] {
+        match block_type.state_equivalent() {
+            Some(BlockType::Receive) => self.network_receive_current,
+            _ => self.network_current,
+        }
+    }
+}
+
+/// A block created by the node's `block_create`
+#[derive(Debug, Clone, PartialEq, Eq)]
+#[cfg_attr(feature = "serde", derive(Serialize, Deserialize))]
+pub struct CreatedBlock {
+    /// Hash of the block, as calculated by the node
+    #[cfg_attr(feature = "serde", serde(with = "crate::serde_util::hex_bytes"))]
+    pub hash: [u8; 32],
+    /// The block, signed by the node
+    pub block: Block,
+}
+
+/// Whether a block created by the node's `block_create` matches the same block created locally
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+#[cfg_attr(feature = "serde", derive(Serialize, Deserialize))]
+pub struct BlockConformance {
+    /// Whether the node calculated the same hash
+    pub hash: bool,
+    /// Whether the node created the same signature
+    pub signature: bool,
+    /// Whether the node's JSON has the same fields and values as `Block::to_json_string()`
+    pub json: bool,
+}
+impl BlockConformance {
+    /// Whether the node's block matches the local block in every way
+    pub fn matches(&self) -> bool {
+        self.hash && self.signature && self.json
+    }
+}
+
+/// The node's validation of proof-of-work, which has been checked locally
+#[derive(Debug, Clone, Copy, PartialEq)]
+#[cfg_attr(feature = "serde", derive(Serialize, Deserialize))]
+pub struct WorkValidation {
+    /// Whether the work meets the custom difficulty, if one was given
+    pub valid: Option<bool>,
+    /// Whether the work meets the threshold of all blocks (`send` and `change` blocks)
+    pub valid_all: bool,
+    /// Whether the work meets the threshold of `receive` blocks
+    pub valid_receive: bool,
+    /// The difficulty which the work achieves
+    #[cfg_attr(feature = "serde", serde(with = "crate::serde_util::hex_bytes"))]
+    pub difficulty: [u8; 8],
+    /// The achieved difficulty relative to the network's base difficulty
+    pub multiplier: f64,
+}
+
+/// The voting weight needed to confirm blocks, and the weight of the representatives which are online.
+/// All weights are in raw.
+#[derive(Debug, Clone, PartialEq, Eq)]
+#[cfg_attr(feature = "serde", derive(Serialize, Deserialize))]
+pub struct ConfirmationQuorum {
+    /// The voting weight needed to confirm a block
+    #[cfg_attr(feature = "serde", serde(with = "crate::serde_util::raw"))]
+    pub quorum_delta: u128,
+    /// The percentage of the online weight which is needed to confirm a block
+    pub online_weight_quorum_percent: u64,
+    /// The minimum online weight used to calculate `quorum_delta`
+    #[cfg_attr(feature = "serde", serde(with = "crate::serde_util::raw"))]
+    pub online_weight_minimum: u128,
+    /// The weight of the representatives which have recently voted
+    #[cfg_attr(feature = "serde", serde(with = "crate::serde_util::raw"))]
+    pub online_stake_total: u128,
+    /// The median online weight over the last two weeks
+    #[cfg_attr(feature = "serde", serde(with = "crate::serde_util::raw"))]
+    pub trended_stake_total: u128,
+    /// The weight of the representatives which the node is connected to
+    #[cfg_attr(feature = "serde", serde(with = "crate::serde_util::raw"))]
+    pub peers_stake_total: u128,
+    /// The representatives which the node is connected to, if requested
+    pub peers: Option<Vec<QuorumPeer>>,
+}
+
+/// An election which is active on a node.
+/// All tallies are in raw.
+#[derive(Debug, Clone, PartialEq, Eq)]
+#[cfg_attr(feature = "serde", derive(Serialize, Deserialize))]
+pub struct ElectionInfo {
+    /// The number of times the election has been broadcast
+    pub announcements: u64,
+    /// The number of representatives which have voted
+    pub voters: u64,
+    /// Hash of the block which is currently winning the election
+    #[cfg_attr(feature = "serde", serde(with = "crate::serde_util::hex_bytes"))]
+    pub last_winner: [u8; 32],
+    /// The total voting weight of all votes
+    #[cfg_attr(feature = "serde", serde(with = "crate::serde_util::raw"))]
+    pub total_tally: u128,
+    /// The total voting weight of all final votes
+    #[cfg_attr(feature = "serde", serde(with = "crate::serde_util::raw"))]
+    pub final_tally: u128,
+    /// The blocks competing in the election, sorted by tally (highest first)
+    pub candidates: Vec<ElectionCandidate>,
+}
+
+/// A block competing in an election
+#[derive(Debug, Clone, PartialEq, Eq)]
+#[cfg_attr(feature = "serde", derive(Serialize, Deserialize))]
+pub struct ElectionCandidate {
+    #[cfg_attr(feature = "serde", serde(with = "crate::serde_util::hex_bytes"))]
+    pub hash: [u8; 32],
+    /// The voting weight of the votes for this block
+    #[cfg_attr(feature = "serde", serde(with = "crate::serde_util::raw"))]
+    pub tally: u128,
+    /// The voting weight of the final votes for this block
+    #[cfg_attr(feature = "serde", serde(with = "crate::serde_util::raw"))]
+    pub final_tally: u128,
+    /// The representatives which voted for this block, and their weights, sorted by weight (highest first)
+    pub representatives: Vec<(Account, u128)>,
+}
+
+/// A representative which a node is connected to
+#[derive(Debug, Clone, PartialEq, Eq)]
+#[cfg_attr(feature = "serde", derive(Serialize, Deserialize))]
+pub struct QuorumPeer {
+    pub account: Account,
+    /// Such as `"[::ffff:127.0.0.1]:7075"`
+    pub ip: String,
+    #[cfg_attr(feature = "serde", serde(with = "crate::serde_util::raw"))]
+    pub weight: u128,
+}
+
+/// Telemetry data reported by a node, or averaged across its peers
+#[derive(Debug, Clone, PartialEq, Eq)]
+#[cfg_attr(feature = "serde", derive(Serialize, Deserialize))]
+pub struct NodeTelemetry {
+    pub block_count: u64,
+    pub cemented_count: u64,
+    pub unchecked_count: u64,
+    pub account_count: u64,
+    /// Outbound bandwidth limit, in bytes per second (`0` if unlimited)
+    pub bandwidth_cap: u64,
+    pub peer_count: u64,
+    pub protocol_version: u64,
+    /// Uptime, in seconds
+    pub uptime: u64,
+    /// Hash of the network's genesis block
+    #[cfg_attr(feature = "serde", serde(with = "crate::serde_util::hex_bytes"))]
+    pub genesis_block: [u8; 32],
+    pub major_version: u64,
+    pub minor_version: u64,
+    pub patch_version: u64,
+    pub pre_release_version: u64,
+    pub maker: u64,
+    /// Milliseconds since the UNIX epoch
+    pub timestamp: u64,
+    /// Only included in the telemetry of individual peers
+    pub node_id: Option<String>,
+    /// Only included in the telemetry of individual peers
+    pub address: Option<String>,
+    /// Only included in the telemetry of individual peers
+    pub port: Option<u16>,
+}
+
+/// A peer of the node
+#[derive(Debug, Clone, PartialEq, Eq)]
+#[cfg_attr(feature = "serde", derive(Serialize, Deserialize))]
+pub struct Peer {
+    /// Such as `"[::ffff:127.0.0.1]:7075"`
+    pub address: String,
+    pub protocol_version: u64,
+    /// Such as `"node_1y7j5rdqhg99uyab1145gu3yeo1tt8enjp7kn9gyywme7q8enp5a8hc6x4fm"`
+    pub node_id: String,
+    /// The type of connection, such as `"tcp"`
+    pub connection_type: String,
+}
+
+/// A block which the caller already trusts, used to verify account histories end-to-end
+#[derive(Debug, Clone, Zeroize, ZeroizeOnDrop, PartialEq, Eq)]
+#[cfg_attr(feature = "serde", derive(Serialize, Deserialize))]
+pub struct TrustAnchor {
+    /// Hash of the trusted block (e.g. a previously verified frontier)
+    #[cfg_attr(feature = "serde", serde(with = "crate::serde_util::hex_bytes"))]
+    pub hash: [u8; 32],
+    /// Balance of the account as of the trusted block, if known
+    #[cfg_attr(feature = "serde", serde(with = "crate::serde_util::raw_option"))]
+    pub balance: Option<u128>,
+}
+
+/// A block in the node's unchecked table, which is waiting for the block it depends on
+#[derive(Debug, Clone, Zeroize, ZeroizeOnDrop, PartialEq, Eq)]
+#[cfg_attr(feature = "serde", derive(Serialize, Deserialize))]
+pub struct UncheckedBlock {
+    /// Hash of the block which this block depends on (its `previous`, or the `send` block which it receives)
+    #[cfg_attr(feature = "serde", serde(with = "crate::serde_util::hex_bytes"))]
+    pub key: [u8; 32],
+    /// Timestamp of when this block was last modified in the unchecked table
+    pub modified_timestamp: u64,
+    /// The block, whose `block_type` is inferred (see `Rpc::unchecked()`)
+    pub block: Block,
+}
+
+/// Options for waiting on a block to be confirmed
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct ConfirmationOptions {
+    /// How often to poll the node for the block's status
+    pub poll_interval: Duration,
+    /// How long to wait for the block to be confirmed before giving up
+    pub timeout: Duration,
 }
-impl From<(Account, [u8; 32], u128)> for Receivable {
-    fn from(value: (Account, [u8; 32], u128)) -> Self {
-        Receivable {
-            recipient: value.0,
-            block_hash: value.1,
-            amount: value.2,
+impl Default for ConfirmationOptions {
+    /// Poll every second, for up to one minute
+    fn default() -> Self {
+        ConfirmationOptions {
+            poll_interval: Duration::from_secs(1),
+            timeout: Duration::from_secs(60),
         }
     }
 }
 
+/// The outcome of publishing a block and waiting for it to be confirmed
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum ConfirmationOutcome {
+    /// The block was confirmed
+    Confirmed(Box<BlockInfo>),
+    /// The block was not confirmed before the timeout
+    TimedOut,
+    /// The block was rejected as a fork, or was replaced by a competing block
+    Forked,
+}
+
 /// See the official [Nano RPC documentation](https://docs.nano.org/commands/rpc-protocol/) for details.
 #[derive(Debug, Clone)]
 pub struct Rpc(DebugRpc);
 impl Rpc {
+    /// Create a new RPC, using the default HTTP client.
+    ///
+    /// `reqwest` (which requires a `tokio` runtime) is used if the `rpc` feature is enabled,
+    /// otherwise `isahc` (which works with any runtime) is used if the `rpc-isahc` feature is enabled.
+    /// If neither is enabled, the browser's `fetch` API is used if the `wasm` feature is enabled.
     pub fn new(url: &str, proxy: impl Into<Option<String>>) -> Result<Rpc, RpcError> {
         Ok(Rpc(DebugRpc::new(url, proxy)?))
     }
 
+    /// Configure a new RPC, with control over connection reuse and HTTP versions
+    pub fn builder(url: &str) -> RpcBuilder {
+        RpcBuilder::new(url)
+    }
+
+    /// Probe the built-in list of public nodes, and use the fastest healthy one,
+    /// failing over to the other healthy nodes (fastest first) if it fails.
+    ///
+    /// See the `nodes` module for details.
+    #[cfg(feature = "nodes")]
+    pub async fn auto() -> Result<Rpc, RpcError> {
+        nodes::auto().await
+    }
+
+    /// Create a new RPC using `isahc`, which works with any async runtime
+    #[cfg(feature = "rpc-isahc")]
+    pub fn new_isahc(url: &str, proxy: impl Into<Option<String>>) -> Result<Rpc, RpcError> {
+        Ok(Rpc(DebugRpc::new_isahc(url, proxy)?))
+    }
+
+    /// Create an RPC which replays the responses recorded in `fixture`, without sending any requests
+    pub fn from_fixture(fixture: Fixture) -> Rpc {
+        Rpc(DebugRpc::from_fixture(fixture))
+    }
+
     /// Get the URL of this RPC
     pub fn get_url(&self) -> &str {
         self.0.get_url()
@@ -99,6 +475,83 @@ impl Rpc {
         self.0.get_proxy()
     }
 
+    /// Get the `AccountCache` used when parsing responses, if set
+    pub fn get_account_cache(&self) -> Option<&AccountCache> {
+        self.0.get_account_cache()
+    }
+
+    /// Set the `AccountCache` used when parsing responses.
+    /// The cache may be shared with other RPCs, or other parts of the application.
+    pub fn set_account_cache(&mut self, cache: Option<AccountCache>) {
+        self.0.set_account_cache(cache)
+    }
+
+    /// Get the `RetryPolicy` of this RPC, if set
+    pub fn get_retry_policy(&self) -> Option<&RetryPolicy> {
+        self.0.get_retry_policy()
+    }
+
+    /// Retry requests which fail due to transient problems.
+    /// If `None`, failed requests are never retried.
+    pub fn set_retry_policy(&mut self, policy: Option<RetryPolicy>) {
+        self.0.set_retry_policy(policy)
+    }
+
+    /// Get the timeout of each request, if set
+    pub fn get_timeout(&self) -> Option<Duration> {
+        self.0.get_timeout()
+    }
+
+    /// Fail requests which take longer than `timeout` with `RpcError::Timeout`.
+    /// If a `RetryPolicy` is set, each attempt has its own timeout, and timeouts are retried.
+    ///
+    /// If `None`, requests only time out if the HTTP client times out.
+    pub fn set_timeout(&mut self, timeout: Option<Duration>) {
+        self.0.set_timeout(timeout)
+    }
+
+    /// Get a clone of this RPC with a different timeout, for a single slow request (such as `work_generate`).
+    /// The clone shares this RPC's connections.
+    pub fn with_timeout(&self, timeout: Option<Duration>) -> Rpc {
+        Rpc(self.0.with_timeout(timeout))
+    }
+
+    /// Get the maximum number of accounts sent in a single request
+    pub fn get_batch_size(&self) -> usize {
+        self.0.get_batch_size()
+    }
+
+    /// Set the maximum number of accounts sent in a single request (at least `1`).
+    ///
+    /// `accounts_balances`, `accounts_frontiers` and `accounts_receivable` split larger slices into several requests,
+    /// and merge the results.
+    pub fn set_batch_size(&mut self, batch_size: usize) {
+        self.0.set_batch_size(batch_size)
+    }
+
+    /// Get the maximum number of batches which are requested at the same time
+    pub fn get_batch_concurrency(&self) -> usize {
+        self.0.get_batch_concurrency()
+    }
+
+    /// Set the maximum number of batches (see `set_batch_size`) which are requested at the same time (at least `1`).
+    ///
+    /// By default, batches are requested one at a time.
+    /// Results are always merged in the order of the accounts, regardless of which batch completes first.
+    pub fn set_batch_concurrency(&mut self, batch_concurrency: usize) {
+        self.0.set_batch_concurrency(batch_concurrency)
+    }
+
+    /// Get the `FixtureMode` of this RPC, if set
+    pub fn get_fixture_mode(&self) -> Option<&FixtureMode> {
+        self.0.get_fixture_mode()
+    }
+
+    /// Record requests to, or replay responses from, a `Fixture`
+    pub fn set_fixture_mode(&mut self, mode: Option<FixtureMode>) {
+        self.0.set_fixture_mode(mode)
+    }
+
     /// Same as `command`, but *everything* must be set manually
     pub async fn _raw_request(&self, json: JsonValue) -> Result<JsonValue, RpcError> {
         self.0._raw_request(json).await.result
@@ -117,8 +570,34 @@ impl Rpc {
         self.0.account_balance(account).await.result
     }
 
+    /// Same as `account_balance`, but also returns the sum of the receivable transactions
+    pub async fn account_balance_detailed(&self, account: &Account) -> Result<Balances, RpcError> {
+        self.0.account_balance_detailed(account).await.result
+    }
+
+    /// Returns `None` if the account has not been opened
+    pub async fn account_block_count(&self, account: &Account) -> Result<Option<usize>, RpcError> {
+        self.0.account_block_count(account).await.result
+    }
+
+    /// The account with the given public key.
+    ///
+    /// Checked against the local conversion (`Account::from_bytes()`), which should be used instead where possible.
+    pub async fn account_get(&self, public_key: &[u8; 32]) -> Result<Account, RpcError> {
+        self.0.account_get(public_key).await.result
+    }
+
+    /// Indirect, relies on `accounts_frontiers`.
+    ///
+    /// Returns `false` if the account has not been opened.
+    pub async fn account_exists(&self, account: &Account) -> Result<bool, RpcError> {
+        self.0.account_exists(account).await.result
+    }
+
     /// Lists the account's blocks, starting at `head` (or the newest block if `head` is `None`), and going back at most `count` number of blocks.
-    /// Will stop at first legacy block.
+    ///
+    /// Legacy blocks other than `send` blocks do not contain their balance, so it is found from the blocks around them.
+    /// Will stop at the first legacy block whose balance cannot be found.
     pub async fn account_history(
         &self,
         account: &Account,
@@ -132,6 +611,58 @@ impl Rpc {
             .result
     }
 
+    /// Lists the account's whole history, starting at the newest block, requesting `page_size` blocks at a time.
+    /// Will stop at the first legacy block whose balance cannot be found (see `account_history()`).
+    ///
+    /// No requests are made until the returned `AccountHistoryPages` is used.
+    pub fn account_history_paged(
+        &self,
+        account: &Account,
+        page_size: usize,
+    ) -> AccountHistoryPages {
+        AccountHistoryPages::new(self.clone(), account.clone(), page_size)
+    }
+
+    /// Lists the account's blocks, starting at the newest block, and going back to (and including) the `anchor` block.
+    /// Blocks are requested `page_size` at a time, until the anchor is reached.
+    ///
+    /// Since every block is linked to the previous one by its hash, and every signature is checked,
+    /// the returned blocks are verified against the anchor, rather than simply trusted.
+    /// If the history does not reach the anchor, or the anchor's balance does not match, an error is returned.
+    pub async fn account_history_anchored(
+        &self,
+        account: &Account,
+        page_size: usize,
+        anchor: &TrustAnchor,
+    ) -> Result<Vec<Block>, RpcError> {
+        self.0
+            .account_history_anchored(account, page_size, anchor)
+            .await
+            .result
+    }
+
+    /// Same as `account_history_anchored()`, but returns each block as soon as its page has been received and verified.
+    ///
+    /// The stream ends after the anchor block, or after the first error
+    /// (such as when the history does not reach the anchor).
+    /// No requests are made until the stream is polled.
+    pub fn account_history_stream(
+        &self,
+        account: &Account,
+        page_size: usize,
+        anchor: &TrustAnchor,
+    ) -> impl Stream<Item = Result<Block, RpcError>> + Send + 'static {
+        AccountHistoryPages::anchored(self.clone(), account.clone(), page_size, anchor.clone())
+            .into_stream()
+    }
+
+    /// The public key of the account.
+    ///
+    /// Checked against the local conversion (`Account::to_hex()`), which should be used instead where possible.
+    pub async fn account_key(&self, account: &Account) -> Result<[u8; 32], RpcError> {
+        self.0.account_key(account).await.result
+    }
+
     /// Gets general information about an account.
     /// Returns `None` if the account has not been opened.
     pub async fn account_info(&self, account: &Account) -> Result<Option<AccountInfo>, RpcError> {
@@ -149,10 +680,23 @@ impl Rpc {
         self.0.account_representative(account).await.result
     }
 
+    /// The voting weight delegated to the account
+    pub async fn account_weight(&self, account: &Account) -> Result<u128, RpcError> {
+        self.0.account_weight(account).await.result
+    }
+
     pub async fn accounts_balances(&self, accounts: &[Account]) -> Result<Vec<u128>, RpcError> {
         self.0.accounts_balances(accounts).await.result
     }
 
+    /// Same as `accounts_balances`, but also returns the sum of each account's receivable transactions
+    pub async fn accounts_balances_detailed(
+        &self,
+        accounts: &[Account],
+    ) -> Result<Vec<Balances>, RpcError> {
+        self.0.accounts_balances_detailed(accounts).await.result
+    }
+
     /// Returns the hash of the frontier (newest) block of the given accounts.
     /// If an account is not yet opened, its frontier will be returned as `None`.
     pub async fn accounts_frontiers(
@@ -162,7 +706,7 @@ impl Rpc {
         self.0.accounts_frontiers(accounts).await.result
     }
 
-    /// For each account, returns the receivable transactions as `Vec<Receivable>`
+    /// For each account, returns the receivable transactions (including their senders) as `Vec<Receivable>`
     pub async fn accounts_receivable(
         &self,
         accounts: &[Account],
@@ -175,6 +719,22 @@ impl Rpc {
             .result
     }
 
+    /// Same as `accounts_receivable`, but looks up each send block with `blocks_info`,
+    /// dropping any transactions whose send block does not exist, or does not actually send the amount to the recipient.
+    ///
+    /// This protects against nodes which report receivable transactions that don't exist.
+    pub async fn accounts_receivable_verified(
+        &self,
+        accounts: &[Account],
+        count: usize,
+        threshold: u128,
+    ) -> Result<Vec<Vec<Receivable>>, RpcError> {
+        self.0
+            .accounts_receivable_verified(accounts, count, threshold)
+            .await
+            .result
+    }
+
     /// If an account is not yet opened, its representative will be returned as `None`
     pub async fn accounts_representatives(
         &self,
@@ -196,11 +756,324 @@ impl Rpc {
         self.0.blocks_info(hashes).await.result
     }
 
+    /// Get the hashes of the block and its predecessors, newest first
+    pub async fn chain(&self, hash: [u8; 32], count: usize) -> Result<Vec<[u8; 32]>, RpcError> {
+        self.0.chain(hash, count).await.result
+    }
+
+    /// Same as `chain`, but fetches the blocks with `blocks_info`,
+    /// and checks that they are signed and link together.
+    /// Stops at the first legacy block.
+    pub async fn chain_verified(
+        &self,
+        hash: [u8; 32],
+        count: usize,
+    ) -> Result<Vec<BlockInfo>, RpcError> {
+        self.0.chain_verified(hash, count).await.result
+    }
+
+    /// Get the hashes of the block and its successors, oldest first
+    pub async fn successors(
+        &self,
+        hash: [u8; 32],
+        count: usize,
+    ) -> Result<Vec<[u8; 32]>, RpcError> {
+        self.0.successors(hash, count).await.result
+    }
+
+    /// Same as `successors`, but fetches the blocks with `blocks_info`,
+    /// and checks that they are signed and link together.
+    /// Returns an empty list if `hash` is a legacy block.
+    pub async fn successors_verified(
+        &self,
+        hash: [u8; 32],
+        count: usize,
+    ) -> Result<Vec<BlockInfo>, RpcError> {
+        self.0.successors_verified(hash, count).await.result
+    }
+
+    /// The node's peers, sorted by address
+    pub async fn peers(&self) -> Result<Vec<Peer>, RpcError> {
+        self.0.peers().await.result
+    }
+
     /// Returns the hash of the block
     pub async fn process(&self, block: &Block) -> Result<[u8; 32], RpcError> {
         self.0.process(block).await.result
     }
 
+    /// Publish the block, first generating work for it with the node's `work_generate` if its work is not valid
+    /// for the given difficulty
+    pub async fn process_with_work(
+        &self,
+        block: &mut Block,
+        difficulty: [u8; 8],
+    ) -> Result<[u8; 32], RpcError> {
+        self.0.process_with_work(block, difficulty).await.result
+    }
+
+    /// Same as `process_with_work`, but generates work with the given `WorkProvider`
+    pub async fn process_with_work_from(
+        &self,
+        block: &mut Block,
+        difficulty: [u8; 8],
+        provider: &dyn WorkProvider,
+    ) -> Result<[u8; 32], RpcError> {
+        self.0
+            .process_with_work_from(block, difficulty, provider)
+            .await
+            .result
+    }
+
+    /// Publish the block, and wait until it is either confirmed, replaced by a fork, or the timeout is reached.
+    /// Publishing a block which already exists on the ledger is not considered an error.
+    ///
+    /// A block which the node has not seen (yet) is waited for. It is only considered to be replaced by a fork
+    /// if the node rejects it as a fork, or the node has a different block after `block.previous`
+    /// (or a different `open` block, if `block` opens its account).
+    pub async fn process_and_confirm(
+        &self,
+        block: &Block,
+        options: ConfirmationOptions,
+    ) -> Result<ConfirmationOutcome, RpcError> {
+        self.0.process_and_confirm(block, options).await.result
+    }
+
+    /// Wait until the block with the given hash is confirmed, or the timeout is reached.
+    ///
+    /// Unlike `process_and_confirm`, the block is never checked for forks,
+    /// so this never returns `ConfirmationOutcome::Forked`.
+    pub async fn wait_for_confirmation(
+        &self,
+        hash: [u8; 32],
+        options: ConfirmationOptions,
+    ) -> Result<ConfirmationOutcome, RpcError> {
+        self.0.wait_for_confirmation(hash, options).await.result
+    }
+
+    /// The receivable transactions of a single account, including their senders, sorted by amount (highest first)
+    pub async fn receivable(
+        &self,
+        account: &Account,
+        count: usize,
+        threshold: u128,
+    ) -> Result<Vec<Receivable>, RpcError> {
+        self.0.receivable(account, count, threshold).await.result
+    }
+
+    /// The minimum amount which the node's wallets will automatically receive
+    pub async fn receive_minimum(&self) -> Result<u128, RpcError> {
+        self.0.receive_minimum().await.result
+    }
+
+    /// Makes the node search for receivable transactions for the accounts in one of its wallets, and receive them.
+    /// Returns whether the search was started.
+    ///
+    /// Requires `enable_control` on the node.
+    pub async fn search_receivable(&self, wallet: &[u8; 32]) -> Result<bool, RpcError> {
+        self.0.search_receivable(wallet).await.result
+    }
+
+    /// Makes the node search for receivable transactions for the accounts in all of its unlocked wallets, and receive them.
+    ///
+    /// Requires `enable_control` on the node.
+    pub async fn search_receivable_all(&self) -> Result<(), RpcError> {
+        self.0.search_receivable_all().await.result
+    }
+
+    pub async fn active_difficulty(&self) -> Result<ActiveDifficulty, RpcError> {
+        self.0.active_difficulty().await.result
+    }
+
+    /// Same as `active_difficulty`, but reuses the result for up to a minute.
+    /// The cached result is shared by all clones of this RPC.
+    pub async fn active_difficulty_cached(&self) -> Result<ActiveDifficulty, RpcError> {
+        self.0.active_difficulty_cached().await.result
+    }
+
+    /// The total supply, minus the balances of the burn and reserve accounts
+    pub async fn available_supply(&self) -> Result<u128, RpcError> {
+        self.0.available_supply().await.result
+    }
+
+    /// The account which created the block.
+    /// Blocks that don't exist will return `None`.
+    pub async fn block_account(&self, hash: [u8; 32]) -> Result<Option<Account>, RpcError> {
+        self.0.block_account(hash).await.result
+    }
+
+    /// Have the node create and sign `block` (a `state` block) with `key`, using the block's `work`.
+    ///
+    /// **The private key is sent to the node**: only use this with a node which you control.
+    /// Blocks can be created locally with `BlockBuilder`, so this is mostly useful for testing (see `block_create_conformance()`).
+    pub async fn block_create(&self, block: &Block, key: &Key) -> Result<CreatedBlock, RpcError> {
+        self.0.block_create(block, key).await.result
+    }
+
+    /// Have the node create `block` with `key` (as in `block_create()`), and compare it to the block signed locally,
+    /// checking that this library hashes, signs, and serializes blocks exactly as the node does.
+    ///
+    /// **The private key is sent to the node**: only use this with a node which you control, and a key used only for testing.
+    pub async fn block_create_conformance(
+        &self,
+        block: &Block,
+        key: &Key,
+    ) -> Result<BlockConformance, RpcError> {
+        self.0.block_create_conformance(block, key).await.result
+    }
+
+    pub async fn block_count(&self) -> Result<BlockCount, RpcError> {
+        self.0.block_count().await.result
+    }
+
+    /// The qualified roots (the previous block's hash, followed by the root) of the node's active elections
+    pub async fn confirmation_active(&self) -> Result<Vec<[u8; 64]>, RpcError> {
+        self.0.confirmation_active().await.result
+    }
+
+    /// Gets the status of an active election, given its qualified root.
+    /// Returns `None` if the election is not active.
+    pub async fn confirmation_info(
+        &self,
+        root: [u8; 64],
+    ) -> Result<Option<ElectionInfo>, RpcError> {
+        self.0.confirmation_info(root).await.result
+    }
+
+    /// The voting weight needed to confirm blocks, and the weight of the representatives which are online
+    pub async fn confirmation_quorum(&self) -> Result<ConfirmationQuorum, RpcError> {
+        self.0.confirmation_quorum().await.result
+    }
+
+    /// Same as `confirmation_quorum`, but also returns the representatives which the node is connected to
+    pub async fn confirmation_quorum_detailed(&self) -> Result<ConfirmationQuorum, RpcError> {
+        self.0.confirmation_quorum_detailed().await.result
+    }
+
+    /// Up to `count` accounts which delegate at least `threshold` (in raw) to the representative `account`,
+    /// and their balances, sorted by balance (highest first)
+    pub async fn delegators(
+        &self,
+        account: &Account,
+        threshold: u128,
+        count: usize,
+    ) -> Result<Vec<(Account, u128)>, RpcError> {
+        self.0.delegators(account, threshold, count).await.result
+    }
+
+    /// The number of accounts which delegate to the representative `account`
+    pub async fn delegators_count(&self, account: &Account) -> Result<usize, RpcError> {
+        self.0.delegators_count(account).await.result
+    }
+
+    /// The frontiers of (at most) `count` accounts, starting at `start`, sorted by public key.
+    ///
+    /// The frontier of `start` is included, if it has been opened.
+    pub async fn frontiers(
+        &self,
+        start: &Account,
+        count: usize,
+    ) -> Result<Vec<(Account, [u8; 32])>, RpcError> {
+        self.0.frontiers(start, count).await.result
+    }
+
+    /// Lists the frontiers of every account from `start` onwards, sorted by public key, requesting `page_size` frontiers at a time.
+    /// To list the whole ledger, start at the account with the public key `[0; 32]`.
+    ///
+    /// No requests are made until the returned `FrontierPages` is used.
+    pub fn frontiers_paged(&self, start: &Account, page_size: usize) -> FrontierPages {
+        FrontierPages::new(self.clone(), start.clone(), page_size)
+    }
+
+    /// The ledger entries of (at most) `count` accounts, starting at `start`, sorted by public key.
+    /// If `modified_since` is set, only accounts which have been modified since that timestamp are included.
+    ///
+    /// The entry of `start` is included, if it has been opened.
+    pub async fn ledger(
+        &self,
+        start: &Account,
+        count: usize,
+        modified_since: Option<u64>,
+    ) -> Result<Vec<(Account, LedgerEntry)>, RpcError> {
+        self.0.ledger(start, count, modified_since).await.result
+    }
+
+    /// Lists the ledger entries of every account from `start` onwards, sorted by public key, requesting `page_size` entries at a time.
+    /// To export the whole ledger, start at the account with the public key `[0; 32]`.
+    ///
+    /// No requests are made until the returned `LedgerPages` is used.
+    pub fn ledger_paged(
+        &self,
+        start: &Account,
+        page_size: usize,
+        modified_since: Option<u64>,
+    ) -> LedgerPages {
+        LedgerPages::new(self.clone(), start.clone(), page_size, modified_since)
+    }
+
+    /// The `count` representatives with the most voting weight, and their weights, sorted by weight (highest first)
+    pub async fn representatives(&self, count: usize) -> Result<Vec<(Account, u128)>, RpcError> {
+        self.0.representatives(count).await.result
+    }
+
+    /// The representatives which have recently voted
+    pub async fn representatives_online(&self) -> Result<Vec<Account>, RpcError> {
+        self.0.representatives_online().await.result
+    }
+
+    /// Same as `representatives_online`, but also returns the weight of each representative, sorted by weight (highest first)
+    pub async fn representatives_online_detailed(&self) -> Result<Vec<(Account, u128)>, RpcError> {
+        self.0.representatives_online_detailed().await.result
+    }
+
+    /// Rebroadcasts the block, and (if non-zero) up to `sources` blocks before each of the sends it receives,
+    /// and up to `destinations` blocks after each of the receives of its sends.
+    /// Returns the hashes of the rebroadcast blocks.
+    pub async fn republish(
+        &self,
+        hash: [u8; 32],
+        sources: usize,
+        destinations: usize,
+    ) -> Result<Vec<[u8; 32]>, RpcError> {
+        self.0.republish(hash, sources, destinations).await.result
+    }
+
+    /// Telemetry data averaged across the node's peers
+    pub async fn telemetry(&self) -> Result<NodeTelemetry, RpcError> {
+        self.0.telemetry().await.result
+    }
+
+    /// Telemetry data of each of the node's peers
+    pub async fn telemetry_raw(&self) -> Result<Vec<NodeTelemetry>, RpcError> {
+        self.0.telemetry_raw().await.result
+    }
+
+    /// Up to `count` blocks from the node's unchecked table.
+    ///
+    /// Since the subtype of a block depends on the balance of its previous block, which may not be known yet,
+    /// `block_type` is inferred from the block alone:
+    /// blocks with an epoch link are `Epoch`, open blocks are `Receive`, blocks with a zero link are `Change`,
+    /// and all other blocks are `Send`, even though they may be receives.
+    /// Legacy blocks are skipped.
+    pub async fn unchecked(&self, count: usize) -> Result<Vec<Block>, RpcError> {
+        self.0.unchecked(count).await.result
+    }
+
+    /// Up to `count` blocks from the node's unchecked table, starting at the dependency `key`.
+    ///
+    /// `block_type` is inferred in the same way as `unchecked()`. Legacy blocks are skipped.
+    pub async fn unchecked_keys(
+        &self,
+        key: [u8; 32],
+        count: usize,
+    ) -> Result<Vec<UncheckedBlock>, RpcError> {
+        self.0.unchecked_keys(key, count).await.result
+    }
+
+    pub async fn version(&self) -> Result<NodeVersion, RpcError> {
+        self.0.version().await.result
+    }
+
     /// Returns the generated work, assuming no error is encountered
     pub async fn work_generate(
         &self,
@@ -212,6 +1085,30 @@ impl Rpc {
             .await
             .result
     }
+
+    /// Generate work for a block of the given type, at the network's current difficulty for that type
+    /// (see `active_difficulty_cached()`)
+    pub async fn work_generate_for(
+        &self,
+        work_hash: [u8; 32],
+        block_type: &BlockType,
+    ) -> Result<[u8; 8], RpcError> {
+        self.0.work_generate_for(work_hash, block_type).await.result
+    }
+
+    /// Check `work` against the `send` and `receive` thresholds (and `custom_difficulty`, if given).
+    /// The node's answer is checked against the local calculation.
+    pub async fn work_validate(
+        &self,
+        work: [u8; 8],
+        work_hash: [u8; 32],
+        custom_difficulty: Option<[u8; 8]>,
+    ) -> Result<WorkValidation, RpcError> {
+        self.0
+            .work_validate(work, work_hash, custom_difficulty)
+            .await
+            .result
+    }
 }
 
 #[cfg(test)]
@@ -227,6 +1124,8 @@ mod serde_tests {
         height: 939,
         timestamp: 3902193,
         confirmed: true,
+        amount: ONE_NANO,
+        successor: Some([7; 32]),
         block: Block {
             block_type: BlockType::Receive,
             account: get_genesis_account(),
@@ -237,7 +1136,7 @@ mod serde_tests {
             signature: Signature::default(),
             work: [22; 8]
         }
-    } => USIZE_LEN + 8 + 1 + 220);
+    } => USIZE_LEN + 8 + 1 + 16 + 1 + 32 + 220);
 
     serde_test!(account_info: AccountInfo {
         frontier: [92; 32],
@@ -251,9 +1150,113 @@ mod serde_tests {
         receivable: 100
     } => 32 + 32 + 16 + 8 + USIZE_LEN + USIZE_LEN + 32 + 16 + USIZE_LEN);
 
-    serde_test!(receivable: Receivable {
-        recipient: get_genesis_account(),
-        block_hash: [51; 32],
-        amount: 432894284243
-    } => 32 + 32 + 16);
+    serde_test!(balances: Balances {
+        confirmed: ONE_NANO,
+        receivable: 382
+    } => 16 + 16);
+
+    serde_test!(node_version: NodeVersion {
+        rpc_version: 1,
+        store_version: 21,
+        protocol_version: 20,
+        node_vendor: "Nano V27.1".into(),
+        store_vendor: "LMDB".into(),
+        network: "live".into(),
+        network_identifier: [4; 32],
+        build_info: "".into()
+    } => (3 * 8) + (8 + 10) + (8 + 4) + (8 + 4) + 32 + 8);
+
+    serde_test!(block_count: BlockCount {
+        count: 1000,
+        unchecked: 10,
+        cemented: 25
+    } => 8 * 3);
+
+    serde_test!(election_info: ElectionInfo {
+        announcements: 2,
+        voters: 29,
+        last_winner: [5; 32],
+        total_tally: ONE_NANO,
+        final_tally: 0,
+        candidates: vec![ElectionCandidate {
+            hash: [5; 32],
+            tally: ONE_NANO,
+            final_tally: 0,
+            representatives: vec![(get_genesis_account(), ONE_NANO)]
+        }]
+    } => (2 * 8) + 32 + (2 * 16) + 8 + (32 + (2 * 16) + 8 + (32 + 16)));
+
+    serde_test!(confirmation_quorum: ConfirmationQuorum {
+        quorum_delta: 5 * ONE_NANO,
+        online_weight_quorum_percent: 67,
+        online_weight_minimum: 6 * ONE_NANO,
+        online_stake_total: 8 * ONE_NANO,
+        trended_stake_total: 7 * ONE_NANO,
+        peers_stake_total: 8 * ONE_NANO,
+        peers: Some(vec![QuorumPeer {
+            account: get_genesis_account(),
+            ip: "[::1]:7075".into(),
+            weight: ONE_NANO
+        }])
+    } => 16 + 8 + (4 * 16) + 1 + 8 + (32 + (8 + 10) + 16));
+
+    serde_test!(node_telemetry: NodeTelemetry {
+        block_count: 8662,
+        cemented_count: 8600,
+        unchecked_count: 0,
+        account_count: 1200,
+        bandwidth_cap: 10485760,
+        peer_count: 200,
+        protocol_version: 20,
+        uptime: 133,
+        genesis_block: [3; 32],
+        major_version: 27,
+        minor_version: 1,
+        patch_version: 0,
+        pre_release_version: 0,
+        maker: 0,
+        timestamp: 1594654710521,
+        node_id: None,
+        address: None,
+        port: Some(7075)
+    } => (8 * 8) + 32 + (6 * 8) + 1 + 1 + 3);
+
+    serde_test!(peer: Peer {
+        address: "[::ffff:127.0.0.1]:7075".into(),
+        protocol_version: 20,
+        node_id: "node_1y7j5rdqhg99uyab1145gu3yeo1tt8enjp7kn9gyywme7q8enp5a8hc6x4fm".into(),
+        connection_type: "tcp".into()
+    } => (8 + 23) + 8 + (8 + 65) + (8 + 3));
+
+    serde_test!(ledger_entry: LedgerEntry {
+        frontier: [1; 32],
+        open_block: [2; 32],
+        representative_block: [3; 32],
+        balance: ONE_NANO,
+        modified_timestamp: 1594654710,
+        block_count: 12,
+        representative: get_genesis_account(),
+        weight: 0,
+        receivable: 7
+    } => (3 * 32) + 16 + 8 + USIZE_LEN + 32 + (2 * 16));
+
+    serde_test!(unchecked_block: UncheckedBlock {
+        key: [44; 32],
+        modified_timestamp: 1565856525,
+        block: Block {
+            block_type: BlockType::Send,
+            account: get_genesis_account(),
+            previous: [44; 32],
+            representative: get_genesis_account(),
+            balance: ONE_NANO,
+            link: [3; 32],
+            signature: Signature::default(),
+            work: [9; 8]
+        }
+    } => 32 + 8 + 220);
+
+    serde_test!(trust_anchor: TrustAnchor {
+        hash: [8; 32],
+        balance: Some(329)
+    } => 32 + 1 + 16);
 }
@@ -0,0 +1,239 @@
+//! The node's built-in wallet, for tooling which intentionally delegates key management to the node.
+//!
+//! **Everything in this module trusts the node**: private keys are sent to it, it signs blocks on your behalf,
+//! and its responses cannot be verified. Only use this with a node which you control,
+//! with `enable_control` set, and never over an untrusted network.
+
+use super::util::{
+    account_from_json_cached, bytes_from_json, map_keys_from_json, to_uppercase_hex,
+};
+use super::{parse, Balances, Rpc, RpcError};
+use crate::{Account, Key};
+use json::{Map, Value as JsonValue};
+use serde_json as json;
+
+/// A wallet stored on the node, identified by its wallet ID
+#[derive(Debug, Clone)]
+pub struct NodeWallet {
+    rpc: Rpc,
+    id: [u8; 32],
+}
+impl NodeWallet {
+    /// Use an existing wallet on the node
+    pub fn new(rpc: Rpc, id: [u8; 32]) -> NodeWallet {
+        NodeWallet { rpc, id }
+    }
+
+    /// Create a new, empty wallet on the node
+    pub async fn create(rpc: Rpc) -> Result<NodeWallet, RpcError> {
+        let json = rpc.command("wallet_create", Map::new()).await?;
+        let id = bytes_from_json(&json["wallet"])?;
+        Ok(NodeWallet { rpc, id })
+    }
+
+    /// The wallet ID
+    pub fn id(&self) -> [u8; 32] {
+        self.id
+    }
+
+    fn arguments(&self) -> Map<String, JsonValue> {
+        let mut arguments = Map::new();
+        arguments.insert("wallet".into(), to_uppercase_hex(&self.id).into());
+        arguments
+    }
+
+    /// Add a private key to the wallet, returning its account.
+    ///
+    /// **The private key is sent to the node.**
+    pub async fn add(&self, key: &Key) -> Result<Account, RpcError> {
+        let mut arguments = self.arguments();
+        arguments.insert("key".into(), to_uppercase_hex(key.as_bytes()).into());
+        let json = self.rpc.command("wallet_add", arguments).await?;
+
+        let account =
+            account_from_json_cached(&json["account"], "account", self.rpc.get_account_cache())?;
+        if account != key.to_account() {
+            return Err(RpcError::InvalidData);
+        }
+        Ok(account)
+    }
+
+    /// Create `count` new accounts, derived from the wallet's seed
+    pub async fn accounts_create(&self, count: usize) -> Result<Vec<Account>, RpcError> {
+        let mut arguments = self.arguments();
+        arguments.insert("count".into(), count.to_string().into());
+        let json = self.rpc.command("accounts_create", arguments).await?;
+
+        json["accounts"]
+            .as_array()
+            .ok_or_else(|| RpcError::InvalidJsonDataType("accounts".into()))?
+            .iter()
+            .map(|account| {
+                account_from_json_cached(account, "accounts", self.rpc.get_account_cache())
+            })
+            .collect()
+    }
+
+    /// Send `amount` from `source` (which must be in the wallet) to `destination`, returning the hash of the send block.
+    ///
+    /// If `id` is set, the node will only ever send once for that `id`, so the request can safely be retried.
+    pub async fn send(
+        &self,
+        source: &Account,
+        destination: &Account,
+        amount: u128,
+        id: Option<&str>,
+    ) -> Result<[u8; 32], RpcError> {
+        let mut arguments = self.arguments();
+        arguments.insert("source".into(), source.into());
+        arguments.insert("destination".into(), destination.into());
+        arguments.insert("amount".into(), amount.to_string().into());
+        if let Some(id) = id {
+            arguments.insert("id".into(), id.into());
+        }
+        let json = self.rpc.command("send", arguments).await?;
+        bytes_from_json(&json["block"])
+    }
+
+    /// Receive the send block with the given hash to `account` (which must be in the wallet),
+    /// returning the hash of the receive block
+    pub async fn receive(&self, account: &Account, hash: [u8; 32]) -> Result<[u8; 32], RpcError> {
+        let mut arguments = self.arguments();
+        arguments.insert("account".into(), account.into());
+        arguments.insert("block".into(), to_uppercase_hex(&hash).into());
+        let json = self.rpc.command("receive", arguments).await?;
+        bytes_from_json(&json["block"])
+    }
+
+    /// The balances of the accounts in the wallet, optionally only including those with at least `threshold` receivable
+    pub async fn balances(
+        &self,
+        threshold: Option<u128>,
+    ) -> Result<Vec<(Account, Balances)>, RpcError> {
+        let mut arguments = self.arguments();
+        if let Some(threshold) = threshold {
+            arguments.insert("threshold".into(), threshold.to_string().into());
+        }
+        let json = self.rpc.command("wallet_balances", arguments).await?;
+
+        let balances = match &json["balances"] {
+            // the node returns an empty string, rather than an empty map
+            JsonValue::String(balances) if balances.is_empty() => return Ok(vec![]),
+            balances => balances,
+        };
+        map_keys_from_json(balances, "balances")?
+            .into_iter()
+            .map(|account| {
+                Ok((
+                    account_from_json_cached(
+                        &account.as_str().into(),
+                        "balances",
+                        self.rpc.get_account_cache(),
+                    )?,
+                    parse::account_balance_detailed(balances[account].clone())?,
+                ))
+            })
+            .collect()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::NodeWallet;
+    use crate::constants::get_genesis_account;
+    use crate::rpc::mock::MockRpc;
+    use crate::rpc::Balances;
+    use crate::{Key, SecretBytes};
+    use futures::executor::block_on;
+    use serde_json::json;
+
+    const WALLET: &str = "000D1BAEC8EC208142C99059B393051BAC8380F9B5A2E6B2489A277D81789F3F";
+
+    #[test]
+    fn create() {
+        let mock = MockRpc::new();
+        mock.respond("wallet_create", json!({"wallet": WALLET}));
+        let wallet = block_on(NodeWallet::create(mock.rpc())).unwrap();
+        assert!(hex::encode_upper(wallet.id()) == WALLET);
+    }
+
+    #[test]
+    fn add() {
+        let key = Key::from_seed(&SecretBytes::from([0; 32]), 0);
+        let mock = MockRpc::new();
+        let wallet = NodeWallet::new(mock.rpc(), [0xab; 32]);
+
+        mock.respond(
+            "wallet_add",
+            json!({"account": key.to_account().to_string()}),
+        );
+        assert!(block_on(wallet.add(&key)).unwrap() == key.to_account());
+        assert!(mock.requests()[0]["key"] == hex::encode_upper(key.as_bytes()));
+        assert!(mock.requests()[0]["wallet"] == hex::encode_upper([0xab; 32]));
+
+        mock.respond(
+            "wallet_add",
+            json!({"account": get_genesis_account().to_string()}),
+        );
+        assert!(block_on(wallet.add(&key)).is_err());
+    }
+
+    #[test]
+    fn send_and_receive() {
+        let genesis = get_genesis_account();
+        let account = Key::from_seed(&SecretBytes::from([0; 32]), 0).to_account();
+        let mock = MockRpc::new();
+        let wallet = NodeWallet::new(mock.rpc(), [0xab; 32]);
+
+        mock.respond(
+            "accounts_create",
+            json!({"accounts": [account.to_string()]}),
+        );
+        assert!(block_on(wallet.accounts_create(1)).unwrap() == [account.clone()]);
+
+        mock.respond("send", json!({"block": hex::encode([1; 32])}));
+        let hash = block_on(wallet.send(&account, &genesis, 1000, Some("order-42"))).unwrap();
+        assert!(hash == [1; 32]);
+        assert!(mock.requests()[1]["amount"] == "1000");
+        assert!(mock.requests()[1]["id"] == "order-42");
+
+        mock.respond("receive", json!({"block": hex::encode([2; 32])}));
+        assert!(block_on(wallet.receive(&genesis, hash)).unwrap() == [2; 32]);
+        assert!(mock.requests()[2]["block"] == hex::encode_upper([1; 32]));
+    }
+
+    #[test]
+    fn balances() {
+        let genesis = get_genesis_account();
+        let mock = MockRpc::new();
+        let wallet = NodeWallet::new(mock.rpc(), [0xab; 32]);
+
+        mock.respond(
+            "wallet_balances",
+            json!({
+                "balances": {
+                    genesis.to_string(): {
+                        "balance": "10000",
+                        "pending": "10000",
+                        "receivable": "10000"
+                    }
+                }
+            }),
+        );
+        let balances = block_on(wallet.balances(Some(1))).unwrap();
+        assert!(
+            balances
+                == [(
+                    genesis,
+                    Balances {
+                        confirmed: 10000,
+                        receivable: 10000
+                    }
+                )]
+        );
+        assert!(mock.requests()[0]["threshold"] == "1");
+
+        mock.respond("wallet_balances", json!({"balances": ""}));
+        assert!(block_on(wallet.balances(None)).unwrap().is_empty());
+    }
+}
@@ -0,0 +1,196 @@
+use super::{Rpc, RpcError, RpcPool};
+use futures::future::{self, join_all, Either};
+use futures_timer::Delay;
+use json::Map;
+use serde_json as json;
+use std::time::Duration;
+
+#[cfg(not(feature = "wasm"))]
+use std::time::Instant;
+#[cfg(feature = "wasm")]
+use web_time::Instant;
+
+/// How long a node is given to respond to a probe, by default
+pub const DEFAULT_PROBE_TIMEOUT: Duration = Duration::from_secs(5);
+
+/// A well-known public RPC node
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct PublicNode {
+    /// A human-readable name for this node
+    pub name: &'static str,
+    /// The RPC endpoint of this node
+    pub url: &'static str,
+    /// Whether this node is known to support `work_generate`
+    pub work_generate: bool,
+    /// Notes on rate limits and other restrictions
+    pub notes: &'static str,
+}
+impl PublicNode {
+    /// Create an `Rpc` using this node
+    pub fn to_rpc(&self) -> Result<Rpc, RpcError> {
+        Rpc::new(self.url, None)
+    }
+}
+
+/// Well-known public RPC nodes.
+///
+/// Public nodes come and go, and are run by third parties: this list may be out of date,
+/// and none of these nodes should be trusted with anything that matters.
+pub const PUBLIC_NODES: &[PublicNode] = &[
+    PublicNode {
+        name: "Rainstorm City",
+        url: "https://rainstorm.city/api",
+        work_generate: true,
+        notes: "rate limited",
+    },
+    PublicNode {
+        name: "NanOslo",
+        url: "https://nanoslo.0x.no/proxy",
+        work_generate: true,
+        notes: "rate limited",
+    },
+    PublicNode {
+        name: "Nanos.cc",
+        url: "https://proxy.nanos.cc/proxy",
+        work_generate: true,
+        notes: "rate limited; work_generate may be disabled under load",
+    },
+    PublicNode {
+        name: "SomeNano",
+        url: "https://node.somenano.com/proxy",
+        work_generate: false,
+        notes: "rate limited; limited set of commands",
+    },
+    PublicNode {
+        name: "Nano.to",
+        url: "https://rpc.nano.to",
+        work_generate: true,
+        notes: "rate limited without an API key",
+    },
+];
+
+/// The result of probing a public node
+#[derive(Debug, Clone)]
+pub struct ProbedNode {
+    pub node: PublicNode,
+    pub rpc: Rpc,
+    /// Round-trip time of the `version` request
+    pub latency: Duration,
+}
+
+/// Send a `version` request to `node`, returning the round-trip time if it responded successfully within `timeout`
+pub async fn probe(node: &PublicNode, timeout: Duration) -> Result<ProbedNode, RpcError> {
+    let rpc = node.to_rpc()?;
+    let start = Instant::now();
+    let request = rpc.command("version", Map::new());
+    let response = match future::select(Box::pin(request), Delay::new(timeout)).await {
+        Either::Left((response, _)) => response?,
+        Either::Right(_) => return Err(RpcError::NoNodeAvailable),
+    };
+    let latency = start.elapsed();
+
+    if !response["error"].is_null() || response["node_vendor"].is_null() {
+        return Err(RpcError::InvalidData);
+    }
+    Ok(ProbedNode {
+        node: *node,
+        rpc,
+        latency,
+    })
+}
+
+/// Probe all of the given nodes concurrently, returning the healthy ones (fastest first)
+pub async fn select(nodes: &[PublicNode], timeout: Duration) -> Vec<ProbedNode> {
+    let probes = nodes.iter().map(|node| probe(node, timeout));
+    let mut healthy: Vec<ProbedNode> = join_all(probes)
+        .await
+        .into_iter()
+        .filter_map(|result| result.ok())
+        .collect();
+    healthy.sort_by_key(|probed| probed.latency);
+    healthy
+}
+
+/// Create an `RpcPool` of probed nodes, keeping their order
+///
+/// Returns `RpcError::NoNodeAvailable` if `probed` is empty.
+pub fn pool(probed: &[ProbedNode]) -> Result<RpcPool, RpcError> {
+    if probed.is_empty() {
+        return Err(RpcError::NoNodeAvailable);
+    }
+    let transports = probed
+        .iter()
+        .map(|probed| (probed.node.url, probed.rpc.0.transport()))
+        .collect();
+    Ok(RpcPool::from_transports(transports))
+}
+
+/// Probe all of the given nodes concurrently, and create an `RpcPool` of the healthy ones (fastest first)
+pub async fn select_pool(nodes: &[PublicNode], timeout: Duration) -> Result<RpcPool, RpcError> {
+    pool(&select(nodes, timeout).await)
+}
+
+/// Probe all of the nodes in `PUBLIC_NODES`, and return an `Rpc` which sends requests to the fastest healthy one,
+/// failing over to the next fastest if it fails (see `select_pool()`)
+pub async fn auto() -> Result<Rpc, RpcError> {
+    Ok(select_pool(PUBLIC_NODES, DEFAULT_PROBE_TIMEOUT)
+        .await?
+        .rpc())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{pool, ProbedNode, PUBLIC_NODES};
+    use crate::rpc::mock::MockRpc;
+    use crate::rpc::RpcError;
+    use futures::executor::block_on;
+    use serde_json::json;
+    use std::collections::HashSet;
+    use std::time::Duration;
+
+    #[test]
+    fn public_nodes() {
+        let mut urls = HashSet::new();
+        for node in PUBLIC_NODES {
+            assert!(node.url.starts_with("https://"));
+            assert!(urls.insert(node.url));
+            #[cfg(any(feature = "rpc", feature = "rpc-isahc"))]
+            assert!(node.to_rpc().unwrap().get_url() == node.url);
+        }
+    }
+
+    #[test]
+    fn probed_pool() {
+        assert!(matches!(pool(&[]), Err(RpcError::NoNodeAvailable)));
+
+        let (fast, slow) = (MockRpc::new(), MockRpc::new());
+        fast.on("block_count", |_| {
+            Err(RpcError::IoError(std::io::ErrorKind::Other.into()))
+        });
+        slow.respond(
+            "block_count",
+            json!({"count": "2", "unchecked": "0", "cemented": "0"}),
+        );
+        let probed = [
+            ProbedNode {
+                node: PUBLIC_NODES[1],
+                rpc: fast.rpc(),
+                latency: Duration::from_millis(10),
+            },
+            ProbedNode {
+                node: PUBLIC_NODES[0],
+                rpc: slow.rpc(),
+                latency: Duration::from_millis(50),
+            },
+        ];
+        let pool = pool(&probed).unwrap();
+        let urls: Vec<String> = pool.health().into_iter().map(|node| node.url).collect();
+        assert!(urls == [PUBLIC_NODES[1].url, PUBLIC_NODES[0].url]);
+
+        // the fastest node is used first, and the pool fails over to the next
+        let count = block_on(pool.rpc().block_count()).unwrap();
+        assert!(count.count == 2);
+        assert!(fast.requests().len() == 1);
+        assert!(slow.requests().len() == 1);
+    }
+}
@@ -1,42 +1,190 @@
-use super::{util::*, AccountInfo, BlockInfo, Receivable, RpcError};
-use crate::{block::check_work, Account, Block};
+use super::{
+    util::*, AccountInfo, ActiveDifficulty, Balances, BlockConformance, BlockCount, BlockInfo,
+    ConfirmationOutcome, ConfirmationQuorum, CreatedBlock, ElectionCandidate, ElectionInfo,
+    LedgerEntry, NodeTelemetry, NodeVersion, Peer, QuorumPeer, Receivable, RpcError, TrustAnchor,
+    UncheckedBlock, WorkValidation,
+};
+use crate::block::{check_work, validate_chain, work_difficulty};
+use crate::constants::{RECEIVE_WORK_DIFFICULTY, SEND_WORK_DIFFICULTY};
+use crate::{Account, AccountCache, Block, BlockType};
 use hex::FromHexError;
 
 pub fn account_balance(raw_json: JsonValue) -> Result<u128, RpcError> {
-    let balances = u128_from_json(&raw_json["balance"])?;
+    let balances = u128_from_json(&raw_json["balance"], "balance")?;
     Ok(balances)
 }
 
+/// `raw_json` is either the response to `account_balance`, or one of the entries in the response to `accounts_balances`
+pub fn account_balance_detailed(raw_json: JsonValue) -> Result<Balances, RpcError> {
+    // older nodes only return `pending`
+    let receivable = match &raw_json["receivable"] {
+        JsonValue::Null => &raw_json["pending"],
+        receivable => receivable,
+    };
+    Ok(Balances {
+        confirmed: u128_from_json(&raw_json["balance"], "balance")?,
+        receivable: u128_from_json(receivable, "receivable")?,
+    })
+}
+
+/// If an account is not yet opened, its block count will be returned as `None`
+pub fn account_block_count(raw_json: JsonValue) -> Result<Option<usize>, RpcError> {
+    match raw_json["error"].as_str() {
+        None => Ok(Some(usize_from_json(
+            &raw_json["block_count"],
+            "block_count",
+        )?)),
+        Some("Account not found") => Ok(None),
+        Some(error) => Err(RpcError::NodeError(error.into())),
+    }
+}
+
+/// Checked against the public key which was requested
+pub fn account_get(
+    raw_json: JsonValue,
+    public_key: &[u8; 32],
+    cache: Option<&AccountCache>,
+) -> Result<Account, RpcError> {
+    let account = account_from_json_cached(&raw_json["account"], "account", cache)?;
+    if account.as_bytes() != public_key {
+        return Err(RpcError::InvalidData);
+    }
+    Ok(account)
+}
+
 /// Will stop at first legacy block
-pub fn account_history(raw_json: JsonValue, account: &Account) -> Result<Vec<Block>, RpcError> {
+pub fn account_history(
+    raw_json: JsonValue,
+    account: &Account,
+    cache: Option<&AccountCache>,
+) -> Result<Vec<Block>, RpcError> {
     let json_blocks = &raw_json["history"];
     let json_blocks = json_blocks
         .as_array()
-        .ok_or(RpcError::InvalidJsonDataType)?;
+        .ok_or_else(|| RpcError::InvalidJsonDataType("history".into()))?;
 
     let mut blocks: Vec<Block> = vec![];
-    for block in json_blocks {
-        if trim_json(&block["type"].to_string()) != "state" {
-            break;
-        }
-
-        let mut block = block_from_history_json(block)?;
+    let mut amounts: Vec<Option<u128>> = vec![];
+    for json_block in json_blocks {
+        let mut block = block_from_history_json(json_block, cache)?;
         // "account" field may be wrong due to a compatibility feature in the RPC protocol
         block.account = account.clone();
+        if block.block_type.is_legacy()
+            && block.block_type.state_equivalent() == Some(BlockType::Receive)
+        {
+            block.balance = 0;
+        }
+        blocks.push(block);
+        amounts.push(u128_from_json(&json_block["amount"], "amount").ok());
+    }
+    legacy_balances(&mut blocks, &amounts)?;
+    validate_chain(&blocks).map_err(|_| RpcError::InvalidData)?;
+    Ok(blocks)
+}
 
-        if let Some(successor_block) = blocks.last() {
-            if successor_block.previous != block.hash() {
-                return Err(RpcError::InvalidData);
+/// Legacy blocks other than `send` blocks do not contain their balance,
+/// so find it using the balance of the blocks around them, and the amounts returned by the node.
+///
+/// These balances are not signed, so the amounts of legacy `receive` and `open` blocks are checked against the blocks on both sides.
+/// The history is truncated at the first block whose balance cannot be found.
+fn legacy_balances(blocks: &mut Vec<Block>, amounts: &[Option<u128>]) -> Result<(), RpcError> {
+    let mut known: Vec<bool> = blocks
+        .iter()
+        .map(|block| match &block.block_type {
+            BlockType::Legacy(block_type) => block_type == "send",
+            _ => true,
+        })
+        .collect();
+
+    // from the balance of the newer block
+    for i in 1..blocks.len() {
+        if known[i] || !known[i - 1] {
+            continue;
+        }
+        let newer = &blocks[i - 1];
+        let balance = match newer.block_type.state_equivalent() {
+            Some(BlockType::Send) => {
+                amounts[i - 1].and_then(|amount| newer.balance.checked_add(amount))
+            }
+            Some(BlockType::Receive) => {
+                amounts[i - 1].and_then(|amount| newer.balance.checked_sub(amount))
+            }
+            _ => Some(newer.balance),
+        };
+        if let Some(balance) = balance {
+            blocks[i].balance = balance;
+            known[i] = true;
+        }
+    }
+    // from the balance of the older block
+    for i in (0..blocks.len()).rev() {
+        if known[i] {
+            continue;
+        }
+        let older = blocks
+            .get(i + 1)
+            .filter(|_| known.get(i + 1) == Some(&true));
+        let balance = match (&blocks[i].block_type, older) {
+            (BlockType::Legacy(block_type), _) if block_type == "open" => amounts[i],
+            (BlockType::Legacy(block_type), Some(older)) if block_type == "receive" => {
+                amounts[i].and_then(|amount| older.balance.checked_add(amount))
             }
+            (_, Some(older)) => Some(older.balance),
+            (_, None) => None,
+        };
+        if let Some(balance) = balance {
+            blocks[i].balance = balance;
+            known[i] = true;
         }
+    }
 
-        if !block.has_valid_signature() {
+    if let Some(unknown) = known.iter().position(|known| !known) {
+        blocks.truncate(unknown)
+    }
+
+    // the amounts must agree with the balances on both sides
+    for i in 0..blocks.len() {
+        let older_balance = match blocks.get(i + 1) {
+            Some(older) => older.balance,
+            None if blocks[i].previous == [0; 32] => 0,
+            None => continue,
+        };
+        if blocks[i].block_type.is_legacy()
+            && blocks[i].block_type.state_equivalent() == Some(BlockType::Receive)
+            && blocks[i].balance.checked_sub(older_balance) != amounts[i]
+        {
+            return Err(RpcError::InvalidData);
+        }
+    }
+    Ok(())
+}
+
+/// Truncate the (already verified) history at the anchor block.
+/// Returns an error if the anchor is not part of the history, or if its balance does not match.
+pub fn account_history_anchored(
+    mut history: Vec<Block>,
+    anchor: &TrustAnchor,
+) -> Result<Vec<Block>, RpcError> {
+    let anchor_index = history
+        .iter()
+        .position(|block| block.hash() == anchor.hash)
+        .ok_or(RpcError::InvalidData)?;
+    if let Some(balance) = anchor.balance {
+        if history[anchor_index].balance != balance {
             return Err(RpcError::InvalidData);
         }
+    }
+    history.truncate(anchor_index + 1);
+    Ok(history)
+}
 
-        blocks.push(block)
+/// Checked against the account which was requested
+pub fn account_key(raw_json: JsonValue, account: &Account) -> Result<[u8; 32], RpcError> {
+    let public_key: [u8; 32] = bytes_from_json(&raw_json["key"])?;
+    if &public_key != account.as_bytes() {
+        return Err(RpcError::InvalidData);
     }
-    Ok(blocks)
+    Ok(public_key)
 }
 
 /// If an account is not yet opened, its frontier will be returned as `None`
@@ -48,13 +196,13 @@ pub fn account_info(raw_json: JsonValue) -> Result<Option<AccountInfo>, RpcError
     Ok(Some(AccountInfo {
         frontier: bytes_from_json(&raw_json["frontier"])?,
         open_block: bytes_from_json(&raw_json["open_block"])?,
-        balance: u128_from_json(&raw_json["balance"])?,
-        modified_timestamp: u64_from_json(&raw_json["modified_timestamp"])?,
-        block_count: usize_from_json(&raw_json["block_count"])?,
-        version: usize_from_json(&raw_json["account_version"])?,
-        representative: account_from_json(&raw_json["representative"])?,
-        weight: u128_from_json(&raw_json["weight"])?,
-        receivable: usize_from_json(&raw_json["receivable"])?,
+        balance: u128_from_json(&raw_json["balance"], "balance")?,
+        modified_timestamp: u64_from_json(&raw_json["modified_timestamp"], "modified_timestamp")?,
+        block_count: usize_from_json(&raw_json["block_count"], "block_count")?,
+        version: usize_from_json(&raw_json["account_version"], "account_version")?,
+        representative: account_from_json(&raw_json["representative"], "representative")?,
+        weight: u128_from_json(&raw_json["weight"], "weight")?,
+        receivable: usize_from_json(&raw_json["receivable"], "receivable")?,
     }))
 }
 
@@ -62,14 +210,29 @@ pub fn account_representative(history: Vec<Block>) -> Result<Option<Account>, Rp
     Ok(history.first().map(|newest| newest.representative.clone()))
 }
 
+pub fn account_weight(raw_json: JsonValue) -> Result<u128, RpcError> {
+    u128_from_json(&raw_json["weight"], "weight")
+}
+
+/// If an account is not yet opened, its balance will be returned as `0`
 pub fn accounts_balances(raw_json: JsonValue, accounts: &[Account]) -> Result<Vec<u128>, RpcError> {
+    let balances = accounts_balances_detailed(raw_json, accounts)?;
+    Ok(balances.iter().map(|balances| balances.confirmed).collect())
+}
+
+/// If an account is not yet opened, its balances will be returned as `0`
+pub fn accounts_balances_detailed(
+    mut raw_json: JsonValue,
+    accounts: &[Account],
+) -> Result<Vec<Balances>, RpcError> {
     let mut balances = vec![];
     for account in accounts {
-        let result = &raw_json["balances"][account.to_string()]["balance"];
+        let result = raw_json["balances"][account.to_string()].take();
         if result.is_null() {
-            balances.push(0)
+            balances.push(Balances::default());
+            continue;
         }
-        balances.push(u128_from_json(result)?)
+        balances.push(account_balance_detailed(result)?)
     }
     Ok(balances)
 }
@@ -99,28 +262,65 @@ pub fn accounts_receivable(
     for account in accounts {
         let mut receivable = vec![];
 
-        let account_hashes = map_keys_from_json(&raw_json["blocks"][&account.to_string()]);
+        // accounts without receivable transactions are empty strings, or missing
+        let account_hashes =
+            map_keys_from_json(&raw_json["blocks"][&account.to_string()], "blocks");
         if account_hashes.is_err() {
+            all_receivable.push(receivable);
             continue;
         }
 
         for hash in account_hashes? {
-            let amount = u128_from_json(&raw_json["blocks"][&account.to_string()][&hash])?;
-            let bytes = from_hex(hash)?
-                .try_into()
-                .map_err(|_| FromHexError::InvalidStringLength)?;
-
-            receivable.push((account.clone(), bytes, amount).into());
+            let value = &raw_json["blocks"][&account.to_string()][&hash];
+            receivable.push(receivable_from_json(account, hash, value, None)?);
         }
         all_receivable.push(receivable);
     }
     Ok(all_receivable)
 }
 
+/// Drop any receivable transactions whose send block could not be found,
+/// is not a `send` block, does not send the claimed amount to the recipient, or was not sent by the sender (if known).
+///
+/// `infos` must contain the `BlockInfo` for each transaction in `receivable`, in order.
+pub fn accounts_receivable_verified(
+    receivable: Vec<Vec<Receivable>>,
+    infos: Vec<Option<BlockInfo>>,
+) -> Result<Vec<Vec<Receivable>>, RpcError> {
+    if infos.len() != receivable.iter().map(|r| r.len()).sum::<usize>() {
+        return Err(RpcError::InvalidData);
+    }
+
+    let mut infos = infos.into_iter();
+    let mut all_verified = vec![];
+    for account_receivable in receivable {
+        let mut verified = vec![];
+        for receivable in account_receivable {
+            let info = infos.next().flatten();
+            let is_valid = info.is_some_and(|info| {
+                info.block.block_type.is_send()
+                    && info.block.hash() == receivable.block_hash
+                    && info.block.link == *receivable.recipient.as_bytes()
+                    && info.amount == receivable.amount
+                    && receivable
+                        .sender
+                        .as_ref()
+                        .is_none_or(|sender| sender == &info.block.account)
+            });
+            if is_valid {
+                verified.push(receivable)
+            }
+        }
+        all_verified.push(verified)
+    }
+    Ok(all_verified)
+}
+
 /// If an account is not yet opened, its representative will be returned as `None`
 pub fn accounts_representatives(
     raw_json: JsonValue,
     accounts: &[Account],
+    cache: Option<&AccountCache>,
 ) -> Result<Vec<Option<Account>>, RpcError> {
     let mut representatives = vec![];
     for account in accounts {
@@ -129,16 +329,20 @@ pub fn accounts_representatives(
             representatives.push(None);
             continue;
         }
-        let representative: Account = trim_json(&representative.to_string())
-            .parse()
-            .map_err(|_| RpcError::InvalidAccount)?;
-        representatives.push(Some(representative));
+        representatives.push(Some(account_from_json_cached(
+            representative,
+            "representatives",
+            cache,
+        )?));
     }
     Ok(representatives)
 }
 
 /// Legacy blocks, and blocks that don't exist, will return `None`
-pub fn block_info(raw_json: JsonValue) -> Result<Option<BlockInfo>, RpcError> {
+pub fn block_info(
+    raw_json: JsonValue,
+    cache: Option<&AccountCache>,
+) -> Result<Option<BlockInfo>, RpcError> {
     if !raw_json["error"].is_null() {
         return Ok(None);
     }
@@ -146,20 +350,409 @@ pub fn block_info(raw_json: JsonValue) -> Result<Option<BlockInfo>, RpcError> {
         return Ok(None);
     }
 
-    let block = block_from_info_json(&raw_json)?;
+    let block = block_from_info_json(&raw_json, cache)?;
     if !block.has_valid_signature() {
         return Err(RpcError::InvalidData);
     }
     Ok(Some(block_info_from_json(&raw_json, block)?))
 }
 
+pub fn available_supply(raw_json: JsonValue) -> Result<u128, RpcError> {
+    u128_from_json(&raw_json["available"], "available")
+}
+
+pub fn active_difficulty(raw_json: JsonValue) -> Result<ActiveDifficulty, RpcError> {
+    Ok(ActiveDifficulty {
+        network_minimum: bytes_from_json(&raw_json["network_minimum"])?,
+        network_receive_minimum: bytes_from_json(&raw_json["network_receive_minimum"])?,
+        network_current: bytes_from_json(&raw_json["network_current"])?,
+        network_receive_current: bytes_from_json(&raw_json["network_receive_current"])?,
+        multiplier: f64_from_json(&raw_json["multiplier"], "multiplier")?,
+    })
+}
+
+/// Blocks that don't exist will return `None`
+pub fn block_account(
+    raw_json: JsonValue,
+    cache: Option<&AccountCache>,
+) -> Result<Option<Account>, RpcError> {
+    match raw_json["error"].as_str() {
+        None => Ok(Some(account_from_json_cached(
+            &raw_json["account"],
+            "account",
+            cache,
+        )?)),
+        Some("Block not found") => Ok(None),
+        Some(error) => Err(RpcError::NodeError(error.into())),
+    }
+}
+
+/// Not verified: see `DebugRpc::block_create_conformance()`
+pub fn block_create(
+    raw_json: JsonValue,
+    block_type: BlockType,
+    cache: Option<&AccountCache>,
+) -> Result<CreatedBlock, RpcError> {
+    Ok(CreatedBlock {
+        hash: bytes_from_json(&raw_json["hash"])?,
+        block: block_from_json_cached(&raw_json["block"], block_type, cache)?,
+    })
+}
+
+/// Compare the node's response to `block_create` with `block`, which was created locally with the same inputs
+pub fn block_create_conformance(
+    raw_json: &JsonValue,
+    block: &Block,
+    cache: Option<&AccountCache>,
+) -> Result<BlockConformance, RpcError> {
+    let created = block_create(raw_json.clone(), block.block_type.clone(), cache)?;
+    let local_json: JsonValue = serde_json::from_str(&block.to_json_string())?;
+    let local_json = local_json
+        .as_object()
+        .ok_or_else(|| RpcError::InvalidJsonDataType("block".into()))?;
+    Ok(BlockConformance {
+        hash: created.hash == block.hash(),
+        signature: created.block.signature == block.signature,
+        // the node may include extra fields, such as "link_as_account"
+        json: local_json
+            .iter()
+            .all(|(field, value)| &raw_json["block"][field] == value),
+    })
+}
+
+pub fn block_count(raw_json: JsonValue) -> Result<BlockCount, RpcError> {
+    Ok(BlockCount {
+        count: u64_from_json(&raw_json["count"], "count")?,
+        unchecked: u64_from_json(&raw_json["unchecked"], "unchecked")?,
+        cemented: u64_from_json(&raw_json["cemented"], "cemented")?,
+    })
+}
+
+pub fn confirmation_active(raw_json: JsonValue) -> Result<Vec<[u8; 64]>, RpcError> {
+    match &raw_json["confirmations"] {
+        // the node returns an empty string, rather than an empty list
+        JsonValue::String(roots) if roots.is_empty() => Ok(vec![]),
+        JsonValue::Array(roots) => roots.iter().map(bytes_from_json).collect(),
+        _ => Err(RpcError::InvalidJsonDataType("confirmations".into())),
+    }
+}
+
+/// Elections which are not active will return `None`
+pub fn confirmation_info(
+    raw_json: JsonValue,
+    cache: Option<&AccountCache>,
+) -> Result<Option<ElectionInfo>, RpcError> {
+    match raw_json["error"].as_str() {
+        None => (),
+        Some("Active confirmation not found") => return Ok(None),
+        Some(error) => return Err(RpcError::NodeError(error.into())),
+    }
+
+    let mut candidates = raw_json["blocks"]
+        .as_object()
+        .ok_or_else(|| RpcError::InvalidJsonDataType("blocks".into()))?
+        .iter()
+        .map(|(hash, candidate)| {
+            Ok(ElectionCandidate {
+                hash: bytes_from_json(&hash.as_str().into())?,
+                tally: u128_from_json(&candidate["tally"], "tally")?,
+                final_tally: u128_from_json(&candidate["final_tally"], "final_tally")?,
+                representatives: match &candidate["representatives"] {
+                    // the node returns an empty string, rather than an empty map
+                    JsonValue::String(representatives) if representatives.is_empty() => vec![],
+                    representatives => {
+                        account_amounts_from_json(representatives, "representatives", cache)?
+                    }
+                },
+            })
+        })
+        .collect::<Result<Vec<ElectionCandidate>, RpcError>>()?;
+    candidates.sort_by_key(|candidate| std::cmp::Reverse(candidate.tally));
+
+    Ok(Some(ElectionInfo {
+        announcements: u64_from_json(&raw_json["announcements"], "announcements")?,
+        voters: u64_from_json(&raw_json["voters"], "voters")?,
+        last_winner: bytes_from_json(&raw_json["last_winner"])?,
+        total_tally: u128_from_json(&raw_json["total_tally"], "total_tally")?,
+        final_tally: u128_from_json(&raw_json["final_tally"], "final_tally")?,
+        candidates,
+    }))
+}
+
+/// `peers` is only included if `peer_details` was requested
+pub fn confirmation_quorum(
+    raw_json: JsonValue,
+    cache: Option<&AccountCache>,
+) -> Result<ConfirmationQuorum, RpcError> {
+    let peers = match &raw_json["peers"] {
+        JsonValue::Null => None,
+        peers => Some(
+            peers
+                .as_array()
+                .ok_or_else(|| RpcError::InvalidJsonDataType("peers".into()))?
+                .iter()
+                .map(|peer| {
+                    Ok(QuorumPeer {
+                        account: account_from_json_cached(&peer["account"], "account", cache)?,
+                        ip: string_from_json(&peer["ip"], "ip")?,
+                        weight: u128_from_json(&peer["weight"], "weight")?,
+                    })
+                })
+                .collect::<Result<Vec<QuorumPeer>, RpcError>>()?,
+        ),
+    };
+    Ok(ConfirmationQuorum {
+        quorum_delta: u128_from_json(&raw_json["quorum_delta"], "quorum_delta")?,
+        online_weight_quorum_percent: u64_from_json(
+            &raw_json["online_weight_quorum_percent"],
+            "online_weight_quorum_percent",
+        )?,
+        online_weight_minimum: u128_from_json(
+            &raw_json["online_weight_minimum"],
+            "online_weight_minimum",
+        )?,
+        online_stake_total: u128_from_json(&raw_json["online_stake_total"], "online_stake_total")?,
+        trended_stake_total: u128_from_json(
+            &raw_json["trended_stake_total"],
+            "trended_stake_total",
+        )?,
+        peers_stake_total: u128_from_json(&raw_json["peers_stake_total"], "peers_stake_total")?,
+        peers,
+    })
+}
+
+/// Parse a map of accounts to amounts, sorted by amount (highest first)
+fn account_amounts_from_json(
+    value: &JsonValue,
+    field: &str,
+    cache: Option<&AccountCache>,
+) -> Result<Vec<(Account, u128)>, RpcError> {
+    let mut amounts = value
+        .as_object()
+        .ok_or_else(|| RpcError::InvalidJsonDataType(field.into()))?
+        .iter()
+        .map(|(account, amount)| {
+            // `representatives_online` nests the weight in an object
+            let amount = match amount {
+                JsonValue::Object(_) => &amount["weight"],
+                amount => amount,
+            };
+            Ok((
+                account_from_json_cached(&account.as_str().into(), field, cache)?,
+                u128_from_json(amount, field)?,
+            ))
+        })
+        .collect::<Result<Vec<(Account, u128)>, RpcError>>()?;
+    amounts.sort_by_key(|(_, amount)| std::cmp::Reverse(*amount));
+    Ok(amounts)
+}
+
+/// Sorted by balance, highest first
+pub fn delegators(
+    raw_json: JsonValue,
+    cache: Option<&AccountCache>,
+) -> Result<Vec<(Account, u128)>, RpcError> {
+    match &raw_json["delegators"] {
+        // the node returns an empty string, rather than an empty map
+        JsonValue::String(delegators) if delegators.is_empty() => Ok(vec![]),
+        delegators => account_amounts_from_json(delegators, "delegators", cache),
+    }
+}
+
+pub fn delegators_count(raw_json: JsonValue) -> Result<usize, RpcError> {
+    usize_from_json(&raw_json["count"], "count")
+}
+
+/// Sorted by public key, as the node does
+pub fn frontiers(
+    raw_json: JsonValue,
+    cache: Option<&AccountCache>,
+) -> Result<Vec<(Account, [u8; 32])>, RpcError> {
+    let mut frontiers = match &raw_json["frontiers"] {
+        // the node returns an empty string, rather than an empty map
+        JsonValue::String(frontiers) if frontiers.is_empty() => return Ok(vec![]),
+        JsonValue::Object(frontiers) => frontiers
+            .iter()
+            .map(|(account, frontier)| {
+                Ok((
+                    account_from_json_cached(&account.as_str().into(), "frontiers", cache)?,
+                    bytes_from_json(frontier)?,
+                ))
+            })
+            .collect::<Result<Vec<(Account, [u8; 32])>, RpcError>>()?,
+        _ => return Err(RpcError::InvalidJsonDataType("frontiers".into())),
+    };
+    frontiers.sort_by_key(|(account, _)| *account.as_bytes());
+    Ok(frontiers)
+}
+
+/// Sorted by public key, as the node does
+pub fn ledger(
+    raw_json: JsonValue,
+    cache: Option<&AccountCache>,
+) -> Result<Vec<(Account, LedgerEntry)>, RpcError> {
+    let mut entries = match &raw_json["accounts"] {
+        // the node returns an empty string, rather than an empty map
+        JsonValue::String(accounts) if accounts.is_empty() => return Ok(vec![]),
+        JsonValue::Object(accounts) => accounts
+            .iter()
+            .map(|(account, entry)| {
+                // older nodes only return `pending`
+                let receivable = match &entry["receivable"] {
+                    JsonValue::Null => &entry["pending"],
+                    receivable => receivable,
+                };
+                Ok((
+                    account_from_json_cached(&account.as_str().into(), "accounts", cache)?,
+                    LedgerEntry {
+                        frontier: bytes_from_json(&entry["frontier"])?,
+                        open_block: bytes_from_json(&entry["open_block"])?,
+                        representative_block: bytes_from_json(&entry["representative_block"])?,
+                        balance: u128_from_json(&entry["balance"], "balance")?,
+                        modified_timestamp: u64_from_json(
+                            &entry["modified_timestamp"],
+                            "modified_timestamp",
+                        )?,
+                        block_count: usize_from_json(&entry["block_count"], "block_count")?,
+                        representative: account_from_json_cached(
+                            &entry["representative"],
+                            "representative",
+                            cache,
+                        )?,
+                        weight: u128_from_json(&entry["weight"], "weight")?,
+                        receivable: u128_from_json(receivable, "receivable")?,
+                    },
+                ))
+            })
+            .collect::<Result<Vec<(Account, LedgerEntry)>, RpcError>>()?,
+        _ => return Err(RpcError::InvalidJsonDataType("accounts".into())),
+    };
+    entries.sort_by_key(|(account, _)| *account.as_bytes());
+    Ok(entries)
+}
+
+/// `value` is either the amount, or an object containing the amount,
+/// and the sender and minimum epoch version (if requested with `source` and `min_version`)
+fn receivable_from_json(
+    recipient: &Account,
+    hash: &str,
+    value: &JsonValue,
+    cache: Option<&AccountCache>,
+) -> Result<Receivable, RpcError> {
+    let block_hash = from_hex(hash)?
+        .try_into()
+        .map_err(|_| FromHexError::InvalidStringLength)?;
+    if !value.is_object() {
+        return Ok((
+            recipient.clone(),
+            block_hash,
+            u128_from_json(value, "blocks")?,
+        )
+            .into());
+    }
+
+    let sender = match &value["source"] {
+        JsonValue::Null => None,
+        sender => Some(account_from_json_cached(sender, "source", cache)?),
+    };
+    let min_version = match &value["min_version"] {
+        JsonValue::Null => None,
+        version => Some(
+            u8::try_from(u64_from_json(version, "min_version")?)
+                .or(Err(RpcError::InvalidInteger("min_version".into())))?,
+        ),
+    };
+    Ok(Receivable {
+        recipient: recipient.clone(),
+        block_hash,
+        amount: u128_from_json(&value["amount"], "amount")?,
+        sender,
+        min_version,
+    })
+}
+
+/// Sorted by amount, highest first
+pub fn receivable(
+    raw_json: JsonValue,
+    account: &Account,
+    cache: Option<&AccountCache>,
+) -> Result<Vec<Receivable>, RpcError> {
+    let blocks = match &raw_json["blocks"] {
+        // the node returns an empty string, rather than an empty map
+        JsonValue::String(blocks) if blocks.is_empty() => return Ok(vec![]),
+        blocks => blocks
+            .as_object()
+            .ok_or_else(|| RpcError::InvalidJsonDataType("blocks".into()))?,
+    };
+    let mut receivable = blocks
+        .iter()
+        .map(|(hash, value)| receivable_from_json(account, hash, value, cache))
+        .collect::<Result<Vec<Receivable>, RpcError>>()?;
+    receivable.sort_by(|a, b| b.cmp(a));
+    Ok(receivable)
+}
+
+pub fn receive_minimum(raw_json: JsonValue) -> Result<u128, RpcError> {
+    u128_from_json(&raw_json["amount"], "amount")
+}
+
+/// Returns whether the search was started
+pub fn search_receivable(raw_json: JsonValue) -> Result<bool, RpcError> {
+    flag_from_json(&raw_json["started"], "started")
+}
+
+pub fn search_receivable_all(raw_json: JsonValue) -> Result<(), RpcError> {
+    match raw_json["success"] {
+        JsonValue::String(_) => Ok(()),
+        _ => Err(RpcError::InvalidJsonDataType("success".into())),
+    }
+}
+
+/// Sorted by weight, highest first
+pub fn representatives(
+    raw_json: JsonValue,
+    cache: Option<&AccountCache>,
+) -> Result<Vec<(Account, u128)>, RpcError> {
+    account_amounts_from_json(&raw_json["representatives"], "representatives", cache)
+}
+
+pub fn representatives_online(
+    raw_json: JsonValue,
+    cache: Option<&AccountCache>,
+) -> Result<Vec<Account>, RpcError> {
+    raw_json["representatives"]
+        .as_array()
+        .ok_or_else(|| RpcError::InvalidJsonDataType("representatives".into()))?
+        .iter()
+        .map(|representative| account_from_json_cached(representative, "representatives", cache))
+        .collect()
+}
+
+/// Sorted by weight, highest first
+pub fn representatives_online_detailed(
+    raw_json: JsonValue,
+    cache: Option<&AccountCache>,
+) -> Result<Vec<(Account, u128)>, RpcError> {
+    representatives(raw_json, cache)
+}
+
+pub fn republish(raw_json: JsonValue) -> Result<Vec<[u8; 32]>, RpcError> {
+    match &raw_json["blocks"] {
+        // the node returns an empty string, rather than an empty list
+        JsonValue::String(blocks) if blocks.is_empty() => Ok(vec![]),
+        JsonValue::Array(blocks) => blocks.iter().map(bytes_from_json).collect(),
+        _ => Err(RpcError::InvalidJsonDataType("blocks".into())),
+    }
+}
+
 /// Legacy blocks, and blocks that don't exist, will return `None`
 pub fn blocks_info(
     raw_json: JsonValue,
     hashes: &[[u8; 32]],
+    cache: Option<&AccountCache>,
 ) -> Result<Vec<Option<BlockInfo>>, RpcError> {
     if !raw_json["error"].is_null() && raw_json["blocks"].is_null() {
-        return Err(RpcError::InvalidJsonDataType);
+        return Err(RpcError::InvalidJsonDataType("blocks".into()));
     }
 
     let mut infos = vec![];
@@ -170,10 +763,11 @@ pub fn blocks_info(
             continue;
         }
         if trim_json(&json_block["contents"]["type"].to_string()) != "state" {
-            infos.push(None)
+            infos.push(None);
+            continue;
         }
 
-        let block = block_from_info_json(json_block)?;
+        let block = block_from_info_json(json_block, cache)?;
         if !block.has_valid_signature() {
             return Err(RpcError::InvalidData);
         }
@@ -189,6 +783,74 @@ pub fn blocks_info(
     Ok(infos)
 }
 
+/// The response to `successors` has the same format
+pub fn chain(raw_json: JsonValue) -> Result<Vec<[u8; 32]>, RpcError> {
+    let hashes = match &raw_json["blocks"] {
+        // the node returns an empty string, rather than an empty list
+        JsonValue::String(hashes) if hashes.is_empty() => return Ok(vec![]),
+        hashes => hashes
+            .as_array()
+            .ok_or_else(|| RpcError::InvalidJsonDataType("blocks".into()))?,
+    };
+    hashes.iter().map(bytes_from_json).collect()
+}
+
+/// Check that each block matches its hash in `hashes`, and links to the block before it.
+/// If `successors`, each block must be the successor of the block before it, otherwise its predecessor.
+///
+/// `infos` must contain the `BlockInfo` for each hash in `hashes`, in order.
+/// The chain is truncated at the first legacy (or missing) block.
+pub fn chain_verified(
+    hashes: &[[u8; 32]],
+    infos: Vec<Option<BlockInfo>>,
+    successors: bool,
+) -> Result<Vec<BlockInfo>, RpcError> {
+    if infos.len() != hashes.len() {
+        return Err(RpcError::InvalidData);
+    }
+
+    let mut verified: Vec<BlockInfo> = vec![];
+    for (hash, info) in hashes.iter().zip(infos) {
+        let Some(info) = info else {
+            break;
+        };
+        if info.block.hash() != *hash {
+            return Err(RpcError::InvalidData);
+        }
+        if let Some(last) = verified.last() {
+            let linked = match successors {
+                true => info.block.previous == last.block.hash(),
+                false => last.block.previous == *hash,
+            };
+            if !linked {
+                return Err(RpcError::InvalidData);
+            }
+        }
+        verified.push(info)
+    }
+    Ok(verified)
+}
+
+/// Sorted by address
+pub fn peers(raw_json: JsonValue) -> Result<Vec<Peer>, RpcError> {
+    match &raw_json["peers"] {
+        // the node returns an empty string, rather than an empty map
+        JsonValue::String(peers) if peers.is_empty() => Ok(vec![]),
+        JsonValue::Object(peers) => peers
+            .iter()
+            .map(|(address, peer)| {
+                Ok(Peer {
+                    address: address.clone(),
+                    protocol_version: u64_from_json(&peer["protocol_version"], "protocol_version")?,
+                    node_id: string_from_json(&peer["node_id"], "node_id")?,
+                    connection_type: string_from_json(&peer["type"], "type")?,
+                })
+            })
+            .collect(),
+        _ => Err(RpcError::InvalidJsonDataType("peers".into())),
+    }
+}
+
 pub fn process(raw_json: JsonValue, hash: [u8; 32]) -> Result<[u8; 32], RpcError> {
     let rpc_hash: [u8; 32] = bytes_from_json(&raw_json["hash"])?;
 
@@ -198,6 +860,177 @@ pub fn process(raw_json: JsonValue, hash: [u8; 32]) -> Result<[u8; 32], RpcError
     Ok(hash)
 }
 
+/// Interpret the `block_info` of a published block.
+/// Returns `None` if the block is not yet confirmed, or the node has not seen it (yet).
+pub fn confirmation_outcome(info: Option<BlockInfo>) -> Option<ConfirmationOutcome> {
+    match info {
+        Some(info) if info.confirmed => Some(ConfirmationOutcome::Confirmed(Box::new(info))),
+        _ => None,
+    }
+}
+
+fn string_from_json(value: &JsonValue, field: &str) -> Result<String, RpcError> {
+    Ok(value
+        .as_str()
+        .ok_or_else(|| RpcError::InvalidJsonDataType(field.into()))?
+        .to_string())
+}
+
+fn f64_from_json(value: &JsonValue, field: &str) -> Result<f64, RpcError> {
+    trim_json(&value.to_string())
+        .parse()
+        .or(Err(RpcError::InvalidJsonDataType(field.into())))
+}
+
+/// `"1"` or `"0"`
+fn flag_from_json(value: &JsonValue, field: &str) -> Result<bool, RpcError> {
+    match trim_json(&value.to_string()) {
+        "1" => Ok(true),
+        "0" => Ok(false),
+        _ => Err(RpcError::InvalidJsonDataType(field.into())),
+    }
+}
+
+fn optional_string_from_json(value: &JsonValue, field: &str) -> Result<Option<String>, RpcError> {
+    match value {
+        JsonValue::Null => Ok(None),
+        value => Ok(Some(string_from_json(value, field)?)),
+    }
+}
+
+fn telemetry_from_json(value: &JsonValue) -> Result<NodeTelemetry, RpcError> {
+    let port = match &value["port"] {
+        JsonValue::Null => None,
+        port => Some(
+            u16::try_from(u64_from_json(port, "port")?)
+                .or(Err(RpcError::InvalidInteger("port".into())))?,
+        ),
+    };
+
+    Ok(NodeTelemetry {
+        block_count: u64_from_json(&value["block_count"], "block_count")?,
+        cemented_count: u64_from_json(&value["cemented_count"], "cemented_count")?,
+        unchecked_count: u64_from_json(&value["unchecked_count"], "unchecked_count")?,
+        account_count: u64_from_json(&value["account_count"], "account_count")?,
+        bandwidth_cap: u64_from_json(&value["bandwidth_cap"], "bandwidth_cap")?,
+        peer_count: u64_from_json(&value["peer_count"], "peer_count")?,
+        protocol_version: u64_from_json(&value["protocol_version"], "protocol_version")?,
+        uptime: u64_from_json(&value["uptime"], "uptime")?,
+        genesis_block: bytes_from_json(&value["genesis_block"])?,
+        major_version: u64_from_json(&value["major_version"], "major_version")?,
+        minor_version: u64_from_json(&value["minor_version"], "minor_version")?,
+        patch_version: u64_from_json(&value["patch_version"], "patch_version")?,
+        pre_release_version: u64_from_json(&value["pre_release_version"], "pre_release_version")?,
+        maker: u64_from_json(&value["maker"], "maker")?,
+        timestamp: u64_from_json(&value["timestamp"], "timestamp")?,
+        node_id: optional_string_from_json(&value["node_id"], "node_id")?,
+        address: optional_string_from_json(&value["address"], "address")?,
+        port,
+    })
+}
+
+pub fn telemetry(raw_json: JsonValue) -> Result<NodeTelemetry, RpcError> {
+    telemetry_from_json(&raw_json)
+}
+
+pub fn telemetry_raw(raw_json: JsonValue) -> Result<Vec<NodeTelemetry>, RpcError> {
+    raw_json["metrics"]
+        .as_array()
+        .ok_or_else(|| RpcError::InvalidJsonDataType("metrics".into()))?
+        .iter()
+        .map(telemetry_from_json)
+        .collect()
+}
+
+/// Legacy blocks will return `None`.
+///
+/// The subtype of an unchecked block may not be known, so it is inferred from the block alone (see `Rpc::unchecked()`).
+fn unchecked_block_from_json(
+    hash: &str,
+    contents: &JsonValue,
+    cache: Option<&AccountCache>,
+) -> Result<Option<Block>, RpcError> {
+    if trim_json(&contents["type"].to_string()) != "state" {
+        return Ok(None);
+    }
+
+    let mut block = block_from_json_cached(contents, BlockType::Send, cache)?;
+    block.block_type = if block.link.starts_with(b"epoch v") {
+        BlockType::Epoch
+    } else if block.previous == [0; 32] {
+        BlockType::Receive
+    } else if block.link == [0; 32] {
+        BlockType::Change
+    } else {
+        BlockType::Send
+    };
+    if block.hash()[..] != from_hex(hash)? {
+        return Err(RpcError::InvalidData);
+    }
+    Ok(Some(block))
+}
+
+pub fn unchecked(
+    raw_json: JsonValue,
+    cache: Option<&AccountCache>,
+) -> Result<Vec<Block>, RpcError> {
+    let blocks = match &raw_json["blocks"] {
+        // the node returns an empty string, rather than an empty map
+        JsonValue::String(blocks) if blocks.is_empty() => return Ok(vec![]),
+        JsonValue::Object(blocks) => blocks,
+        _ => return Err(RpcError::InvalidJsonDataType("blocks".into())),
+    };
+
+    let mut unchecked = vec![];
+    for (hash, contents) in blocks {
+        if let Some(block) = unchecked_block_from_json(hash, contents, cache)? {
+            unchecked.push(block)
+        }
+    }
+    Ok(unchecked)
+}
+
+pub fn unchecked_keys(
+    raw_json: JsonValue,
+    cache: Option<&AccountCache>,
+) -> Result<Vec<UncheckedBlock>, RpcError> {
+    let entries = match &raw_json["unchecked"] {
+        // the node returns an empty string, rather than an empty list
+        JsonValue::String(entries) if entries.is_empty() => return Ok(vec![]),
+        JsonValue::Array(entries) => entries,
+        _ => return Err(RpcError::InvalidJsonDataType("unchecked".into())),
+    };
+
+    let mut unchecked = vec![];
+    for entry in entries {
+        let hash = string_from_json(&entry["hash"], "hash")?;
+        if let Some(block) = unchecked_block_from_json(&hash, &entry["contents"], cache)? {
+            unchecked.push(UncheckedBlock {
+                key: bytes_from_json(&entry["key"])?,
+                modified_timestamp: u64_from_json(
+                    &entry["modified_timestamp"],
+                    "modified_timestamp",
+                )?,
+                block,
+            })
+        }
+    }
+    Ok(unchecked)
+}
+
+pub fn version(raw_json: JsonValue) -> Result<NodeVersion, RpcError> {
+    Ok(NodeVersion {
+        rpc_version: u64_from_json(&raw_json["rpc_version"], "rpc_version")?,
+        store_version: u64_from_json(&raw_json["store_version"], "store_version")?,
+        protocol_version: u64_from_json(&raw_json["protocol_version"], "protocol_version")?,
+        node_vendor: string_from_json(&raw_json["node_vendor"], "node_vendor")?,
+        store_vendor: string_from_json(&raw_json["store_vendor"], "store_vendor")?,
+        network: string_from_json(&raw_json["network"], "network")?,
+        network_identifier: bytes_from_json(&raw_json["network_identifier"])?,
+        build_info: string_from_json(&raw_json["build_info"], "build_info")?,
+    })
+}
+
 pub fn work_generate(
     raw_json: JsonValue,
     work_hash: [u8; 32],
@@ -217,10 +1050,47 @@ pub fn work_generate(
     }
 }
 
+/// The node's answer is checked against the local calculation
+pub fn work_validate(
+    raw_json: JsonValue,
+    work: [u8; 8],
+    work_hash: [u8; 32],
+    custom_difficulty: Option<[u8; 8]>,
+) -> Result<WorkValidation, RpcError> {
+    let validation = WorkValidation {
+        valid: match custom_difficulty {
+            Some(_) => Some(flag_from_json(&raw_json["valid"], "valid")?),
+            None => None,
+        },
+        valid_all: flag_from_json(&raw_json["valid_all"], "valid_all")?,
+        valid_receive: flag_from_json(&raw_json["valid_receive"], "valid_receive")?,
+        difficulty: bytes_from_json(&raw_json["difficulty"])?,
+        multiplier: f64_from_json(&raw_json["multiplier"], "multiplier")?,
+    };
+
+    let difficulty = work_difficulty(work_hash, work);
+    if validation.difficulty != difficulty
+        || validation.valid_all != (difficulty >= SEND_WORK_DIFFICULTY)
+        || validation.valid_receive != (difficulty >= RECEIVE_WORK_DIFFICULTY)
+        || validation.valid != custom_difficulty.map(|custom| difficulty >= custom)
+    {
+        return Err(RpcError::InvalidData);
+    }
+    Ok(validation)
+}
+
 #[cfg(test)]
+#[allow(clippy::useless_vec)]
 mod tests {
-    use super::to_uppercase_hex;
-    use crate::{block::check_work, Account, Block, BlockType};
+    use super::{
+        block_to_json, to_uppercase_hex, Balances, BlockInfo, ConfirmationOutcome, Peer,
+        Receivable, RpcError, TrustAnchor, UncheckedBlock,
+    };
+    use crate::constants::{RECEIVE_WORK_DIFFICULTY, SEND_WORK_DIFFICULTY};
+    use crate::{
+        block::check_work, constants::get_genesis_account, Account, AccountCache, Block,
+        BlockBuilder, BlockType, Key, SecretBytes, Signature,
+    };
     use serde_json::json;
 
     #[test]
@@ -234,6 +1104,41 @@ mod tests {
         assert!(balance == 10000)
     }
 
+    #[test]
+    fn account_block_count() {
+        let count = super::account_block_count(json!({"block_count": "19"})).unwrap();
+        assert!(count == Some(19));
+        let count = super::account_block_count(json!({"error": "Account not found"})).unwrap();
+        assert!(count.is_none());
+        assert!(matches!(
+            super::account_block_count(json!({"error": "Bad account number"})),
+            Err(RpcError::NodeError(error)) if error == "Bad account number"
+        ));
+        assert!(super::account_block_count(json!({})).is_err());
+    }
+
+    #[test]
+    fn account_get() {
+        let genesis = get_genesis_account();
+        let public_key = *genesis.as_bytes();
+        let json = json!({"account": genesis.to_string()});
+        assert!(super::account_get(json.clone(), &public_key, None).unwrap() == genesis);
+        assert!(super::account_get(json, &[1; 32], None).is_err());
+        assert!(super::account_get(json!({"account": ""}), &public_key, None).is_err());
+    }
+
+    #[test]
+    fn account_key() {
+        let genesis = get_genesis_account();
+        let json =
+            json!({"key": "E89208DD038FBB269987689621D52292AE9C35941A7484756ECCED92A65093BA"});
+        assert!(super::account_key(json.clone(), &genesis).unwrap() == *genesis.as_bytes());
+
+        let other = Key::from_seed(&SecretBytes::from([0; 32]), 0).to_account();
+        assert!(super::account_key(json, &other).is_err());
+        assert!(super::account_key(json!({"key": "E892"}), &genesis).is_err());
+    }
+
     #[test]
     fn account_history() {
         let history = super::account_history(
@@ -276,7 +1181,8 @@ mod tests {
                 ],
                 "previous":"EC9A8131D76E820818AD84554F3AE276542A642DB118C1B098C77A0A8A8446B5"
             }),
-            &Account::try_from("nano_1ipx847tk8o46pwxt5qjdbncjqcbwcc1rrmqnkztrfjy5k7z4imsrata9est").unwrap()
+            &Account::try_from("nano_1ipx847tk8o46pwxt5qjdbncjqcbwcc1rrmqnkztrfjy5k7z4imsrata9est").unwrap(),
+            None
         ).unwrap();
 
         let signature_1: [u8; 64] = hex::decode("3D45D616545D5CCE9766E3F6268C9AE88C0DCA61A6B034AE4804D46C9F75EA94BCA7E7AEBA46EA98117120FB491FE2F7D0664675EF36D8BFD9818DAE62209F06").unwrap().try_into().unwrap();
@@ -341,14 +1247,136 @@ mod tests {
     }
 
     #[test]
-    fn account_info() {
-        let info = super::account_info(json!({
-            "frontier": "80A6745762493FA21A22718ABFA4F635656A707B48B3324198AC7F3938DE6D4F",
-            "open_block": "0E3F07F7F2B8AEDEA4A984E29BFE1E3933BA473DD3E27C662EC041F6EA3917A0",
-            "representative_block": "80A6745762493FA21A22718ABFA4F635656A707B48B3324198AC7F3938DE6D41",
-            "balance": "11999999999999999918751838129509869131",
-            "modified_timestamp": "1606934662",
-            "block_count": "22966",
+    fn account_history_legacy() {
+        let key = Key::from_seed(&SecretBytes::from([0; 32]), 0);
+        let account = key.to_account();
+        let genesis = get_genesis_account();
+        let legacy = |block_type: &str, previous: [u8; 32], link: [u8; 32], balance: u128| {
+            let mut block = Block {
+                block_type: BlockType::Legacy(block_type.into()),
+                account: account.clone(),
+                previous,
+                representative: genesis.clone(),
+                balance,
+                link,
+                signature: Signature::default(),
+                work: [0; 8],
+            };
+            block.sign(&key);
+            block
+        };
+        // `open` (10), `send` (3), `receive` (5), and `change`, followed by a `state` `send` (2)
+        let open = legacy("open", [0; 32], [1; 32], 0);
+        let send = legacy("send", open.hash(), *genesis.as_bytes(), 7);
+        let receive = legacy("receive", send.hash(), [2; 32], 0);
+        let change = legacy("change", receive.hash(), [0; 32], 0);
+        let mut state = BlockBuilder::new(account.clone(), change.hash(), genesis.clone(), 12)
+            .send(&genesis, 2)
+            .unwrap();
+        state.sign(&key);
+
+        let entry = |block: &Block, amount: Option<u128>| {
+            let mut json = block_to_json(block);
+            json.insert("hash".into(), to_uppercase_hex(&block.hash()).into());
+            if let Some(amount) = amount {
+                json.insert("amount".into(), amount.to_string().into());
+            }
+            match &block.block_type {
+                BlockType::Legacy(block_type) if block_type == "send" => {
+                    json.insert("balance".into(), block.balance.to_string().into());
+                }
+                BlockType::Legacy(block_type) if block_type == "open" => {
+                    json.insert("opened".into(), json["account"].clone());
+                    json.remove("account");
+                }
+                BlockType::Legacy(_) => (),
+                _ => {
+                    json.insert("subtype".into(), "send".into());
+                }
+            }
+            json
+        };
+        let history = |entries: Vec<serde_json::Map<String, serde_json::Value>>| {
+            super::account_history(json!({ "history": entries }), &account, None)
+        };
+        let all = vec![
+            entry(&state, Some(2)),
+            entry(&change, None),
+            entry(&receive, Some(5)),
+            entry(&send, Some(3)),
+            entry(&open, Some(10)),
+        ];
+
+        let blocks = history(all.clone()).unwrap();
+        let balances: Vec<u128> = blocks.iter().map(|block| block.balance).collect();
+        assert!(balances == [10, 12, 12, 7, 10]);
+        assert!(blocks[4].hash() == open.hash());
+        assert!(blocks[2].hash() == receive.hash());
+
+        // balances are found from newer blocks too
+        let blocks = history(all[..3].to_vec()).unwrap();
+        let balances: Vec<u128> = blocks.iter().map(|block| block.balance).collect();
+        assert!(balances == [10, 12, 12]);
+
+        // the history stops at blocks whose balance cannot be found
+        assert!(history(all[1..3].to_vec()).unwrap().is_empty());
+
+        // the amounts must be consistent with the signed balances
+        let mut wrong = all.clone();
+        wrong[2].insert("amount".into(), "4".into());
+        assert!(history(wrong).is_err());
+    }
+
+    #[test]
+    fn account_history_anchored() {
+        let history: Vec<Block> = (0..4)
+            .map(|i| Block {
+                block_type: BlockType::Send,
+                account: get_genesis_account(),
+                previous: [i; 32],
+                representative: get_genesis_account(),
+                balance: 1000 + i as u128,
+                link: [0; 32],
+                signature: Signature::default(),
+                work: [0; 8],
+            })
+            .collect();
+        let anchor = TrustAnchor {
+            hash: history[2].hash(),
+            balance: None,
+        };
+
+        let anchored = super::account_history_anchored(history.clone(), &anchor).unwrap();
+        assert!(anchored == history[..3]);
+
+        let anchor = TrustAnchor {
+            hash: history[2].hash(),
+            balance: Some(1002),
+        };
+        assert!(super::account_history_anchored(history.clone(), &anchor).is_ok());
+
+        let anchor = TrustAnchor {
+            hash: history[2].hash(),
+            balance: Some(1003),
+        };
+        assert!(super::account_history_anchored(history.clone(), &anchor).is_err());
+
+        let anchor = TrustAnchor {
+            hash: [0; 32],
+            balance: None,
+        };
+        assert!(super::account_history_anchored(history, &anchor).is_err());
+    }
+
+    #[test]
+    fn account_info() {
+        let info = super::account_info(json!({
+            "frontier": "80A6745762493FA21A22718ABFA4F635656A707B48B3324198AC7F3938DE6D4F",
+            "open_block": "0E3F07F7F2B8AEDEA4A984E29BFE1E3933BA473DD3E27C662EC041F6EA3917A0",
+            "representative_block": "80A6745762493FA21A22718ABFA4F635656A707B48B3324198AC7F3938DE6D41",
+            "balance": "11999999999999999918751838129509869131",
+            "modified_timestamp": "1606934662",
+            "block_count": "22966",
             "account_version": "1",
             "representative": "nano_1gyeqc6u5j3oaxbe5qy1hyz3q745a318kh8h9ocnpan7fuxnq85cxqboapu5",
             "weight": "11999999999999999918751838129509869132",
@@ -419,6 +1447,13 @@ mod tests {
         )
     }
 
+    #[test]
+    fn account_weight() {
+        let weight = super::account_weight(json!({"weight": "10000"})).unwrap();
+        assert!(weight == 10000);
+        assert!(super::account_weight(json!({"error": "Bad account number"})).is_err());
+    }
+
     #[test]
     fn accounts_balances() {
         let balances = super::accounts_balances(
@@ -450,6 +1485,46 @@ mod tests {
         assert!(balances[1] == 10000000)
     }
 
+    #[test]
+    fn accounts_balances_detailed() {
+        let accounts: [Account; 2] = [
+            "nano_3t6k35gi95xu6tergt6p69ck76ogmitsa8mnijtpxm9fkcm736xtoncuohr3"
+                .try_into()
+                .unwrap(),
+            "nano_1hrts7hcoozxccnffoq9hqhngnn9jz783usapejm57ejtqcyz9dpso1bibuy"
+                .try_into()
+                .unwrap(),
+        ];
+        let response = json!({
+            "balances":{
+                "nano_3t6k35gi95xu6tergt6p69ck76ogmitsa8mnijtpxm9fkcm736xtoncuohr3":{
+                    "balance": "325586539664609129644855132177",
+                    "pending": "2309372032769300000000000000000000",
+                    "receivable": "2309372032769300000000000000000000"
+                }
+            },
+            "errors":{
+                "nano_1hrts7hcoozxccnffoq9hqhngnn9jz783usapejm57ejtqcyz9dpso1bibuy": "Account not found"
+            }
+        });
+        let balances = super::accounts_balances_detailed(response.clone(), &accounts).unwrap();
+        assert!(balances[0].confirmed == 325586539664609129644855132177);
+        assert!(balances[0].receivable == 2309372032769300000000000000000000);
+        assert!(balances[1] == Balances::default());
+        assert!(
+            super::accounts_balances(response, &accounts).unwrap()
+                == vec![325586539664609129644855132177, 0]
+        );
+
+        let balances = super::account_balance_detailed(json!({
+            "balance": "10000",
+            "pending": "20000"
+        }))
+        .unwrap();
+        assert!(balances.confirmed == 10000);
+        assert!(balances.receivable == 20000);
+    }
+
     #[test]
     fn accounts_frontiers() {
         let frontiers = super::accounts_frontiers(
@@ -494,7 +1569,10 @@ mod tests {
                         "6A32397F4E95AF025DE29D9BF1ACE864D5404362258E06489FABDBA9DCCC046F": "9000000000000000000000000000005"
                     },
                     "nano_3t6k35gi95xu6tergt6p69ck76ogmitsa8mnijtpxm9fkcm736xtoncuohr3": {
-                        "4C1FEEF0BEA7F50BE35489A1233FE002B212DEA554B55B1B470D78BD8F210C74": "106370018000000000000000000000000"
+                        "4C1FEEF0BEA7F50BE35489A1233FE002B212DEA554B55B1B470D78BD8F210C74": {
+                            "amount": "106370018000000000000000000000000",
+                            "source": "nano_16u1uufyoig8777y6r8iqjtrw8sg8maqrm36zzcm95jmbd9i9aj5i8abr8u5"
+                        }
                     }
                 }
             }),
@@ -546,6 +1624,100 @@ mod tests {
         );
         assert!(receivable[1][0].block_hash == hash_3);
         assert!(receivable[1][0].amount == 106370018000000000000000000000000);
+        assert!(
+            receivable[1][0].sender
+                == Some(
+                    "nano_16u1uufyoig8777y6r8iqjtrw8sg8maqrm36zzcm95jmbd9i9aj5i8abr8u5"
+                        .parse()
+                        .unwrap()
+                )
+        );
+        assert!(receivable[0][0].sender.is_none());
+    }
+
+    #[test]
+    fn accounts_receivable_verified() {
+        let signature: [u8; 64] = hex::decode("82D41BC16F313E4B2243D14DFFA2FB04679C540C2095FEE7EAE0F2F26880AD56DD48D87A7CC5DD760C5B2D76EE2C205506AA557BF00B60D8DEE312EC7343A501").unwrap().try_into().unwrap();
+        let block = Block {
+            block_type: BlockType::Send,
+            account: "nano_1ipx847tk8o46pwxt5qjdbncjqcbwcc1rrmqnkztrfjy5k7z4imsrata9est"
+                .try_into()
+                .unwrap(),
+            previous: hex::decode(
+                "CE898C131AAEE25E05362F247760F8A3ACF34A9796A5AE0D9204E86B0637965E",
+            )
+            .unwrap()
+            .try_into()
+            .unwrap(),
+            representative: "nano_1stofnrxuz3cai7ze75o174bpm7scwj9jn3nxsn8ntzg784jf1gzn1jjdkou"
+                .try_into()
+                .unwrap(),
+            balance: 5606157000000000000000000000000000000,
+            link: hex::decode("5D1AA8A45F8736519D707FCB375976A7F9AF795091021D7E9C7548D6F45DD8D5")
+                .unwrap()
+                .try_into()
+                .unwrap(),
+            signature: signature.try_into().unwrap(),
+            work: hex::decode("8a142e07a10996d5").unwrap().try_into().unwrap(),
+        };
+        let info = BlockInfo {
+            height: 58,
+            timestamp: 999888777,
+            confirmed: true,
+            amount: 30000000000000000000000000000000000,
+            successor: None,
+            block: block.clone(),
+        };
+        let amount = info.amount;
+        let recipient: Account =
+            "nano_1qato4k7z3spc8gq1zyd8xeqfbzsoxwo36a45ozbrxcatut7up8ohyardu1z"
+                .parse()
+                .unwrap();
+
+        let receivable = vec![
+            vec![
+                // valid
+                Receivable::from((recipient.clone(), block.hash(), amount)),
+                // send block not found
+                Receivable::from((recipient.clone(), [1; 32], amount)),
+            ],
+            // wrong recipient
+            vec![Receivable::from((
+                get_genesis_account(),
+                block.hash(),
+                amount,
+            ))],
+        ];
+        let verified = super::accounts_receivable_verified(
+            receivable.clone(),
+            vec![Some(info.clone()), None, Some(info.clone())],
+        )
+        .unwrap();
+        assert!(verified == vec![vec![receivable[0][0].clone()], vec![]]);
+
+        assert!(super::accounts_receivable_verified(receivable, vec![Some(info.clone())]).is_err());
+
+        // the amount of a real send is inflated
+        let inflated = Receivable::from((recipient.clone(), block.hash(), amount + 1));
+        let verified =
+            super::accounts_receivable_verified(vec![vec![inflated]], vec![Some(info.clone())])
+                .unwrap();
+        assert!(verified == vec![vec![]]);
+
+        // wrong sender
+        let mut receivable = Receivable::from((recipient, block.hash(), amount));
+        receivable.sender = Some(get_genesis_account());
+        let verified = super::accounts_receivable_verified(
+            vec![vec![receivable.clone()]],
+            vec![Some(info.clone())],
+        )
+        .unwrap();
+        assert!(verified == vec![vec![]]);
+        receivable.sender = Some(block.account.clone());
+        let verified =
+            super::accounts_receivable_verified(vec![vec![receivable.clone()]], vec![Some(info)])
+                .unwrap();
+        assert!(verified == vec![vec![receivable]]);
     }
 
     #[test]
@@ -564,7 +1736,8 @@ mod tests {
                 "nano_16u1uufyoig8777y6r8iqjtrw8sg8maqrm36zzcm95jmbd9i9aj5i8abr8u5".try_into().unwrap(),
                 "nano_3t6k35gi95xu6tergt6p69ck76ogmitsa8mnijtpxm9fkcm736xtoncuohr3".try_into().unwrap(),
                 "nano_1hrts7hcoozxccnffoq9hqhngnn9jz783usapejm57ejtqcyz9dpso1bibuy".try_into().unwrap()
-            )
+            ),
+            Some(&AccountCache::new())
         ).unwrap();
 
         assert!(
@@ -611,7 +1784,8 @@ mod tests {
                     "work": "8a142e07a10996d5"
                 },
                 "subtype": "send"
-            })
+            }),
+            None
         ).unwrap().unwrap();
 
         let block = Block {
@@ -640,49 +1814,59 @@ mod tests {
         assert!(info.height == 58);
         assert!(info.timestamp == 999888777);
         assert!(info.confirmed);
+        assert!(info.amount == 30000000000000000000000000000000000);
+        assert!(
+            info.successor.map(hex::encode_upper).as_deref()
+                == Some("8D3AB98B301224253750D448B4BD997132400CEDD0A8432F775724F2D9821C72")
+        );
         assert!(info.block == block);
 
         // block not found
-        let info = super::block_info(json!({"error":"Block not found"})).unwrap();
+        let info = super::block_info(json!({"error":"Block not found"}), None).unwrap();
         assert!(info.is_none())
     }
 
     #[test]
     fn blocks_info() {
-        let infos = super::blocks_info(
-            json!({
-                "blocks": {
-                    "87434F8041869A01C8F6F263B87972D7BA443A72E0A97D7A3FD0CCC2358FD6F9": {
-                        "block_account": "nano_1ipx847tk8o46pwxt5qjdbncjqcbwcc1rrmqnkztrfjy5k7z4imsrata9est",
-                        "amount": "30000000000000000000000000000000000",
+        let response = json!({
+            "blocks": {
+                "87434F8041869A01C8F6F263B87972D7BA443A72E0A97D7A3FD0CCC2358FD6F9": {
+                    "block_account": "nano_1ipx847tk8o46pwxt5qjdbncjqcbwcc1rrmqnkztrfjy5k7z4imsrata9est",
+                    "amount": "30000000000000000000000000000000000",
+                    "balance": "5606157000000000000000000000000000000",
+                    "height": "581",
+                    "local_timestamp": "12299",
+                    "successor": "8D3AB98B301224253750D448B4BD997132400CEDD0A8432F775724F2D9821C72",
+                    "confirmed": "false",
+                    "contents": {
+                        "type": "state",
+                        "account": "nano_1ipx847tk8o46pwxt5qjdbncjqcbwcc1rrmqnkztrfjy5k7z4imsrata9est",
+                        "previous": "CE898C131AAEE25E05362F247760F8A3ACF34A9796A5AE0D9204E86B0637965E",
+                        "representative": "nano_1stofnrxuz3cai7ze75o174bpm7scwj9jn3nxsn8ntzg784jf1gzn1jjdkou",
                         "balance": "5606157000000000000000000000000000000",
-                        "height": "581",
-                        "local_timestamp": "12299",
-                        "successor": "8D3AB98B301224253750D448B4BD997132400CEDD0A8432F775724F2D9821C72",
-                        "confirmed": "false",
-                        "contents": {
-                            "type": "state",
-                            "account": "nano_1ipx847tk8o46pwxt5qjdbncjqcbwcc1rrmqnkztrfjy5k7z4imsrata9est",
-                            "previous": "CE898C131AAEE25E05362F247760F8A3ACF34A9796A5AE0D9204E86B0637965E",
-                            "representative": "nano_1stofnrxuz3cai7ze75o174bpm7scwj9jn3nxsn8ntzg784jf1gzn1jjdkou",
-                            "balance": "5606157000000000000000000000000000000",
-                            "link": "5D1AA8A45F8736519D707FCB375976A7F9AF795091021D7E9C7548D6F45DD8D5",
-                            "link_as_account": "nano_1qato4k7z3spc8gq1zyd8xeqfbzsoxwo36a45ozbrxcatut7up8ohyardu1z",
-                            "signature": "82D41BC16F313E4B2243D14DFFA2FB04679C540C2095FEE7EAE0F2F26880AD56DD48D87A7CC5DD760C5B2D76EE2C205506AA557BF00B60D8DEE312EC7343A501",
-                            "work": "8a142e07a10996d5"
-                        },
-                        "subtype": "send"
-                    }
-                },
-                "blocks_not_found": [
-                    "5D1AA8A45F8736519D707FCB375976A7F9AF795091021D7E9C7548D6F45DD8D5"
-                ]
-            }),
-            &[
-                hex::decode("87434F8041869A01C8F6F263B87972D7BA443A72E0A97D7A3FD0CCC2358FD6F9").unwrap().try_into().unwrap(),
-                hex::decode("5D1AA8A45F8736519D707FCB375976A7F9AF795091021D7E9C7548D6F45DD8D5").unwrap().try_into().unwrap()
+                        "link": "5D1AA8A45F8736519D707FCB375976A7F9AF795091021D7E9C7548D6F45DD8D5",
+                        "link_as_account": "nano_1qato4k7z3spc8gq1zyd8xeqfbzsoxwo36a45ozbrxcatut7up8ohyardu1z",
+                        "signature": "82D41BC16F313E4B2243D14DFFA2FB04679C540C2095FEE7EAE0F2F26880AD56DD48D87A7CC5DD760C5B2D76EE2C205506AA557BF00B60D8DEE312EC7343A501",
+                        "work": "8a142e07a10996d5"
+                    },
+                    "subtype": "send"
+                }
+            },
+            "blocks_not_found": [
+                "5D1AA8A45F8736519D707FCB375976A7F9AF795091021D7E9C7548D6F45DD8D5"
             ]
-        ).unwrap();
+        });
+        let hashes: [[u8; 32]; 2] = [
+            hex::decode("87434F8041869A01C8F6F263B87972D7BA443A72E0A97D7A3FD0CCC2358FD6F9")
+                .unwrap()
+                .try_into()
+                .unwrap(),
+            hex::decode("5D1AA8A45F8736519D707FCB375976A7F9AF795091021D7E9C7548D6F45DD8D5")
+                .unwrap()
+                .try_into()
+                .unwrap(),
+        ];
+        let infos = super::blocks_info(response.clone(), &hashes, None).unwrap();
 
         let signature: [u8; 64] = hex::decode("82D41BC16F313E4B2243D14DFFA2FB04679C540C2095FEE7EAE0F2F26880AD56DD48D87A7CC5DD760C5B2D76EE2C205506AA557BF00B60D8DEE312EC7343A501").unwrap().try_into().unwrap();
 
@@ -713,9 +1897,133 @@ mod tests {
         assert!(info.height == 581);
         assert!(info.timestamp == 12299);
         assert!(!info.confirmed);
+        assert!(info.amount == 30000000000000000000000000000000000);
+        assert!(
+            info.successor.map(hex::encode_upper).as_deref()
+                == Some("8D3AB98B301224253750D448B4BD997132400CEDD0A8432F775724F2D9821C72")
+        );
         assert!(info.block == block);
 
         assert!(infos[1].is_none());
+
+        // the node returns a zero successor for the frontier of an account
+        let mut response = response;
+        response["blocks"]["87434F8041869A01C8F6F263B87972D7BA443A72E0A97D7A3FD0CCC2358FD6F9"]
+            ["successor"] = hex::encode([0; 32]).into();
+        let infos = super::blocks_info(response, &hashes, None).unwrap();
+        assert!(infos[0].as_ref().unwrap().successor.is_none());
+    }
+
+    #[test]
+    fn chain() {
+        let hashes = super::chain(json!({
+            "blocks": [
+                "000D1BAEC8EC208142C99059B393051BAC8380F9B5A2E6B2489A277D81789F3F",
+                "FFFFFFFFFFFFFFFFFFFFFFFFFFFFFFFFFFFFFFFFFFFFFFFFFFFFFFFFFFFFFFFF"
+            ]
+        }))
+        .unwrap();
+        assert!(hashes.len() == 2);
+        assert!(hashes[1] == [255; 32]);
+        assert!(super::chain(json!({"blocks": ""})).unwrap().is_empty());
+        assert!(super::chain(json!({"error": "Block not found"})).is_err());
+    }
+
+    #[test]
+    fn chain_verified() {
+        let key = Key::from_seed(&SecretBytes::from([0; 32]), 0);
+        let mut builder = BlockBuilder::unopened(key.to_account(), get_genesis_account());
+        let mut infos = vec![];
+        for height in 1..=3 {
+            let mut block = match height {
+                1 => builder.receive([1; 32], 100).unwrap(),
+                _ => builder.send(&get_genesis_account(), 1).unwrap(),
+            };
+            block.sign(&key);
+            builder.advance(&block);
+            infos.push(BlockInfo {
+                height,
+                timestamp: 0,
+                confirmed: true,
+                amount: 1,
+                successor: None,
+                block,
+            });
+        }
+        let hashes: Vec<[u8; 32]> = infos.iter().map(|info| info.block.hash()).collect();
+
+        let successors =
+            super::chain_verified(&hashes, infos.iter().cloned().map(Some).collect(), true)
+                .unwrap();
+        assert!(successors == infos);
+        assert!(
+            super::chain_verified(&hashes, infos.iter().cloned().map(Some).collect(), false)
+                .is_err()
+        );
+
+        let reversed: Vec<[u8; 32]> = hashes.iter().rev().cloned().collect();
+        let chain = super::chain_verified(
+            &reversed,
+            infos.iter().rev().cloned().map(Some).collect(),
+            false,
+        )
+        .unwrap();
+        assert!(chain.len() == 3);
+
+        // truncated at a missing block
+        let chain = super::chain_verified(
+            &reversed,
+            vec![Some(infos[2].clone()), None, Some(infos[0].clone())],
+            false,
+        )
+        .unwrap();
+        assert!(chain == [infos[2].clone()]);
+
+        // wrong hash
+        assert!(super::chain_verified(&hashes[..1], vec![Some(infos[1].clone())], true).is_err());
+        assert!(super::chain_verified(&hashes, vec![], true).is_err());
+    }
+
+    #[test]
+    fn peers() {
+        let peers = super::peers(json!({
+            "peers": {
+                "[::ffff:172.17.0.1]:32841": {
+                    "protocol_version": "18",
+                    "node_id": "node_1y7j5rdqhg99uyab1145gu3yeo1tt8enjp7kn9gyywme7q8enp5a8hc6x4fm",
+                    "type": "tcp"
+                },
+                "[::ffff:10.0.0.1]:7075": {
+                    "protocol_version": "20",
+                    "node_id": "node_3jrwstf4qqaxps36py6ripnhqpjbjrfu14apdedk37uj51oic4g94qcabf1i",
+                    "type": "tcp"
+                }
+            }
+        }))
+        .unwrap();
+        assert!(
+            peers
+                == [
+                    Peer {
+                        address: "[::ffff:10.0.0.1]:7075".into(),
+                        protocol_version: 20,
+                        node_id:
+                            "node_3jrwstf4qqaxps36py6ripnhqpjbjrfu14apdedk37uj51oic4g94qcabf1i"
+                                .into(),
+                        connection_type: "tcp".into()
+                    },
+                    Peer {
+                        address: "[::ffff:172.17.0.1]:32841".into(),
+                        protocol_version: 18,
+                        node_id:
+                            "node_1y7j5rdqhg99uyab1145gu3yeo1tt8enjp7kn9gyywme7q8enp5a8hc6x4fm"
+                                .into(),
+                        connection_type: "tcp".into()
+                    }
+                ]
+        );
+        assert!(super::peers(json!({"peers": ""})).unwrap().is_empty());
+        assert!(super::peers(json!({"peers": {"[::1]:7075": "18"}})).is_err());
     }
 
     #[test]
@@ -738,6 +2046,579 @@ mod tests {
         assert!(hash == block_hash)
     }
 
+    #[test]
+    fn confirmation_outcome() {
+        let mut info = BlockInfo {
+            height: 1,
+            timestamp: 1,
+            confirmed: false,
+            amount: 1,
+            successor: None,
+            block: Block {
+                block_type: BlockType::Receive,
+                account: get_genesis_account(),
+                previous: [0; 32],
+                representative: get_genesis_account(),
+                balance: 1,
+                link: [1; 32],
+                signature: Signature::default(),
+                work: [0; 8],
+            },
+        };
+        assert!(super::confirmation_outcome(Some(info.clone())).is_none());
+        assert!(super::confirmation_outcome(None).is_none());
+
+        info.confirmed = true;
+        assert!(
+            super::confirmation_outcome(Some(info.clone()))
+                == Some(ConfirmationOutcome::Confirmed(Box::new(info)))
+        );
+    }
+
+    #[test]
+    fn available_supply() {
+        let supply = super::available_supply(json!({
+            "available": "133248061996216572282917317807824970865"
+        }))
+        .unwrap();
+        assert!(supply == 133248061996216572282917317807824970865);
+        assert!(super::available_supply(json!({})).is_err());
+    }
+
+    #[test]
+    fn active_difficulty() {
+        let difficulty = super::active_difficulty(json!({
+            "deprecated": "1",
+            "network_minimum": "fffffff800000000",
+            "network_receive_minimum": "fffffe0000000000",
+            "network_current": "fffffff800000000",
+            "network_receive_current": "fffffe0000000000",
+            "multiplier": "1"
+        }))
+        .unwrap();
+        assert!(difficulty.network_minimum == SEND_WORK_DIFFICULTY);
+        assert!(difficulty.network_receive_minimum == RECEIVE_WORK_DIFFICULTY);
+        assert!(difficulty.work_difficulty(&BlockType::Receive) == RECEIVE_WORK_DIFFICULTY);
+        assert!(difficulty.work_difficulty(&BlockType::Change) == SEND_WORK_DIFFICULTY);
+        assert!(difficulty.multiplier == 1.0);
+        assert!(super::active_difficulty(json!({"multiplier": "1"})).is_err());
+    }
+
+    #[test]
+    fn block_account() {
+        let genesis = get_genesis_account();
+        let account = super::block_account(json!({"account": genesis.to_string()}), None).unwrap();
+        assert!(account == Some(genesis));
+        let account = super::block_account(json!({"error": "Block not found"}), None).unwrap();
+        assert!(account.is_none());
+        assert!(super::block_account(json!({"error": "Invalid block hash"}), None).is_err());
+        assert!(super::block_account(json!({}), None).is_err());
+    }
+
+    #[test]
+    fn block_count() {
+        let count = super::block_count(json!({
+            "count": "1000",
+            "unchecked": "10",
+            "cemented": "25"
+        }))
+        .unwrap();
+        assert!(count.count == 1000 && count.unchecked == 10 && count.cemented == 25);
+        assert!(super::block_count(json!({"count": "1000"})).is_err());
+    }
+
+    #[test]
+    fn confirmation_active() {
+        let roots = super::confirmation_active(json!({
+            "confirmations": [
+                "8031B600827C5CC05FDC911C28BBAC12A0E096CCB30FA8324F56C123676281B28031B600827C5CC05FDC911C28BBAC12A0E096CCB30FA8324F56C123676281B2"
+            ],
+            "unconfirmed": "133",
+            "confirmed": "5"
+        }))
+        .unwrap();
+        assert!(roots.len() == 1);
+        assert!(roots[0][..32] == roots[0][32..]);
+        assert!(super::confirmation_active(json!({"confirmations": ""}))
+            .unwrap()
+            .is_empty());
+        assert!(super::confirmation_active(json!({"confirmations": ["8031"]})).is_err());
+    }
+
+    #[test]
+    fn confirmation_info() {
+        let genesis = get_genesis_account();
+        let other = Key::from_seed(&SecretBytes::from([0; 32]), 0).to_account();
+        let info = super::confirmation_info(
+            json!({
+                "announcements": "2",
+                "voters": "3",
+                "last_winner": "B94C505029F04BB7A2A7F5A1E1F8D8D8E6C1BB4E6D9A8D8F6A8B1DF8A5B2C6A1",
+                "total_tally": "300",
+                "final_tally": "0",
+                "blocks": {
+                    "0F0F0F0F0F0F0F0F0F0F0F0F0F0F0F0F0F0F0F0F0F0F0F0F0F0F0F0F0F0F0F0F": {
+                        "tally": "100",
+                        "final_tally": "0",
+                        "representatives": {genesis.to_string(): "100"}
+                    },
+                    "B94C505029F04BB7A2A7F5A1E1F8D8D8E6C1BB4E6D9A8D8F6A8B1DF8A5B2C6A1": {
+                        "tally": "200",
+                        "final_tally": "0",
+                        "representatives": {genesis.to_string(): "50", other.to_string(): "150"}
+                    }
+                }
+            }),
+            None,
+        )
+        .unwrap()
+        .unwrap();
+        assert!(info.announcements == 2 && info.voters == 3);
+        assert!(info.total_tally == 300 && info.final_tally == 0);
+        assert!(info.candidates.len() == 2);
+        assert!(info.candidates[0].hash == info.last_winner);
+        assert!(info.candidates[0].representatives == [(other, 150), (genesis.clone(), 50)]);
+        assert!(info.candidates[1].hash == [0x0f; 32]);
+        assert!(info.candidates[1].representatives == [(genesis, 100)]);
+
+        let info =
+            super::confirmation_info(json!({"error": "Active confirmation not found"}), None);
+        assert!(info.unwrap().is_none());
+        assert!(super::confirmation_info(json!({"error": "Invalid root hash"}), None).is_err());
+        assert!(super::confirmation_info(json!({}), None).is_err());
+    }
+
+    #[test]
+    fn confirmation_quorum() {
+        let mut json = json!({
+            "quorum_delta": "41469707173777717318245825935516662250",
+            "online_weight_quorum_percent": "50",
+            "online_weight_minimum": "60000000000000000000000000000000000000",
+            "online_stake_total": "82939414347555434636491651871033324568",
+            "trended_stake_total": "81939414347555434636491651871033324568",
+            "peers_stake_total": "69026910610720098597176027400951402360"
+        });
+        let quorum = super::confirmation_quorum(json.clone(), None).unwrap();
+        assert!(quorum.quorum_delta == 41469707173777717318245825935516662250);
+        assert!(quorum.online_weight_quorum_percent == 50);
+        assert!(quorum.trended_stake_total == 81939414347555434636491651871033324568);
+        assert!(quorum.peers.is_none());
+
+        json["peers"] = json!([{
+            "account": "nano_16u1uufyoig8777y6r8iqjtrw8sg8maqrm36zzcm95jmbd9i9aj5i8abr8u5",
+            "ip": "[::ffff:151.80.106.65]:7075",
+            "weight": "17485301001009658440014218087813216220"
+        }]);
+        let quorum = super::confirmation_quorum(json.clone(), None).unwrap();
+        let peers = quorum.peers.unwrap();
+        assert!(peers.len() == 1);
+        assert!(
+            peers[0].account
+                == "nano_16u1uufyoig8777y6r8iqjtrw8sg8maqrm36zzcm95jmbd9i9aj5i8abr8u5"
+                    .try_into()
+                    .unwrap()
+        );
+        assert!(peers[0].ip == "[::ffff:151.80.106.65]:7075");
+        assert!(peers[0].weight == 17485301001009658440014218087813216220);
+
+        json["peers"] = json!([{"account": "nano_1134", "ip": "", "weight": "1"}]);
+        assert!(super::confirmation_quorum(json, None).is_err());
+    }
+
+    #[test]
+    fn delegators() {
+        let delegators = super::delegators(
+            json!({
+                "delegators": {
+                    "nano_16u1uufyoig8777y6r8iqjtrw8sg8maqrm36zzcm95jmbd9i9aj5i8abr8u5": "500000000000000000000000000000000000",
+                    "nano_3t6k35gi95xu6tergt6p69ck76ogmitsa8mnijtpxm9fkcm736xtoncuohr3": "961647970820730000000000000000000000"
+                }
+            }),
+            None,
+        )
+        .unwrap();
+        assert!(delegators.len() == 2);
+        assert!(
+            delegators[0]
+                == (
+                    "nano_3t6k35gi95xu6tergt6p69ck76ogmitsa8mnijtpxm9fkcm736xtoncuohr3"
+                        .try_into()
+                        .unwrap(),
+                    961647970820730000000000000000000000
+                )
+        );
+        assert!(super::delegators(json!({"delegators": ""}), None)
+            .unwrap()
+            .is_empty());
+        assert!(super::delegators(json!({"error": "Bad account number"}), None).is_err());
+
+        assert!(super::delegators_count(json!({"count": "2"})).unwrap() == 2);
+        assert!(super::delegators_count(json!({"error": "Bad account number"})).is_err());
+    }
+
+    #[test]
+    fn ledger() {
+        let genesis = get_genesis_account();
+        let entries = super::ledger(
+            json!({
+                "accounts": {
+                    "nano_3t6k35gi95xu6tergt6p69ck76ogmitsa8mnijtpxm9fkcm736xtoncuohr3": {
+                        "frontier": "991CF190094C00F0B68E2E5F75F6BEE95A2E0BD93CEAA4A6734DB9F19B728948",
+                        "open_block": "991CF190094C00F0B68E2E5F75F6BEE95A2E0BD93CEAA4A6734DB9F19B728948",
+                        "representative_block": "991CF190094C00F0B68E2E5F75F6BEE95A2E0BD93CEAA4A6734DB9F19B728948",
+                        "balance": "0",
+                        "modified_timestamp": "1511476234",
+                        "block_count": "2",
+                        "representative": "nano_3t6k35gi95xu6tergt6p69ck76ogmitsa8mnijtpxm9fkcm736xtoncuohr3",
+                        "weight": "0",
+                        "pending": "0",
+                        "receivable": "10"
+                    }
+                }
+            }),
+            None,
+        )
+        .unwrap();
+        assert!(entries.len() == 1);
+        let (account, entry) = &entries[0];
+        assert!(account == &genesis);
+        assert!(entry.representative == genesis);
+        assert!(entry.block_count == 2 && entry.modified_timestamp == 1511476234);
+        assert!(entry.receivable == 10);
+        assert!(
+            to_uppercase_hex(&entry.representative_block)
+                == "991CF190094C00F0B68E2E5F75F6BEE95A2E0BD93CEAA4A6734DB9F19B728948"
+        );
+
+        assert!(super::ledger(json!({"accounts": ""}), None)
+            .unwrap()
+            .is_empty());
+        assert!(super::ledger(
+            json!({"accounts": {genesis.to_string(): {"frontier": "991C"}}}),
+            None
+        )
+        .is_err());
+    }
+
+    #[test]
+    fn frontiers() {
+        let genesis = get_genesis_account();
+        let burn: Account = "nano_1111111111111111111111111111111111111111111111111111hifc8npp"
+            .try_into()
+            .unwrap();
+        let frontiers = super::frontiers(
+            json!({
+                "frontiers": {
+                    "nano_3t6k35gi95xu6tergt6p69ck76ogmitsa8mnijtpxm9fkcm736xtoncuohr3": "991CF190094C00F0B68E2E5F75F6BEE95A2E0BD93CEAA4A6734DB9F19B728948",
+                    "nano_1111111111111111111111111111111111111111111111111111hifc8npp": "ECCB8CB65CD3106EDA8CE9AA893FEAD497A91BCA903890CBD7A5C59F06AB9113"
+                }
+            }),
+            None,
+        )
+        .unwrap();
+        assert!(frontiers.len() == 2);
+        assert!(frontiers[0].0 == burn);
+        assert!(frontiers[1].0 == genesis);
+        assert!(
+            to_uppercase_hex(&frontiers[1].1)
+                == "991CF190094C00F0B68E2E5F75F6BEE95A2E0BD93CEAA4A6734DB9F19B728948"
+        );
+
+        assert!(super::frontiers(json!({"frontiers": ""}), None)
+            .unwrap()
+            .is_empty());
+        assert!(super::frontiers(json!({}), None).is_err());
+        assert!(super::frontiers(json!({"frontiers": {"nano_1234": "00"}}), None).is_err());
+    }
+
+    #[test]
+    fn receivable() {
+        let account: Account = "nano_1111111111111111111111111111111111111111111111111117353trpda"
+            .parse()
+            .unwrap();
+        let receivable = super::receivable(
+            json!({
+                "blocks": {
+                    "142A538F36833D1CC78B94E11C766F75818F8B940771335C6C1B8AB880C5BB1D": {
+                        "amount": "6000000000000000000000000000000",
+                        "source": "nano_3t6k35gi95xu6tergt6p69ck76ogmitsa8mnijtpxm9fkcm736xtoncuohr3",
+                        "min_version": "2"
+                    },
+                    "6A32397F4E95AF025DE29D9BF1ACE864D5404362258E06489FABDBA9DCCC046F": {
+                        "amount": "9000000000000000000000000000005",
+                        "source": "nano_16u1uufyoig8777y6r8iqjtrw8sg8maqrm36zzcm95jmbd9i9aj5i8abr8u5"
+                    }
+                }
+            }),
+            &account,
+            None,
+        )
+        .unwrap();
+        assert!(receivable.len() == 2);
+        assert!(receivable
+            .iter()
+            .all(|receivable| receivable.recipient == account));
+        assert!(receivable[1].amount == 6000000000000000000000000000000);
+        assert!(
+            receivable[1].sender
+                == Some(
+                    "nano_3t6k35gi95xu6tergt6p69ck76ogmitsa8mnijtpxm9fkcm736xtoncuohr3"
+                        .parse()
+                        .unwrap()
+                )
+        );
+        assert!(receivable[1].min_version == Some(2));
+        assert!(receivable[0].min_version.is_none());
+
+        assert!(super::receivable(json!({"blocks": ""}), &account, None)
+            .unwrap()
+            .is_empty());
+        assert!(super::receivable(
+            json!({"blocks": {"142A538F36833D1CC78B94E11C766F75818F8B940771335C6C1B8AB880C5BB1D": {"amount": "1", "source": "nano_1"}}}),
+            &account,
+            None
+        )
+        .is_err());
+    }
+
+    #[test]
+    fn search_receivable() {
+        let amount =
+            super::receive_minimum(json!({"amount": "1000000000000000000000000"})).unwrap();
+        assert!(amount == 1000000000000000000000000);
+        assert!(super::search_receivable(json!({"started": "1"})).unwrap());
+        assert!(!super::search_receivable(json!({"started": "0"})).unwrap());
+        assert!(super::search_receivable(json!({"error": "Wallet not found"})).is_err());
+        assert!(super::search_receivable_all(json!({"success": ""})).is_ok());
+        assert!(super::search_receivable_all(json!({"error": "Wallet is locked"})).is_err());
+    }
+
+    #[test]
+    fn representatives() {
+        let representatives = super::representatives(
+            json!({
+                "representatives": {
+                    "nano_16u1uufyoig8777y6r8iqjtrw8sg8maqrm36zzcm95jmbd9i9aj5i8abr8u5": "100",
+                    "nano_3t6k35gi95xu6tergt6p69ck76ogmitsa8mnijtpxm9fkcm736xtoncuohr3": "3822372327060170000000000000000000000"
+                }
+            }),
+            None,
+        )
+        .unwrap();
+        assert!(
+            representatives
+                == vec![
+                    (
+                        "nano_3t6k35gi95xu6tergt6p69ck76ogmitsa8mnijtpxm9fkcm736xtoncuohr3"
+                            .try_into()
+                            .unwrap(),
+                        3822372327060170000000000000000000000
+                    ),
+                    (
+                        "nano_16u1uufyoig8777y6r8iqjtrw8sg8maqrm36zzcm95jmbd9i9aj5i8abr8u5"
+                            .try_into()
+                            .unwrap(),
+                        100
+                    )
+                ]
+        );
+        assert!(super::representatives(json!({"representatives": ""}), None).is_err());
+    }
+
+    #[test]
+    fn representatives_online() {
+        let representatives = super::representatives_online(
+            json!({
+                "representatives": [
+                    "nano_16u1uufyoig8777y6r8iqjtrw8sg8maqrm36zzcm95jmbd9i9aj5i8abr8u5",
+                    "nano_3t6k35gi95xu6tergt6p69ck76ogmitsa8mnijtpxm9fkcm736xtoncuohr3"
+                ]
+            }),
+            None,
+        )
+        .unwrap();
+        assert!(representatives.len() == 2);
+        assert!(
+            representatives[1]
+                == "nano_3t6k35gi95xu6tergt6p69ck76ogmitsa8mnijtpxm9fkcm736xtoncuohr3"
+                    .try_into()
+                    .unwrap()
+        );
+
+        let representatives = super::representatives_online_detailed(
+            json!({
+                "representatives": {
+                    "nano_16u1uufyoig8777y6r8iqjtrw8sg8maqrm36zzcm95jmbd9i9aj5i8abr8u5": {"weight": "5"},
+                    "nano_3t6k35gi95xu6tergt6p69ck76ogmitsa8mnijtpxm9fkcm736xtoncuohr3": {"weight": "7"}
+                }
+            }),
+            None,
+        )
+        .unwrap();
+        assert!(
+            representatives
+                .iter()
+                .map(|(_, weight)| *weight)
+                .collect::<Vec<u128>>()
+                == [7, 5]
+        );
+        assert!(super::representatives_online_detailed(
+            json!({
+                "representatives": {
+                    "nano_16u1uufyoig8777y6r8iqjtrw8sg8maqrm36zzcm95jmbd9i9aj5i8abr8u5": {}
+                }
+            }),
+            None
+        )
+        .is_err());
+    }
+
+    #[test]
+    fn unchecked() {
+        let key = Key::from_seed(&SecretBytes::from([0; 32]), 0);
+        let mut builder = BlockBuilder::unopened(key.to_account(), get_genesis_account());
+        let mut open = builder.receive([1; 32], 100).unwrap();
+        open.sign(&key);
+        builder.advance(&open);
+        let mut send = builder.send(&get_genesis_account(), 1).unwrap();
+        send.sign(&key);
+        let mut legacy = block_to_json(&open);
+        legacy.insert("type".into(), "open".into());
+
+        let blocks = super::unchecked(
+            json!({
+                "blocks": {
+                    to_uppercase_hex(&open.hash()): block_to_json(&open),
+                    to_uppercase_hex(&send.hash()): block_to_json(&send),
+                    to_uppercase_hex(&[3; 32]): legacy
+                }
+            }),
+            None,
+        )
+        .unwrap();
+        assert!(blocks.len() == 2);
+        assert!(blocks.contains(&open));
+        assert!(blocks.contains(&send));
+        assert!(super::unchecked(json!({"blocks": ""}), None)
+            .unwrap()
+            .is_empty());
+        assert!(super::unchecked(
+            json!({"blocks": {to_uppercase_hex(&[3; 32]): block_to_json(&send)}}),
+            None
+        )
+        .is_err());
+
+        let unchecked = super::unchecked_keys(
+            json!({
+                "unchecked": [{
+                    "key": to_uppercase_hex(&open.hash()),
+                    "hash": to_uppercase_hex(&send.hash()),
+                    "modified_timestamp": "1565856525",
+                    "contents": block_to_json(&send)
+                }]
+            }),
+            None,
+        )
+        .unwrap();
+        assert!(
+            unchecked
+                == [UncheckedBlock {
+                    key: open.hash(),
+                    modified_timestamp: 1565856525,
+                    block: send
+                }]
+        );
+        assert!(super::unchecked_keys(json!({"unchecked": ""}), None)
+            .unwrap()
+            .is_empty());
+    }
+
+    #[test]
+    fn version() {
+        let version = json!({
+            "rpc_version": "1",
+            "store_version": "21",
+            "protocol_version": "20",
+            "node_vendor": "Nano V27.1",
+            "store_vendor": "LMDB 0.9.70",
+            "network": "live",
+            "network_identifier": "991CF190094C00F0B68E2E5F75F6BEE95A2E0BD93CEAA4A6734DB9F19B728948",
+            "build_info": "f4f1a3d \"GNU C++ version \" \"9.4.0\""
+        });
+        let parsed = super::version(version.clone()).unwrap();
+        assert!(parsed.store_version == 21);
+        assert!(parsed.node_vendor == "Nano V27.1");
+        assert!(parsed.network == "live");
+        assert!(parsed.network_identifier[0] == 0x99);
+
+        let mut invalid = version;
+        invalid["node_vendor"] = 27.into();
+        assert!(super::version(invalid).is_err());
+    }
+
+    #[test]
+    fn republish() {
+        let hashes = super::republish(json!({
+            "success": "",
+            "blocks": [
+                "991CF190094C00F0B68E2E5F75F6BEE95A2E0BD93CEAA4A6734DB9F19B728948",
+                "A170D51B94E00371ACE76E35AC81DC9405D5D04D4CEBC399AEACE07AE05DD293"
+            ]
+        }))
+        .unwrap();
+        assert!(hashes.len() == 2);
+        assert!(
+            to_uppercase_hex(&hashes[1])
+                == "A170D51B94E00371ACE76E35AC81DC9405D5D04D4CEBC399AEACE07AE05DD293"
+        );
+        assert!(super::republish(json!({"blocks": ""})).unwrap().is_empty());
+        assert!(super::republish(json!({"error": "Block not found"})).is_err());
+    }
+
+    #[test]
+    fn telemetry() {
+        let metrics = json!({
+            "block_count": "5",
+            "cemented_count": "0",
+            "unchecked_count": "0",
+            "account_count": "1",
+            "bandwidth_cap": "1572864",
+            "peer_count": "1",
+            "protocol_version": "18",
+            "uptime": "556896",
+            "genesis_block": "F824C697633FAB78B703D75189B7A7E18DA438A2ED5FFE7495F02F681CD56D41",
+            "major_version": "21",
+            "minor_version": "0",
+            "patch_version": "0",
+            "pre_release_version": "0",
+            "maker": "0",
+            "timestamp": "1587055945990",
+            "active_difficulty": "ffffffcdbf40aa45"
+        });
+        let telemetry = super::telemetry(metrics.clone()).unwrap();
+        assert!(telemetry.block_count == 5);
+        assert!(telemetry.bandwidth_cap == 1572864);
+        assert!(telemetry.major_version == 21);
+        assert!(telemetry.genesis_block[0] == 0xF8);
+        assert!(telemetry.node_id.is_none() && telemetry.port.is_none());
+
+        let mut peer = metrics.clone();
+        peer["node_id"] =
+            "node_1cmi8difuruopgzpnb4ybrnnj5rproxwuwe5mad7ucbsekakiwn37qqg1zo5".into();
+        peer["address"] = "::ffff:192.168.0.1".into();
+        peer["port"] = "7075".into();
+        let telemetry = super::telemetry_raw(json!({"metrics": [peer, metrics]})).unwrap();
+        assert!(telemetry.len() == 2);
+        assert!(telemetry[0].address.as_deref() == Some("::ffff:192.168.0.1"));
+        assert!(telemetry[0].port == Some(7075));
+        assert!(telemetry[1].port.is_none());
+
+        let mut invalid = metrics;
+        invalid["port"] = "70750".into();
+        assert!(super::telemetry(invalid).is_err());
+        assert!(super::telemetry(json!({"error": "Peer not found"})).is_err());
+    }
+
     #[test]
     fn work_generate() {
         // valid
@@ -781,4 +2662,38 @@ mod tests {
         )
         .unwrap_err();
     }
+
+    #[test]
+    fn work_validate() {
+        let hash = hex::decode("718CC2121C3E641059BC1C2CFC45666C99E8AE922F7A807B7D07B62C995D79E2")
+            .unwrap()
+            .try_into()
+            .unwrap();
+        let work = hex::decode("2b3d689bbcb21dca").unwrap().try_into().unwrap();
+        let json = json!({
+            "valid_all": "1",
+            "valid_receive": "1",
+            "difficulty": "fffffff93c41ec94",
+            "multiplier": "1.182623871097636"
+        });
+
+        let validation = super::work_validate(json.clone(), work, hash, None).unwrap();
+        assert!(validation.valid.is_none());
+        assert!(validation.valid_all && validation.valid_receive);
+        assert!(validation.difficulty == 0xfffffff93c41ec94_u64.to_be_bytes());
+        assert!(validation.multiplier == 1.182623871097636);
+
+        let mut custom = json.clone();
+        custom["valid"] = "0".into();
+        let validation = super::work_validate(custom.clone(), work, hash, Some([255; 8])).unwrap();
+        assert!(validation.valid == Some(false));
+
+        // the node's answer must match the local calculation
+        custom["valid"] = "1".into();
+        assert!(super::work_validate(custom, work, hash, Some([255; 8])).is_err());
+        let mut wrong = json.clone();
+        wrong["valid_all"] = "0".into();
+        assert!(super::work_validate(wrong, work, hash, None).is_err());
+        assert!(super::work_validate(json, [0; 8], hash, None).is_err());
+    }
 }
@@ -0,0 +1,322 @@
+use super::builder::HttpOptions;
+use super::debug::DebugRpc;
+use super::transport::{default_transport, RpcTransport, TransportFuture};
+use super::{RetryPolicy, Rpc, RpcError};
+use json::Value as JsonValue;
+use serde_json as json;
+use std::sync::atomic::{AtomicUsize, Ordering};
+use std::sync::{Arc, Mutex, MutexGuard};
+use std::time::Duration;
+
+#[cfg(not(feature = "wasm"))]
+use std::time::Instant;
+#[cfg(feature = "wasm")]
+use web_time::Instant;
+
+/// How long a node which failed is avoided, by default
+pub const DEFAULT_COOLDOWN: Duration = Duration::from_secs(30);
+
+#[derive(Debug, Default)]
+struct NodeState {
+    consecutive_failures: u32,
+    /// The node is avoided until this time, after it fails
+    unhealthy_until: Option<Instant>,
+}
+impl NodeState {
+    fn is_healthy(&self, now: Instant) -> bool {
+        match self.unhealthy_until {
+            Some(until) => now >= until,
+            None => true,
+        }
+    }
+}
+
+#[derive(Debug)]
+struct PoolNode {
+    url: String,
+    transport: Arc<dyn RpcTransport>,
+    state: Mutex<NodeState>,
+}
+impl PoolNode {
+    fn lock(&self) -> MutexGuard<'_, NodeState> {
+        self.state
+            .lock()
+            .unwrap_or_else(|poisoned| poisoned.into_inner())
+    }
+
+    fn is_healthy(&self, now: Instant) -> bool {
+        self.lock().is_healthy(now)
+    }
+}
+
+/// The health of a node in an `RpcPool`, returned by `RpcPool::health()`
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct NodeHealth {
+    pub url: String,
+    /// The number of requests which have failed in a row
+    pub consecutive_failures: u32,
+    /// `false` if the node failed recently, and is being avoided
+    pub healthy: bool,
+}
+
+/// Sends each request to one of several nodes, failing over to the next node if the request fails.
+///
+/// A request fails over if it fails while being sent (see `RetryPolicy::is_transport_error()`),
+/// or if the node responds that it is too busy (see `RetryPolicy::is_retryable_response()`).
+/// A node which fails is avoided for the `cooldown`, unless every other node is also failing.
+///
+/// An `RpcPool` is an `RpcTransport`: use `rpc()`, or pass it to `RpcBuilder::transport()`.
+/// Clones of a pool share the health of its nodes.
+#[derive(Debug, Clone)]
+pub struct RpcPool {
+    nodes: Arc<[PoolNode]>,
+    round_robin: bool,
+    cooldown: Duration,
+    failover: RetryPolicy,
+    next: Arc<AtomicUsize>,
+}
+impl RpcPool {
+    /// Create a pool of nodes, using the default HTTP client for each
+    pub fn new(urls: &[&str]) -> Result<RpcPool, RpcError> {
+        let transports = urls
+            .iter()
+            .map(|url| Ok((*url, default_transport(url, &HttpOptions::default())?)))
+            .collect::<Result<Vec<_>, RpcError>>()?;
+        Ok(RpcPool::from_transports(transports))
+    }
+
+    /// Create a pool of nodes from custom transports, each named by a URL (which is only used in `health()`)
+    pub fn from_transports(transports: Vec<(&str, Arc<dyn RpcTransport>)>) -> RpcPool {
+        RpcPool {
+            nodes: transports
+                .into_iter()
+                .map(|(url, transport)| PoolNode {
+                    url: url.into(),
+                    transport,
+                    state: Mutex::new(NodeState::default()),
+                })
+                .collect(),
+            round_robin: false,
+            cooldown: DEFAULT_COOLDOWN,
+            failover: RetryPolicy::default(),
+            next: Arc::new(AtomicUsize::new(0)),
+        }
+    }
+
+    /// Spread requests across all healthy nodes, rather than always preferring the first healthy node
+    pub fn round_robin(mut self, round_robin: bool) -> RpcPool {
+        self.round_robin = round_robin;
+        self
+    }
+
+    /// How long a node which failed is avoided
+    pub fn cooldown(mut self, cooldown: Duration) -> RpcPool {
+        self.cooldown = cooldown;
+        self
+    }
+
+    /// Create an `Rpc` which sends its requests through this pool
+    pub fn rpc(&self) -> Rpc {
+        Rpc(DebugRpc::with_transport(
+            "pool",
+            None,
+            Arc::new(self.clone()),
+        ))
+    }
+
+    /// Get the health of each node, in the order that they were given
+    pub fn health(&self) -> Vec<NodeHealth> {
+        let now = Instant::now();
+        self.nodes
+            .iter()
+            .map(|node| {
+                let state = node.lock();
+                NodeHealth {
+                    url: node.url.clone(),
+                    consecutive_failures: state.consecutive_failures,
+                    healthy: state.is_healthy(now),
+                }
+            })
+            .collect()
+    }
+
+    /// The order in which to try the nodes: healthy nodes first, then the rest as a last resort
+    fn order(&self) -> Vec<&PoolNode> {
+        let start = match self.round_robin && !self.nodes.is_empty() {
+            true => self.next.fetch_add(1, Ordering::Relaxed) % self.nodes.len(),
+            false => 0,
+        };
+        let mut order: Vec<&PoolNode> = self.nodes[start..]
+            .iter()
+            .chain(&self.nodes[..start])
+            .collect();
+
+        let now = Instant::now();
+        order.sort_by_key(|node| !node.is_healthy(now));
+        order
+    }
+
+    fn record_success(&self, node: &PoolNode) {
+        *node.lock() = NodeState::default()
+    }
+
+    fn record_failure(&self, node: &PoolNode) {
+        let mut state = node.lock();
+        state.consecutive_failures = state.consecutive_failures.saturating_add(1);
+        state.unhealthy_until = Some(Instant::now() + self.cooldown);
+    }
+}
+impl RpcTransport for RpcPool {
+    fn send<'a>(&'a self, request: &'a JsonValue) -> TransportFuture<'a> {
+        Box::pin(async move {
+            let mut last = Err(RpcError::NoNodeAvailable);
+            for node in self.order() {
+                let response = node.transport.send(request).await;
+                let failed = match &response {
+                    Ok(response) => self.failover.is_retryable_response(response),
+                    Err(err) => (self.failover.is_retryable)(err),
+                };
+                if !failed {
+                    self.record_success(node);
+                    return response;
+                }
+
+                #[cfg(feature = "tracing")]
+                tracing::debug!(url = node.url, "request failed, trying the next node");
+                self.record_failure(node);
+                last = response;
+            }
+            last
+        })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::super::mock::MockRpc;
+    use super::super::transport::RpcTransport;
+    use super::super::RpcError;
+    use super::{NodeHealth, RpcPool};
+    use futures::executor::block_on;
+    use serde_json::json;
+    use std::sync::Arc;
+    use std::time::Duration;
+
+    fn pool(mocks: &[MockRpc]) -> RpcPool {
+        let transports = mocks
+            .iter()
+            .enumerate()
+            .map(|(i, mock)| {
+                let transport: Arc<dyn RpcTransport> = Arc::new(mock.clone());
+                (["a", "b", "c"][i], transport)
+            })
+            .collect();
+        RpcPool::from_transports(transports)
+    }
+
+    fn block_count(mock: &MockRpc, count: u64) {
+        mock.respond(
+            "block_count",
+            json!({"count": count.to_string(), "unchecked": "0", "cemented": "0"}),
+        );
+    }
+
+    fn fail(mock: &MockRpc) {
+        mock.on("block_count", |_| {
+            Err(RpcError::IoError(std::io::ErrorKind::Other.into()))
+        });
+    }
+
+    #[test]
+    fn failover() {
+        let mocks = [MockRpc::new(), MockRpc::new(), MockRpc::new()];
+        fail(&mocks[0]);
+        mocks[1].respond("block_count", json!({"error": "Too many requests"}));
+        block_count(&mocks[2], 3);
+        let pool = pool(&mocks);
+        let rpc = pool.rpc();
+
+        assert!(block_on(rpc.block_count()).unwrap().count == 3);
+        assert!(mocks.iter().all(|mock| mock.requests().len() == 1));
+
+        // failed nodes are avoided until the cooldown ends
+        block_count(&mocks[0], 1);
+        assert!(block_on(rpc.block_count()).unwrap().count == 3);
+        assert!(mocks[0].requests().len() == 1);
+        assert!(mocks[2].requests().len() == 2);
+
+        // other errors do not fail over
+        mocks[2].respond("block_count", json!({"error": "Unknown command"}));
+        assert!(block_on(rpc.block_count()).is_err());
+        assert!(mocks[0].requests().len() == 1);
+
+        let health = pool.health();
+        assert!(health[0].url == "a");
+        assert!(health[0].consecutive_failures == 1);
+        assert!(!health[0].healthy);
+        assert!(health[2].consecutive_failures == 0);
+        assert!(health[2].healthy);
+    }
+
+    #[test]
+    fn health() {
+        let mocks = [MockRpc::new(), MockRpc::new()];
+        fail(&mocks[0]);
+        block_count(&mocks[1], 2);
+        let request = json!({"action": "block_count"});
+
+        // with no cooldown, failed nodes are tried again immediately
+        let pool = pool(&mocks).cooldown(Duration::ZERO);
+        block_on(pool.send(&request)).unwrap();
+        block_on(pool.send(&request)).unwrap();
+        assert!(mocks[0].requests().len() == 2);
+        let health = pool.health();
+        assert!(health[0].consecutive_failures == 2);
+        assert!(health[0].healthy);
+
+        // nodes which failed are still tried as a last resort
+        let pool = pool.cooldown(Duration::from_secs(60));
+        block_on(pool.send(&request)).unwrap();
+        fail(&mocks[1]);
+        assert!(block_on(pool.send(&request)).is_err());
+        assert!(mocks[0].requests().len() == 4);
+        assert!(pool.health().iter().all(|node| !node.healthy));
+
+        block_count(&mocks[0], 1);
+        assert!(block_on(pool.send(&request)).is_ok());
+        assert!(
+            pool.health()[0]
+                == NodeHealth {
+                    url: "a".into(),
+                    consecutive_failures: 0,
+                    healthy: true
+                }
+        );
+    }
+
+    #[test]
+    fn round_robin() {
+        let mocks = [MockRpc::new(), MockRpc::new(), MockRpc::new()];
+        for (i, mock) in mocks.iter().enumerate() {
+            block_count(mock, i as u64)
+        }
+        let rpc = pool(&mocks).round_robin(true).rpc();
+        let counts: Vec<u64> = (0..4)
+            .map(|_| block_on(rpc.block_count()).unwrap().count)
+            .collect();
+        assert!(counts == [0, 1, 2, 0]);
+
+        let rpc = pool(&mocks).rpc();
+        assert!(block_on(rpc.block_count()).unwrap().count == 0);
+        assert!(block_on(rpc.block_count()).unwrap().count == 0);
+    }
+
+    #[test]
+    fn empty() {
+        let rpc = RpcPool::from_transports(vec![]).rpc();
+        assert!(matches!(
+            block_on(rpc.block_count()),
+            Err(RpcError::NoNodeAvailable)
+        ));
+    }
+}
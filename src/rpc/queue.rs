@@ -0,0 +1,316 @@
+use super::{Rpc, RpcError};
+use crate::{Account, Block};
+
+#[cfg(feature = "serde")]
+use serde::{Deserialize, Serialize};
+
+/// The result of flushing an `OfflineQueue`
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct FlushReport {
+    /// Hashes of the blocks which were published, in the order they were published
+    pub published: Vec<[u8; 32]>,
+    /// Hashes of queued blocks which were found to already be on the ledger
+    pub already_published: Vec<[u8; 32]>,
+    /// Number of blocks left in the queue which do not follow on from their account's frontier
+    pub remaining: usize,
+}
+
+/// A queue of signed blocks waiting to be published, for when the RPC is unreachable.
+///
+/// Blocks are published in the correct order for each account, regardless of the order in which they were queued.
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+#[cfg_attr(feature = "serde", derive(Serialize, Deserialize))]
+pub struct OfflineQueue {
+    blocks: Vec<Block>,
+}
+impl OfflineQueue {
+    pub fn new() -> OfflineQueue {
+        OfflineQueue::default()
+    }
+
+    /// Add a signed block to the queue.
+    /// Blocks which are already queued are ignored.
+    pub fn push(&mut self, block: Block) {
+        if !self.blocks.contains(&block) {
+            self.blocks.push(block)
+        }
+    }
+
+    pub fn len(&self) -> usize {
+        self.blocks.len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.blocks.is_empty()
+    }
+
+    pub fn iter(&self) -> std::slice::Iter<'_, Block> {
+        self.blocks.iter()
+    }
+
+    /// Remove all queued blocks belonging to `account`, returning them
+    pub fn remove_account(&mut self, account: &Account) -> Vec<Block> {
+        let (removed, kept) = self
+            .blocks
+            .drain(..)
+            .partition(|block| &block.account == account);
+        self.blocks = kept;
+        removed
+    }
+
+    /// Get each account with blocks in the queue, in the order they were first queued
+    pub fn accounts(&self) -> Vec<Account> {
+        let mut accounts: Vec<Account> = vec![];
+        for block in &self.blocks {
+            if !accounts.contains(&block.account) {
+                accounts.push(block.account.clone())
+            }
+        }
+        accounts
+    }
+
+    fn position(&self, account: &Account, predicate: impl Fn(&Block) -> bool) -> Option<usize> {
+        self.blocks
+            .iter()
+            .position(|block| &block.account == account && predicate(block))
+    }
+
+    /// Remove the queued blocks of `account` with the given hashes, returning the hashes of the removed blocks
+    fn remove_hashes(&mut self, account: &Account, hashes: &[[u8; 32]]) -> Vec<[u8; 32]> {
+        let mut removed = vec![];
+        for hash in hashes {
+            if let Some(i) = self.position(account, |block| &block.hash() == hash) {
+                self.blocks.remove(i);
+                removed.push(*hash);
+            }
+        }
+        removed
+    }
+
+    /// Remove the queued blocks which are already on the ledger, given the account's current `frontier`.
+    /// Returns the hashes of the removed blocks.
+    fn prune(&mut self, account: &Account, frontier: Option<[u8; 32]>) -> Vec<[u8; 32]> {
+        let mut pruned = vec![];
+        let mut hash = match frontier {
+            Some(frontier) => frontier,
+            None => return pruned,
+        };
+        while let Some(i) = self.position(account, |block| block.hash() == hash) {
+            let block = self.blocks.remove(i);
+            pruned.push(hash);
+            hash = block.previous;
+        }
+        pruned
+    }
+
+    /// Remove and return the queued block which follows on from the account's current `frontier`, if any
+    fn take_next(&mut self, account: &Account, frontier: Option<[u8; 32]>) -> Option<Block> {
+        let previous = frontier.unwrap_or([0; 32]);
+        let i = self.position(account, |block| block.previous == previous)?;
+        Some(self.blocks.remove(i))
+    }
+
+    /// Publish as many queued blocks as possible.
+    ///
+    /// Queued blocks which are already on the ledger (checked with `blocks_info`) are removed first,
+    /// even if they are not directly behind the account's frontier.
+    /// The account's frontier is re-checked before each block is published.
+    /// If an error is encountered, the unpublished blocks are left in the queue.
+    pub async fn flush(&mut self, rpc: &Rpc) -> Result<FlushReport, RpcError> {
+        let mut report = FlushReport::default();
+
+        for account in self.accounts() {
+            let queued: Vec<[u8; 32]> = self
+                .iter()
+                .filter(|block| block.account == account)
+                .map(|block| block.hash())
+                .collect();
+            let on_ledger: Vec<[u8; 32]> = rpc
+                .blocks_info(&queued)
+                .await?
+                .into_iter()
+                .zip(&queued)
+                .filter(|(info, _)| info.is_some())
+                .map(|(_, hash)| *hash)
+                .collect();
+            report
+                .already_published
+                .extend(self.remove_hashes(&account, &on_ledger));
+
+            loop {
+                let frontier = rpc
+                    .accounts_frontiers(std::slice::from_ref(&account))
+                    .await?
+                    .pop()
+                    .ok_or(RpcError::InvalidData)?;
+                report
+                    .already_published
+                    .extend(self.prune(&account, frontier));
+
+                let block = match self.take_next(&account, frontier) {
+                    Some(block) => block,
+                    None => break,
+                };
+                match rpc.process(&block).await {
+                    Ok(hash) => report.published.push(hash),
+                    Err(err) => {
+                        self.blocks.insert(0, block);
+                        return Err(err);
+                    }
+                }
+            }
+        }
+
+        report.remaining = self.len();
+        Ok(report)
+    }
+}
+impl From<Vec<Block>> for OfflineQueue {
+    fn from(value: Vec<Block>) -> Self {
+        let mut queue = OfflineQueue::new();
+        for block in value {
+            queue.push(block)
+        }
+        queue
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::OfflineQueue;
+    use crate::rpc::mock::MockRpc;
+    use crate::rpc::util::block_to_json;
+    use crate::{constants::get_genesis_account, Block, BlockType, Key, SecretBytes, Signature};
+    use futures::executor::block_on;
+    use serde_json::{json, Map, Value as JsonValue};
+
+    fn chain(key: &Key, length: u128) -> Vec<Block> {
+        let mut blocks: Vec<Block> = vec![];
+        for i in 0..length {
+            let previous = blocks.last().map(|block| block.hash()).unwrap_or([0; 32]);
+            let mut block = Block {
+                block_type: BlockType::Receive,
+                account: key.to_account(),
+                previous,
+                representative: get_genesis_account(),
+                balance: i + 1,
+                link: [i as u8; 32],
+                signature: Signature::default(),
+                work: [0; 8],
+            };
+            block.sign(key);
+            blocks.push(block)
+        }
+        blocks
+    }
+
+    /// A `blocks_info` response containing `blocks`
+    fn blocks_info(blocks: &[&Block]) -> JsonValue {
+        let mut infos = Map::new();
+        for (height, block) in blocks.iter().enumerate() {
+            infos.insert(
+                hex::encode_upper(block.hash()),
+                json!({
+                    "block_account": block.account.to_string(),
+                    "amount": "1",
+                    "balance": block.balance.to_string(),
+                    "height": (height + 1).to_string(),
+                    "local_timestamp": "1000",
+                    "successor": hex::encode([0; 32]),
+                    "confirmed": "true",
+                    "contents": block_to_json(block),
+                    "subtype": "receive"
+                }),
+            );
+        }
+        json!({ "blocks": infos })
+    }
+
+    #[test]
+    fn ordering() {
+        let key_1 = Key::from_seed(&SecretBytes::from([0; 32]), 0);
+        let key_2 = Key::from_seed(&SecretBytes::from([0; 32]), 1);
+        let chain_1 = chain(&key_1, 3);
+        let chain_2 = chain(&key_2, 2);
+
+        let mut queue = OfflineQueue::from(vec![
+            chain_1[2].clone(),
+            chain_2[1].clone(),
+            chain_1[0].clone(),
+            chain_1[1].clone(),
+            chain_2[0].clone(),
+            chain_1[0].clone(),
+        ]);
+        assert!(queue.len() == 5);
+        assert!(queue.accounts() == vec![key_1.to_account(), key_2.to_account()]);
+
+        let account = key_1.to_account();
+        assert!(queue.take_next(&account, None) == Some(chain_1[0].clone()));
+        assert!(queue.take_next(&account, Some(chain_1[0].hash())) == Some(chain_1[1].clone()));
+        assert!(queue.take_next(&account, Some([1; 32])).is_none());
+
+        assert!(
+            queue.remove_account(&key_2.to_account())
+                == vec![chain_2[1].clone(), chain_2[0].clone()]
+        );
+        assert!(queue.len() == 1);
+    }
+
+    #[test]
+    fn prune() {
+        let key = Key::from_seed(&SecretBytes::from([0; 32]), 0);
+        let account = key.to_account();
+        let chain = chain(&key, 4);
+        let mut queue = OfflineQueue::from(chain.clone());
+
+        let pruned = queue.prune(&account, Some(chain[1].hash()));
+        assert!(pruned == vec![chain[1].hash(), chain[0].hash()]);
+        assert!(queue.take_next(&account, Some(chain[1].hash())) == Some(chain[2].clone()));
+        assert!(queue.prune(&account, None).is_empty());
+        assert!(queue.len() == 1);
+    }
+
+    #[test]
+    fn flush() {
+        let key = Key::from_seed(&SecretBytes::from([0; 32]), 0);
+        let account = key.to_account();
+        let chain = chain(&key, 4);
+        // the second block is not queued, so the ledger cannot be walked back from the frontier
+        let mut queue =
+            OfflineQueue::from(vec![chain[0].clone(), chain[2].clone(), chain[3].clone()]);
+
+        let mock = MockRpc::new();
+        mock.respond("blocks_info", blocks_info(&[&chain[0], &chain[2]]));
+        mock.respond(
+            "accounts_frontiers",
+            json!({"frontiers": {account.to_string(): hex::encode(chain[2].hash())}}),
+        );
+        mock.respond("process", json!({"hash": hex::encode(chain[3].hash())}));
+
+        let report = block_on(queue.flush(&mock.rpc())).unwrap();
+        assert!(report.already_published == vec![chain[0].hash(), chain[2].hash()]);
+        assert!(report.published == vec![chain[3].hash()]);
+        assert!(report.remaining == 0 && queue.is_empty());
+    }
+}
+
+#[cfg(test)]
+#[cfg(feature = "serde")]
+mod serde_tests {
+    use super::OfflineQueue;
+    use crate::{
+        constants::{get_genesis_account, ONE_NANO},
+        serde_test, Block, BlockType, Signature,
+    };
+
+    serde_test!(offline_queue: OfflineQueue::from(vec![Block {
+        block_type: BlockType::Send,
+        account: get_genesis_account(),
+        previous: [19; 32],
+        representative: get_genesis_account(),
+        balance: ONE_NANO,
+        link: [91; 32],
+        signature: Signature::default(),
+        work: [22; 8]
+    }]) => 8 + 4 + 32 + 32 + 32 + 16 + 32 + 64 + 8);
+}
@@ -0,0 +1,163 @@
+use super::{Rpc, RpcError};
+use crate::{Account, Block};
+use futures::future::join_all;
+use std::future::Future;
+
+/// Sends the same query to several nodes, and only returns a result which enough of them agree on.
+///
+/// Blocks are checked to be correctly signed as they are parsed, so a single node cannot forge them,
+/// but it can still omit or withhold them: cross-checking against other nodes protects against this.
+#[derive(Debug, Clone)]
+pub struct RpcQuorum {
+    rpcs: Vec<Rpc>,
+    threshold: usize,
+}
+impl RpcQuorum {
+    /// Require at least `threshold` of `rpcs` to return the same result (at least `1`)
+    pub fn new(rpcs: Vec<Rpc>, threshold: usize) -> RpcQuorum {
+        RpcQuorum {
+            rpcs,
+            threshold: threshold.max(1),
+        }
+    }
+
+    pub fn rpcs(&self) -> &[Rpc] {
+        &self.rpcs
+    }
+
+    pub fn threshold(&self) -> usize {
+        self.threshold
+    }
+
+    /// Send `query` to every node at the same time, and return the result returned by at least `threshold` of them.
+    ///
+    /// Nodes which fail are treated as disagreeing.
+    /// If no result reaches the threshold, or more than one does, `RpcError::QuorumMismatch` is returned.
+    pub async fn query<'a, T, F, Fut>(&'a self, query: F) -> Result<T, RpcError>
+    where
+        T: PartialEq,
+        F: Fn(&'a Rpc) -> Fut,
+        Fut: Future<Output = Result<T, RpcError>>,
+    {
+        let results = join_all(self.rpcs.iter().map(query)).await;
+
+        let mut votes: Vec<(T, usize)> = vec![];
+        for result in results.into_iter().flatten() {
+            match votes.iter_mut().find(|(value, _)| value == &result) {
+                Some((_, count)) => *count += 1,
+                None => votes.push((result, 1)),
+            }
+        }
+        let mut agreed = votes
+            .into_iter()
+            .filter(|(_, count)| *count >= self.threshold);
+        match (agreed.next(), agreed.next()) {
+            (Some((value, _)), None) => Ok(value),
+            // with a threshold of half the nodes or less, two results can both reach it
+            _ => Err(RpcError::QuorumMismatch),
+        }
+    }
+
+    pub async fn account_balance(&self, account: &Account) -> Result<u128, RpcError> {
+        self.query(|rpc| rpc.account_balance(account)).await
+    }
+
+    pub async fn accounts_balances(&self, accounts: &[Account]) -> Result<Vec<u128>, RpcError> {
+        self.query(|rpc| rpc.accounts_balances(accounts)).await
+    }
+
+    /// Returns the hash of the frontier (newest) block of the given accounts.
+    /// If an account is not yet opened, its frontier will be returned as `None`.
+    pub async fn accounts_frontiers(
+        &self,
+        accounts: &[Account],
+    ) -> Result<Vec<Option<[u8; 32]>>, RpcError> {
+        self.query(|rpc| rpc.accounts_frontiers(accounts)).await
+    }
+
+    /// Get the blocks with the given hashes, using `blocks_info`.
+    /// Legacy blocks, and blocks that don't exist, will return `None`.
+    ///
+    /// Only the blocks themselves are compared, since other info (such as the timestamp) differs between nodes.
+    pub async fn blocks(&self, hashes: &[[u8; 32]]) -> Result<Vec<Option<Block>>, RpcError> {
+        self.query(|rpc| async move {
+            let info = rpc.blocks_info(hashes).await?;
+            Ok(info
+                .into_iter()
+                .map(|info| info.map(|info| info.block.clone()))
+                .collect::<Vec<Option<Block>>>())
+        })
+        .await
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::super::mock::MockRpc;
+    use super::super::RpcError;
+    use super::RpcQuorum;
+    use crate::constants::get_genesis_account;
+    use futures::executor::block_on;
+    use serde_json::json;
+
+    fn frontier(mock: &MockRpc, frontier: [u8; 32]) {
+        mock.respond(
+            "accounts_frontiers",
+            json!({"frontiers": {get_genesis_account().to_string(): hex::encode(frontier)}}),
+        );
+    }
+
+    #[test]
+    fn quorum() {
+        let genesis = [get_genesis_account()];
+        let mocks = [MockRpc::new(), MockRpc::new(), MockRpc::new()];
+        let quorum = RpcQuorum::new(mocks.iter().map(|mock| mock.rpc()).collect(), 2);
+
+        frontier(&mocks[0], [1; 32]);
+        frontier(&mocks[1], [2; 32]);
+        frontier(&mocks[2], [1; 32]);
+        let frontiers = block_on(quorum.accounts_frontiers(&genesis)).unwrap();
+        assert!(frontiers == [Some([1; 32])]);
+        assert!(mocks.iter().all(|mock| mock.requests().len() == 1));
+
+        // failed nodes disagree
+        frontier(&mocks[2], [3; 32]);
+        assert!(matches!(
+            block_on(quorum.accounts_frontiers(&genesis)),
+            Err(RpcError::QuorumMismatch)
+        ));
+        mocks[1].remove("accounts_frontiers");
+        frontier(&mocks[2], [1; 32]);
+        assert!(block_on(quorum.accounts_frontiers(&genesis)).is_ok());
+        mocks[2].remove("accounts_frontiers");
+        assert!(matches!(
+            block_on(quorum.accounts_frontiers(&genesis)),
+            Err(RpcError::QuorumMismatch)
+        ));
+    }
+
+    #[test]
+    fn split() {
+        let genesis = [get_genesis_account()];
+        let mocks = [
+            MockRpc::new(),
+            MockRpc::new(),
+            MockRpc::new(),
+            MockRpc::new(),
+        ];
+        let quorum = RpcQuorum::new(mocks.iter().map(|mock| mock.rpc()).collect(), 2);
+
+        frontier(&mocks[0], [1; 32]);
+        frontier(&mocks[1], [1; 32]);
+        frontier(&mocks[2], [2; 32]);
+        frontier(&mocks[3], [2; 32]);
+        assert!(matches!(
+            block_on(quorum.accounts_frontiers(&genesis)),
+            Err(RpcError::QuorumMismatch)
+        ));
+
+        frontier(&mocks[3], [3; 32]);
+        let frontiers = block_on(quorum.accounts_frontiers(&genesis)).unwrap();
+        assert!(frontiers == [Some([1; 32])]);
+    }
+}
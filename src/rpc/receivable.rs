@@ -0,0 +1,266 @@
+use crate::Account;
+use std::cmp::Ordering;
+use std::collections::{HashMap, HashSet};
+use zeroize::{Zeroize, ZeroizeOnDrop};
+
+#[cfg(feature = "serde")]
+use serde::{Deserialize, Serialize};
+
+/// A receivable (pending) transaction.
+///
+/// Receivable transactions are ordered by amount, then by block hash
+/// (and then by the remaining fields, so that the order is consistent with `Eq`).
+#[derive(Debug, Clone, Zeroize, ZeroizeOnDrop, PartialEq, Eq)]
+#[cfg_attr(feature = "serde", derive(Serialize, Deserialize))]
+pub struct Receivable {
+    /// The recipient account of this transaction
+    pub recipient: Account,
+    /// The hash of the send block on the sender's account
+    #[cfg_attr(feature = "serde", serde(rename = "hash"))]
+    #[cfg_attr(feature = "serde", serde(with = "crate::serde_util::hex_bytes"))]
+    pub block_hash: [u8; 32],
+    /// The amount being transferred
+    #[cfg_attr(feature = "serde", serde(with = "crate::serde_util::raw"))]
+    pub amount: u128,
+    /// The account which sent this transaction, if known
+    #[cfg_attr(feature = "serde", serde(default))]
+    pub sender: Option<Account>,
+    /// The minimum epoch version of the send block, if known
+    #[cfg_attr(feature = "serde", serde(default))]
+    pub min_version: Option<u8>,
+}
+impl From<(Account, [u8; 32], u128)> for Receivable {
+    fn from(value: (Account, [u8; 32], u128)) -> Self {
+        Receivable {
+            recipient: value.0,
+            block_hash: value.1,
+            amount: value.2,
+            sender: None,
+            min_version: None,
+        }
+    }
+}
+impl PartialOrd for Receivable {
+    fn partial_cmp(&self, other: &Self) -> Option<Ordering> {
+        Some(self.cmp(other))
+    }
+}
+impl Ord for Receivable {
+    fn cmp(&self, other: &Self) -> Ordering {
+        self.amount
+            .cmp(&other.amount)
+            .then_with(|| self.block_hash.cmp(&other.block_hash))
+            .then_with(|| self.recipient.account.cmp(&other.recipient.account))
+            .then_with(|| {
+                let sender = other.sender.as_ref().map(|sender| &sender.account);
+                self.sender
+                    .as_ref()
+                    .map(|sender| &sender.account)
+                    .cmp(&sender)
+            })
+            .then_with(|| self.min_version.cmp(&other.min_version))
+    }
+}
+
+/// A collection of receivable transactions, possibly for multiple accounts
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+#[cfg_attr(feature = "serde", derive(Serialize, Deserialize))]
+pub struct ReceivableSet(Vec<Receivable>);
+impl ReceivableSet {
+    pub fn new() -> ReceivableSet {
+        ReceivableSet::default()
+    }
+
+    pub fn push(&mut self, receivable: Receivable) {
+        self.0.push(receivable)
+    }
+
+    pub fn len(&self) -> usize {
+        self.0.len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.0.is_empty()
+    }
+
+    pub fn iter(&self) -> std::slice::Iter<'_, Receivable> {
+        self.0.iter()
+    }
+
+    pub fn as_slice(&self) -> &[Receivable] {
+        &self.0
+    }
+
+    pub fn into_vec(self) -> Vec<Receivable> {
+        self.0
+    }
+
+    /// Sort by amount (smallest first), then by block hash
+    pub fn sort(&mut self) {
+        self.0.sort()
+    }
+
+    /// Sort by amount (largest first), then by block hash
+    pub fn sort_descending(&mut self) {
+        self.0.sort_by(|a, b| b.cmp(a))
+    }
+
+    /// Remove all transactions with an amount lower than `threshold`
+    pub fn filter_threshold(&mut self, threshold: u128) {
+        self.0.retain(|receivable| receivable.amount >= threshold)
+    }
+
+    /// Remove transactions with duplicate block hashes, keeping the first occurrence
+    pub fn dedup(&mut self) {
+        let mut seen = HashSet::new();
+        self.0
+            .retain(|receivable| seen.insert(receivable.block_hash))
+    }
+
+    /// The sum of all amounts (saturating at `u128::MAX`)
+    pub fn total(&self) -> u128 {
+        self.iter().fold(0, |total, receivable| {
+            total.saturating_add(receivable.amount)
+        })
+    }
+
+    /// Group the transactions by recipient
+    pub fn by_account(&self) -> HashMap<Account, Vec<Receivable>> {
+        let mut accounts: HashMap<Account, Vec<Receivable>> = HashMap::new();
+        for receivable in self.iter() {
+            accounts
+                .entry(receivable.recipient.clone())
+                .or_default()
+                .push(receivable.clone())
+        }
+        accounts
+    }
+
+    /// The sum of all amounts for each recipient (saturating at `u128::MAX`)
+    pub fn total_by_account(&self) -> HashMap<Account, u128> {
+        let mut totals: HashMap<Account, u128> = HashMap::new();
+        for receivable in self.iter() {
+            let total = totals.entry(receivable.recipient.clone()).or_default();
+            *total = total.saturating_add(receivable.amount)
+        }
+        totals
+    }
+}
+impl From<Vec<Receivable>> for ReceivableSet {
+    fn from(value: Vec<Receivable>) -> Self {
+        ReceivableSet(value)
+    }
+}
+/// Flattens the output of `accounts_receivable`
+impl From<Vec<Vec<Receivable>>> for ReceivableSet {
+    fn from(value: Vec<Vec<Receivable>>) -> Self {
+        ReceivableSet(value.into_iter().flatten().collect())
+    }
+}
+impl FromIterator<Receivable> for ReceivableSet {
+    fn from_iter<T: IntoIterator<Item = Receivable>>(iter: T) -> Self {
+        ReceivableSet(iter.into_iter().collect())
+    }
+}
+impl IntoIterator for ReceivableSet {
+    type Item = Receivable;
+    type IntoIter = std::vec::IntoIter<Receivable>;
+
+    fn into_iter(self) -> Self::IntoIter {
+        self.0.into_iter()
+    }
+}
+impl<'a> IntoIterator for &'a ReceivableSet {
+    type Item = &'a Receivable;
+    type IntoIter = std::slice::Iter<'a, Receivable>;
+
+    fn into_iter(self) -> Self::IntoIter {
+        self.0.iter()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{constants::get_genesis_account, Key, SecretBytes};
+
+    fn test_set() -> ReceivableSet {
+        let account = Key::from_seed(&SecretBytes::from([0; 32]), 0).to_account();
+        ReceivableSet::from(vec![
+            vec![
+                Receivable::from((account.clone(), [3; 32], 500)),
+                Receivable::from((account.clone(), [1; 32], 100)),
+            ],
+            vec![
+                Receivable::from((get_genesis_account(), [2; 32], 100)),
+                Receivable::from((get_genesis_account(), [1; 32], 100)),
+            ],
+        ])
+    }
+
+    #[test]
+    fn ord() {
+        let mut set = test_set();
+        set.sort();
+        let amounts: Vec<u128> = set.iter().map(|r| r.amount).collect();
+        assert!(amounts == vec![100, 100, 100, 500]);
+        assert!(set.as_slice()[2].block_hash == [2; 32]);
+
+        set.sort_descending();
+        assert!(set.as_slice()[0].amount == 500);
+
+        // receivables which differ only by their sender or version are not equal
+        let receivable = set.as_slice()[0].clone();
+        let mut with_sender = receivable.clone();
+        with_sender.sender = Some(get_genesis_account());
+        assert!(receivable != with_sender);
+        assert!(receivable.cmp(&with_sender) == Ordering::Less);
+        let mut with_version = receivable.clone();
+        with_version.min_version = Some(2);
+        assert!(receivable.cmp(&with_version) == Ordering::Less);
+        assert!(receivable.cmp(&receivable.clone()) == Ordering::Equal);
+    }
+
+    #[test]
+    fn filter_and_dedup() {
+        let mut set = test_set();
+        set.dedup();
+        assert!(set.len() == 3);
+        set.filter_threshold(101);
+        assert!(set.len() == 1);
+        assert!(set.total() == 500);
+    }
+
+    #[test]
+    fn aggregate() {
+        let set = test_set();
+        assert!(set.total() == 800);
+
+        let totals = set.total_by_account();
+        assert!(totals[&get_genesis_account()] == 200);
+        assert!(set.by_account()[&get_genesis_account()].len() == 2);
+    }
+}
+
+#[cfg(test)]
+#[cfg(feature = "serde")]
+mod serde_tests {
+    use super::*;
+    use crate::{constants::get_genesis_account, serde_test};
+
+    serde_test!(receivable: Receivable {
+        recipient: get_genesis_account(),
+        block_hash: [51; 32],
+        amount: 432894284243,
+        sender: Some(get_genesis_account()),
+        min_version: Some(2)
+    } => 32 + 32 + 16 + (1 + 32) + (1 + 1));
+
+    serde_test!(receivable_set: ReceivableSet::from(vec![Receivable {
+        recipient: get_genesis_account(),
+        block_hash: [51; 32],
+        amount: 432894284243,
+        sender: None,
+        min_version: None
+    }]) => 8 + 32 + 32 + 16 + 1 + 1);
+}
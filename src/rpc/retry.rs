@@ -0,0 +1,193 @@
+use super::{RpcError, RpcErrorKind};
+use json::Value as JsonValue;
+use serde_json as json;
+use std::collections::hash_map::RandomState;
+use std::hash::{BuildHasher, Hasher};
+use std::time::Duration;
+
+/// Node errors (in lowercase) which mean that the node is too busy, and the request should be retried later
+pub(crate) const BUSY_NODE_ERRORS: &[&str] = &["too busy", "too many requests", "rate limit"];
+
+/// How to retry requests which fail due to transient problems
+#[derive(Debug, Clone)]
+pub struct RetryPolicy {
+    /// The maximum number of attempts, including the first
+    pub max_attempts: u32,
+    /// Delay before the first retry
+    pub initial_delay: Duration,
+    /// The delay is never increased beyond this
+    pub max_delay: Duration,
+    /// The delay is multiplied by this after each failed attempt
+    pub multiplier: u32,
+    /// Randomly shorten each delay by up to half, so that many clients do not retry in lockstep
+    pub jitter: bool,
+    /// Whether a failed request should be retried
+    pub is_retryable: fn(&RpcError) -> bool,
+    /// Responses whose `error` field contains one of these (case-insensitive) are also retried,
+    /// such as when the node is too busy
+    pub retryable_node_errors: Vec<String>,
+}
+impl RetryPolicy {
+    /// Never retry
+    pub fn none() -> RetryPolicy {
+        RetryPolicy {
+            max_attempts: 1,
+            ..RetryPolicy::default()
+        }
+    }
+
+    /// Returns `true` for errors which occur while sending a request, such as connection failures and timeouts
+    /// (see `RpcErrorKind::Network`).
+    /// This is the default for `is_retryable`.
+    pub fn is_transport_error(error: &RpcError) -> bool {
+        error.kind() == RpcErrorKind::Network
+    }
+
+    /// The delay before retry number `retry` (starting at 0), without jitter
+    pub fn delay(&self, retry: u32) -> Duration {
+        let mut delay = self.initial_delay;
+        for _ in 0..retry {
+            delay = delay.saturating_mul(self.multiplier);
+            if delay >= self.max_delay {
+                return self.max_delay;
+            }
+        }
+        delay.min(self.max_delay)
+    }
+
+    /// The delay before retry number `retry` (starting at 0), with jitter if enabled
+    pub(crate) fn jittered_delay(&self, retry: u32) -> Duration {
+        let delay = self.delay(retry);
+        if !self.jitter {
+            return delay;
+        }
+        // `RandomState` is randomly seeded each time it is created
+        let random = RandomState::new().build_hasher().finish();
+        let fraction = (random % 1000) as u32;
+        delay - (delay / 2) * fraction / 1000
+    }
+
+    /// Returns `true` if `response` is a node error which should be retried
+    pub fn is_retryable_response(&self, response: &JsonValue) -> bool {
+        let error = match response["error"].as_str() {
+            Some(error) => error.to_lowercase(),
+            None => return false,
+        };
+        self.retryable_node_errors
+            .iter()
+            .any(|retryable| error.contains(&retryable.to_lowercase()))
+    }
+}
+impl Default for RetryPolicy {
+    fn default() -> Self {
+        RetryPolicy {
+            max_attempts: 3,
+            initial_delay: Duration::from_millis(250),
+            max_delay: Duration::from_secs(10),
+            multiplier: 2,
+            jitter: true,
+            is_retryable: RetryPolicy::is_transport_error,
+            retryable_node_errors: BUSY_NODE_ERRORS.iter().map(|&error| error.into()).collect(),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::super::debug::DebugRpc;
+    use super::super::transport::{RpcTransport, TransportFuture};
+    use super::super::RpcError;
+    use super::RetryPolicy;
+    use futures::executor::block_on;
+    use serde_json::{json, Map, Value as JsonValue};
+    use std::io::{Error as IoError, ErrorKind};
+    use std::sync::atomic::{AtomicUsize, Ordering};
+    use std::sync::Arc;
+    use std::time::Duration;
+
+    /// Returns each response in turn, and then repeats the last
+    #[derive(Debug)]
+    struct Flaky {
+        responses: Vec<Option<JsonValue>>,
+        sent: AtomicUsize,
+    }
+    impl RpcTransport for Flaky {
+        fn send<'a>(&'a self, _: &'a JsonValue) -> TransportFuture<'a> {
+            let i = self.sent.fetch_add(1, Ordering::Relaxed);
+            let response = self.responses[i.min(self.responses.len() - 1)].clone();
+            Box::pin(async move {
+                response.ok_or(RpcError::IoError(IoError::from(ErrorKind::ConnectionReset)))
+            })
+        }
+    }
+
+    fn flaky_rpc(responses: Vec<Option<JsonValue>>, policy: RetryPolicy) -> (DebugRpc, Arc<Flaky>) {
+        let transport = Arc::new(Flaky {
+            responses,
+            sent: AtomicUsize::new(0),
+        });
+        let mut rpc = DebugRpc::with_transport("flaky", None, transport.clone());
+        rpc.set_retry_policy(Some(policy));
+        (rpc, transport)
+    }
+
+    fn policy() -> RetryPolicy {
+        RetryPolicy {
+            initial_delay: Duration::from_millis(1),
+            ..RetryPolicy::default()
+        }
+    }
+
+    #[test]
+    fn delay() {
+        let policy = RetryPolicy {
+            initial_delay: Duration::from_secs(1),
+            max_delay: Duration::from_secs(5),
+            multiplier: 2,
+            jitter: false,
+            ..RetryPolicy::default()
+        };
+        let delays: Vec<u64> = (0..5).map(|i| policy.delay(i).as_secs()).collect();
+        assert!(delays == vec![1, 2, 4, 5, 5]);
+        assert!(policy.jittered_delay(2) == Duration::from_secs(4));
+
+        let policy = RetryPolicy {
+            jitter: true,
+            ..policy
+        };
+        for _ in 0..100 {
+            let delay = policy.jittered_delay(2);
+            assert!(delay > Duration::from_secs(2) && delay <= Duration::from_secs(4));
+        }
+    }
+
+    #[test]
+    fn retries() {
+        let ok = json!({"count": "1"});
+        let busy = json!({"error": "Node is too busy"});
+
+        let (rpc, transport) =
+            flaky_rpc(vec![None, Some(busy.clone()), Some(ok.clone())], policy());
+        let response = block_on(rpc.command("block_count", Map::new()));
+        assert!(response.result.unwrap() == ok);
+        assert!(transport.sent.load(Ordering::Relaxed) == 3);
+
+        // gives up after `max_attempts`, returning the last response
+        let (rpc, transport) = flaky_rpc(vec![None, Some(busy.clone())], policy());
+        let response = block_on(rpc.command("block_count", Map::new()));
+        assert!(response.result.unwrap() == busy);
+        assert!(transport.sent.load(Ordering::Relaxed) == 3);
+
+        let (rpc, transport) = flaky_rpc(vec![None, Some(ok.clone())], RetryPolicy::none());
+        let response = block_on(rpc.command("block_count", Map::new()));
+        assert!(matches!(response.result, Err(RpcError::IoError(_))));
+        assert!(transport.sent.load(Ordering::Relaxed) == 1);
+
+        // other node errors are not retried
+        let not_found = json!({"error": "Account not found"});
+        let (rpc, transport) = flaky_rpc(vec![Some(not_found.clone()), Some(ok)], policy());
+        let response = block_on(rpc.command("account_info", Map::new()));
+        assert!(response.result.unwrap() == not_found);
+        assert!(transport.sent.load(Ordering::Relaxed) == 1);
+    }
+}
@@ -0,0 +1,357 @@
+use super::builder::HttpOptions;
+#[cfg(any(feature = "rpc", feature = "rpc-isahc"))]
+use super::builder::HttpVersion;
+use super::RpcError;
+use json::Value as JsonValue;
+use serde_json as json;
+use std::fmt::Debug;
+use std::future::Future;
+use std::pin::Pin;
+use std::sync::Arc;
+
+/// The response to a request, returned by `RpcTransport::send()`
+pub type TransportFuture<'a> =
+    Pin<Box<dyn Future<Output = Result<JsonValue, RpcError>> + Send + 'a>>;
+
+/// Sends JSON requests to a node, and returns the node's responses.
+///
+/// Implement this to use another HTTP client, or a different transport (such as IPC),
+/// and pass it to `RpcBuilder::transport()`.
+pub trait RpcTransport: Debug + Send + Sync {
+    /// Send `request` to the node, and return the parsed JSON response.
+    ///
+    /// Errors which occur while sending the request should be returned as `RpcError::TransportError`
+    /// (or `RpcError::IoError`), so that they are retried by the default `RetryPolicy`.
+    fn send<'a>(&'a self, request: &'a JsonValue) -> TransportFuture<'a>;
+}
+
+/// HTTP transport using `reqwest`, which requires a `tokio` runtime
+#[cfg(feature = "rpc")]
+#[derive(Debug)]
+pub(crate) struct ReqwestTransport {
+    client: reqwest::Client,
+    url: reqwest::Url,
+}
+#[cfg(feature = "rpc")]
+impl ReqwestTransport {
+    pub(crate) fn new(url: &str, options: &HttpOptions) -> Result<ReqwestTransport, RpcError> {
+        let mut builder = reqwest::Client::builder().tcp_keepalive(options.tcp_keepalive);
+        if let Some(timeout) = options.connect_timeout {
+            builder = builder.connect_timeout(timeout)
+        }
+        if let Some(max) = options.pool_max_idle_per_host {
+            builder = builder.pool_max_idle_per_host(max)
+        }
+        if let Some(timeout) = options.pool_idle_timeout {
+            builder = builder.pool_idle_timeout(timeout)
+        }
+        builder = match options.http_version {
+            HttpVersion::Negotiate => builder,
+            HttpVersion::Http1Only => builder.http1_only(),
+            HttpVersion::Http2PriorKnowledge => builder.http2_prior_knowledge(),
+        };
+        if let Some(interval) = options.http2_keep_alive_interval {
+            builder = builder
+                .http2_keep_alive_interval(interval)
+                .http2_keep_alive_while_idle(true)
+        }
+        if let Some(proxy) = &options.proxy {
+            builder = builder.proxy(reqwest::Proxy::all(proxy)?)
+        }
+        if !options.headers.is_empty() {
+            use reqwest::header::{HeaderMap, HeaderName, HeaderValue};
+
+            let mut headers = HeaderMap::new();
+            for (name, value) in &options.headers {
+                let name =
+                    HeaderName::from_bytes(name.as_bytes()).map_err(|_| RpcError::InvalidRPC)?;
+                let mut value = HeaderValue::from_str(value).map_err(|_| RpcError::InvalidRPC)?;
+                // keep credentials out of debug output
+                value.set_sensitive(true);
+                headers.append(name, value);
+            }
+            builder = builder.default_headers(headers)
+        }
+
+        Ok(ReqwestTransport {
+            client: builder.build()?,
+            url: url.parse().map_err(|_| RpcError::InvalidRPC)?,
+        })
+    }
+}
+#[cfg(feature = "rpc")]
+impl RpcTransport for ReqwestTransport {
+    fn send<'a>(&'a self, request: &'a JsonValue) -> TransportFuture<'a> {
+        Box::pin(async move {
+            let response = self
+                .client
+                .post(self.url.clone())
+                .json(request)
+                .send()
+                .await?;
+            Ok(response.json::<JsonValue>().await?)
+        })
+    }
+}
+
+/// HTTP transport using `isahc`, which works with any async runtime
+#[cfg(feature = "rpc-isahc")]
+#[derive(Debug)]
+pub(crate) struct IsahcTransport {
+    client: isahc::HttpClient,
+    url: isahc::http::Uri,
+}
+#[cfg(feature = "rpc-isahc")]
+impl IsahcTransport {
+    pub(crate) fn new(url: &str, options: &HttpOptions) -> Result<IsahcTransport, RpcError> {
+        use isahc::config::{Configurable, VersionNegotiation};
+
+        let mut builder = isahc::HttpClient::builder();
+        if let Some(timeout) = options.connect_timeout {
+            builder = builder.connect_timeout(timeout)
+        }
+        if let Some(interval) = options.tcp_keepalive {
+            builder = builder.tcp_keepalive(interval)
+        }
+        if let Some(max) = options.pool_max_idle_per_host {
+            builder = builder.connection_cache_size(max)
+        }
+        if let Some(timeout) = options.pool_idle_timeout {
+            builder = builder.connection_cache_ttl(timeout)
+        }
+        builder = match options.http_version {
+            HttpVersion::Negotiate => builder,
+            HttpVersion::Http1Only => builder.version_negotiation(VersionNegotiation::http11()),
+            HttpVersion::Http2PriorKnowledge => {
+                builder.version_negotiation(VersionNegotiation::http2())
+            }
+        };
+        if let Some(proxy) = &options.proxy {
+            builder = builder.proxy(Some(proxy.parse().map_err(|_| RpcError::InvalidRPC)?))
+        }
+        for (name, value) in &options.headers {
+            builder = builder.default_header(name.as_str(), value.as_str())
+        }
+
+        Ok(IsahcTransport {
+            client: builder.build()?,
+            url: url.parse().map_err(|_| RpcError::InvalidRPC)?,
+        })
+    }
+}
+#[cfg(feature = "rpc-isahc")]
+impl RpcTransport for IsahcTransport {
+    fn send<'a>(&'a self, request: &'a JsonValue) -> TransportFuture<'a> {
+        use isahc::{http::Request, AsyncReadResponseExt};
+
+        Box::pin(async move {
+            let request = Request::post(self.url.clone())
+                .header("content-type", "application/json")
+                .body(json::to_vec(request)?)
+                .map_err(isahc::Error::from)?;
+            let mut response = self.client.send_async(request).await?;
+            Ok(json::from_slice(&response.bytes().await?)?)
+        })
+    }
+}
+
+/// HTTP transport using the browser's `fetch` API, for `wasm32-unknown-unknown`
+#[cfg(feature = "wasm")]
+#[derive(Debug)]
+pub(crate) struct FetchTransport {
+    url: String,
+    headers: Vec<(String, String)>,
+}
+#[cfg(feature = "wasm")]
+impl FetchTransport {
+    /// `url` must be an absolute `http` or `https` URL.
+    /// `fetch` is configured by the browser, so all `options` other than `headers` are ignored.
+    pub(crate) fn new(url: &str, options: &HttpOptions) -> Result<FetchTransport, RpcError> {
+        let absolute = url.starts_with("http://") || url.starts_with("https://");
+        if !absolute || url.contains(char::is_whitespace) {
+            return Err(RpcError::InvalidRPC);
+        }
+        Ok(FetchTransport {
+            url: url.into(),
+            headers: options.headers.clone(),
+        })
+    }
+
+    async fn fetch(
+        url: &str,
+        headers: &[(String, String)],
+        request: &JsonValue,
+    ) -> Result<JsonValue, RpcError> {
+        let mut builder = gloo_net::http::Request::post(url);
+        for (name, value) in headers {
+            builder = builder.header(name, value)
+        }
+        let response = builder.json(request)?.send().await?;
+        Ok(response.json::<JsonValue>().await?)
+    }
+}
+/// JavaScript futures are not `Send`, so the request is spawned on the browser's event loop,
+/// and its result is sent back through a channel
+#[cfg(feature = "wasm")]
+impl RpcTransport for FetchTransport {
+    fn send<'a>(&'a self, request: &'a JsonValue) -> TransportFuture<'a> {
+        let (sender, receiver) = futures::channel::oneshot::channel();
+        let (url, headers, request) = (self.url.clone(), self.headers.clone(), request.clone());
+        wasm_bindgen_futures::spawn_local(async move {
+            let _ = sender.send(FetchTransport::fetch(&url, &headers, &request).await);
+        });
+        Box::pin(async move {
+            receiver.await.unwrap_or_else(|_| {
+                Err(gloo_net::Error::GlooError("request was cancelled".into()).into())
+            })
+        })
+    }
+}
+
+/// Create the default HTTP transport for the enabled features.
+///
+/// If both `rpc` and `rpc-isahc` are enabled, `reqwest` is used.
+/// The `wasm` transport is only used if neither is enabled.
+pub(crate) fn default_transport(
+    url: &str,
+    options: &HttpOptions,
+) -> Result<Arc<dyn RpcTransport>, RpcError> {
+    #[cfg(feature = "rpc")]
+    return Ok(Arc::new(ReqwestTransport::new(url, options)?));
+
+    #[cfg(all(feature = "rpc-isahc", not(feature = "rpc")))]
+    return Ok(Arc::new(IsahcTransport::new(url, options)?));
+
+    #[cfg(all(feature = "wasm", not(any(feature = "rpc", feature = "rpc-isahc"))))]
+    return Ok(Arc::new(FetchTransport::new(url, options)?));
+
+    #[cfg(not(any(feature = "rpc", feature = "rpc-isahc", feature = "wasm")))]
+    {
+        let _ = (url, options);
+        Err(RpcError::NoTransport)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::super::Rpc;
+    #[cfg(any(feature = "rpc", feature = "rpc-isahc"))]
+    use {
+        serde_json::{json, Value as JsonValue},
+        std::io::{BufRead, BufReader, Read, Write},
+        std::net::TcpListener,
+        std::sync::mpsc::{channel, Receiver},
+        std::thread,
+    };
+
+    #[cfg(any(feature = "rpc", feature = "rpc-isahc"))]
+    /// Serve a single HTTP request on localhost, responding with `response`.
+    /// Returns the URL, and a channel which receives the request body.
+    fn serve_once(response: JsonValue) -> (String, Receiver<JsonValue>) {
+        let listener = TcpListener::bind("127.0.0.1:0").unwrap();
+        let url = format!("http://{}", listener.local_addr().unwrap());
+        let (sender, receiver) = channel();
+
+        thread::spawn(move || {
+            let (stream, _) = listener.accept().unwrap();
+            let mut reader = BufReader::new(stream);
+            let mut length = 0;
+            loop {
+                let mut line = String::new();
+                reader.read_line(&mut line).unwrap();
+                if line == "\r\n" {
+                    break;
+                }
+                if let Some((name, value)) = line.split_once(':') {
+                    if name.eq_ignore_ascii_case("content-length") {
+                        length = value.trim().parse().unwrap();
+                    }
+                }
+            }
+            let mut body = vec![0; length];
+            reader.read_exact(&mut body).unwrap();
+            sender.send(serde_json::from_slice(&body).unwrap()).unwrap();
+
+            let body = response.to_string();
+            let mut stream = reader.into_inner();
+            write!(
+                stream,
+                "HTTP/1.1 200 OK\r\ncontent-type: application/json\r\ncontent-length: {}\r\nconnection: close\r\n\r\n{}",
+                body.len(),
+                body
+            )
+            .unwrap();
+        });
+        (url, receiver)
+    }
+
+    #[cfg(any(feature = "rpc", feature = "rpc-isahc"))]
+    fn version_request() -> JsonValue {
+        json!({"action": "version"})
+    }
+
+    #[cfg(any(feature = "rpc", feature = "rpc-isahc"))]
+    fn version_response() -> JsonValue {
+        json!({"node_vendor": "Nano V26.1"})
+    }
+
+    #[cfg(feature = "rpc")]
+    #[tokio::test]
+    async fn reqwest() {
+        let (url, request) = serve_once(version_response());
+        let rpc = Rpc::new(&url, None).unwrap();
+        let response = rpc._raw_request(version_request()).await.unwrap();
+        assert!(response == version_response());
+        assert!(request.recv().unwrap() == version_request());
+    }
+
+    #[cfg(feature = "rpc-isahc")]
+    #[test]
+    fn isahc() {
+        // isahc does not depend on any particular async runtime
+        let (url, request) = serve_once(version_response());
+        let rpc = Rpc::new_isahc(&url, None).unwrap();
+        let response = futures::executor::block_on(rpc._raw_request(version_request())).unwrap();
+        assert!(response == version_response());
+        assert!(request.recv().unwrap() == version_request());
+    }
+
+    #[cfg(feature = "rpc-isahc")]
+    #[test]
+    fn isahc_smol() {
+        let (url, request) = serve_once(version_response());
+        let rpc = Rpc::new_isahc(&url, None).unwrap();
+        let response = smol::block_on(smol::spawn(async move {
+            rpc._raw_request(version_request()).await
+        }))
+        .unwrap();
+        assert!(response == version_response());
+        assert!(request.recv().unwrap() == version_request());
+    }
+
+    /// `serve_once()` acts as the proxy, so the request only succeeds if it is sent through the proxy
+    #[cfg(feature = "rpc")]
+    #[tokio::test]
+    async fn reqwest_proxy() {
+        let (proxy, request) = serve_once(version_response());
+        let rpc = Rpc::new("http://node.invalid:7076", proxy).unwrap();
+        let response = rpc._raw_request(version_request()).await.unwrap();
+        assert!(response == version_response());
+        assert!(request.recv().unwrap() == version_request());
+    }
+
+    #[cfg(feature = "rpc-isahc")]
+    #[test]
+    fn isahc_proxy() {
+        let (proxy, request) = serve_once(version_response());
+        let rpc = Rpc::new_isahc("http://node.invalid:7076", proxy).unwrap();
+        let response = futures::executor::block_on(rpc._raw_request(version_request())).unwrap();
+        assert!(response == version_response());
+        assert!(request.recv().unwrap() == version_request());
+    }
+
+    #[test]
+    fn invalid_url() {
+        assert!(Rpc::new("not a url", None).is_err());
+    }
+}
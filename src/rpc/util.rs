@@ -1,5 +1,5 @@
 use super::{BlockInfo, RpcError};
-use crate::{Account, Block, BlockType};
+use crate::{Account, AccountCache, Block, BlockType};
 use hex::FromHexError;
 
 pub mod parse {
@@ -23,36 +23,41 @@ pub fn to_uppercase_hex(bytes: &[u8]) -> String {
 }
 
 /// Get the keys in a Json map.
-pub fn map_keys_from_json(value: &JsonValue) -> Result<Vec<&String>, RpcError> {
+///
+/// `field` is the name of the value, and is only used in errors (as are the `field`s of the functions below).
+pub fn map_keys_from_json<'a>(
+    value: &'a JsonValue,
+    field: &str,
+) -> Result<Vec<&'a String>, RpcError> {
     Ok(value
         .as_object()
-        .ok_or(RpcError::InvalidJsonDataType)?
+        .ok_or_else(|| RpcError::InvalidJsonDataType(field.into()))?
         .keys()
         .collect())
 }
 
-pub fn usize_from_json(value: &JsonValue) -> Result<usize, RpcError> {
+pub fn usize_from_json(value: &JsonValue, field: &str) -> Result<usize, RpcError> {
     trim_json(&value.to_string())
         .parse::<usize>()
-        .map_err(|_| RpcError::InvalidInteger)
+        .map_err(|_| RpcError::InvalidInteger(field.into()))
 }
 
-pub fn u64_from_json(value: &JsonValue) -> Result<u64, RpcError> {
+pub fn u64_from_json(value: &JsonValue, field: &str) -> Result<u64, RpcError> {
     trim_json(&value.to_string())
         .parse::<u64>()
-        .map_err(|_| RpcError::InvalidInteger)
+        .map_err(|_| RpcError::InvalidInteger(field.into()))
 }
 
-pub fn u128_from_json(value: &JsonValue) -> Result<u128, RpcError> {
+pub fn u128_from_json(value: &JsonValue, field: &str) -> Result<u128, RpcError> {
     trim_json(&value.to_string())
         .parse::<u128>()
-        .map_err(|_| RpcError::InvalidInteger)
+        .map_err(|_| RpcError::InvalidInteger(field.into()))
 }
 
-pub fn bool_from_json(value: &JsonValue) -> Result<bool, RpcError> {
+pub fn bool_from_json(value: &JsonValue, field: &str) -> Result<bool, RpcError> {
     trim_json(&value.to_string())
         .parse::<bool>()
-        .map_err(|_| RpcError::InvalidInteger)
+        .map_err(|_| RpcError::InvalidInteger(field.into()))
 }
 
 pub fn bytes_from_json<const T: usize>(value: &JsonValue) -> Result<[u8; T], RpcError> {
@@ -63,49 +68,151 @@ pub fn bytes_from_json<const T: usize>(value: &JsonValue) -> Result<[u8; T], Rpc
 
 pub fn block_info_from_json(value: &JsonValue, block: Block) -> Result<BlockInfo, RpcError> {
     Ok(BlockInfo {
-        height: usize_from_json(&value["height"])?,
-        timestamp: u64_from_json(&value["local_timestamp"])?,
-        confirmed: bool_from_json(&value["confirmed"])?,
+        height: usize_from_json(&value["height"], "height")?,
+        timestamp: u64_from_json(&value["local_timestamp"], "local_timestamp")?,
+        confirmed: bool_from_json(&value["confirmed"], "confirmed")?,
+        amount: u128_from_json(&value["amount"], "amount")?,
+        successor: Some(bytes_from_json(&value["successor"])?).filter(|hash| hash != &[0; 32]),
         block,
     })
 }
 
-pub fn account_from_json(value: &JsonValue) -> Result<Account, RpcError> {
-    Account::try_from(trim_json(&value.to_string())).map_err(|_| RpcError::InvalidAccount)
+pub fn account_from_json(value: &JsonValue, field: &str) -> Result<Account, RpcError> {
+    account_from_json_cached(value, field, None)
+}
+
+/// Same as `account_from_json`, but consults the given `AccountCache` first
+pub fn account_from_json_cached(
+    value: &JsonValue,
+    field: &str,
+    cache: Option<&AccountCache>,
+) -> Result<Account, RpcError> {
+    let account = trim_json(&value.to_string()).to_string();
+    match cache {
+        Some(cache) => cache.get_or_parse(&account),
+        None => Account::try_from(account),
+    }
+    .map_err(|_| RpcError::InvalidAccount(field.into()))
 }
 
 pub fn block_from_json(block: &JsonValue, block_type: BlockType) -> Result<Block, RpcError> {
+    block_from_json_cached(block, block_type, None)
+}
+
+/// Same as `block_from_json`, but consults the given `AccountCache` when parsing accounts
+pub fn block_from_json_cached(
+    block: &JsonValue,
+    block_type: BlockType,
+    cache: Option<&AccountCache>,
+) -> Result<Block, RpcError> {
+    if let BlockType::Legacy(legacy_type) = &block_type {
+        return legacy_block_from_json(block, legacy_type, cache);
+    }
     Ok(Block {
         block_type,
-        account: account_from_json(&block["account"])?,
+        account: account_from_json_cached(&block["account"], "account", cache)?,
         previous: bytes_from_json(&block["previous"])?,
-        representative: account_from_json(&block["representative"])?,
-        balance: u128_from_json(&block["balance"])?,
+        representative: account_from_json_cached(
+            &block["representative"],
+            "representative",
+            cache,
+        )?,
+        balance: u128_from_json(&block["balance"], "balance")?,
         link: bytes_from_json(&block["link"])?,
         signature: bytes_from_json::<64>(&block["signature"])?
             .try_into()
-            .unwrap(),
+            .or(Err(RpcError::InvalidData))?,
+        work: bytes_from_json(&block["work"])?,
+    })
+}
+
+/// Legacy blocks only have the fields of their type.
+/// Fields which are missing (see `Block`) are set to zero, or to the account with the public key `0`.
+fn legacy_block_from_json(
+    block: &JsonValue,
+    legacy_type: &str,
+    cache: Option<&AccountCache>,
+) -> Result<Block, RpcError> {
+    let zero_account = || Account::from_bytes([0; 32]).or(Err(RpcError::InvalidData));
+    let (account, representative) = match legacy_type {
+        "open" => (
+            account_from_json_cached(&block["account"], "account", cache)?,
+            account_from_json_cached(&block["representative"], "representative", cache)?,
+        ),
+        "change" => (
+            zero_account()?,
+            account_from_json_cached(&block["representative"], "representative", cache)?,
+        ),
+        "send" | "receive" => (zero_account()?, zero_account()?),
+        _ => return Err(RpcError::InvalidJsonDataType("type".into())),
+    };
+    let (previous, link, balance) = match legacy_type {
+        "send" => (
+            bytes_from_json(&block["previous"])?,
+            *account_from_json_cached(&block["destination"], "destination", cache)?.as_bytes(),
+            // the balance of legacy `send` blocks is hex
+            u128::from_be_bytes(bytes_from_json(&block["balance"])?),
+        ),
+        "receive" => (
+            bytes_from_json(&block["previous"])?,
+            bytes_from_json(&block["source"])?,
+            0,
+        ),
+        "open" => ([0; 32], bytes_from_json(&block["source"])?, 0),
+        _ => (bytes_from_json(&block["previous"])?, [0; 32], 0),
+    };
+
+    Ok(Block {
+        block_type: BlockType::Legacy(legacy_type.into()),
+        account,
+        previous,
+        representative,
+        balance,
+        link,
+        signature: bytes_from_json::<64>(&block["signature"])?
+            .try_into()
+            .or(Err(RpcError::InvalidData))?,
         work: bytes_from_json(&block["work"])?,
     })
 }
 
 /// Specific to `account_history`
-pub(crate) fn block_from_history_json(block: &JsonValue) -> Result<Block, RpcError> {
+pub(crate) fn block_from_history_json(
+    block: &JsonValue,
+    cache: Option<&AccountCache>,
+) -> Result<Block, RpcError> {
     let block_type = block["type"].to_string();
     let block_type = trim_json(&block_type);
     let block_type = if block_type == "state" {
         // state blocks
         BlockType::from_subtype_string(trim_json(&block["subtype"].to_string()))
     } else {
-        // legacy blocks (shouldn't be needed)
-        Some(BlockType::Legacy(block_type.to_string()))
+        // legacy blocks
+        let mut block = block.clone();
+        // unlike in the usual JSON format, the balance of `send` blocks is decimal, and the account of `open` blocks is "opened"
+        match block_type {
+            "send" => {
+                let balance = u128_from_json(&block["balance"], "balance")?.to_be_bytes();
+                block["balance"] = to_uppercase_hex(&balance).into();
+            }
+            "open" => block["account"] = block["opened"].clone(),
+            _ => (),
+        }
+        return legacy_block_from_json(&block, block_type, cache);
     };
 
-    block_from_json(block, block_type.ok_or(RpcError::InvalidJsonDataType)?)
+    block_from_json_cached(
+        block,
+        block_type.ok_or_else(|| RpcError::InvalidJsonDataType("subtype".into()))?,
+        cache,
+    )
 }
 
 /// Specific to `block_info` and `blocks_info`
-pub(crate) fn block_from_info_json(block: &JsonValue) -> Result<Block, RpcError> {
+pub(crate) fn block_from_info_json(
+    block: &JsonValue,
+    cache: Option<&AccountCache>,
+) -> Result<Block, RpcError> {
     let contents = &block["contents"];
     let block_type = contents["type"].to_string();
     let block_type = trim_json(&block_type);
@@ -117,13 +224,17 @@ pub(crate) fn block_from_info_json(block: &JsonValue) -> Result<Block, RpcError>
         Some(BlockType::Legacy(block_type.to_string()))
     };
 
-    block_from_json(contents, block_type.ok_or(RpcError::InvalidJsonDataType)?)
+    block_from_json_cached(
+        contents,
+        block_type.ok_or_else(|| RpcError::InvalidJsonDataType("subtype".into()))?,
+        cache,
+    )
 }
 
 /// **Does not handle "subtype" field**
 pub fn block_to_json(block: &Block) -> Map<String, JsonValue> {
     let block_type: &str = match &block.block_type {
-        BlockType::Legacy(block_type) => block_type,
+        BlockType::Legacy(block_type) => return legacy_block_to_json(block, block_type),
         _ => "state",
     };
 
@@ -142,6 +253,44 @@ pub fn block_to_json(block: &Block) -> Map<String, JsonValue> {
     json_block
 }
 
+/// Legacy blocks only have the fields of their type, and unknown legacy types have none
+fn legacy_block_to_json(block: &Block, legacy_type: &str) -> Map<String, JsonValue> {
+    let mut json_block = Map::new();
+    json_block.insert("type".into(), legacy_type.into());
+    match legacy_type {
+        "send" => {
+            json_block.insert("previous".into(), to_uppercase_hex(&block.previous).into());
+            if let Ok(destination) = Account::from_bytes(block.link) {
+                json_block.insert("destination".into(), destination.into());
+            }
+            json_block.insert(
+                "balance".into(),
+                to_uppercase_hex(&block.balance.to_be_bytes()).into(),
+            );
+        }
+        "receive" => {
+            json_block.insert("previous".into(), to_uppercase_hex(&block.previous).into());
+            json_block.insert("source".into(), to_uppercase_hex(&block.link).into());
+        }
+        "open" => {
+            json_block.insert("source".into(), to_uppercase_hex(&block.link).into());
+            json_block.insert("representative".into(), block.representative.clone().into());
+            json_block.insert("account".into(), block.account.clone().into());
+        }
+        "change" => {
+            json_block.insert("previous".into(), to_uppercase_hex(&block.previous).into());
+            json_block.insert("representative".into(), block.representative.clone().into());
+        }
+        _ => (),
+    }
+    json_block.insert("work".into(), hex::encode(block.work).into());
+    json_block.insert(
+        "signature".into(),
+        to_uppercase_hex(&block.signature.to_bytes()).into(),
+    );
+    json_block
+}
+
 /// Sanity check to ensure that no overflow occurs
 pub fn balances_sanity_check(blocks: &[Block]) -> Result<(), RpcError> {
     let mut total: u128 = 0;
@@ -154,3 +303,48 @@ pub fn balances_sanity_check(blocks: &[Block]) -> Result<(), RpcError> {
     }
     Ok(())
 }
+
+/// Choose representatives for a wallet to suggest to its users, sorted by weight (highest first).
+///
+/// `online` should be the result of `Rpc::representatives_online_detailed()`, so that offline representatives are excluded.
+/// `online_weight` is the total online voting weight (such as `ConfirmationQuorum::online_stake_total`).
+/// Representatives with no weight, or with more than `max_share` (such as `0.05` for 5%) of `online_weight`, are excluded,
+/// so that users are not encouraged to centralize the network further.
+pub fn recommend_representatives(
+    online: &[(Account, u128)],
+    online_weight: u128,
+    max_share: f64,
+) -> Vec<(Account, u128)> {
+    let max_weight = online_weight as f64 * max_share;
+    let mut candidates: Vec<(Account, u128)> = online
+        .iter()
+        .filter(|(_, weight)| *weight > 0 && *weight as f64 <= max_weight)
+        .cloned()
+        .collect();
+    candidates.sort_by_key(|(_, weight)| std::cmp::Reverse(*weight));
+    candidates
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::{Key, SecretBytes};
+
+    #[test]
+    fn recommend_representatives() {
+        let accounts: Vec<_> = (0..4)
+            .map(|i| Key::from_seed(&SecretBytes::from([0; 32]), i).to_account())
+            .collect();
+        let online = [
+            (accounts[0].clone(), 10),
+            (accounts[1].clone(), 0),
+            (accounts[2].clone(), 60),
+            (accounts[3].clone(), 30),
+        ];
+
+        let recommended = super::recommend_representatives(&online, 100, 0.5);
+        assert!(recommended == [online[3].clone(), online[0].clone()]);
+        let recommended = super::recommend_representatives(&online, 100, 1.0);
+        assert!(recommended == [online[2].clone(), online[3].clone(), online[0].clone()]);
+        assert!(super::recommend_representatives(&online, 100, 0.0).is_empty());
+    }
+}
@@ -0,0 +1,363 @@
+use super::reconnect::blocks_since;
+use super::{ConfirmationFilter, Event, GapRecovery, ReconnectOptions, Topic, WebSocket};
+use crate::rpc::{Receivable, Rpc, RpcError};
+use crate::{Account, Block, BlockType};
+use futures::stream::{self, Stream};
+use futures_timer::Delay;
+use std::collections::{HashSet, VecDeque};
+use std::pin::Pin;
+use std::task::{Context, Poll};
+use std::time::Duration;
+
+/// An update to one of the accounts watched by a `ConfirmationStream`
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum AccountUpdate {
+    /// A new block on a watched account
+    Block(Box<Block>),
+    /// A new receivable transaction for a watched account
+    Receivable(Box<Receivable>),
+}
+
+/// Whether a `ConfirmationStream` is using the WebSocket, or polling the RPC
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum StreamMode {
+    WebSocket,
+    Polling,
+}
+
+/// Options for `ConfirmationStream`
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct StreamOptions {
+    /// How often to poll the RPC, if the WebSocket is unavailable
+    pub poll_interval: Duration,
+    /// The maximum number of blocks or receivable transactions to fetch for each account, per poll
+    pub count: usize,
+    /// How to reconnect, if the WebSocket connection is lost
+    pub reconnect: ReconnectOptions,
+}
+impl Default for StreamOptions {
+    fn default() -> Self {
+        StreamOptions {
+            poll_interval: Duration::from_secs(5),
+            count: 100,
+            reconnect: ReconnectOptions::default(),
+        }
+    }
+}
+
+/// Tracks the state of the watched accounts when polling
+struct Poller {
+    rpc: Rpc,
+    accounts: Vec<Account>,
+    frontiers: Vec<Option<[u8; 32]>>,
+    receivable: HashSet<[u8; 32]>,
+    options: StreamOptions,
+}
+impl Poller {
+    async fn new(
+        rpc: Rpc,
+        accounts: Vec<Account>,
+        options: StreamOptions,
+    ) -> Result<Poller, RpcError> {
+        let mut poller = Poller {
+            frontiers: vec![None; accounts.len()],
+            rpc,
+            accounts,
+            receivable: HashSet::new(),
+            options,
+        };
+        // only report changes from this point onwards
+        poller.poll().await?;
+        Ok(poller)
+    }
+
+    async fn poll(&mut self) -> Result<Vec<AccountUpdate>, RpcError> {
+        let mut updates = vec![];
+
+        let frontiers = self.rpc.accounts_frontiers(&self.accounts).await?;
+        if frontiers.len() != self.accounts.len() {
+            return Err(RpcError::InvalidData);
+        }
+        for (i, account) in self.accounts.iter().enumerate() {
+            if frontiers[i] == self.frontiers[i] || frontiers[i].is_none() {
+                continue;
+            }
+            let history = self
+                .rpc
+                .account_history(account, self.options.count, None, None)
+                .await?;
+            let new_blocks = blocks_since(history, self.frontiers[i]);
+            updates.extend(
+                new_blocks
+                    .into_iter()
+                    .rev()
+                    .map(|block| AccountUpdate::Block(Box::new(block))),
+            );
+        }
+
+        let receivable = self
+            .rpc
+            .accounts_receivable(&self.accounts, self.options.count, 0)
+            .await?;
+        let receivable: Vec<Receivable> = receivable.into_iter().flatten().collect();
+        let hashes = receivable.iter().map(|r| r.block_hash).collect();
+        updates.extend(
+            receivable
+                .into_iter()
+                .filter(|r| !self.receivable.contains(&r.block_hash))
+                .map(|receivable| AccountUpdate::Receivable(Box::new(receivable))),
+        );
+
+        self.frontiers = frontiers;
+        self.receivable = hashes;
+        Ok(updates)
+    }
+}
+
+/// Convert a WebSocket event into updates for the watched accounts
+fn updates_from_event(event: Event, accounts: &[Account]) -> Vec<AccountUpdate> {
+    let mut updates = vec![];
+    match event {
+        Event::Confirmation(confirmation) => {
+            let block = confirmation.block;
+            if block.block_type == BlockType::Send {
+                let recipient = accounts
+                    .iter()
+                    .find(|account| account.as_bytes() == &block.link);
+                if let Some(recipient) = recipient {
+                    updates.push(AccountUpdate::Receivable(Box::new(Receivable {
+                        recipient: recipient.clone(),
+                        block_hash: confirmation.hash,
+                        amount: confirmation.amount,
+                        sender: Some(block.account.clone()),
+                        min_version: None,
+                    })))
+                }
+            }
+            if accounts.contains(&block.account) {
+                updates.push(AccountUpdate::Block(Box::new(block)))
+            }
+        }
+        Event::Reconnected(reconnection) => {
+            if let Some(recovered) = reconnection.recovered {
+                for (_, blocks) in recovered.history {
+                    updates.extend(
+                        blocks
+                            .into_iter()
+                            .rev()
+                            .map(|block| AccountUpdate::Block(Box::new(block))),
+                    )
+                }
+                updates.extend(
+                    recovered
+                        .receivable
+                        .into_iter()
+                        .map(|receivable| AccountUpdate::Receivable(Box::new(receivable))),
+                )
+            }
+        }
+        _ => (),
+    }
+    updates
+}
+
+enum Source {
+    WebSocket(Box<WebSocket>, Vec<Account>),
+    Polling(Box<Poller>),
+}
+impl Source {
+    async fn next_updates(&mut self) -> Option<Result<Vec<AccountUpdate>, RpcError>> {
+        match self {
+            Source::WebSocket(websocket, accounts) => match websocket.next_event().await {
+                Ok(Some(event)) => Some(Ok(updates_from_event(event, accounts))),
+                Ok(None) => None,
+                Err(err) => Some(Err(err)),
+            },
+            Source::Polling(poller) => {
+                Delay::new(poller.options.poll_interval).await;
+                Some(poller.poll().await)
+            }
+        }
+    }
+}
+
+type UpdateStream = Pin<Box<dyn Stream<Item = Result<AccountUpdate, RpcError>> + Send>>;
+
+/// A stream of updates to a set of accounts.
+///
+/// Uses the node's WebSocket if possible, and otherwise falls back to periodically polling the RPC.
+pub struct ConfirmationStream {
+    mode: StreamMode,
+    inner: UpdateStream,
+}
+impl ConfirmationStream {
+    /// Watch `accounts` for new blocks and receivable transactions.
+    ///
+    /// If `websocket_url` is `None`, or a WebSocket connection cannot be established, the RPC is polled instead.
+    pub async fn new(
+        rpc: Rpc,
+        websocket_url: Option<&str>,
+        accounts: Vec<Account>,
+        options: StreamOptions,
+    ) -> Result<ConfirmationStream, RpcError> {
+        let websocket = match websocket_url {
+            Some(url) => Self::connect(url, &rpc, &accounts, options).await.ok(),
+            None => None,
+        };
+        let source = match websocket {
+            Some(websocket) => Source::WebSocket(Box::new(websocket), accounts),
+            None => Source::Polling(Box::new(Poller::new(rpc, accounts, options).await?)),
+        };
+        let mode = match source {
+            Source::WebSocket(..) => StreamMode::WebSocket,
+            Source::Polling(_) => StreamMode::Polling,
+        };
+
+        let inner = stream::unfold(
+            (source, VecDeque::new(), false),
+            |(mut source, mut queue, mut done)| async move {
+                loop {
+                    if let Some(update) = queue.pop_front() {
+                        return Some((Ok(update), (source, queue, done)));
+                    }
+                    if done {
+                        return None;
+                    }
+                    match source.next_updates().await {
+                        Some(Ok(updates)) => queue.extend(updates),
+                        Some(Err(err)) => return Some((Err(err), (source, queue, done))),
+                        None => done = true,
+                    }
+                }
+            },
+        );
+        Ok(ConfirmationStream {
+            mode,
+            inner: Box::pin(inner),
+        })
+    }
+
+    async fn connect(
+        url: &str,
+        rpc: &Rpc,
+        accounts: &[Account],
+        options: StreamOptions,
+    ) -> Result<WebSocket, RpcError> {
+        let mut websocket = WebSocket::connect(url).await?;
+        websocket.set_reconnect(Some(options.reconnect));
+        let mut recovery = GapRecovery::new(rpc.clone(), accounts.to_vec()).await?;
+        recovery.history_count = options.count;
+        recovery.receivable_count = options.count;
+        websocket.set_gap_recovery(Some(recovery));
+        websocket
+            .subscribe(Topic::Confirmation(ConfirmationFilter {
+                accounts: accounts.to_vec(),
+            }))
+            .await?;
+        Ok(websocket)
+    }
+
+    /// Whether the WebSocket is being used, or the RPC is being polled
+    pub fn mode(&self) -> StreamMode {
+        self.mode
+    }
+}
+impl Stream for ConfirmationStream {
+    type Item = Result<AccountUpdate, RpcError>;
+
+    fn poll_next(mut self: Pin<&mut Self>, cx: &mut Context<'_>) -> Poll<Option<Self::Item>> {
+        self.inner.as_mut().poll_next(cx)
+    }
+}
+impl std::fmt::Debug for ConfirmationStream {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.debug_struct("ConfirmationStream")
+            .field("mode", &self.mode)
+            .finish_non_exhaustive()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::constants::get_genesis_account;
+    use crate::rpc::{encode, websocket::Confirmation, Fixture};
+    use crate::{Key, SecretBytes, Signature};
+    use futures::StreamExt;
+    use serde_json::json;
+
+    const HASH: &str = "791AF413173EEE674A6FCF633B5DFC0F3C33F397F0DA08E987D9E0741D40D81A";
+
+    #[tokio::test]
+    async fn polling() {
+        let account = Key::from_seed(&SecretBytes::from([0; 32]), 0).to_account();
+        let accounts = vec![account.clone()];
+        let options = StreamOptions {
+            poll_interval: Duration::ZERO,
+            ..Default::default()
+        };
+
+        let fixture = Fixture::new();
+        let frontiers = encode::accounts_frontiers(&accounts);
+        let receivable = encode::accounts_receivable(&accounts, options.count, 0);
+        let not_found = json!({"errors": {account.to_string(): "Account not found"}});
+        fixture.record(frontiers.clone(), not_found.clone());
+        fixture.record(receivable.clone(), json!({"blocks": ""}));
+        fixture.record(frontiers, not_found);
+        fixture.record(
+            receivable,
+            json!({"blocks": {account.to_string(): {HASH: "1000"}}}),
+        );
+
+        let rpc = Rpc::from_fixture(fixture);
+        let mut stream = ConfirmationStream::new(rpc, None, accounts, options)
+            .await
+            .unwrap();
+        assert!(stream.mode() == StreamMode::Polling);
+
+        let Some(Ok(AccountUpdate::Receivable(receivable))) = stream.next().await else {
+            panic!("expected receivable transaction")
+        };
+        assert!(receivable.recipient == account);
+        assert!(receivable.amount == 1000);
+        // the fixture has run out of responses
+        assert!(matches!(stream.next().await, Some(Err(_))));
+    }
+
+    #[test]
+    fn websocket_updates() {
+        let recipient = Key::from_seed(&SecretBytes::from([0; 32]), 0).to_account();
+        let block = Block {
+            block_type: BlockType::Send,
+            account: get_genesis_account(),
+            previous: [1; 32],
+            representative: get_genesis_account(),
+            balance: 0,
+            link: *recipient.as_bytes(),
+            signature: Signature::default(),
+            work: [0; 8],
+        };
+        let event = Event::Confirmation(Box::new(Confirmation {
+            account: get_genesis_account(),
+            amount: 1000,
+            hash: block.hash(),
+            confirmation_type: "active_quorum".into(),
+            block: block.clone(),
+        }));
+
+        let updates = updates_from_event(event.clone(), std::slice::from_ref(&recipient));
+        assert!(
+            updates
+                == vec![AccountUpdate::Receivable(Box::new(Receivable {
+                    recipient,
+                    block_hash: block.hash(),
+                    amount: 1000,
+                    sender: Some(block.account.clone()),
+                    min_version: None
+                }))]
+        );
+
+        let updates = updates_from_event(event, &[get_genesis_account()]);
+        assert!(updates == vec![AccountUpdate::Block(Box::new(block))]);
+    }
+}
@@ -0,0 +1,514 @@
+use super::super::util::{
+    account_from_json_cached, block_from_history_json, bytes_from_json, trim_json, u128_from_json,
+    u64_from_json,
+};
+use super::super::RpcError;
+use super::Reconnection;
+use crate::{Account, AccountCache, Block, Signature};
+use json::{Map, Value as JsonValue};
+use serde_json as json;
+
+/// The kind of a WebSocket `Topic`
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum TopicKind {
+    Confirmation,
+    Vote,
+    Telemetry,
+    StartedElection,
+    NewUnconfirmedBlock,
+}
+impl TopicKind {
+    /// The name of this topic, as used by the node
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            TopicKind::Confirmation => "confirmation",
+            TopicKind::Vote => "vote",
+            TopicKind::Telemetry => "telemetry",
+            TopicKind::StartedElection => "started_election",
+            TopicKind::NewUnconfirmedBlock => "new_unconfirmed_block",
+        }
+    }
+
+    fn from_str(topic: &str) -> Option<TopicKind> {
+        match topic {
+            "confirmation" => Some(TopicKind::Confirmation),
+            "vote" => Some(TopicKind::Vote),
+            "telemetry" => Some(TopicKind::Telemetry),
+            "started_election" => Some(TopicKind::StartedElection),
+            "new_unconfirmed_block" => Some(TopicKind::NewUnconfirmedBlock),
+            _ => None,
+        }
+    }
+}
+
+/// Options for the `confirmation` topic
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct ConfirmationFilter {
+    /// Only receive confirmations for blocks involving these accounts (all accounts if empty)
+    pub accounts: Vec<Account>,
+}
+
+/// Options for the `vote` topic
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct VoteFilter {
+    /// Only receive votes from these representatives (all representatives if empty)
+    pub representatives: Vec<Account>,
+    /// Also receive votes which have already been seen
+    pub include_replays: bool,
+    /// Also receive votes which are not (yet) known to be valid
+    pub include_indeterminate: bool,
+}
+
+/// A WebSocket subscription
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum Topic {
+    Confirmation(ConfirmationFilter),
+    Vote(VoteFilter),
+    Telemetry,
+    StartedElection,
+    NewUnconfirmedBlock,
+}
+impl Topic {
+    pub fn kind(&self) -> TopicKind {
+        match self {
+            Topic::Confirmation(_) => TopicKind::Confirmation,
+            Topic::Vote(_) => TopicKind::Vote,
+            Topic::Telemetry => TopicKind::Telemetry,
+            Topic::StartedElection => TopicKind::StartedElection,
+            Topic::NewUnconfirmedBlock => TopicKind::NewUnconfirmedBlock,
+        }
+    }
+
+    fn options(&self) -> Option<JsonValue> {
+        let accounts = |accounts: &[Account]| -> JsonValue {
+            accounts.iter().map(|account| account.to_string()).collect()
+        };
+
+        let mut options = Map::new();
+        match self {
+            Topic::Confirmation(filter) if !filter.accounts.is_empty() => {
+                options.insert("accounts".into(), accounts(&filter.accounts));
+            }
+            Topic::Vote(filter) => {
+                if !filter.representatives.is_empty() {
+                    options.insert("representatives".into(), accounts(&filter.representatives));
+                }
+                if filter.include_replays {
+                    options.insert("include_replays".into(), "true".into());
+                }
+                if filter.include_indeterminate {
+                    options.insert("include_indeterminate".into(), "true".into());
+                }
+            }
+            _ => (),
+        }
+        match options.is_empty() {
+            true => None,
+            false => Some(JsonValue::Object(options)),
+        }
+    }
+
+    /// The JSON message which subscribes to this topic
+    pub fn subscribe_json(&self) -> JsonValue {
+        let mut message = Map::new();
+        message.insert("action".into(), "subscribe".into());
+        message.insert("topic".into(), self.kind().as_str().into());
+        if let Some(options) = self.options() {
+            message.insert("options".into(), options);
+        }
+        JsonValue::Object(message)
+    }
+}
+impl TopicKind {
+    /// The JSON message which unsubscribes from this topic
+    pub fn unsubscribe_json(&self) -> JsonValue {
+        let mut message = Map::new();
+        message.insert("action".into(), "unsubscribe".into());
+        message.insert("topic".into(), self.as_str().into());
+        JsonValue::Object(message)
+    }
+}
+
+/// A confirmed block
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Confirmation {
+    pub account: Account,
+    pub amount: u128,
+    pub hash: [u8; 32],
+    /// How the block was confirmed, for example `active_quorum`
+    pub confirmation_type: String,
+    pub block: Block,
+}
+
+/// A vote from a representative
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Vote {
+    pub representative: Account,
+    pub signature: Signature,
+    pub timestamp: u64,
+    /// The hashes of the blocks being voted for
+    pub blocks: Vec<[u8; 32]>,
+    /// Whether this vote is new, a replay, or indeterminate
+    pub vote_type: String,
+}
+
+/// Telemetry data from a peer
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Telemetry {
+    pub block_count: u64,
+    pub cemented_count: u64,
+    pub unchecked_count: u64,
+    pub account_count: u64,
+    pub peer_count: u64,
+    pub protocol_version: u64,
+    pub uptime: u64,
+    pub major_version: u64,
+    pub minor_version: u64,
+    pub patch_version: u64,
+    pub timestamp: u64,
+    pub node_id: String,
+    pub address: String,
+    pub port: u64,
+}
+
+/// A message received from a WebSocket subscription
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum Event {
+    Confirmation(Box<Confirmation>),
+    Vote(Box<Vote>),
+    Telemetry(Box<Telemetry>),
+    /// The hash of the block for which an election was started
+    StartedElection([u8; 32]),
+    NewUnconfirmedBlock(Box<Block>),
+    /// The connection was lost, and has been re-established
+    Reconnected(Box<Reconnection>),
+}
+impl Event {
+    /// The topic of this event (`None` for `Reconnected`)
+    pub fn kind(&self) -> Option<TopicKind> {
+        match self {
+            Event::Confirmation(_) => Some(TopicKind::Confirmation),
+            Event::Vote(_) => Some(TopicKind::Vote),
+            Event::Telemetry(_) => Some(TopicKind::Telemetry),
+            Event::StartedElection(_) => Some(TopicKind::StartedElection),
+            Event::NewUnconfirmedBlock(_) => Some(TopicKind::NewUnconfirmedBlock),
+            Event::Reconnected(_) => None,
+        }
+    }
+}
+
+fn string_from_json(value: &JsonValue, field: &str) -> Result<String, RpcError> {
+    Ok(value
+        .as_str()
+        .ok_or_else(|| RpcError::InvalidJsonDataType(field.into()))?
+        .to_string())
+}
+
+/// Parse a block, rejecting it if it is not correctly signed
+fn signed_block_from_json(
+    value: &JsonValue,
+    cache: Option<&AccountCache>,
+) -> Result<Block, RpcError> {
+    let block = block_from_history_json(value, cache)?;
+    match block.has_valid_signature() {
+        true => Ok(block),
+        false => Err(RpcError::InvalidData),
+    }
+}
+
+fn confirmation_from_json(
+    message: &JsonValue,
+    cache: Option<&AccountCache>,
+) -> Result<Confirmation, RpcError> {
+    let hash = bytes_from_json(&message["hash"])?;
+    let block = signed_block_from_json(&message["block"], cache)?;
+    let account = account_from_json_cached(&message["account"], "account", cache)?;
+    if block.hash() != hash || block.account != account {
+        return Err(RpcError::InvalidData);
+    }
+    Ok(Confirmation {
+        account,
+        amount: u128_from_json(&message["amount"], "amount")?,
+        hash,
+        confirmation_type: string_from_json(&message["confirmation_type"], "confirmation_type")?,
+        block,
+    })
+}
+
+fn vote_from_json(message: &JsonValue, cache: Option<&AccountCache>) -> Result<Vote, RpcError> {
+    let blocks = message["blocks"]
+        .as_array()
+        .ok_or_else(|| RpcError::InvalidJsonDataType("blocks".into()))?
+        .iter()
+        .map(bytes_from_json)
+        .collect::<Result<Vec<[u8; 32]>, RpcError>>()?;
+    Ok(Vote {
+        representative: account_from_json_cached(&message["account"], "account", cache)?,
+        signature: bytes_from_json::<64>(&message["signature"])?
+            .try_into()
+            .or(Err(RpcError::InvalidData))?,
+        timestamp: u64_from_json(&message["timestamp"], "timestamp")?,
+        blocks,
+        vote_type: string_from_json(&message["type"], "type")?,
+    })
+}
+
+fn telemetry_from_json(message: &JsonValue) -> Result<Telemetry, RpcError> {
+    Ok(Telemetry {
+        block_count: u64_from_json(&message["block_count"], "block_count")?,
+        cemented_count: u64_from_json(&message["cemented_count"], "cemented_count")?,
+        unchecked_count: u64_from_json(&message["unchecked_count"], "unchecked_count")?,
+        account_count: u64_from_json(&message["account_count"], "account_count")?,
+        peer_count: u64_from_json(&message["peer_count"], "peer_count")?,
+        protocol_version: u64_from_json(&message["protocol_version"], "protocol_version")?,
+        uptime: u64_from_json(&message["uptime"], "uptime")?,
+        major_version: u64_from_json(&message["major_version"], "major_version")?,
+        minor_version: u64_from_json(&message["minor_version"], "minor_version")?,
+        patch_version: u64_from_json(&message["patch_version"], "patch_version")?,
+        timestamp: u64_from_json(&message["timestamp"], "timestamp")?,
+        node_id: string_from_json(&message["node_id"], "node_id")?,
+        address: string_from_json(&message["address"], "address")?,
+        port: u64_from_json(&message["port"], "port")?,
+    })
+}
+
+/// Parse a message received from the node.
+///
+/// Returns `None` for messages which are not events, such as acknowledgements, or events from unknown topics.
+pub fn event_from_json(
+    raw_json: &JsonValue,
+    cache: Option<&AccountCache>,
+) -> Result<Option<Event>, RpcError> {
+    let topic = trim_json(&raw_json["topic"].to_string()).to_string();
+    let message = &raw_json["message"];
+    let event = match TopicKind::from_str(&topic) {
+        Some(TopicKind::Confirmation) => {
+            Event::Confirmation(Box::new(confirmation_from_json(message, cache)?))
+        }
+        Some(TopicKind::Vote) => Event::Vote(Box::new(vote_from_json(message, cache)?)),
+        Some(TopicKind::Telemetry) => Event::Telemetry(Box::new(telemetry_from_json(message)?)),
+        Some(TopicKind::StartedElection) => {
+            Event::StartedElection(bytes_from_json(&message["hash"])?)
+        }
+        Some(TopicKind::NewUnconfirmedBlock) => {
+            Event::NewUnconfirmedBlock(Box::new(signed_block_from_json(message, cache)?))
+        }
+        None => return Ok(None),
+    };
+    Ok(Some(event))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::rpc::util::block_to_json;
+    use crate::{constants::get_genesis_account, BlockType, Key, SecretBytes};
+    use serde_json::json;
+
+    const HASH: &str = "791AF413173EEE674A6FCF633B5DFC0F3C33F397F0DA08E987D9E0741D40D81A";
+    const SIGNATURE: &str = "3D45D616545D5CCE9766E3F6268C9AE88C0DCA61A6B034AE4804D46C9F75EA94BCA7E7AEBA46EA98117120FB491FE2F7D0664675EF36D8BFD9818DAE62209F06";
+
+    fn signed_block() -> Block {
+        let key = Key::from_seed(&SecretBytes::from([0; 32]), 0);
+        let mut block = Block {
+            block_type: BlockType::Send,
+            account: key.to_account(),
+            previous: [1; 32],
+            representative: get_genesis_account(),
+            balance: 1000,
+            link: [2; 32],
+            signature: Signature::default(),
+            work: [0; 8],
+        };
+        block.sign(&key);
+        block
+    }
+
+    fn block_json(block: &Block) -> JsonValue {
+        let mut json_block = block_to_json(block);
+        json_block.insert("subtype".into(), "send".into());
+        json_block.into()
+    }
+
+    #[test]
+    fn subscribe_json() {
+        let topic = Topic::Confirmation(ConfirmationFilter {
+            accounts: vec![get_genesis_account()],
+        });
+        assert!(
+            topic.subscribe_json()
+                == json!({
+                    "action": "subscribe",
+                    "topic": "confirmation",
+                    "options": {"accounts": [get_genesis_account().to_string()]}
+                })
+        );
+
+        let topic = Topic::Vote(VoteFilter {
+            include_replays: true,
+            ..Default::default()
+        });
+        assert!(
+            topic.subscribe_json()
+                == json!({
+                    "action": "subscribe",
+                    "topic": "vote",
+                    "options": {"include_replays": "true"}
+                })
+        );
+        assert!(Topic::Telemetry.subscribe_json()["options"].is_null());
+        assert!(
+            TopicKind::StartedElection.unsubscribe_json()
+                == json!({"action": "unsubscribe", "topic": "started_election"})
+        );
+    }
+
+    #[test]
+    fn confirmation() {
+        let block = signed_block();
+        let message = |hash: [u8; 32], block_json: JsonValue| {
+            json!({
+                "topic": "confirmation",
+                "time": "1587109856687",
+                "message": {
+                    "account": block.account.to_string(),
+                    "amount": "1000",
+                    "hash": hex::encode_upper(hash),
+                    "confirmation_type": "active_quorum",
+                    "block": block_json
+                }
+            })
+        };
+        let event = event_from_json(&message(block.hash(), block_json(&block)), None)
+            .unwrap()
+            .unwrap();
+
+        let Event::Confirmation(confirmation) = event else {
+            panic!("wrong event type")
+        };
+        assert!(confirmation.amount == 1000);
+        assert!(confirmation.confirmation_type == "active_quorum");
+        assert!(confirmation.block == block);
+
+        // blocks which do not match their hash or account, or are not correctly signed, are rejected
+        let wrong_hash = message([3; 32], block_json(&block));
+        assert!(matches!(
+            event_from_json(&wrong_hash, None),
+            Err(RpcError::InvalidData)
+        ));
+        let mut wrong_account = message(block.hash(), block_json(&block));
+        wrong_account["message"]["account"] = get_genesis_account().to_string().into();
+        assert!(matches!(
+            event_from_json(&wrong_account, None),
+            Err(RpcError::InvalidData)
+        ));
+        let mut forged = block.clone();
+        forged.balance = 0;
+        let forged = message(forged.hash(), block_json(&forged));
+        assert!(matches!(
+            event_from_json(&forged, None),
+            Err(RpcError::InvalidData)
+        ));
+    }
+
+    #[test]
+    fn vote() {
+        let event = event_from_json(
+            &json!({
+                "topic": "vote",
+                "time": "1587109856687",
+                "message": {
+                    "account": get_genesis_account().to_string(),
+                    "signature": SIGNATURE,
+                    "sequence": "18446744073709551615",
+                    "timestamp": "18446744073709551615",
+                    "duration": "15",
+                    "blocks": [HASH, HASH],
+                    "type": "vote"
+                }
+            }),
+            None,
+        )
+        .unwrap()
+        .unwrap();
+
+        let Event::Vote(vote) = event else {
+            panic!("wrong event type")
+        };
+        assert!(vote.representative == get_genesis_account());
+        assert!(vote.blocks.len() == 2);
+        assert!(vote.timestamp == u64::MAX);
+        assert!(vote.vote_type == "vote");
+    }
+
+    #[test]
+    fn telemetry() {
+        let event = event_from_json(
+            &json!({
+                "topic": "telemetry",
+                "time": "1594654710305",
+                "message": {
+                    "block_count": "8662",
+                    "cemented_count": "1",
+                    "unchecked_count": "0",
+                    "account_count": "1",
+                    "bandwidth_cap": "10485760",
+                    "peer_count": "1",
+                    "protocol_version": "18",
+                    "uptime": "133",
+                    "genesis_block": HASH,
+                    "major_version": "25",
+                    "minor_version": "0",
+                    "patch_version": "0",
+                    "pre_release_version": "0",
+                    "maker": "0",
+                    "timestamp": "1594654710521",
+                    "active_difficulty": "ffffffc000000000",
+                    "node_id": "node_1cmi8difuruopgzpnb4ybrnnj5rproxwuwe5mad7ucbsekakiwn37qqg1zo5",
+                    "signature": SIGNATURE,
+                    "address": "::ffff:192.168.0.1",
+                    "port": "7075"
+                }
+            }),
+            None,
+        )
+        .unwrap()
+        .unwrap();
+
+        let Event::Telemetry(telemetry) = event else {
+            panic!("wrong event type")
+        };
+        assert!(telemetry.block_count == 8662);
+        assert!(telemetry.major_version == 25);
+        assert!(telemetry.port == 7075);
+    }
+
+    #[test]
+    fn other_topics() {
+        let event = event_from_json(
+            &json!({"topic": "started_election", "message": {"hash": HASH}}),
+            None,
+        )
+        .unwrap()
+        .unwrap();
+        assert!(event.kind() == Some(TopicKind::StartedElection));
+
+        let mut block = signed_block();
+        let event = event_from_json(
+            &json!({"topic": "new_unconfirmed_block", "message": block_json(&block)}),
+            None,
+        )
+        .unwrap()
+        .unwrap();
+        assert!(event.kind() == Some(TopicKind::NewUnconfirmedBlock));
+        block.balance = 0;
+        let forged = json!({"topic": "new_unconfirmed_block", "message": block_json(&block)});
+        assert!(matches!(
+            event_from_json(&forged, None),
+            Err(RpcError::InvalidData)
+        ));
+
+        let ack = json!({"ack": "subscribe", "time": "1587109856687"});
+        assert!(event_from_json(&ack, None).unwrap().is_none());
+    }
+}
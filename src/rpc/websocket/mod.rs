@@ -0,0 +1,328 @@
+//! A client for the node's [WebSocket interface](https://docs.nano.org/integration-guides/websockets/).
+//!
+//! All topics are multiplexed over a single connection. Requires a `tokio` runtime.
+
+mod confirmation;
+mod event;
+mod reconnect;
+
+use super::RpcError;
+use crate::AccountCache;
+use futures::stream::{self, BoxStream};
+use futures::{SinkExt, StreamExt};
+use futures_timer::Delay;
+use json::Value as JsonValue;
+use serde_json as json;
+use std::time::Instant;
+use tokio::net::TcpStream;
+use tokio_tungstenite::{tungstenite::Message, MaybeTlsStream, WebSocketStream};
+
+pub use confirmation::{AccountUpdate, ConfirmationStream, StreamMode, StreamOptions};
+pub use event::{
+    event_from_json, Confirmation, ConfirmationFilter, Event, Telemetry, Topic, TopicKind, Vote,
+    VoteFilter,
+};
+pub use reconnect::{GapRecovery, ReconnectOptions, Reconnection, RecoveredGap};
+
+type Stream = WebSocketStream<MaybeTlsStream<TcpStream>>;
+
+/// A WebSocket connection to a node
+#[derive(Debug)]
+pub struct WebSocket {
+    stream: Stream,
+    url: String,
+    topics: Vec<Topic>,
+    account_cache: Option<AccountCache>,
+    reconnect: Option<ReconnectOptions>,
+    gap_recovery: Option<GapRecovery>,
+}
+impl WebSocket {
+    pub async fn connect(url: &str) -> Result<WebSocket, RpcError> {
+        let (stream, _) = tokio_tungstenite::connect_async(url).await?;
+        Ok(WebSocket {
+            stream,
+            url: url.into(),
+            topics: vec![],
+            account_cache: None,
+            reconnect: None,
+            gap_recovery: None,
+        })
+    }
+
+    /// Get the URL of this WebSocket
+    pub fn get_url(&self) -> &str {
+        &self.url
+    }
+
+    /// Get the `AccountCache` used when parsing events, if set
+    pub fn get_account_cache(&self) -> Option<&AccountCache> {
+        self.account_cache.as_ref()
+    }
+
+    /// Set the `AccountCache` used when parsing events
+    pub fn set_account_cache(&mut self, cache: Option<AccountCache>) {
+        self.account_cache = cache
+    }
+
+    /// Get the `ReconnectOptions`, if set
+    pub fn get_reconnect(&self) -> Option<&ReconnectOptions> {
+        self.reconnect.as_ref()
+    }
+
+    /// Automatically reconnect (and resubscribe to all active topics) if the connection is lost.
+    /// If `None`, `next_event()` will return `None` (or an error) when the connection is lost.
+    pub fn set_reconnect(&mut self, options: Option<ReconnectOptions>) {
+        self.reconnect = options
+    }
+
+    /// Get the `GapRecovery`, if set
+    pub fn get_gap_recovery(&self) -> Option<&GapRecovery> {
+        self.gap_recovery.as_ref()
+    }
+
+    /// Re-poll the RPC after reconnecting, to find anything which was missed while disconnected.
+    /// Has no effect unless reconnection is enabled.
+    pub fn set_gap_recovery(&mut self, recovery: Option<GapRecovery>) {
+        self.gap_recovery = recovery
+    }
+
+    /// Get the currently active subscriptions
+    pub fn topics(&self) -> &[Topic] {
+        &self.topics
+    }
+
+    async fn send(&mut self, message: JsonValue) -> Result<(), RpcError> {
+        Ok(self.stream.send(Message::Text(message.to_string())).await?)
+    }
+
+    /// Subscribe to `topic`, replacing any existing subscription of the same kind
+    pub async fn subscribe(&mut self, topic: Topic) -> Result<(), RpcError> {
+        self.send(topic.subscribe_json()).await?;
+        self.topics.retain(|active| active.kind() != topic.kind());
+        self.topics.push(topic);
+        Ok(())
+    }
+
+    pub async fn unsubscribe(&mut self, topic: TopicKind) -> Result<(), RpcError> {
+        self.send(topic.unsubscribe_json()).await?;
+        self.topics.retain(|active| active.kind() != topic);
+        Ok(())
+    }
+
+    /// Wait for the next event from any subscribed topic.
+    ///
+    /// Returns `None` if the connection was closed, and reconnection is not enabled.
+    pub async fn next_event(&mut self) -> Result<Option<Event>, RpcError> {
+        loop {
+            let text = match self.stream.next().await {
+                Some(Ok(Message::Text(text))) => text,
+                Some(Ok(Message::Close(_))) | None => match self.reconnect {
+                    Some(options) => return Ok(Some(self.reconnect(options).await?)),
+                    None => return Ok(None),
+                },
+                Some(Ok(_)) => continue,
+                Some(Err(err)) => match self.reconnect {
+                    Some(options) => return Ok(Some(self.reconnect(options).await?)),
+                    None => return Err(err.into()),
+                },
+            };
+
+            let raw_json: JsonValue = json::from_str(&text)?;
+            if let Some(event) = event_from_json(&raw_json, self.account_cache.as_ref())? {
+                if let (Some(recovery), Event::Confirmation(confirmation)) =
+                    (&mut self.gap_recovery, &event)
+                {
+                    recovery.observe(&confirmation.block)
+                }
+                return Ok(Some(event));
+            }
+        }
+    }
+
+    async fn reconnect(&mut self, options: ReconnectOptions) -> Result<Event, RpcError> {
+        let start = Instant::now();
+        let mut attempts = 0;
+        loop {
+            Delay::new(options.delay(attempts)).await;
+            attempts += 1;
+            match tokio_tungstenite::connect_async(&self.url).await {
+                Ok((stream, _)) => {
+                    self.stream = stream;
+                    break;
+                }
+                Err(err) if options.max_attempts.is_some_and(|max| attempts >= max) => {
+                    return Err(err.into())
+                }
+                Err(_) => continue,
+            }
+        }
+
+        for topic in self.topics.clone() {
+            self.send(topic.subscribe_json()).await?;
+        }
+        let recovered = match &mut self.gap_recovery {
+            Some(recovery) => Some(recovery.recover().await?),
+            None => None,
+        };
+        Ok(Event::Reconnected(Box::new(Reconnection {
+            attempts,
+            downtime: start.elapsed(),
+            recovered,
+        })))
+    }
+
+    /// Subscribe to confirmations matching `filter`, and turn this WebSocket into a stream of confirmed blocks.
+    ///
+    /// Events from other topics are discarded. If reconnection is not enabled, the default `ReconnectOptions` are used.
+    pub async fn confirmations(
+        mut self,
+        filter: ConfirmationFilter,
+    ) -> Result<BoxStream<'static, Result<Confirmation, RpcError>>, RpcError> {
+        if self.reconnect.is_none() {
+            self.reconnect = Some(ReconnectOptions::default())
+        }
+        self.subscribe(Topic::Confirmation(filter)).await?;
+
+        let confirmations = stream::unfold(self, |mut websocket| async move {
+            loop {
+                match websocket.next_event().await {
+                    Ok(Some(Event::Confirmation(confirmation))) => {
+                        return Some((Ok(*confirmation), websocket))
+                    }
+                    Ok(Some(_)) => continue,
+                    Ok(None) => return None,
+                    Err(err) => return Some((Err(err), websocket)),
+                }
+            }
+        });
+        Ok(confirmations.boxed())
+    }
+
+    /// Close the connection
+    pub async fn close(mut self) -> Result<(), RpcError> {
+        Ok(self.stream.close(None).await?)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{ConfirmationFilter, Event, ReconnectOptions, Topic, WebSocket};
+    use crate::rpc::util::block_to_json;
+    use crate::{constants::get_genesis_account, Block, BlockType, Key, SecretBytes, Signature};
+    use futures::{SinkExt, StreamExt};
+    use serde_json::{json, Value as JsonValue};
+    use std::time::Duration;
+    use tokio::net::TcpListener;
+    use tokio_tungstenite::{accept_async, tungstenite::Message};
+
+    #[tokio::test]
+    async fn reconnect() {
+        let listener = TcpListener::bind("127.0.0.1:0").await.unwrap();
+        let url = format!("ws://{}", listener.local_addr().unwrap());
+
+        let server = tokio::spawn(async move {
+            let mut subscriptions = vec![];
+            for i in 0..2 {
+                let (stream, _) = listener.accept().await.unwrap();
+                let mut stream = accept_async(stream).await.unwrap();
+                let Some(Ok(Message::Text(text))) = stream.next().await else {
+                    panic!("expected subscription")
+                };
+                subscriptions.push(serde_json::from_str::<JsonValue>(&text).unwrap());
+                if i == 1 {
+                    let event = json!({
+                        "topic": "started_election",
+                        "message": {"hash": "791AF413173EEE674A6FCF633B5DFC0F3C33F397F0DA08E987D9E0741D40D81A"}
+                    });
+                    stream.send(Message::Text(event.to_string())).await.unwrap();
+                }
+                stream.close(None).await.unwrap();
+            }
+            subscriptions
+        });
+
+        let mut websocket = WebSocket::connect(&url).await.unwrap();
+        websocket.set_reconnect(Some(ReconnectOptions {
+            initial_delay: Duration::from_millis(10),
+            max_attempts: Some(3),
+            ..Default::default()
+        }));
+        websocket.subscribe(Topic::StartedElection).await.unwrap();
+
+        let Some(Event::Reconnected(reconnection)) = websocket.next_event().await.unwrap() else {
+            panic!("expected reconnection")
+        };
+        assert!(reconnection.attempts == 1);
+        assert!(reconnection.recovered.is_none());
+        assert!(matches!(
+            websocket.next_event().await.unwrap(),
+            Some(Event::StartedElection(_))
+        ));
+
+        let subscriptions = server.await.unwrap();
+        assert!(subscriptions[0] == subscriptions[1]);
+        assert!(subscriptions[0] == Topic::StartedElection.subscribe_json());
+    }
+
+    #[tokio::test]
+    async fn confirmations() {
+        let listener = TcpListener::bind("127.0.0.1:0").await.unwrap();
+        let url = format!("ws://{}", listener.local_addr().unwrap());
+        let key = Key::from_seed(&SecretBytes::from([0; 32]), 0);
+        let mut block = Block {
+            block_type: BlockType::Send,
+            account: key.to_account(),
+            previous: [1; 32],
+            representative: get_genesis_account(),
+            balance: 1000,
+            link: [2; 32],
+            signature: Signature::default(),
+            work: [0; 8],
+        };
+        block.sign(&key);
+
+        let mut block_json = block_to_json(&block);
+        block_json.insert("subtype".into(), "send".into());
+        let events = [
+            json!({
+                "topic": "started_election",
+                "message": {"hash": hex::encode_upper(block.hash())}
+            }),
+            json!({
+                "topic": "confirmation",
+                "message": {
+                    "account": key.to_account().to_string(),
+                    "amount": "1000",
+                    "hash": hex::encode_upper(block.hash()),
+                    "confirmation_type": "active_quorum",
+                    "block": block_json
+                }
+            }),
+        ];
+        let server = tokio::spawn(async move {
+            let (stream, _) = listener.accept().await.unwrap();
+            let mut stream = accept_async(stream).await.unwrap();
+            let Some(Ok(Message::Text(text))) = stream.next().await else {
+                panic!("expected subscription")
+            };
+            for event in events {
+                stream.send(Message::Text(event.to_string())).await.unwrap();
+            }
+            serde_json::from_str::<JsonValue>(&text).unwrap()
+        });
+
+        let filter = ConfirmationFilter {
+            accounts: vec![key.to_account()],
+        };
+        let mut confirmations = WebSocket::connect(&url)
+            .await
+            .unwrap()
+            .confirmations(filter.clone())
+            .await
+            .unwrap();
+        let confirmation = confirmations.next().await.unwrap().unwrap();
+        assert!(confirmation.block == block);
+        assert!(confirmation.amount == 1000);
+        assert!(server.await.unwrap() == Topic::Confirmation(filter).subscribe_json());
+    }
+}
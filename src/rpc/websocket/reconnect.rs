@@ -0,0 +1,176 @@
+use super::super::{ReceivableSet, Rpc, RpcError};
+use crate::{Account, Block};
+use std::time::Duration;
+
+/// How to reconnect when the WebSocket connection is lost
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct ReconnectOptions {
+    /// Delay before the second attempt to reconnect (the first attempt is immediate)
+    pub initial_delay: Duration,
+    /// The delay is never increased beyond this
+    pub max_delay: Duration,
+    /// The delay is multiplied by this after each failed attempt
+    pub multiplier: u32,
+    /// Give up after this many failed attempts (never give up if `None`)
+    pub max_attempts: Option<u32>,
+}
+impl ReconnectOptions {
+    /// The delay before reconnection attempt number `attempt` (starting at 0)
+    pub fn delay(&self, attempt: u32) -> Duration {
+        if attempt == 0 {
+            return Duration::ZERO;
+        }
+        let mut delay = self.initial_delay;
+        for _ in 1..attempt {
+            delay = delay.saturating_mul(self.multiplier);
+            if delay >= self.max_delay {
+                return self.max_delay;
+            }
+        }
+        delay.min(self.max_delay)
+    }
+}
+impl Default for ReconnectOptions {
+    fn default() -> Self {
+        ReconnectOptions {
+            initial_delay: Duration::from_secs(1),
+            max_delay: Duration::from_secs(60),
+            multiplier: 2,
+            max_attempts: None,
+        }
+    }
+}
+
+/// Re-polls the RPC after a reconnection, to find anything which was missed while disconnected
+#[derive(Debug, Clone)]
+pub struct GapRecovery {
+    rpc: Rpc,
+    accounts: Vec<Account>,
+    frontiers: Vec<Option<[u8; 32]>>,
+    /// The maximum number of blocks to fetch from each account's history
+    pub history_count: usize,
+    /// The maximum number of receivable transactions to fetch for each account
+    pub receivable_count: usize,
+}
+impl GapRecovery {
+    /// Watch the given accounts, starting from their current frontiers
+    pub async fn new(rpc: Rpc, accounts: Vec<Account>) -> Result<GapRecovery, RpcError> {
+        let frontiers = rpc.accounts_frontiers(&accounts).await?;
+        Ok(GapRecovery {
+            rpc,
+            accounts,
+            frontiers,
+            history_count: 100,
+            receivable_count: 100,
+        })
+    }
+
+    pub fn accounts(&self) -> &[Account] {
+        &self.accounts
+    }
+
+    /// Note that `block` was seen, so that it is not recovered again
+    pub(super) fn observe(&mut self, block: &Block) {
+        if let Some(i) = self.accounts.iter().position(|a| a == &block.account) {
+            self.frontiers[i] = Some(block.hash())
+        }
+    }
+
+    pub(super) async fn recover(&mut self) -> Result<RecoveredGap, RpcError> {
+        let frontiers = self.rpc.accounts_frontiers(&self.accounts).await?;
+        if frontiers.len() != self.accounts.len() {
+            return Err(RpcError::InvalidData);
+        }
+
+        let mut history = vec![];
+        for (i, account) in self.accounts.iter().enumerate() {
+            if frontiers[i] == self.frontiers[i] {
+                continue;
+            }
+            let blocks = self
+                .rpc
+                .account_history(account, self.history_count, None, None)
+                .await?;
+            history.push((account.clone(), blocks_since(blocks, self.frontiers[i])));
+        }
+        let receivable = self
+            .rpc
+            .accounts_receivable(&self.accounts, self.receivable_count, 0)
+            .await?;
+
+        self.frontiers = frontiers;
+        Ok(RecoveredGap {
+            history,
+            receivable: receivable.into(),
+        })
+    }
+}
+
+/// Blocks from `history` (newest first) which are newer than `frontier`
+pub(super) fn blocks_since(mut history: Vec<Block>, frontier: Option<[u8; 32]>) -> Vec<Block> {
+    if let Some(frontier) = frontier {
+        if let Some(i) = history.iter().position(|block| block.hash() == frontier) {
+            history.truncate(i)
+        }
+    }
+    history
+}
+
+/// What was missed while the WebSocket was disconnected
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct RecoveredGap {
+    /// New blocks (newest first) for each watched account whose frontier changed
+    pub history: Vec<(Account, Vec<Block>)>,
+    /// All receivable transactions for the watched accounts
+    pub receivable: ReceivableSet,
+}
+
+/// Information about a reconnection
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Reconnection {
+    /// The number of attempts it took to reconnect
+    pub attempts: u32,
+    /// How long the WebSocket was disconnected for
+    pub downtime: Duration,
+    /// What was missed while disconnected, if `GapRecovery` is set
+    pub recovered: Option<RecoveredGap>,
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{blocks_since, ReconnectOptions};
+    use crate::{constants::get_genesis_account, Block, BlockType, Signature};
+    use std::time::Duration;
+
+    #[test]
+    fn backoff() {
+        let options = ReconnectOptions::default();
+        assert!(options.delay(0) == Duration::ZERO);
+        assert!(options.delay(1) == Duration::from_secs(1));
+        assert!(options.delay(2) == Duration::from_secs(2));
+        assert!(options.delay(4) == Duration::from_secs(8));
+        assert!(options.delay(7) == Duration::from_secs(60));
+        assert!(options.delay(u32::MAX) == Duration::from_secs(60));
+    }
+
+    #[test]
+    fn gap() {
+        let history: Vec<Block> = (0..4)
+            .map(|i| Block {
+                block_type: BlockType::Receive,
+                account: get_genesis_account(),
+                previous: [i; 32],
+                representative: get_genesis_account(),
+                balance: 0,
+                link: [0; 32],
+                signature: Signature::default(),
+                work: [0; 8],
+            })
+            .collect();
+
+        let since = blocks_since(history.clone(), Some(history[2].hash()));
+        assert!(since == history[..2]);
+        assert!(blocks_since(history.clone(), None) == history);
+        assert!(blocks_since(history.clone(), Some([9; 32])) == history);
+    }
+}
@@ -6,7 +6,7 @@ use curve25519_dalek::{
 };
 use std::convert::From;
 use std::fmt::Debug;
-use zeroize::{Zeroize, ZeroizeOnDrop};
+use zeroize::{Zeroize, ZeroizeOnDrop, Zeroizing};
 
 #[cfg(feature = "serde")]
 use serde::{Deserialize, Serialize};
@@ -30,7 +30,22 @@ macro_rules! scalar {
     }};
 }
 
-/// A wrapper for `[u8; N]` that automatically calls `zeroize` when dropped
+/// Concatenate byte slices which include secret material into a buffer which is zeroized when dropped.
+///
+/// The buffer is allocated once, so no copies are left behind by reallocation.
+pub(crate) fn secret_concat(parts: &[&[u8]]) -> Zeroizing<Vec<u8>> {
+    let mut buffer = Zeroizing::new(Vec::with_capacity(
+        parts.iter().map(|part| part.len()).sum(),
+    ));
+    for part in parts {
+        buffer.extend_from_slice(part)
+    }
+    buffer
+}
+
+/// A wrapper for `[u8; N]` that automatically calls `zeroize` when dropped.
+///
+/// The bytes are boxed, so moving a `SecretBytes` does not leave copies of them behind.
 #[derive(Clone, Zeroize, ZeroizeOnDrop, PartialEq, Eq)]
 pub struct SecretBytes<const N: usize> {
     bytes: Box<[u8; N]>,
@@ -45,6 +60,17 @@ impl<const N: usize> SecretBytes<N> {
     pub fn as_ptr(&self) -> *const u8 {
         self.bytes.as_ptr()
     }
+
+    /// Generate random bytes (such as a new seed) with the operating system's secure random number generator
+    #[cfg(feature = "rand")]
+    pub fn random() -> Result<SecretBytes<N>, NanoError> {
+        let mut secret = SecretBytes {
+            bytes: Box::new([0; N]),
+        };
+        getrandom::getrandom(secret.bytes.as_mut_slice())
+            .or(Err(NanoError::RandomnessUnavailable))?;
+        Ok(secret)
+    }
 }
 impl<const N: usize> From<[u8; N]> for SecretBytes<N> {
     fn from(mut value: [u8; N]) -> Self {
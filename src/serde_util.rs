@@ -0,0 +1,104 @@
+//! Helpers for `#[serde(with = "...")]`, which serialize fields as the node does in human-readable formats
+//! (such as JSON), and in the same compact form as before in binary formats (such as bincode).
+
+use crate::Amount;
+use serde::{Deserialize, Deserializer, Serialize, Serializer};
+
+/// Bytes which are serialized as uppercase hex in human-readable formats
+struct Hex<const N: usize>([u8; N]);
+impl<const N: usize> Serialize for Hex<N> {
+    fn serialize<S: Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+        if serializer.is_human_readable() {
+            serializer.serialize_str(&hex::encode_upper(self.0))
+        } else {
+            serde_arrays::serialize(&self.0, serializer)
+        }
+    }
+}
+impl<'de, const N: usize> Deserialize<'de> for Hex<N> {
+    fn deserialize<D: Deserializer<'de>>(deserializer: D) -> Result<Self, D::Error> {
+        if deserializer.is_human_readable() {
+            let string = String::deserialize(deserializer)?;
+            let bytes = hex::decode(&string)
+                .ok()
+                .and_then(|bytes| bytes.try_into().ok())
+                .ok_or_else(|| {
+                    serde::de::Error::custom(format!("expected {} hex characters", N * 2))
+                })?;
+            Ok(Hex(bytes))
+        } else {
+            serde_arrays::deserialize(deserializer).map(Hex)
+        }
+    }
+}
+
+/// `[u8; N]` as uppercase hex in human-readable formats
+pub(crate) mod hex_bytes {
+    use super::*;
+
+    pub(crate) fn serialize<S: Serializer, const N: usize>(
+        value: &[u8; N],
+        serializer: S,
+    ) -> Result<S::Ok, S::Error> {
+        Hex(*value).serialize(serializer)
+    }
+
+    pub(crate) fn deserialize<'de, D: Deserializer<'de>, const N: usize>(
+        deserializer: D,
+    ) -> Result<[u8; N], D::Error> {
+        Ok(Hex::deserialize(deserializer)?.0)
+    }
+}
+
+/// `Option<[u8; N]>` as uppercase hex in human-readable formats
+#[cfg(feature = "rpc-core")]
+pub(crate) mod hex_bytes_option {
+    use super::*;
+
+    pub(crate) fn serialize<S: Serializer, const N: usize>(
+        value: &Option<[u8; N]>,
+        serializer: S,
+    ) -> Result<S::Ok, S::Error> {
+        value.map(Hex).serialize(serializer)
+    }
+
+    pub(crate) fn deserialize<'de, D: Deserializer<'de>, const N: usize>(
+        deserializer: D,
+    ) -> Result<Option<[u8; N]>, D::Error> {
+        Ok(Option::<Hex<N>>::deserialize(deserializer)?.map(|hex| hex.0))
+    }
+}
+
+/// `u128` amounts of raw as decimal strings in human-readable formats (see `Amount`)
+pub(crate) mod raw {
+    use super::*;
+
+    pub(crate) fn serialize<S: Serializer>(value: &u128, serializer: S) -> Result<S::Ok, S::Error> {
+        Amount::from(*value).serialize(serializer)
+    }
+
+    pub(crate) fn deserialize<'de, D: Deserializer<'de>>(
+        deserializer: D,
+    ) -> Result<u128, D::Error> {
+        Ok(Amount::deserialize(deserializer)?.raw())
+    }
+}
+
+/// `Option<u128>` amounts of raw as decimal strings in human-readable formats (see `Amount`)
+#[cfg(feature = "rpc-core")]
+pub(crate) mod raw_option {
+    use super::*;
+
+    pub(crate) fn serialize<S: Serializer>(
+        value: &Option<u128>,
+        serializer: S,
+    ) -> Result<S::Ok, S::Error> {
+        value.map(Amount::from).serialize(serializer)
+    }
+
+    pub(crate) fn deserialize<'de, D: Deserializer<'de>>(
+        deserializer: D,
+    ) -> Result<Option<u128>, D::Error> {
+        Ok(Option::<Amount>::deserialize(deserializer)?.map(|amount| amount.raw()))
+    }
+}
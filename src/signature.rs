@@ -1,9 +1,10 @@
 use super::{try_point_from_slice, Account, Key, NanoError};
 use crate::auto_from_impl;
 use curve25519_dalek::{EdwardsPoint, Scalar as RawScalar};
+use std::fmt::{Display, LowerHex, UpperHex};
 use zeroize::Zeroize;
 
-pub use crate::nanopy::{is_valid_signature, sign_message};
+pub use crate::nanopy::{is_valid_signature, sign_message, verify_batch};
 pub mod hazmat {
     pub use crate::nanopy::sign_message_with_r;
 }
@@ -11,8 +12,13 @@ pub mod hazmat {
 #[cfg(feature = "serde")]
 use serde::{Deserialize, Serialize};
 
+/// A signature of a message or block.
+///
+/// Signatures contain no secret material (`r` and `s` are published along with the block),
+/// so unlike keys they are `Copy`, and are not zeroized when dropped.
+///
+/// Serialized as uppercase hex in human-readable formats.
 #[derive(Debug, Clone, Copy, Zeroize, PartialEq, Eq, Default)]
-#[cfg_attr(feature = "serde", derive(Serialize, Deserialize))]
 pub struct Signature {
     pub r: EdwardsPoint,
     pub s: RawScalar,
@@ -33,6 +39,7 @@ impl Signature {
     }
 }
 
+auto_from_impl!(FromStr: Signature);
 auto_from_impl!(From: Signature => [u8; 64]);
 auto_from_impl!(TryFrom: [u8; 64] => Signature);
 
@@ -53,10 +60,83 @@ impl TryFrom<&[u8; 64]> for Signature {
         Ok(Signature { r, s })
     }
 }
+impl TryFrom<&str> for Signature {
+    type Error = NanoError;
+
+    /// Parse a signature from 128 hex characters (either case)
+    fn try_from(value: &str) -> Result<Self, NanoError> {
+        let bytes: [u8; 64] = hex::decode(value)
+            .or(Err(NanoError::InvalidHex))?
+            .try_into()
+            .or(Err(NanoError::InvalidHex))?;
+        Signature::try_from(bytes)
+    }
+}
+#[cfg(feature = "serde")]
+impl Serialize for Signature {
+    fn serialize<S>(&self, serializer: S) -> Result<S::Ok, S::Error>
+    where
+        S: serde::Serializer,
+    {
+        if serializer.is_human_readable() {
+            serializer.serialize_str(&self.to_string())
+        } else {
+            SignatureSerde {
+                r: self.r,
+                s: self.s,
+            }
+            .serialize(serializer)
+        }
+    }
+}
+#[cfg(feature = "serde")]
+impl<'de> Deserialize<'de> for Signature {
+    fn deserialize<D>(deserializer: D) -> Result<Self, D::Error>
+    where
+        D: serde::Deserializer<'de>,
+    {
+        if deserializer.is_human_readable() {
+            let signature = String::deserialize(deserializer)?;
+            Signature::try_from(signature.as_str()).map_err(serde::de::Error::custom)
+        } else {
+            let signature = SignatureSerde::deserialize(deserializer)?;
+            Ok(Signature {
+                r: signature.r,
+                s: signature.s,
+            })
+        }
+    }
+}
+/// Serde-compatible representation of `Signature` in binary formats
+#[cfg(feature = "serde")]
+#[derive(Serialize, Deserialize)]
+#[serde(rename = "Signature")]
+struct SignatureSerde {
+    r: EdwardsPoint,
+    s: RawScalar,
+}
+
+/// Uppercase hex, as used by the node
+impl Display for Signature {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(f, "{self:X}")
+    }
+}
+impl LowerHex for Signature {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(f, "{}", hex::encode(self.to_bytes()))
+    }
+}
+impl UpperHex for Signature {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(f, "{}", hex::encode_upper(self.to_bytes()))
+    }
+}
 
 #[cfg(test)]
 mod tests {
-    use crate::{Key, SecretBytes};
+    use super::verify_batch;
+    use crate::{Account, Key, SecretBytes, Signature};
 
     fn get_key(seed: [u8; 32], i: u32) -> Key {
         let seed = SecretBytes::from(seed);
@@ -87,6 +167,40 @@ mod tests {
         assert!(!account.is_valid_signature(b"test 2", &signature))
     }
 
+    #[test]
+    fn batch() {
+        let keys: Vec<Key> = (0..50).map(|i| get_key([0; 32], i)).collect();
+        let accounts: Vec<Account> = keys.iter().map(|key| key.to_account()).collect();
+        let messages: Vec<Vec<u8>> = (0..50).map(|i| format!("test {i}").into_bytes()).collect();
+        let mut signatures: Vec<Signature> = keys
+            .iter()
+            .zip(&messages)
+            .map(|(key, message)| key.sign_message(message))
+            .collect();
+
+        let batch = |signatures: &[Signature]| {
+            let batch: Vec<(&[u8], &Signature, &Account)> = messages
+                .iter()
+                .zip(signatures)
+                .zip(&accounts)
+                .map(|((message, signature), account)| (message.as_slice(), signature, account))
+                .collect();
+            verify_batch(&batch)
+        };
+        assert!(verify_batch(&[]));
+        assert!(batch(&signatures));
+        assert!(batch(&signatures[..1]));
+
+        // one signature for the wrong message
+        signatures[17] = keys[17].sign_message(b"test 0");
+        assert!(!batch(&signatures));
+        signatures[17] = keys[17].sign_message(&messages[17]);
+
+        // two signatures swapped
+        signatures.swap(3, 4);
+        assert!(!batch(&signatures));
+    }
+
     #[test]
     fn r_safety() {
         let key = get_key([0; 32], 0);
@@ -96,6 +210,18 @@ mod tests {
         assert!(signature_1.r != signature_2.r);
         assert!(signature_1.s != signature_2.s);
     }
+
+    #[test]
+    fn hex() {
+        let encoded = "82D41BC16F313E4B2243D14DFFA2FB04679C540C2095FEE7EAE0F2F26880AD56DD48D87A7CC5DD760C5B2D76EE2C205506AA557BF00B60D8DEE312EC7343A501";
+        let signature: Signature = encoded.parse().unwrap();
+        assert!(signature.to_string() == encoded);
+        assert!(format!("{signature:x}") == encoded.to_lowercase());
+        assert!(encoded.to_lowercase().parse::<Signature>().unwrap() == signature);
+
+        assert!(encoded[..126].parse::<Signature>().is_err());
+        assert!(encoded.replace('8', "g").parse::<Signature>().is_err());
+    }
 }
 
 #[cfg(test)]
@@ -105,4 +231,13 @@ mod serde_tests {
     use crate::serde_test;
 
     serde_test!(signature: Signature::default() => 32 + 32);
+
+    #[test]
+    fn signature_json() {
+        let key = Key::from_seed(&[0; 32].into(), 0);
+        let signature = key.sign_message(b"test");
+        let json = serde_json::to_string(&signature).unwrap();
+        assert!(json == format!("\"{signature:X}\""));
+        assert!(serde_json::from_str::<Signature>(&json).unwrap() == signature);
+    }
 }
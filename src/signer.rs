@@ -0,0 +1,45 @@
+//! Signing blocks without direct access to the private key, such as with a hardware wallet.
+
+use crate::{Account, Block, Key, Signature};
+use std::convert::Infallible;
+use std::fmt::Debug;
+
+/// Something which can sign blocks for a single account
+pub trait Signer {
+    type Error: Debug;
+
+    /// Get the account whose key signs blocks
+    fn account(&self) -> Result<Account, Self::Error>;
+
+    /// Sign `block`, which must belong to `account()`
+    fn sign_block(&self, block: &Block) -> Result<Signature, Self::Error>;
+}
+impl Signer for Key {
+    type Error = Infallible;
+
+    fn account(&self) -> Result<Account, Self::Error> {
+        Ok(self.to_account())
+    }
+
+    fn sign_block(&self, block: &Block) -> Result<Signature, Self::Error> {
+        Ok(Key::sign_block(self, block))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::Signer;
+    use crate::constants::get_genesis_account;
+    use crate::{BlockBuilder, Key, SecretBytes};
+
+    #[test]
+    fn key() {
+        let key = Key::from_seed(&SecretBytes::from([0; 32]), 0);
+        let signer: &dyn Signer<Error = _> = &key;
+        let block = BlockBuilder::unopened(key.to_account(), get_genesis_account())
+            .receive([1; 32], 1)
+            .unwrap();
+        assert!(signer.account().unwrap() == key.to_account());
+        assert!(signer.sign_block(&block).unwrap() == key.sign_block(&block));
+    }
+}
@@ -0,0 +1,107 @@
+//! [SLIP-0010](https://github.com/satoshilabs/slips/blob/master/slip-0010.md) key derivation for Ed25519,
+//! as used by BIP44 wallets such as the Nano app on Ledger devices.
+//!
+//! Nano accounts are derived from a BIP39 seed on the path `m/44'/165'/i'`, and the resulting private key
+//! is used in the same way as a key derived with `Key::from_seed()`.
+//! Ed25519 only supports hardened derivation, so every index of a path is hardened.
+
+use crate::hashes::blake2b_scalar;
+use crate::{Account, Key, SecretBytes};
+use hmac::{Hmac, Mac};
+use sha2::Sha512;
+use zeroize::Zeroize;
+
+/// The BIP44 purpose
+pub const PURPOSE: u32 = 44;
+/// Nano's registered [SLIP-0044](https://github.com/satoshilabs/slips/blob/master/slip-0044.md) coin type
+pub const NANO_COIN_TYPE: u32 = 165;
+
+const HARDENED: u32 = 0x8000_0000;
+
+fn hmac_sha512(key: &[u8], data: &[u8]) -> SecretBytes<64> {
+    let mut mac = Hmac::<Sha512>::new_from_slice(key).expect("HMAC accepts keys of any length");
+    mac.update(data);
+    let bytes: [u8; 64] = mac.finalize().into_bytes().into();
+    SecretBytes::from(bytes)
+}
+
+/// Derive the private key at `path` from `seed` (usually a 64-byte BIP39 seed).
+///
+/// Every index is hardened: `[44, 165, 0]` is the path `m/44'/165'/0'`.
+pub fn derive_private_key(seed: &[u8], path: &[u32]) -> SecretBytes<32> {
+    let mut node = hmac_sha512(b"ed25519 seed", seed);
+    let mut data = [0; 37];
+    for index in path {
+        let (key, chain_code) = node.as_slice().split_at(32);
+        data[1..33].copy_from_slice(key);
+        data[33..].copy_from_slice(&(index | HARDENED).to_be_bytes());
+        node = hmac_sha512(chain_code, &data);
+    }
+    data.zeroize();
+
+    let key: [u8; 32] = node.as_slice()[..32].try_into().unwrap();
+    SecretBytes::from(key)
+}
+
+/// Get the private key of the Nano account at index `i` of `seed`, on the path `m/44'/165'/i'`
+pub fn get_nano_private_key(seed: &[u8], i: u32) -> SecretBytes<32> {
+    derive_private_key(seed, &[PURPOSE, NANO_COIN_TYPE, i])
+}
+
+/// Get the key of the Nano account at index `i` of `seed`, on the path `m/44'/165'/i'`
+pub fn get_nano_key(seed: &[u8], i: u32) -> Key {
+    Key::from(blake2b_scalar(get_nano_private_key(seed, i).as_slice()))
+}
+
+/// Get the Nano account at index `i` of `seed`, on the path `m/44'/165'/i'`
+pub fn get_nano_account(seed: &[u8], i: u32) -> Account {
+    get_nano_key(seed, i).to_account()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    /// Test vector 1 for ed25519, from SLIP-0010
+    #[test]
+    fn slip10() {
+        let seed = hex::decode("000102030405060708090a0b0c0d0e0f").unwrap();
+        let vectors: [(&[u32], &str); 4] = [
+            (
+                &[],
+                "2b4be7f19ee27bbf30c667b642d5f4aa69fd169872f8fc3059c08ebae2eb19e7",
+            ),
+            (
+                &[0],
+                "68e0fe46dfb67e368c75379acec591dad19df3cde26e63b93a8e704f1dade7a3",
+            ),
+            (
+                &[0, 1, 2],
+                "92a5b23c0b8a99e37d07df3fb9966917f5d06e02ddbd909c7e184371463e9fc9",
+            ),
+            (
+                &[0, 1, 2, 2, 1000000000],
+                "8f94d394a8e8fd6b1bc2f3f49f5c47e385281d5c17e65324b0f62483e37e8793",
+            ),
+        ];
+        for (path, key) in vectors {
+            assert!(hex::encode(derive_private_key(&seed, path).as_slice()) == key);
+        }
+        // indexes are always hardened
+        assert!(derive_private_key(&seed, &[HARDENED]) == derive_private_key(&seed, &[0]));
+    }
+
+    /// From the BIP39/BIP44 test vector in the Nano documentation
+    #[test]
+    fn nano() {
+        let seed = hex::decode("0dc285fde768f7ff29b66ce7252d56ed92fe003b605907f7a4f683c3dc8586d34a914d3c71fc099bb38ee4a59e5b081a3497b7a323e90cc68f67b5837690310c").unwrap();
+        assert!(
+            hex::encode(get_nano_private_key(&seed, 0).as_slice())
+                == "3be4fc2ef3f3b7374e6fc4fb6e7bb153f8a2998b3b3dab50853eabe128024143"
+        );
+        assert!(
+            get_nano_account(&seed, 0).to_string()
+                == "nano_1pu7p5n3ghq1i1p4rhmek41f5add1uh34xpb94nkbxe8g4a6x1p69emk8y1d"
+        );
+    }
+}
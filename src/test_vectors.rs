@@ -0,0 +1,153 @@
+//! Test vectors for Nano keys, signatures, and work, and (in `camo`) for the Camo protocol.
+//!
+//! Alternative implementations and bindings can use these to check that they are compatible with this library.
+//! Hex values are uppercase, as used by the node.
+
+use crate::block::check_work;
+use crate::hashes::{blake2b_scalar, hazmat::get_account_seed};
+use crate::{Account, Key, SecretBytes, Signature};
+
+pub use crate::camo::test_vectors as camo;
+
+fn hex_bytes<const N: usize>(value: &str) -> Option<[u8; N]> {
+    hex::decode(value).ok()?.try_into().ok()
+}
+
+/// An account derived from a seed
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct KeyVector {
+    pub seed: &'static str,
+    pub index: u32,
+    /// The private key of the account, as shown by other wallets (before it is hashed into a scalar)
+    pub private_key: &'static str,
+    pub public_key: &'static str,
+    pub account: &'static str,
+}
+impl KeyVector {
+    /// Check that this library produces the expected values for this test vector
+    pub fn verify(&self) -> bool {
+        let Some(seed) = hex_bytes::<32>(self.seed) else {
+            return false;
+        };
+        let seed = SecretBytes::from(seed);
+        let private_key = get_account_seed(&seed, self.index);
+        let key = Key::from_seed(&seed, self.index);
+        let account = key.to_account();
+
+        hex::encode_upper(private_key.as_slice()) == self.private_key
+            && Key::from(blake2b_scalar(private_key.as_slice())) == key
+            && account.to_hex() == self.public_key
+            && account.to_string() == self.account
+            && Account::try_from(self.account).ok() == Some(account)
+    }
+}
+
+/// A signature of a block hash
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct SignatureVector {
+    pub account: &'static str,
+    /// The hash of the signed block
+    pub hash: &'static str,
+    pub signature: &'static str,
+}
+impl SignatureVector {
+    /// Check that this library accepts the signature
+    pub fn verify(&self) -> bool {
+        let (Ok(account), Some(hash), Ok(signature)) = (
+            Account::try_from(self.account),
+            hex_bytes::<32>(self.hash),
+            Signature::try_from(self.signature),
+        ) else {
+            return false;
+        };
+        account.is_valid_signature(&hash, &signature)
+    }
+}
+
+/// Proof-of-work for a block
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct WorkVector {
+    /// The `previous` of the block, or the account's public key for the first block (see `Block::work_hash()`)
+    pub work_hash: &'static str,
+    /// The work, in hex (in the same byte order as block JSON)
+    pub work: &'static str,
+    /// The difficulty which the work meets
+    pub difficulty: &'static str,
+}
+impl WorkVector {
+    /// Check that this library accepts the work at the given difficulty
+    pub fn verify(&self) -> bool {
+        match (
+            hex_bytes(self.work_hash),
+            hex_bytes(self.difficulty),
+            hex_bytes(self.work),
+        ) {
+            (Some(work_hash), Some(difficulty), Some(work)) => {
+                check_work(work_hash, difficulty, work)
+            }
+            _ => false,
+        }
+    }
+}
+
+/// From the Nano documentation
+pub const KEY_VECTORS: &[KeyVector] = &[KeyVector {
+    seed: "0000000000000000000000000000000000000000000000000000000000000000",
+    index: 0,
+    private_key: "9F0E444C69F77A49BD0BE89DB92C38FE713E0963165CCA12FAF5712D7657120F",
+    public_key: "C008B814A7D269A1FA3C6528B19201A24D797912DB9996FF02A1FF356E45552B",
+    account: "nano_3i1aq1cchnmbn9x5rsbap8b15akfh7wj7pwskuzi7ahz8oq6cobd99d4r3b7",
+}];
+
+/// Signatures of blocks on the live network
+pub const SIGNATURE_VECTORS: &[SignatureVector] = &[
+    // the genesis block
+    SignatureVector {
+        account: "nano_3t6k35gi95xu6tergt6p69ck76ogmitsa8mnijtpxm9fkcm736xtoncuohr3",
+        hash: "991CF190094C00F0B68E2E5F75F6BEE95A2E0BD93CEAA4A6734DB9F19B728948",
+        signature: "9F0C933C8ADE004D808EA1985FA746A7E95BA2A38F867640F53EC8F180BDFE9E2C1268DEAD7C2664F356E37ABA362BC58E46DBA03E523A7B5A19E4B6EB12BB02",
+    },
+];
+
+/// Work of blocks on the live network
+pub const WORK_VECTORS: &[WorkVector] = &[
+    // the genesis block, at the original (epoch 0) difficulty
+    WorkVector {
+        work_hash: "E89208DD038FBB269987689621D52292AE9C35941A7484756ECCED92A65093BA",
+        work: "62F05417DD3FB691",
+        difficulty: "FFFFFFC000000000",
+    },
+    // a `send` block, at the current `send` difficulty
+    WorkVector {
+        work_hash: "33BEFD80E215B3FD3C2E453E71708DC522BD33EC26982D038B897445B6A8F8D8",
+        work: "0DA2025ABA5298F1",
+        difficulty: "FFFFFFF800000000",
+    },
+];
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_vectors() {
+        for vector in KEY_VECTORS {
+            assert!(vector.verify());
+            let mut wrong = *vector;
+            wrong.index += 1;
+            assert!(!wrong.verify());
+        }
+        for vector in SIGNATURE_VECTORS {
+            assert!(vector.verify());
+            let mut wrong = *vector;
+            wrong.hash = "0000000000000000000000000000000000000000000000000000000000000000";
+            assert!(!wrong.verify());
+        }
+        for vector in WORK_VECTORS {
+            assert!(vector.verify());
+            let mut wrong = *vector;
+            wrong.difficulty = "FFFFFFFFFFFFFFFF";
+            assert!(!wrong.verify());
+        }
+    }
+}
@@ -0,0 +1,226 @@
+//! Parsing and building `nano:` payment URIs, such as `nano:nano_1abc...?amount=1000&label=Coffee`.
+//!
+//! Amounts are in raw. Unknown query parameters are ignored when parsing.
+
+use crate::{auto_from_impl, Account, Amount, NanoError};
+use std::fmt::Display;
+
+#[cfg(feature = "camo")]
+use crate::camo::CamoAccount;
+
+const SCHEME: &str = "nano:";
+
+/// The recipient of a payment URI
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum UriRecipient {
+    Account(Account),
+    #[cfg(feature = "camo")]
+    Camo(CamoAccount),
+}
+impl Display for UriRecipient {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            UriRecipient::Account(account) => write!(f, "{account}"),
+            #[cfg(feature = "camo")]
+            UriRecipient::Camo(camo_account) => write!(f, "{camo_account}"),
+        }
+    }
+}
+impl From<Account> for UriRecipient {
+    fn from(value: Account) -> Self {
+        UriRecipient::Account(value)
+    }
+}
+#[cfg(feature = "camo")]
+impl From<CamoAccount> for UriRecipient {
+    fn from(value: CamoAccount) -> Self {
+        UriRecipient::Camo(value)
+    }
+}
+
+/// A `nano:` payment URI
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct PaymentUri {
+    pub recipient: UriRecipient,
+    /// The requested amount, in raw
+    pub amount: Option<u128>,
+    /// A label for the recipient, such as a merchant's name
+    pub label: Option<String>,
+    /// A description of the payment
+    pub message: Option<String>,
+}
+impl PaymentUri {
+    pub fn new(recipient: impl Into<UriRecipient>) -> PaymentUri {
+        PaymentUri {
+            recipient: recipient.into(),
+            amount: None,
+            label: None,
+            message: None,
+        }
+    }
+
+    /// Get the recipient as a `nano_` account, or `None` if it is a `camo_` account
+    pub fn account(&self) -> Option<&Account> {
+        match &self.recipient {
+            UriRecipient::Account(account) => Some(account),
+            #[cfg(feature = "camo")]
+            UriRecipient::Camo(_) => None,
+        }
+    }
+}
+impl TryFrom<&str> for PaymentUri {
+    type Error = NanoError;
+
+    fn try_from(value: &str) -> Result<Self, Self::Error> {
+        let scheme = value.get(..SCHEME.len()).ok_or(NanoError::InvalidUri)?;
+        if !scheme.eq_ignore_ascii_case(SCHEME) {
+            return Err(NanoError::InvalidUri);
+        }
+        let (address, query) = value[SCHEME.len()..]
+            .split_once('?')
+            .unwrap_or((&value[SCHEME.len()..], ""));
+        let mut uri = PaymentUri::new(parse_recipient(address)?);
+
+        for parameter in query.split('&').filter(|parameter| !parameter.is_empty()) {
+            let (key, value) = parameter.split_once('=').unwrap_or((parameter, ""));
+            match key {
+                "amount" => uri.amount = Some(Amount::from_raw_str(value)?.raw()),
+                "label" => uri.label = Some(percent_decode(value)?),
+                "message" => uri.message = Some(percent_decode(value)?),
+                _ => (),
+            }
+        }
+        Ok(uri)
+    }
+}
+auto_from_impl!(FromStr: PaymentUri);
+impl Display for PaymentUri {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(f, "{SCHEME}{}", self.recipient)?;
+        let mut separator = '?';
+        let mut parameter = |f: &mut std::fmt::Formatter<'_>, key: &str, value: String| {
+            let result = write!(f, "{separator}{key}={value}");
+            separator = '&';
+            result
+        };
+        if let Some(amount) = self.amount {
+            parameter(f, "amount", amount.to_string())?;
+        }
+        if let Some(label) = &self.label {
+            parameter(f, "label", percent_encode(label))?;
+        }
+        if let Some(message) = &self.message {
+            parameter(f, "message", percent_encode(message))?;
+        }
+        Ok(())
+    }
+}
+
+fn parse_recipient(address: &str) -> Result<UriRecipient, NanoError> {
+    #[cfg(feature = "camo")]
+    if address.starts_with(crate::constants::CAMO_ACCOUNT_PREFIX) {
+        return Ok(UriRecipient::Camo(address.parse()?));
+    }
+    Ok(UriRecipient::Account(Account::try_from(address)?))
+}
+
+fn percent_encode(s: &str) -> String {
+    s.bytes()
+        .map(|byte| match byte {
+            b'A'..=b'Z' | b'a'..=b'z' | b'0'..=b'9' | b'-' | b'.' | b'_' | b'~' => {
+                (byte as char).to_string()
+            }
+            _ => format!("%{byte:02X}"),
+        })
+        .collect()
+}
+
+fn percent_decode(s: &str) -> Result<String, NanoError> {
+    let mut bytes = Vec::with_capacity(s.len());
+    let mut iter = s.bytes();
+    while let Some(byte) = iter.next() {
+        if byte != b'%' {
+            bytes.push(byte);
+            continue;
+        }
+        let hex = [
+            iter.next().ok_or(NanoError::InvalidUri)?,
+            iter.next().ok_or(NanoError::InvalidUri)?,
+        ];
+        if !hex.iter().all(u8::is_ascii_hexdigit) {
+            return Err(NanoError::InvalidUri);
+        }
+        let hex = std::str::from_utf8(&hex).or(Err(NanoError::InvalidUri))?;
+        bytes.push(u8::from_str_radix(hex, 16).or(Err(NanoError::InvalidUri))?);
+    }
+    String::from_utf8(bytes).or(Err(NanoError::InvalidUri))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{PaymentUri, UriRecipient};
+    use crate::constants::{get_genesis_account, ONE_NANO};
+    use crate::NanoError;
+
+    #[test]
+    fn parse() {
+        let genesis = get_genesis_account();
+        let uri: PaymentUri = format!("nano:{genesis}?amount=1000000000000000000000000000000&label=Coffee%20Shop&message=Order%20%2342&foo=bar")
+            .parse()
+            .unwrap();
+        assert!(uri.recipient == UriRecipient::Account(genesis.clone()));
+        assert!(uri.account() == Some(&genesis));
+        assert!(uri.amount == Some(ONE_NANO));
+        assert!(uri.label.as_deref() == Some("Coffee Shop"));
+        assert!(uri.message.as_deref() == Some("Order #42"));
+
+        let uri: PaymentUri = format!("NANO:{genesis}").parse().unwrap();
+        assert!(uri == PaymentUri::new(genesis.clone()));
+
+        for invalid in [
+            format!("nano_:{genesis}"),
+            format!("{genesis}"),
+            "nano:nano_1234".into(),
+            format!("nano:{genesis}?amount=1.5"),
+            format!("nano:{genesis}?label=%2"),
+            format!("nano:{genesis}?label=%+f"),
+            format!("nano:{genesis}?label=%ff"),
+        ] {
+            assert!(PaymentUri::try_from(invalid.as_str()).is_err());
+        }
+        assert!(PaymentUri::try_from("https://nano.org") == Err(NanoError::InvalidUri));
+    }
+
+    #[test]
+    fn build() {
+        let genesis = get_genesis_account();
+        let mut uri = PaymentUri::new(genesis.clone());
+        assert!(uri.to_string() == format!("nano:{genesis}"));
+
+        uri.amount = Some(ONE_NANO);
+        uri.label = Some("Café & Bar".into());
+        assert!(
+            uri.to_string()
+                == format!("nano:{genesis}?amount={ONE_NANO}&label=Caf%C3%A9%20%26%20Bar")
+        );
+        assert!(uri.to_string().parse::<PaymentUri>().unwrap() == uri);
+    }
+
+    #[test]
+    #[cfg(feature = "camo")]
+    fn camo() {
+        use crate::camo::{CamoKeys, CamoVersion, CamoVersions};
+        use crate::SecretBytes;
+
+        let mut versions = CamoVersions::empty();
+        versions.enable_version(CamoVersion::One);
+        let keys = CamoKeys::from_seed(&SecretBytes::from([0; 32]), 0, versions).unwrap();
+        let camo_account = keys.to_camo_account();
+
+        let uri: PaymentUri = format!("nano:{camo_account}?amount=1").parse().unwrap();
+        assert!(uri.recipient == UriRecipient::Camo(camo_account));
+        assert!(uri.account().is_none());
+        assert!(uri.amount == Some(1));
+        assert!(uri.to_string().parse::<PaymentUri>().unwrap() == uri);
+    }
+}
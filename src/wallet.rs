@@ -0,0 +1,790 @@
+use crate::constants::{RECEIVE_WORK_DIFFICULTY, SEND_WORK_DIFFICULTY};
+use crate::rpc::{Balances, Rpc, RpcError};
+use crate::work::WorkProvider;
+use crate::{Account, Block, BlockBuilder, BlockType, Key, NanoError, SecretBytes};
+use std::sync::Arc;
+use thiserror::Error;
+
+/// Maximum number of receivable transactions received per account by `Wallet::receive_all()`
+const RECEIVE_COUNT: usize = 100;
+
+/// Find the indexes of the accounts of `seed` which have been used, meaning that they are opened or have receivable transactions.
+///
+/// Indexes are checked in order, in batches, until `gap_limit` (at least `1`) unused indexes are found in a row.
+pub async fn discover_accounts(
+    seed: &SecretBytes<32>,
+    rpc: &Rpc,
+    gap_limit: u32,
+) -> Result<Vec<u32>, RpcError> {
+    let gap_limit = gap_limit.max(1);
+    let mut used: Vec<u32> = vec![];
+    let mut index: u32 = 0;
+    loop {
+        // the index at which the current gap would reach `gap_limit`
+        let gap_end = match used.last() {
+            Some(last) => last.saturating_add(1).saturating_add(gap_limit),
+            None => gap_limit,
+        };
+        if index >= gap_end {
+            return Ok(used);
+        }
+
+        let indexes: Vec<u32> = (index..gap_end).collect();
+        let accounts = Account::from_seed_range(seed, index, gap_end - index);
+        let frontiers = rpc.accounts_frontiers(&accounts).await?;
+        let receivable = rpc.accounts_receivable(&accounts, 1, 0).await?;
+        for ((i, frontier), receivable) in indexes.iter().zip(frontiers).zip(receivable) {
+            if frontier.is_some() || !receivable.is_empty() {
+                used.push(*i)
+            }
+        }
+        index = gap_end;
+    }
+}
+
+#[derive(Debug, Error)]
+pub enum WalletError {
+    #[error(transparent)]
+    RpcError(#[from] RpcError),
+    #[error(transparent)]
+    NanoError(#[from] NanoError),
+    /// The account does not belong to this wallet
+    #[error("the account does not belong to this wallet")]
+    UnknownAccount,
+    /// The account has not been opened
+    #[error("the account has not been opened")]
+    UnopenedAccount,
+}
+
+/// A set of keys, which publishes blocks using an `Rpc`.
+///
+/// Account frontiers and balances are fetched from the node before each block is built,
+/// so the wallet never needs to be resynchronized.
+#[derive(Debug, Clone)]
+pub struct Wallet {
+    rpc: Rpc,
+    work: Arc<dyn WorkProvider>,
+    keys: Vec<Key>,
+    /// The representative used when opening accounts
+    pub representative: Account,
+    /// The work difficulty of `send` and `change` blocks
+    pub send_difficulty: [u8; 8],
+    /// The work difficulty of `receive` blocks
+    pub receive_difficulty: [u8; 8],
+}
+impl Wallet {
+    /// Create a wallet with the first `count` keys of `seed`
+    pub fn from_seed(
+        rpc: Rpc,
+        seed: &SecretBytes<32>,
+        count: u32,
+        representative: Account,
+    ) -> Wallet {
+        let keys = Key::from_seed_range(seed, 0, count);
+        Wallet::from_keys(rpc, keys, representative)
+    }
+
+    pub fn from_keys(rpc: Rpc, keys: Vec<Key>, representative: Account) -> Wallet {
+        Wallet {
+            work: Arc::new(rpc.clone()),
+            rpc,
+            keys,
+            representative,
+            send_difficulty: SEND_WORK_DIFFICULTY,
+            receive_difficulty: RECEIVE_WORK_DIFFICULTY,
+        }
+    }
+
+    pub fn get_rpc(&self) -> &Rpc {
+        &self.rpc
+    }
+
+    pub fn get_work_provider(&self) -> &dyn WorkProvider {
+        self.work.as_ref()
+    }
+
+    /// Set the source of proof-of-work for new blocks.
+    /// By default, work is generated by the node.
+    pub fn set_work_provider(&mut self, provider: impl WorkProvider + 'static) {
+        self.work = Arc::new(provider)
+    }
+
+    pub fn add_key(&mut self, key: Key) {
+        if !self.keys.contains(&key) {
+            self.keys.push(key)
+        }
+    }
+
+    /// Get the accounts of this wallet, in the order their keys were added
+    pub fn accounts(&self) -> Vec<Account> {
+        self.keys.iter().map(|key| key.to_account()).collect()
+    }
+
+    fn key(&self, account: &Account) -> Result<&Key, WalletError> {
+        self.keys
+            .iter()
+            .find(|key| &key.to_account() == account)
+            .ok_or(WalletError::UnknownAccount)
+    }
+
+    /// Get the balances of each account, in the same order as `accounts()`
+    pub async fn balances(&self) -> Result<Vec<Balances>, WalletError> {
+        Ok(self
+            .rpc
+            .accounts_balances_detailed(&self.accounts())
+            .await?)
+    }
+
+    /// Get a `BlockBuilder` on top of the account's current frontier
+    async fn builder(&self, account: &Account) -> Result<BlockBuilder, WalletError> {
+        Ok(match self.rpc.account_info(account).await? {
+            Some(info) => BlockBuilder::new(
+                account.clone(),
+                info.frontier,
+                info.representative.clone(),
+                info.balance,
+            ),
+            None => BlockBuilder::unopened(account.clone(), self.representative.clone()),
+        })
+    }
+
+    /// Sign `block`, generate work for it, and publish it
+    async fn publish(&self, key: &Key, mut block: Block) -> Result<[u8; 32], WalletError> {
+        let difficulty = match block.block_type {
+            BlockType::Receive => self.receive_difficulty,
+            _ => self.send_difficulty,
+        };
+        block.sign(key);
+        block.work = self.work.generate(block.work_hash(), difficulty).await?;
+        Ok(self.rpc.process(&block).await?)
+    }
+
+    /// Send `amount` from `from` (which must belong to this wallet) to `to`, returning the hash of the `send` block
+    pub async fn send(
+        &self,
+        from: &Account,
+        to: &Account,
+        amount: u128,
+    ) -> Result<[u8; 32], WalletError> {
+        let key = self.key(from)?;
+        let block = self.build_send(from, to, amount).await?;
+        self.publish(key, block).await
+    }
+
+    /// Build the `send` block which `send()` would publish, without signing or publishing it (a "dry run")
+    pub async fn build_send(
+        &self,
+        from: &Account,
+        to: &Account,
+        amount: u128,
+    ) -> Result<Block, WalletError> {
+        self.key(from)?;
+        Ok(self.builder(from).await?.send(to, amount)?)
+    }
+
+    /// Receive the receivable transactions of every account in this wallet, largest first,
+    /// returning the hashes of the `receive` blocks
+    pub async fn receive_all(&self) -> Result<Vec<[u8; 32]>, WalletError> {
+        let accounts = self.accounts();
+        let all_receivable = self
+            .rpc
+            .accounts_receivable(&accounts, RECEIVE_COUNT, 0)
+            .await?;
+
+        let mut hashes = vec![];
+        for (account, mut receivable) in accounts.iter().zip(all_receivable) {
+            if receivable.is_empty() {
+                continue;
+            }
+            receivable.sort_by(|a, b| b.cmp(a));
+
+            let key = self.key(account)?;
+            let mut builder = self.builder(account).await?;
+            for receivable in receivable {
+                let block = builder.receive(receivable.block_hash, receivable.amount)?;
+                hashes.push(self.publish(key, block.clone()).await?);
+                builder.advance(&block);
+            }
+        }
+        Ok(hashes)
+    }
+
+    /// Send the entire balance of each of `keys` (which need not belong to this wallet) to `destination`.
+    ///
+    /// The frontiers are looked up in batches, and a result is returned for each key, in the same order:
+    /// the hash of the `send` block, or `None` if the account is unopened or empty.
+    /// An error is only returned for the whole sweep if the frontiers could not be looked up.
+    pub async fn sweep(
+        &self,
+        keys: &[Key],
+        destination: &Account,
+    ) -> Result<Vec<Result<Option<[u8; 32]>, WalletError>>, WalletError> {
+        let accounts: Vec<Account> = keys.iter().map(|key| key.to_account()).collect();
+        let frontiers = self.rpc.accounts_frontiers(&accounts).await?;
+        let opened: Vec<[u8; 32]> = frontiers.iter().flatten().copied().collect();
+        let mut infos = self.rpc.blocks_info(&opened).await?.into_iter();
+
+        let mut results = vec![];
+        for ((key, account), frontier) in keys.iter().zip(&accounts).zip(frontiers) {
+            let Some(frontier) = frontier else {
+                results.push(Ok(None));
+                continue;
+            };
+            let builder = match infos.next().flatten() {
+                Some(info) if info.block.hash() == frontier && &info.block.account == account => {
+                    BlockBuilder::from(&info.block)
+                }
+                Some(_) => {
+                    results.push(Err(RpcError::InvalidData.into()));
+                    continue;
+                }
+                // legacy frontier blocks are not returned by `blocks_info`
+                None => match self.builder(account).await {
+                    Ok(builder) => builder,
+                    Err(err) => {
+                        results.push(Err(err));
+                        continue;
+                    }
+                },
+            };
+            if builder.balance == 0 {
+                results.push(Ok(None));
+                continue;
+            }
+            let result = match builder.send(destination, builder.balance) {
+                Ok(block) => self.publish(key, block).await.map(Some),
+                Err(err) => Err(err.into()),
+            };
+            results.push(result);
+        }
+        Ok(results)
+    }
+
+    /// Change the representative of `account` (which must belong to this wallet), returning the hash of the `change` block
+    pub async fn change_representative(
+        &self,
+        account: &Account,
+        representative: &Account,
+    ) -> Result<[u8; 32], WalletError> {
+        let key = self.key(account)?;
+        let builder = self.builder(account).await?;
+        if !builder.is_opened() {
+            return Err(WalletError::UnopenedAccount);
+        }
+        self.publish(key, builder.change(representative)).await
+    }
+}
+
+/// The state of an account watched by a `WatchWallet`
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub struct AccountState {
+    /// Hash of the account's frontier, or `None` if it has not been opened
+    pub frontier: Option<[u8; 32]>,
+    pub balances: Balances,
+}
+
+/// A change in the state of an account, returned by `WatchWallet::refresh()`
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum WatchEvent {
+    /// The account has a new frontier, so blocks were added to it
+    NewFrontier {
+        account: Account,
+        frontier: [u8; 32],
+    },
+    BalanceChanged {
+        account: Account,
+        old: u128,
+        new: u128,
+    },
+    ReceivableChanged {
+        account: Account,
+        old: u128,
+        new: u128,
+    },
+}
+
+/// A set of accounts (without their keys) whose state is tracked using an `Rpc`, for monitoring.
+///
+/// The state of an account is unknown until it is first refreshed, and no events are returned for it then.
+#[derive(Debug, Clone)]
+pub struct WatchWallet {
+    rpc: Rpc,
+    accounts: Vec<(Account, Option<AccountState>)>,
+}
+impl WatchWallet {
+    pub fn new(rpc: Rpc, accounts: Vec<Account>) -> WatchWallet {
+        let mut wallet = WatchWallet {
+            rpc,
+            accounts: vec![],
+        };
+        for account in accounts {
+            wallet.add_account(account)
+        }
+        wallet
+    }
+
+    pub fn get_rpc(&self) -> &Rpc {
+        &self.rpc
+    }
+
+    pub fn add_account(&mut self, account: Account) {
+        if !self.accounts.iter().any(|(watched, _)| watched == &account) {
+            self.accounts.push((account, None))
+        }
+    }
+
+    pub fn remove_account(&mut self, account: &Account) {
+        self.accounts.retain(|(watched, _)| watched != account)
+    }
+
+    /// Get the watched accounts, in the order they were added
+    pub fn accounts(&self) -> Vec<Account> {
+        self.accounts
+            .iter()
+            .map(|(account, _)| account.clone())
+            .collect()
+    }
+
+    /// Get the last known state of `account`, or `None` if it is not watched or has not been refreshed yet
+    pub fn state(&self, account: &Account) -> Option<&AccountState> {
+        self.accounts
+            .iter()
+            .find(|(watched, _)| watched == account)
+            .and_then(|(_, state)| state.as_ref())
+    }
+
+    /// Look up the state of every account, returning the changes since the last refresh
+    pub async fn refresh(&mut self) -> Result<Vec<WatchEvent>, RpcError> {
+        let accounts = self.accounts();
+        let frontiers = self.rpc.accounts_frontiers(&accounts).await?;
+        let balances = self.rpc.accounts_balances_detailed(&accounts).await?;
+
+        let mut events = vec![];
+        for ((account, state), (frontier, balances)) in self
+            .accounts
+            .iter_mut()
+            .zip(frontiers.into_iter().zip(balances))
+        {
+            let new = AccountState { frontier, balances };
+            if let Some(old) = state.replace(new) {
+                if let Some(frontier) = new.frontier.filter(|_| new.frontier != old.frontier) {
+                    events.push(WatchEvent::NewFrontier {
+                        account: account.clone(),
+                        frontier,
+                    })
+                }
+                if new.balances.confirmed != old.balances.confirmed {
+                    events.push(WatchEvent::BalanceChanged {
+                        account: account.clone(),
+                        old: old.balances.confirmed,
+                        new: new.balances.confirmed,
+                    })
+                }
+                if new.balances.receivable != old.balances.receivable {
+                    events.push(WatchEvent::ReceivableChanged {
+                        account: account.clone(),
+                        old: old.balances.receivable,
+                        new: new.balances.receivable,
+                    })
+                }
+            }
+        }
+        Ok(events)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{Wallet, WalletError, WatchEvent, WatchWallet};
+    use crate::constants::{get_genesis_account, ONE_NANO};
+    use crate::rpc::mock::MockRpc;
+    use crate::rpc::util::block_to_json;
+    use crate::rpc::{Fixture, Rpc};
+    use crate::work::LocalWork;
+    use crate::{Account, Block, BlockBuilder, Key, NanoError, SecretBytes, Signature};
+    use futures::executor::block_on;
+    use serde_json::{json, Map, Value as JsonValue};
+
+    const SEND_HASH: &str = "791AF413173EEE674A6FCF633B5DFC0F3C33F397F0DA08E987D9E0741D40D81A";
+
+    fn wallet(fixture: &Fixture) -> Wallet {
+        let mut wallet = Wallet::from_seed(
+            Rpc::from_fixture(fixture.clone()),
+            &SecretBytes::from([0; 32]),
+            2,
+            get_genesis_account(),
+        );
+        // any work is valid
+        wallet.send_difficulty = [0; 8];
+        wallet.receive_difficulty = [0; 8];
+        wallet
+    }
+
+    /// Record the requests made when publishing `block`
+    fn record_publish(fixture: &Fixture, key: &Key, mut block: Block, node_work: bool) {
+        if node_work {
+            fixture.record(
+                json!({
+                    "action": "work_generate",
+                    "hash": hex::encode_upper(block.work_hash()),
+                    "use_peers": true,
+                    "difficulty": "0000000000000000"
+                }),
+                json!({"work": "0000000000000000"}),
+            );
+        }
+        block.sign(key);
+        fixture.record(
+            json!({
+                "action": "process",
+                "subtype": block.block_type.to_string(),
+                "block": block_to_json(&block),
+                "json_block": true
+            }),
+            json!({"hash": hex::encode_upper(block.hash())}),
+        );
+    }
+
+    fn receive_all(local_work: bool) {
+        let fixture = Fixture::new();
+        let mut wallet = wallet(&fixture);
+        if local_work {
+            wallet.set_work_provider(LocalWork);
+        }
+        let accounts = wallet.accounts();
+        let [account, other] = [accounts[0].to_string(), accounts[1].to_string()];
+
+        let open = BlockBuilder::unopened(accounts[0].clone(), get_genesis_account())
+            .receive(
+                hex::decode(SEND_HASH).unwrap().try_into().unwrap(),
+                ONE_NANO,
+            )
+            .unwrap();
+
+        fixture.record(
+            json!({
+                "action": "accounts_receivable",
+                "accounts": [account, other],
+                "count": "100",
+                "threshold": "0",
+                "sorting": true,
+                "source": true
+            }),
+            json!({"blocks": {
+                account.clone(): {SEND_HASH: {
+                    "amount": ONE_NANO.to_string(),
+                    "source": get_genesis_account().to_string()
+                }},
+                other: ""
+            }}),
+        );
+        fixture.record(
+            json!({
+                "action": "account_info",
+                "account": account,
+                "representative": true,
+                "weight": true,
+                "receivable": true
+            }),
+            json!({"error": "Account not found"}),
+        );
+        let key = Key::from_seed(&SecretBytes::from([0; 32]), 0);
+        record_publish(&fixture, &key, open.clone(), !local_work);
+
+        assert!(block_on(wallet.receive_all()).unwrap() == vec![open.hash()]);
+        assert!(fixture.is_empty());
+    }
+
+    #[test]
+    fn receive_all_node_work() {
+        receive_all(false)
+    }
+
+    #[test]
+    fn receive_all_local_work() {
+        receive_all(true)
+    }
+
+    #[test]
+    fn change_representative() {
+        let fixture = Fixture::new();
+        let wallet = wallet(&fixture);
+        let account = wallet.accounts()[0].clone();
+        assert!(matches!(
+            block_on(wallet.change_representative(&get_genesis_account(), &account)),
+            Err(WalletError::UnknownAccount)
+        ));
+
+        fixture.record(
+            json!({
+                "action": "account_info",
+                "account": account.to_string(),
+                "representative": true,
+                "weight": true,
+                "receivable": true
+            }),
+            json!({"error": "Account not found"}),
+        );
+        assert!(matches!(
+            block_on(wallet.change_representative(&account, &account)),
+            Err(WalletError::UnopenedAccount)
+        ));
+
+        fixture.record(
+            json!({
+                "action": "account_info",
+                "account": account.to_string(),
+                "representative": true,
+                "weight": true,
+                "receivable": true
+            }),
+            json!({
+                "frontier": SEND_HASH,
+                "open_block": SEND_HASH,
+                "balance": ONE_NANO.to_string(),
+                "modified_timestamp": "1000",
+                "block_count": "1",
+                "account_version": "2",
+                "representative": get_genesis_account().to_string(),
+                "weight": "0",
+                "receivable": "0"
+            }),
+        );
+        let block = BlockBuilder::new(
+            account.clone(),
+            hex::decode(SEND_HASH).unwrap().try_into().unwrap(),
+            get_genesis_account(),
+            ONE_NANO,
+        )
+        .change(&account);
+        let key = Key::from_seed(&SecretBytes::from([0; 32]), 0);
+        record_publish(&fixture, &key, block.clone(), true);
+        let hash = block_on(wallet.change_representative(&account, &account)).unwrap();
+        assert!(hash == block.hash());
+        assert!(fixture.is_empty());
+    }
+
+    #[test]
+    fn send() {
+        let fixture = Fixture::new();
+        let wallet = wallet(&fixture);
+        let account = wallet.accounts()[0].clone();
+        let genesis = get_genesis_account();
+        let record_info = || {
+            fixture.record(
+                json!({
+                    "action": "account_info",
+                    "account": account.to_string(),
+                    "representative": true,
+                    "weight": true,
+                    "receivable": true
+                }),
+                json!({
+                    "frontier": SEND_HASH,
+                    "open_block": SEND_HASH,
+                    "balance": ONE_NANO.to_string(),
+                    "modified_timestamp": "1000",
+                    "block_count": "1",
+                    "account_version": "2",
+                    "representative": genesis.to_string(),
+                    "weight": "0",
+                    "receivable": "0"
+                }),
+            )
+        };
+
+        // a dry run only looks up the frontier
+        record_info();
+        let block = block_on(wallet.build_send(&account, &genesis, 1)).unwrap();
+        assert!(hex::encode_upper(block.previous) == SEND_HASH);
+        assert!(block.balance == ONE_NANO - 1);
+        assert!(block.signature == Signature::default());
+        assert!(fixture.is_empty());
+
+        record_info();
+        let key = Key::from_seed(&SecretBytes::from([0; 32]), 0);
+        record_publish(&fixture, &key, block.clone(), true);
+        assert!(block_on(wallet.send(&account, &genesis, 1)).unwrap() == block.hash());
+        assert!(fixture.is_empty());
+
+        record_info();
+        assert!(matches!(
+            block_on(wallet.build_send(&account, &genesis, ONE_NANO + 1)),
+            Err(WalletError::NanoError(NanoError::InsufficientBalance))
+        ));
+    }
+
+    #[test]
+    fn sweep() {
+        let fixture = Fixture::new();
+        let wallet = wallet(&fixture);
+        let keys: Vec<Key> = (0..3)
+            .map(|i| Key::from_seed(&SecretBytes::from([1; 32]), i))
+            .collect();
+        let accounts: Vec<Account> = keys.iter().map(|key| key.to_account()).collect();
+        let genesis = get_genesis_account();
+
+        let mut open = BlockBuilder::unopened(accounts[0].clone(), genesis.clone())
+            .receive(
+                hex::decode(SEND_HASH).unwrap().try_into().unwrap(),
+                ONE_NANO,
+            )
+            .unwrap();
+        open.sign(&keys[0]);
+        let mut empty = BlockBuilder::new(accounts[2].clone(), [2; 32], genesis.clone(), ONE_NANO)
+            .send(&genesis, ONE_NANO)
+            .unwrap();
+        empty.sign(&keys[2]);
+
+        fixture.record(
+            json!({
+                "action": "accounts_frontiers",
+                "accounts": accounts.iter().map(|account| account.to_string()).collect::<Vec<_>>()
+            }),
+            json!({"frontiers": {
+                accounts[0].to_string(): hex::encode_upper(open.hash()),
+                accounts[2].to_string(): hex::encode_upper(empty.hash())
+            }}),
+        );
+        let info = |block: &Block, subtype: &str| {
+            json!({
+                "block_account": block.account.to_string(),
+                "amount": ONE_NANO.to_string(),
+                "balance": block.balance.to_string(),
+                "height": "1",
+                "local_timestamp": "1000",
+                "successor": hex::encode([0; 32]),
+                "confirmed": "true",
+                "contents": block_to_json(block),
+                "subtype": subtype
+            })
+        };
+        fixture.record(
+            json!({
+                "action": "blocks_info",
+                "hashes": [hex::encode_upper(open.hash()), hex::encode_upper(empty.hash())],
+                "json_block": true,
+                "include_not_found": true
+            }),
+            json!({"blocks": {
+                hex::encode_upper(open.hash()): info(&open, "receive"),
+                hex::encode_upper(empty.hash()): info(&empty, "send")
+            }}),
+        );
+        let send = BlockBuilder::from(&open).send(&genesis, ONE_NANO).unwrap();
+        record_publish(&fixture, &keys[0], send.clone(), true);
+
+        let results = block_on(wallet.sweep(&keys, &genesis)).unwrap();
+        assert!(results.len() == 3);
+        assert!(results[0].as_ref().unwrap() == &Some(send.hash()));
+        assert!(results[1].as_ref().unwrap().is_none());
+        assert!(results[2].as_ref().unwrap().is_none());
+        assert!(fixture.is_empty());
+    }
+
+    #[test]
+    fn discover_accounts() {
+        let seed = SecretBytes::from([0; 32]);
+        let mock = MockRpc::new();
+        let opened = Key::from_seed(&seed, 1).to_account().to_string();
+        let receivable = Key::from_seed(&seed, 4).to_account().to_string();
+
+        // index 1 is opened, and index 4 has a receivable transaction
+        mock.on("accounts_frontiers", move |request| {
+            let frontiers: Map<String, JsonValue> = request["accounts"]
+                .as_array()
+                .unwrap()
+                .iter()
+                .filter(|a| a.as_str() == Some(&opened))
+                .map(|a| (a.as_str().unwrap().into(), hex::encode([1; 32]).into()))
+                .collect();
+            Ok(json!({ "frontiers": frontiers }))
+        });
+        mock.on("accounts_receivable", move |request| {
+            let blocks: Map<String, JsonValue> = request["accounts"]
+                .as_array()
+                .unwrap()
+                .iter()
+                .map(|a| match a.as_str() == Some(&receivable) {
+                    true => (
+                        a.as_str().unwrap().into(),
+                        json!({hex::encode([2; 32]): {"amount": "1", "source": get_genesis_account().to_string()}}),
+                    ),
+                    false => (a.as_str().unwrap().into(), "".into()),
+                })
+                .collect();
+            Ok(json!({ "blocks": blocks }))
+        });
+
+        let used = block_on(super::discover_accounts(&seed, &mock.rpc(), 3)).unwrap();
+        assert!(used == [1, 4]);
+        // indexes 0-2, 3-4, and 5-7
+        let batches: Vec<usize> = mock
+            .requests()
+            .iter()
+            .filter(|request| request["action"] == "accounts_frontiers")
+            .map(|request| request["accounts"].as_array().unwrap().len())
+            .collect();
+        assert!(batches == [3, 2, 3]);
+
+        assert!(block_on(super::discover_accounts(&seed, &mock.rpc(), 1))
+            .unwrap()
+            .is_empty());
+    }
+
+    #[test]
+    fn watch_wallet() {
+        let mock = MockRpc::new();
+        let account = Key::from_seed(&SecretBytes::from([0; 32]), 0).to_account();
+        let mut wallet = WatchWallet::new(mock.rpc(), vec![account.clone(), account.clone()]);
+        assert!(wallet.accounts() == [account.clone()]);
+
+        let respond = |frontier: Option<[u8; 32]>, balance: u128, receivable: u128| {
+            let frontiers = match frontier {
+                Some(frontier) => json!({account.to_string(): hex::encode(frontier)}),
+                None => json!({}),
+            };
+            mock.respond("accounts_frontiers", json!({ "frontiers": frontiers }));
+            mock.respond(
+                "accounts_balances",
+                json!({"balances": {account.to_string(): {
+                    "balance": balance.to_string(),
+                    "pending": receivable.to_string(),
+                    "receivable": receivable.to_string()
+                }}}),
+            );
+        };
+
+        // no events until the state is known
+        respond(None, 0, 10);
+        assert!(block_on(wallet.refresh()).unwrap().is_empty());
+        assert!(wallet.state(&account).unwrap().frontier.is_none());
+        assert!(block_on(wallet.refresh()).unwrap().is_empty());
+
+        respond(Some([1; 32]), 10, 0);
+        let events = block_on(wallet.refresh()).unwrap();
+        assert!(
+            events
+                == [
+                    WatchEvent::NewFrontier {
+                        account: account.clone(),
+                        frontier: [1; 32]
+                    },
+                    WatchEvent::BalanceChanged {
+                        account: account.clone(),
+                        old: 0,
+                        new: 10
+                    },
+                    WatchEvent::ReceivableChanged {
+                        account: account.clone(),
+                        old: 10,
+                        new: 0
+                    }
+                ]
+        );
+        assert!(wallet.state(&account).unwrap().balances.confirmed == 10);
+
+        wallet.remove_account(&account);
+        assert!(wallet.state(&account).is_none());
+    }
+}
@@ -0,0 +1,140 @@
+use super::{WorkFuture, WorkProvider, SEND_DIFFICULTY};
+use crate::block::check_work;
+use crate::rpc::RpcError;
+use std::collections::HashMap;
+use std::sync::{Arc, Mutex, MutexGuard};
+
+/// Caches work by work hash, generating it with another `WorkProvider` when it is not cached.
+///
+/// Work is removed from the cache once it has been used, since only one block can use a given work hash.
+/// Cached work which does not meet the requested difficulty is replaced.
+///
+/// Cloning a `WorkCache` does not copy the cached work: both clones will refer to the same cache.
+/// Keep a clone after passing it to `Wallet::set_work_provider()` to call `precompute_next()` after publishing.
+#[derive(Debug, Clone)]
+pub struct WorkCache {
+    provider: Arc<dyn WorkProvider>,
+    work: Arc<Mutex<HashMap<[u8; 32], [u8; 8]>>>,
+    /// The difficulty of work generated by `precompute_next()`.
+    ///
+    /// Defaults to `SEND_DIFFICULTY`, which is enough for any block.
+    pub precompute_difficulty: [u8; 8],
+}
+impl WorkCache {
+    pub fn new(provider: impl WorkProvider + 'static) -> WorkCache {
+        WorkCache {
+            provider: Arc::new(provider),
+            work: Arc::default(),
+            precompute_difficulty: SEND_DIFFICULTY,
+        }
+    }
+
+    fn lock(&self) -> MutexGuard<'_, HashMap<[u8; 32], [u8; 8]>> {
+        self.work
+            .lock()
+            .unwrap_or_else(|poisoned| poisoned.into_inner())
+    }
+
+    pub fn get_provider(&self) -> &dyn WorkProvider {
+        self.provider.as_ref()
+    }
+
+    /// Get the cached work for `work_hash`, if it meets the given difficulty (without removing it)
+    pub fn get(&self, work_hash: [u8; 32], difficulty: [u8; 8]) -> Option<[u8; 8]> {
+        self.lock()
+            .get(&work_hash)
+            .copied()
+            .filter(|work| check_work(work_hash, difficulty, *work))
+    }
+
+    /// Add work to the cache, replacing any work already cached for `work_hash`
+    pub fn insert(&self, work_hash: [u8; 32], work: [u8; 8]) {
+        self.lock().insert(work_hash, work);
+    }
+
+    pub fn remove(&self, work_hash: [u8; 32]) -> Option<[u8; 8]> {
+        self.lock().remove(&work_hash)
+    }
+
+    pub fn len(&self) -> usize {
+        self.lock().len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.lock().is_empty()
+    }
+
+    pub fn clear(&self) {
+        self.lock().clear()
+    }
+
+    /// Generate work for the block after `frontier` (the hash of the block just published),
+    /// so that it is ready when that block is built.
+    ///
+    /// Does nothing if suitable work is already cached.
+    pub async fn precompute_next(&self, frontier: [u8; 32]) -> Result<(), RpcError> {
+        if self.get(frontier, self.precompute_difficulty).is_some() {
+            return Ok(());
+        }
+        let work = self
+            .provider
+            .generate(frontier, self.precompute_difficulty)
+            .await?;
+        self.insert(frontier, work);
+        Ok(())
+    }
+}
+impl WorkProvider for WorkCache {
+    fn generate<'a>(&'a self, work_hash: [u8; 32], difficulty: [u8; 8]) -> WorkFuture<'a> {
+        Box::pin(async move {
+            if let Some(work) = self.get(work_hash, difficulty) {
+                self.remove(work_hash);
+                return Ok(work);
+            }
+            self.provider.generate(work_hash, difficulty).await
+        })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::WorkCache;
+    use crate::block::check_work;
+    use crate::rpc::mock::MockRpc;
+    use crate::work::{LocalWork, WorkProvider};
+    use futures::executor::block_on;
+    use serde_json::json;
+
+    const DIFFICULTY: [u8; 8] = 0xfff8000000000000_u64.to_be_bytes();
+
+    #[test]
+    fn cache() {
+        let work = block_on(LocalWork.generate([7; 32], DIFFICULTY)).unwrap();
+        let mock = MockRpc::new();
+        mock.respond("work_generate", json!({"work": hex::encode(work)}));
+
+        let mut cache = WorkCache::new(mock.rpc());
+        cache.precompute_difficulty = DIFFICULTY;
+        block_on(cache.precompute_next([7; 32])).unwrap();
+        assert!(cache.get([7; 32], DIFFICULTY) == Some(work));
+        // already cached
+        block_on(cache.clone().precompute_next([7; 32])).unwrap();
+        assert!(mock.requests().len() == 1);
+
+        // cached work is used once
+        assert!(block_on(cache.generate([7; 32], DIFFICULTY)).unwrap() == work);
+        assert!(mock.requests().len() == 1);
+        assert!(cache.is_empty());
+        assert!(block_on(cache.generate([7; 32], DIFFICULTY)).unwrap() == work);
+        assert!(mock.requests().len() == 2);
+
+        // work which is too easy is not used
+        let easy = block_on(LocalWork.generate([7; 32], [0; 8])).unwrap();
+        if !check_work([7; 32], DIFFICULTY, easy) {
+            cache.insert([7; 32], easy);
+            assert!(cache.get([7; 32], DIFFICULTY).is_none());
+            assert!(block_on(cache.generate([7; 32], DIFFICULTY)).unwrap() == work);
+            assert!(mock.requests().len() == 3);
+        }
+    }
+}
@@ -0,0 +1,134 @@
+use crate::hashes::blake2b_work;
+use futures::channel::oneshot;
+use std::future::Future;
+use std::sync::atomic::{AtomicBool, Ordering};
+use std::sync::{Arc, Mutex};
+use std::thread;
+
+/// How many nonces each thread tries between checks for cancellation
+const CHECK_INTERVAL: u64 = 1 << 12;
+
+/// Cancels work started with `generate_async()`.
+///
+/// Cloning a `CancelToken` does not create a new token: cancelling either clone cancels both.
+#[derive(Debug, Clone, Default)]
+pub struct CancelToken(Arc<AtomicBool>);
+impl CancelToken {
+    pub fn new() -> CancelToken {
+        CancelToken::default()
+    }
+
+    /// Stop any work which uses this token.
+    /// This cannot be undone.
+    pub fn cancel(&self) {
+        self.0.store(true, Ordering::Relaxed)
+    }
+
+    pub fn is_cancelled(&self) -> bool {
+        self.0.load(Ordering::Relaxed)
+    }
+}
+
+/// Stops the search threads when the future is dropped
+struct StopOnDrop(Arc<AtomicBool>);
+impl Drop for StopOnDrop {
+    fn drop(&mut self) {
+        self.0.store(true, Ordering::Relaxed)
+    }
+}
+
+/// Try nonces from `start` until work is found, or `stopped()` returns `true`
+fn search(
+    work_hash: [u8; 32],
+    difficulty: [u8; 8],
+    start: u64,
+    stopped: impl Fn() -> bool,
+) -> Option<[u8; 8]> {
+    let mut data = [0; 40];
+    data[8..].copy_from_slice(&work_hash);
+
+    let mut nonce = start;
+    loop {
+        for _ in 0..CHECK_INTERVAL {
+            data[..8].copy_from_slice(&nonce.to_le_bytes());
+            let mut bytes = blake2b_work(&data);
+            bytes.reverse();
+            if bytes >= difficulty {
+                return Some(nonce.to_be_bytes());
+            }
+            nonce = nonce.wrapping_add(1);
+        }
+        if stopped() {
+            return None;
+        }
+    }
+}
+
+/// Generate work for `work_hash` (see `Block::work_hash()`) which meets the given difficulty,
+/// using one thread per CPU core.
+///
+/// The threads are started immediately, and do not block the async runtime.
+/// They stop once work is found, `cancel_token` is cancelled, or the returned future is dropped,
+/// so local work can be raced against another `WorkProvider` (such as the node's `work_generate`).
+///
+/// The future returns `None` if `cancel_token` was cancelled before work was found.
+///
+/// Not available on WebAssembly, which does not support threads.
+pub fn generate_async(
+    work_hash: [u8; 32],
+    difficulty: [u8; 8],
+    cancel_token: CancelToken,
+) -> impl Future<Output = Option<[u8; 8]>> + Send + 'static {
+    let stop = Arc::new(AtomicBool::new(false));
+    let (sender, receiver) = oneshot::channel();
+    // the channel is closed (cancelling the future) once every thread has exited
+    let sender = Arc::new(Mutex::new(Some(sender)));
+
+    let threads = thread::available_parallelism().map_or(1, |threads| threads.get()) as u64;
+    for i in 0..threads {
+        let stop = stop.clone();
+        let sender = sender.clone();
+        let cancel_token = cancel_token.clone();
+        thread::spawn(move || {
+            let stopped = || stop.load(Ordering::Relaxed) || cancel_token.is_cancelled();
+            let start = i * (u64::MAX / threads);
+            if let Some(work) = search(work_hash, difficulty, start, stopped) {
+                stop.store(true, Ordering::Relaxed);
+                #[cfg(feature = "tracing")]
+                tracing::debug!(work = %hex::encode(work), "found work");
+                let sender = sender.lock().ok().and_then(|mut sender| sender.take());
+                if let Some(sender) = sender {
+                    let _ = sender.send(work);
+                }
+            }
+        });
+    }
+
+    let stop = StopOnDrop(stop);
+    async move {
+        let _stop = stop;
+        receiver.await.ok()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{generate_async, CancelToken};
+    use crate::block::check_work;
+    use futures::executor::block_on;
+
+    const DIFFICULTY: [u8; 8] = 0xfff8000000000000_u64.to_be_bytes();
+
+    #[test]
+    fn generate() {
+        let work = block_on(generate_async([7; 32], DIFFICULTY, CancelToken::new())).unwrap();
+        assert!(check_work([7; 32], DIFFICULTY, work));
+
+        // impossible difficulty, so only cancellation can stop the search
+        let token = CancelToken::new();
+        let future = generate_async([7; 32], [255; 8], token.clone());
+        token.cancel();
+        assert!(block_on(future).is_none());
+        assert!(block_on(generate_async([7; 32], [255; 8], token)).is_none());
+    }
+}
@@ -0,0 +1,253 @@
+use super::{WorkFuture, WorkProvider};
+use crate::block::check_work;
+use crate::rpc::RpcError;
+use futures::channel::oneshot;
+use std::sync::atomic::{AtomicBool, Ordering};
+use std::sync::{mpsc, Arc};
+use std::thread;
+use wgpu::util::{BufferInitDescriptor, DeviceExt};
+use wgpu::{
+    BindGroupDescriptor, BindGroupEntry, Buffer, BufferDescriptor, BufferUsages,
+    CommandEncoderDescriptor, ComputePassDescriptor, ComputePipeline, ComputePipelineDescriptor,
+    Device, DeviceDescriptor, Instance, Limits, Maintain, MapMode, PowerPreference, Queue,
+    RequestAdapterOptions, ShaderModuleDescriptor, ShaderSource,
+};
+
+const SHADER: &str = include_str!("gpu.wgsl");
+/// Must match `@workgroup_size` in the shader
+const WORKGROUP_SIZE: u32 = 64;
+/// How many workgroups are dispatched between checks for work (and for cancellation)
+const WORKGROUPS: u32 = 1 << 14;
+const BATCH_SIZE: u64 = (WORKGROUP_SIZE * WORKGROUPS) as u64;
+
+#[derive(Debug)]
+struct Gpu {
+    device: Device,
+    queue: Queue,
+    pipeline: ComputePipeline,
+}
+impl Gpu {
+    /// Search for work in batches, until work is found or `stop` is set
+    fn search(
+        &self,
+        work_hash: [u8; 32],
+        difficulty: [u8; 8],
+        stop: &AtomicBool,
+    ) -> Result<Option<[u8; 8]>, RpcError> {
+        let mut params = [0_u32; 12];
+        for (param, word) in params.iter_mut().zip(work_hash.chunks_exact(4)) {
+            *param = u32::from_le_bytes(word.try_into().unwrap());
+        }
+        let difficulty = u64::from_be_bytes(difficulty);
+        params[8] = difficulty as u32;
+        params[9] = (difficulty >> 32) as u32;
+
+        let params_buffer = self.buffer(48, BufferUsages::STORAGE | BufferUsages::COPY_DST);
+        let result_buffer = self.device.create_buffer_init(&BufferInitDescriptor {
+            label: Some("work result"),
+            contents: &[0; 12],
+            usage: BufferUsages::STORAGE | BufferUsages::COPY_SRC | BufferUsages::COPY_DST,
+        });
+        let staging_buffer = self.buffer(12, BufferUsages::MAP_READ | BufferUsages::COPY_DST);
+        let bind_group = self.device.create_bind_group(&BindGroupDescriptor {
+            label: None,
+            layout: &self.pipeline.get_bind_group_layout(0),
+            entries: &[
+                BindGroupEntry {
+                    binding: 0,
+                    resource: params_buffer.as_entire_binding(),
+                },
+                BindGroupEntry {
+                    binding: 1,
+                    resource: result_buffer.as_entire_binding(),
+                },
+            ],
+        });
+
+        let mut start = 0_u64;
+        while !stop.load(Ordering::Relaxed) {
+            params[10] = start as u32;
+            params[11] = (start >> 32) as u32;
+            let params_bytes: Vec<u8> = params
+                .iter()
+                .flat_map(|param| param.to_le_bytes())
+                .collect();
+            self.queue.write_buffer(&params_buffer, 0, &params_bytes);
+
+            let mut encoder = self
+                .device
+                .create_command_encoder(&CommandEncoderDescriptor::default());
+            {
+                let mut pass = encoder.begin_compute_pass(&ComputePassDescriptor::default());
+                pass.set_pipeline(&self.pipeline);
+                pass.set_bind_group(0, &bind_group, &[]);
+                pass.dispatch_workgroups(WORKGROUPS, 1, 1);
+            }
+            encoder.copy_buffer_to_buffer(&result_buffer, 0, &staging_buffer, 0, 12);
+            self.queue.submit([encoder.finish()]);
+
+            let result = self.read(&staging_buffer)?;
+            if result[0] != 0 {
+                let nonce = ((result[2] as u64) << 32) | result[1] as u64;
+                let work = nonce.to_be_bytes();
+                #[cfg(feature = "tracing")]
+                tracing::debug!(work = %hex::encode(work), "found work on the GPU");
+                return match check_work(work_hash, difficulty.to_be_bytes(), work) {
+                    true => Ok(Some(work)),
+                    false => Err(RpcError::GpuError("the GPU returned invalid work".into())),
+                };
+            }
+            start = start.wrapping_add(BATCH_SIZE);
+        }
+        Ok(None)
+    }
+
+    fn buffer(&self, size: u64, usage: BufferUsages) -> Buffer {
+        self.device.create_buffer(&BufferDescriptor {
+            label: None,
+            size,
+            usage,
+            mapped_at_creation: false,
+        })
+    }
+
+    /// Wait for the GPU, then read the result from `staging_buffer`
+    fn read(&self, staging_buffer: &Buffer) -> Result<[u32; 3], RpcError> {
+        let slice = staging_buffer.slice(..);
+        let (sender, receiver) = mpsc::channel();
+        slice.map_async(MapMode::Read, move |result| {
+            let _ = sender.send(result);
+        });
+        self.device.poll(Maintain::Wait);
+        receiver
+            .recv()
+            .map_err(|_| RpcError::GpuError("the GPU did not respond".into()))?
+            .map_err(|err| RpcError::GpuError(err.to_string()))?;
+
+        let mut result = [0; 3];
+        for (value, bytes) in result
+            .iter_mut()
+            .zip(slice.get_mapped_range().chunks_exact(4))
+        {
+            *value = u32::from_le_bytes(bytes.try_into().unwrap());
+        }
+        staging_buffer.unmap();
+        Ok(result)
+    }
+}
+
+/// Stops the search thread when the future is dropped
+struct StopOnDrop(Arc<AtomicBool>);
+impl Drop for StopOnDrop {
+    fn drop(&mut self) {
+        self.0.store(true, Ordering::Relaxed)
+    }
+}
+
+/// Generates work using the GPU, with [wgpu](https://docs.rs/wgpu/latest/wgpu/) (Vulkan, Metal, or DirectX 12).
+///
+/// The search runs on a separate thread, so it does not block the async runtime,
+/// and stops when the returned future is dropped.
+///
+/// Not available on WebAssembly.
+#[derive(Debug, Clone)]
+pub struct GpuWork {
+    gpu: Arc<Gpu>,
+}
+impl GpuWork {
+    /// Use the most powerful GPU available.
+    ///
+    /// Returns `None` if there is no GPU (or driver) which supports compute shaders.
+    pub async fn new() -> Option<GpuWork> {
+        let instance = Instance::default();
+        let adapter = instance
+            .request_adapter(&RequestAdapterOptions {
+                power_preference: PowerPreference::HighPerformance,
+                ..Default::default()
+            })
+            .await?;
+        let (device, queue) = adapter
+            .request_device(
+                &DeviceDescriptor {
+                    label: Some("nanopyrs work"),
+                    required_limits: Limits::downlevel_defaults(),
+                    ..Default::default()
+                },
+                None,
+            )
+            .await
+            .ok()?;
+
+        let module = device.create_shader_module(ShaderModuleDescriptor {
+            label: Some("nanopyrs work"),
+            source: ShaderSource::Wgsl(SHADER.into()),
+        });
+        let pipeline = device.create_compute_pipeline(&ComputePipelineDescriptor {
+            label: Some("nanopyrs work"),
+            layout: None,
+            module: &module,
+            entry_point: Some("main"),
+            compilation_options: Default::default(),
+            cache: None,
+        });
+        Some(GpuWork {
+            gpu: Arc::new(Gpu {
+                device,
+                queue,
+                pipeline,
+            }),
+        })
+    }
+}
+impl WorkProvider for GpuWork {
+    fn generate<'a>(&'a self, work_hash: [u8; 32], difficulty: [u8; 8]) -> WorkFuture<'a> {
+        let stop = Arc::new(AtomicBool::new(false));
+        let (sender, receiver) = oneshot::channel();
+        let gpu = self.gpu.clone();
+        let thread_stop = stop.clone();
+        thread::spawn(move || {
+            let _ = sender.send(gpu.search(work_hash, difficulty, &thread_stop));
+        });
+
+        let stop = StopOnDrop(stop);
+        Box::pin(async move {
+            let _stop = stop;
+            match receiver.await {
+                Ok(Ok(Some(work))) => Ok(work),
+                Ok(Err(err)) => Err(err),
+                // the search only stops early if this future is dropped
+                Ok(Ok(None)) | Err(_) => Err(RpcError::GpuError("the search was stopped".into())),
+            }
+        })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{GpuWork, SHADER};
+    use crate::block::check_work;
+    use crate::work::WorkProvider;
+    use futures::executor::block_on;
+    use wgpu::naga::front::wgsl;
+    use wgpu::naga::valid::{Capabilities, ValidationFlags, Validator};
+
+    const DIFFICULTY: [u8; 8] = 0xfff8000000000000_u64.to_be_bytes();
+
+    #[test]
+    fn shader() {
+        let module = wgsl::parse_str(SHADER).unwrap();
+        Validator::new(ValidationFlags::all(), Capabilities::empty())
+            .validate(&module)
+            .unwrap();
+    }
+
+    /// Only runs if a GPU is available
+    #[test]
+    fn generate() {
+        let Some(gpu) = block_on(GpuWork::new()) else {
+            return;
+        };
+        let work = block_on(gpu.generate([7; 32], DIFFICULTY)).unwrap();
+        assert!(check_work([7; 32], DIFFICULTY, work));
+    }
+}
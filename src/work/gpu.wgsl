@@ -0,0 +1,99 @@
+// Searches for Nano proof-of-work: blake2b(nonce || work_hash) with an 8-byte digest.
+// WGSL has no 64-bit integers, so each 64-bit word is a vec2<u32> of (low, high) halves.
+
+// the 32-byte work hash as 8 little-endian words, then the difficulty and the first nonce as (low, high)
+@group(0) @binding(0) var<storage, read> params: array<u32, 12>;
+// set to (1, nonce low, nonce high) by the first invocation to find work
+@group(0) @binding(1) var<storage, read_write> result: array<atomic<u32>, 3>;
+
+var<private> SIGMA: array<u32, 160> = array<u32, 160>(
+    0u, 1u, 2u, 3u, 4u, 5u, 6u, 7u, 8u, 9u, 10u, 11u, 12u, 13u, 14u, 15u,
+    14u, 10u, 4u, 8u, 9u, 15u, 13u, 6u, 1u, 12u, 0u, 2u, 11u, 7u, 5u, 3u,
+    11u, 8u, 12u, 0u, 5u, 2u, 15u, 13u, 10u, 14u, 3u, 6u, 7u, 1u, 9u, 4u,
+    7u, 9u, 3u, 1u, 13u, 12u, 11u, 14u, 2u, 6u, 5u, 10u, 4u, 0u, 15u, 8u,
+    9u, 0u, 5u, 7u, 2u, 4u, 10u, 15u, 14u, 1u, 11u, 12u, 6u, 8u, 3u, 13u,
+    2u, 12u, 6u, 10u, 0u, 11u, 8u, 3u, 4u, 13u, 7u, 5u, 15u, 14u, 1u, 9u,
+    12u, 5u, 1u, 15u, 14u, 13u, 4u, 10u, 0u, 7u, 6u, 3u, 9u, 2u, 8u, 11u,
+    13u, 11u, 7u, 14u, 12u, 1u, 3u, 9u, 5u, 0u, 15u, 4u, 8u, 6u, 2u, 10u,
+    6u, 15u, 14u, 9u, 11u, 3u, 0u, 8u, 12u, 2u, 13u, 7u, 1u, 4u, 10u, 5u,
+    10u, 2u, 8u, 4u, 7u, 6u, 1u, 5u, 15u, 11u, 9u, 14u, 3u, 12u, 13u, 0u,
+);
+
+// IV[0] ^ 0x01010008 (no key, 8-byte digest)
+const H0: vec2<u32> = vec2<u32>(0xf2bdc900u, 0x6a09e667u);
+
+fn add(a: vec2<u32>, b: vec2<u32>) -> vec2<u32> {
+    let low = a.x + b.x;
+    return vec2<u32>(low, a.y + b.y + select(0u, 1u, low < a.x));
+}
+
+// rotate right by `n`, where 0 < n < 32
+fn rotr(a: vec2<u32>, n: u32) -> vec2<u32> {
+    return vec2<u32>((a.x >> n) | (a.y << (32u - n)), (a.y >> n) | (a.x << (32u - n)));
+}
+
+fn g(v: ptr<function, array<vec2<u32>, 16>>, a: u32, b: u32, c: u32, d: u32, x: vec2<u32>, y: vec2<u32>) {
+    (*v)[a] = add(add((*v)[a], (*v)[b]), x);
+    (*v)[d] = ((*v)[d] ^ (*v)[a]).yx;
+    (*v)[c] = add((*v)[c], (*v)[d]);
+    (*v)[b] = rotr((*v)[b] ^ (*v)[c], 24u);
+    (*v)[a] = add(add((*v)[a], (*v)[b]), y);
+    (*v)[d] = rotr((*v)[d] ^ (*v)[a], 16u);
+    (*v)[c] = add((*v)[c], (*v)[d]);
+    (*v)[b] = rotr(((*v)[b] ^ (*v)[c]).yx, 31u);
+}
+
+@compute @workgroup_size(64)
+fn main(@builtin(global_invocation_id) id: vec3<u32>) {
+    let low = params[10] + id.x;
+    let nonce = vec2<u32>(low, params[11] + select(0u, 1u, low < params[10]));
+
+    var m: array<vec2<u32>, 16>;
+    m[0] = nonce;
+    m[1] = vec2<u32>(params[0], params[1]);
+    m[2] = vec2<u32>(params[2], params[3]);
+    m[3] = vec2<u32>(params[4], params[5]);
+    m[4] = vec2<u32>(params[6], params[7]);
+
+    var v = array<vec2<u32>, 16>(
+        H0,
+        vec2<u32>(0x84caa73bu, 0xbb67ae85u),
+        vec2<u32>(0xfe94f82bu, 0x3c6ef372u),
+        vec2<u32>(0x5f1d36f1u, 0xa54ff53au),
+        vec2<u32>(0xade682d1u, 0x510e527fu),
+        vec2<u32>(0x2b3e6c1fu, 0x9b05688cu),
+        vec2<u32>(0xfb41bd6bu, 0x1f83d9abu),
+        vec2<u32>(0x137e2179u, 0x5be0cd19u),
+        vec2<u32>(0xf3bcc908u, 0x6a09e667u),
+        vec2<u32>(0x84caa73bu, 0xbb67ae85u),
+        vec2<u32>(0xfe94f82bu, 0x3c6ef372u),
+        vec2<u32>(0x5f1d36f1u, 0xa54ff53au),
+        // IV[4] ^ 40 (the message length)
+        vec2<u32>(0xade682f9u, 0x510e527fu),
+        vec2<u32>(0x2b3e6c1fu, 0x9b05688cu),
+        // !IV[6] (the last block)
+        vec2<u32>(0x04be4294u, 0xe07c2654u),
+        vec2<u32>(0x137e2179u, 0x5be0cd19u),
+    );
+
+    for (var round = 0u; round < 12u; round++) {
+        let s = (round % 10u) * 16u;
+        g(&v, 0u, 4u, 8u, 12u, m[SIGMA[s]], m[SIGMA[s + 1u]]);
+        g(&v, 1u, 5u, 9u, 13u, m[SIGMA[s + 2u]], m[SIGMA[s + 3u]]);
+        g(&v, 2u, 6u, 10u, 14u, m[SIGMA[s + 4u]], m[SIGMA[s + 5u]]);
+        g(&v, 3u, 7u, 11u, 15u, m[SIGMA[s + 6u]], m[SIGMA[s + 7u]]);
+        g(&v, 0u, 5u, 10u, 15u, m[SIGMA[s + 8u]], m[SIGMA[s + 9u]]);
+        g(&v, 1u, 6u, 11u, 12u, m[SIGMA[s + 10u]], m[SIGMA[s + 11u]]);
+        g(&v, 2u, 7u, 8u, 13u, m[SIGMA[s + 12u]], m[SIGMA[s + 13u]]);
+        g(&v, 3u, 4u, 9u, 14u, m[SIGMA[s + 14u]], m[SIGMA[s + 15u]]);
+    }
+
+    // the digest, read as a little-endian integer
+    let work = H0 ^ v[0] ^ v[8];
+    if (work.y > params[9] || (work.y == params[9] && work.x >= params[8])) {
+        if (atomicExchange(&result[0], 1u) == 0u) {
+            atomicStore(&result[1], nonce.x);
+            atomicStore(&result[2], nonce.y);
+        }
+    }
+}
@@ -0,0 +1,75 @@
+//! Proof-of-work difficulty thresholds and multipliers, and (with the `rpc-core` feature) sources of work.
+
+#[cfg(feature = "rpc-core")]
+mod cache;
+#[cfg(all(feature = "rpc-core", not(target_arch = "wasm32")))]
+mod generate;
+#[cfg(all(feature = "gpu", not(target_arch = "wasm32")))]
+mod gpu;
+#[cfg(feature = "rpc-core")]
+mod provider;
+
+use crate::constants::{LEGACY_WORK_DIFFICULTY, RECEIVE_WORK_DIFFICULTY, SEND_WORK_DIFFICULTY};
+
+#[cfg(feature = "rpc-core")]
+pub use cache::WorkCache;
+#[cfg(all(feature = "rpc-core", not(target_arch = "wasm32")))]
+pub use generate::{generate_async, CancelToken};
+#[cfg(all(feature = "gpu", not(target_arch = "wasm32")))]
+pub use gpu::GpuWork;
+#[cfg(feature = "rpc-core")]
+pub use provider::{DistributedWork, LocalWork, WorkFuture, WorkProvider};
+
+/// The minimum difficulty of `send`, `change`, and `epoch` blocks
+pub const SEND_DIFFICULTY: [u8; 8] = SEND_WORK_DIFFICULTY;
+/// The minimum difficulty of `receive` blocks
+pub const RECEIVE_DIFFICULTY: [u8; 8] = RECEIVE_WORK_DIFFICULTY;
+/// The minimum difficulty of legacy blocks, which all predate epoch v2
+pub const LEGACY_DIFFICULTY: [u8; 8] = LEGACY_WORK_DIFFICULTY;
+
+/// `2^64 - difficulty`, as in the node's multiplier calculations
+fn reverse_difficulty(difficulty: [u8; 8]) -> u128 {
+    (1 << 64) - u64::from_be_bytes(difficulty) as u128
+}
+
+/// Get the multiplier of `difficulty` relative to `base` (usually `SEND_DIFFICULTY`),
+/// as used by the node's `multiplier` fields
+pub fn difficulty_to_multiplier(difficulty: [u8; 8], base: [u8; 8]) -> f64 {
+    reverse_difficulty(base) as f64 / reverse_difficulty(difficulty) as f64
+}
+
+/// Get the difficulty which is `multiplier` times `base` (usually `SEND_DIFFICULTY`).
+///
+/// Very large multipliers result in the maximum difficulty, and non-positive multipliers result in the minimum (zero).
+pub fn multiplier_to_difficulty(multiplier: f64, base: [u8; 8]) -> [u8; 8] {
+    if multiplier.is_nan() || multiplier <= 0.0 {
+        return [0; 8];
+    }
+    // float-to-int casts saturate
+    let reverse = (reverse_difficulty(base) as f64 / multiplier) as u128;
+    let reverse = reverse.clamp(1, 1 << 64);
+    (((1 << 64) - reverse) as u64).to_be_bytes()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn multipliers() {
+        assert!(difficulty_to_multiplier(SEND_DIFFICULTY, SEND_DIFFICULTY) == 1.0);
+        assert!(difficulty_to_multiplier(RECEIVE_DIFFICULTY, SEND_DIFFICULTY) == 1.0 / 64.0);
+        // from the node's `work_generate` documentation
+        let multiplier =
+            difficulty_to_multiplier(0xfffffff93c41ec94_u64.to_be_bytes(), SEND_DIFFICULTY);
+        assert!((multiplier - 1.182623871097636).abs() < 1e-12);
+
+        assert!(multiplier_to_difficulty(1.0, SEND_DIFFICULTY) == SEND_DIFFICULTY);
+        assert!(multiplier_to_difficulty(64.0, RECEIVE_DIFFICULTY) == SEND_DIFFICULTY);
+        assert!(multiplier_to_difficulty(1.0 / 64.0, SEND_DIFFICULTY) == RECEIVE_DIFFICULTY);
+        assert!(multiplier_to_difficulty(f64::INFINITY, SEND_DIFFICULTY) == [255; 8]);
+        assert!(multiplier_to_difficulty(0.0, SEND_DIFFICULTY) == [0; 8]);
+        assert!(multiplier_to_difficulty(-1.0, SEND_DIFFICULTY) == [0; 8]);
+        assert!(multiplier_to_difficulty(f64::NAN, SEND_DIFFICULTY) == [0; 8]);
+    }
+}
@@ -0,0 +1,149 @@
+use crate::block::check_work;
+use crate::rpc::{default_transport, HttpOptions, Rpc, RpcError, RpcTransport};
+use serde_json::{Map, Value as JsonValue};
+use std::fmt::Debug;
+use std::future::Future;
+use std::pin::Pin;
+use std::sync::Arc;
+
+#[cfg(not(target_arch = "wasm32"))]
+use super::{generate_async, CancelToken};
+#[cfg(target_arch = "wasm32")]
+use crate::block::get_local_work;
+
+pub type WorkFuture<'a> = Pin<Box<dyn Future<Output = Result<[u8; 8], RpcError>> + Send + 'a>>;
+
+/// A source of proof-of-work, such as the local CPU, a node, or a distributed work service
+pub trait WorkProvider: Debug + Send + Sync {
+    /// Generate work for `work_hash` (see `Block::work_hash()`) which meets the given difficulty
+    fn generate<'a>(&'a self, work_hash: [u8; 32], difficulty: [u8; 8]) -> WorkFuture<'a>;
+}
+
+/// Generates work using the local CPU (likely very slow).
+///
+/// For GPU work, use `GpuWork` (with the `gpu` feature), or point an `Rpc` at a work server (such as `nano-work-server`).
+///
+/// The search runs on one thread per CPU core (see `generate_async()`), so it does not block the async runtime,
+/// and stops when the returned future is dropped.
+/// On WebAssembly, which does not support threads, the current thread is blocked until work is found.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub struct LocalWork;
+impl WorkProvider for LocalWork {
+    #[cfg(not(target_arch = "wasm32"))]
+    fn generate<'a>(&'a self, work_hash: [u8; 32], difficulty: [u8; 8]) -> WorkFuture<'a> {
+        let search = generate_async(work_hash, difficulty, CancelToken::new());
+        Box::pin(async move {
+            // the token is never cancelled, so the search only ends early if its threads panic
+            search
+                .await
+                .ok_or_else(|| std::io::Error::from(std::io::ErrorKind::Interrupted).into())
+        })
+    }
+
+    #[cfg(target_arch = "wasm32")]
+    fn generate<'a>(&'a self, work_hash: [u8; 32], difficulty: [u8; 8]) -> WorkFuture<'a> {
+        Box::pin(async move { Ok(get_local_work(work_hash, difficulty)) })
+    }
+}
+
+/// Generates work using the node's `work_generate` command
+impl WorkProvider for Rpc {
+    fn generate<'a>(&'a self, work_hash: [u8; 32], difficulty: [u8; 8]) -> WorkFuture<'a> {
+        Box::pin(self.work_generate(work_hash, Some(difficulty)))
+    }
+}
+
+/// Generates work using a distributed work service with a DPoW-style HTTP API (such as DPoW or BoomPoW).
+///
+/// The returned work is checked before it is used.
+/// Errors reported by the service are returned as `RpcError::NodeError`.
+#[derive(Debug, Clone)]
+pub struct DistributedWork {
+    transport: Arc<dyn RpcTransport>,
+    user: String,
+    api_key: String,
+}
+impl DistributedWork {
+    pub fn new(url: &str, user: &str, api_key: &str) -> Result<DistributedWork, RpcError> {
+        let transport = default_transport(url, &HttpOptions::default())?;
+        Ok(DistributedWork::with_transport(transport, user, api_key))
+    }
+
+    fn with_transport(
+        transport: Arc<dyn RpcTransport>,
+        user: &str,
+        api_key: &str,
+    ) -> DistributedWork {
+        DistributedWork {
+            transport,
+            user: user.into(),
+            api_key: api_key.into(),
+        }
+    }
+}
+impl WorkProvider for DistributedWork {
+    fn generate<'a>(&'a self, work_hash: [u8; 32], difficulty: [u8; 8]) -> WorkFuture<'a> {
+        Box::pin(async move {
+            let mut arguments = Map::new();
+            arguments.insert("user".into(), self.user.clone().into());
+            arguments.insert("api_key".into(), self.api_key.clone().into());
+            arguments.insert("hash".into(), hex::encode_upper(work_hash).into());
+            arguments.insert("difficulty".into(), hex::encode(difficulty).into());
+
+            let response = self.transport.send(&JsonValue::Object(arguments)).await?;
+            if let Some(error) = response["error"].as_str() {
+                return Err(RpcError::NodeError(error.into()));
+            }
+            let work: [u8; 8] = hex::decode(response["work"].as_str().unwrap_or_default())?
+                .try_into()
+                .or(Err(RpcError::InvalidData))?;
+            match check_work(work_hash, difficulty, work) {
+                true => Ok(work),
+                false => Err(RpcError::InvalidData),
+            }
+        })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{DistributedWork, LocalWork, WorkProvider};
+    use crate::block::check_work;
+    use crate::rpc::{Fixture, RpcError};
+    use futures::executor::block_on;
+    use serde_json::json;
+    use std::sync::Arc;
+
+    const DIFFICULTY: [u8; 8] = 0xfff8000000000000_u64.to_be_bytes();
+
+    #[test]
+    fn local() {
+        let work = block_on(LocalWork.generate([7; 32], DIFFICULTY)).unwrap();
+        assert!(check_work([7; 32], DIFFICULTY, work));
+    }
+
+    #[test]
+    fn distributed() {
+        let work = block_on(LocalWork.generate([7; 32], DIFFICULTY)).unwrap();
+        let request = json!({
+            "user": "user",
+            "api_key": "key",
+            "hash": hex::encode_upper([7; 32]),
+            "difficulty": "fff8000000000000"
+        });
+
+        let fixture = Fixture::new();
+        let provider = DistributedWork::with_transport(Arc::new(fixture.clone()), "user", "key");
+        fixture.record(request.clone(), json!({"work": hex::encode(work)}));
+        assert!(block_on(provider.generate([7; 32], DIFFICULTY)).unwrap() == work);
+
+        // invalid work is rejected
+        fixture.record(request.clone(), json!({"work": "0000000000000000"}));
+        assert!(block_on(provider.generate([7; 32], DIFFICULTY)).is_err());
+        fixture.record(request, json!({"error": "Invalid key"}));
+        assert!(matches!(
+            block_on(provider.generate([7; 32], DIFFICULTY)),
+            Err(RpcError::NodeError(error)) if error == "Invalid key"
+        ));
+    }
+}